@@ -8,6 +8,7 @@
 import Anthropic from '@anthropic-ai/sdk';
 import type { MessageStreamEvent } from '@anthropic-ai/sdk/resources/messages';
 import type { ContentBlock, PromptPayload } from './prompt';
+import { log } from '../logging';
 
 /** Default Anthropic model. */
 export const DEFAULT_MODEL = 'claude-sonnet-4-6';
@@ -19,15 +20,71 @@ export const DEFAULT_MODEL = 'claude-sonnet-4-6';
  */
 export const DEFAULT_MAX_OUTPUT_TOKENS = 16_000;
 
+/** Anthropic accepts `temperature` in [0, 1]. */
+export const MAX_TEMPERATURE = 1;
+
+/** Values Anthropic accepts for `output_config.effort`. */
+export const REASONING_EFFORTS = ['low', 'medium', 'high'] as const;
+export type ReasoningEffort = (typeof REASONING_EFFORTS)[number];
+
+export function isReasoningEffort(value: string): value is ReasoningEffort {
+  return (REASONING_EFFORTS as readonly string[]).includes(value);
+}
+
+/**
+ * Models that accept the effort parameter: Opus 4.5 and Sonnet 4.6 onward.
+ * Older models reject it, so the setting is dropped for them.
+ */
+const EFFORT_MODEL_RE = /^claude-(?:opus-4-[5-9]|sonnet-4-[6-9]|(?:opus|sonnet)-[5-9])/;
+
+export function modelSupportsReasoningEffort(model: string): boolean {
+  return EFFORT_MODEL_RE.test(model);
+}
+
 export type StreamEvent =
   | { kind: 'text_delta'; delta: string }
   | { kind: 'completed' }
-  | { kind: 'failed'; message: string };
+  | { kind: 'refused'; reason: string }
+  | { kind: 'failed'; message: string; timedOut: boolean };
+
+/** `failed` message emitted when a stream goes quiet past the stall timeout. */
+export const STREAM_STALLED_MESSAGE = 'stream stalled';
+
+/** Error raised when an SSE frame outgrows the configured buffer cap. */
+export const STREAM_BUFFER_OVERFLOW_MESSAGE = 'stream frame exceeded the buffer limit';
 
 export interface LlmClientOptions {
   apiKey: string;
   model?: string;
   maxOutputTokens?: number;
+  /**
+   * Sampling temperature. When unset we leave sampling to the model and keep
+   * adaptive thinking on; Anthropic rejects `temperature` alongside thinking,
+   * so setting it switches thinking off for this client.
+   */
+  temperature?: number | null;
+  /**
+   * How much the model should think before answering. Sent as
+   * `output_config.effort` when set and the model supports it; unset leaves
+   * the model's default (high).
+   */
+  reasoningEffort?: ReasoningEffort | null;
+  /**
+   * API root for proxies / gateways (e.g. `https://llm-gateway.internal/anthropic`).
+   * The SDK appends `/v1/messages`; unset uses api.anthropic.com.
+   */
+  baseUrl?: string | null;
+  /**
+   * Fail a stream that goes this long without a new event. `null`/unset
+   * waits indefinitely (bounded only by the Lambda timeout).
+   */
+  streamStallTimeoutMs?: number | null;
+  /**
+   * Fail a stream once this many bytes arrive without completing an SSE
+   * frame, so a misbehaving upstream can't grow the SDK's parse buffer
+   * without bound. `null`/unset leaves it unbounded.
+   */
+  streamMaxBufferBytes?: number | null;
   /** Optional fetch override for tests. Passed through to the SDK. */
   fetchImpl?: typeof fetch;
 }
@@ -40,6 +97,39 @@ export type StreamingResponse =
 export const TOO_LARGE_MESSAGE =
   'The conversation is too long to summarize in full. Try `summarize last N` in this thread to limit the window.';
 
+/**
+ * Upper bound on map passes in {@link LlmClient.mapReduceSummarize}. Together
+ * with the single reduce pass this caps a rollup at 7 model calls.
+ */
+export const MAX_ROLLUP_WINDOWS = 6;
+
+/** `stop_reason` Anthropic returns when the model's safety classifier declines a request. */
+export const REFUSAL_STOP_REASON = 'refusal';
+
+/** Anthropic doesn't explain refusals, so we surface a fixed, user-safe reason. */
+export const DEFAULT_REFUSAL_REASON = 'the model declined to process it';
+
+/** Raised when Anthropic stops with {@link REFUSAL_STOP_REASON}. */
+export class RefusalError extends Error {
+  constructor(readonly reason: string = DEFAULT_REFUSAL_REASON) {
+    super(`Model refused: ${reason}`);
+    this.name = 'RefusalError';
+  }
+}
+
+/** Raised when a summary stream fails because Anthropic stopped responding in time. */
+export class LlmTimeoutError extends Error {
+  constructor(message: string) {
+    super(message);
+    this.name = 'LlmTimeoutError';
+  }
+}
+
+/** True for {@link LlmTimeoutError} and the SDK's own request timeout. */
+export function isLlmTimeoutError(err: unknown): boolean {
+  return err instanceof LlmTimeoutError || err instanceof Anthropic.APIConnectionTimeoutError;
+}
+
 /**
  * Detect Anthropic's "prompt is too long" / overloaded responses so the
  * worker can show a friendly fallback. Anthropic uses `invalid_request_error`
@@ -63,15 +153,56 @@ export function isPromptTooLargeError(err: unknown): boolean {
 export class LlmClient {
   private readonly client: Anthropic;
   private readonly model: string;
-  private readonly maxOutputTokens: number;
+  readonly maxOutputTokens: number;
+  private readonly temperature: number | null;
+  private readonly reasoningEffort: ReasoningEffort | null;
+  private readonly streamStallTimeoutMs: number | null;
 
   constructor(opts: LlmClientOptions) {
+    const maxBufferBytes = opts.streamMaxBufferBytes ?? null;
+    const fetchImpl =
+      maxBufferBytes !== null
+        ? boundedSseFetch(opts.fetchImpl ?? fetch, maxBufferBytes)
+        : opts.fetchImpl;
     this.client = new Anthropic({
       apiKey: opts.apiKey,
-      ...(opts.fetchImpl ? { fetch: opts.fetchImpl } : {}),
+      ...(opts.baseUrl ? { baseURL: opts.baseUrl } : {}),
+      ...(fetchImpl ? { fetch: fetchImpl } : {}),
     });
     this.model = opts.model ?? DEFAULT_MODEL;
     this.maxOutputTokens = opts.maxOutputTokens ?? DEFAULT_MAX_OUTPUT_TOKENS;
+    this.temperature = opts.temperature ?? null;
+    this.reasoningEffort = opts.reasoningEffort ?? null;
+    if (this.reasoningEffort !== null && !modelSupportsReasoningEffort(this.model)) {
+      log.warn('Ignoring reasoning effort; the model does not support it', {
+        model: this.model,
+        effort: this.reasoningEffort,
+      });
+      this.reasoningEffort = null;
+    }
+    this.streamStallTimeoutMs = opts.streamStallTimeoutMs ?? null;
+  }
+
+  /**
+   * Sampling params shared by both request shapes: either adaptive thinking
+   * (default) or an explicit temperature — never both.
+   */
+  private samplingParams():
+    | { thinking: { type: 'adaptive' } }
+    | { temperature: number } {
+    if (this.temperature !== null) {
+      return { temperature: this.temperature };
+    }
+    // Anthropic's current best practice for Sonnet 4.6: adaptive thinking.
+    // The model decides when and how much to think; budget_tokens is
+    // deprecated on this family. Thinking blocks are emitted separately
+    // from text blocks, so our text-only consumer is unaffected.
+    return { thinking: { type: 'adaptive' } };
+  }
+
+  /** `output_config.effort` when a reasoning effort is configured, else nothing. */
+  private effortParams(): { output_config: { effort: ReasoningEffort } } | Record<string, never> {
+    return this.reasoningEffort !== null ? { output_config: { effort: this.reasoningEffort } } : {};
   }
 
   /** Non-streaming summary. Mostly used by tests / non-streaming destinations. */
@@ -80,11 +211,8 @@ export class LlmClient {
       const response = await this.client.messages.create({
         model: this.model,
         max_tokens: this.maxOutputTokens,
-        // Anthropic's current best practice for Sonnet 4.6: adaptive thinking.
-        // The model decides when and how much to think; budget_tokens is
-        // deprecated on this family. Thinking blocks are emitted separately
-        // from text blocks, so our text-only consumer is unaffected.
-        thinking: { type: 'adaptive' },
+        ...this.samplingParams(),
+        ...this.effortParams(),
         system: prompt.system,
         messages: [
           {
@@ -93,6 +221,9 @@ export class LlmClient {
           },
         ],
       });
+      if (response.stop_reason === REFUSAL_STOP_REASON) {
+        throw new RefusalError();
+      }
       return extractText(response.content);
     } catch (err) {
       if (isPromptTooLargeError(err)) {
@@ -102,6 +233,32 @@ export class LlmClient {
     }
   }
 
+  /**
+   * Summarise an oversized conversation in two passes: each window prompt is
+   * summarised independently (map), then `buildReducePrompt` folds the
+   * partial summaries into the final prompt (reduce). Windows past
+   * {@link MAX_ROLLUP_WINDOWS} are dropped to bound cost; windows that are
+   * still too large are skipped.
+   */
+  async mapReduceSummarize(
+    windowPrompts: PromptPayload[],
+    buildReducePrompt: (partials: string[]) => PromptPayload
+  ): Promise<string> {
+    const windows = windowPrompts.slice(0, MAX_ROLLUP_WINDOWS);
+    const partials = (await Promise.all(windows.map((w) => this.generateSummary(w)))).filter(
+      (partial) => partial !== TOO_LARGE_MESSAGE && partial.trim().length > 0
+    );
+    log.info('Rolled up oversized prompt', {
+      windows: windowPrompts.length,
+      summarized: windows.length,
+      partials: partials.length,
+    });
+    if (partials.length === 0) {
+      return TOO_LARGE_MESSAGE;
+    }
+    return this.generateSummary(buildReducePrompt(partials));
+  }
+
   /**
    * Stream a summary. Returns an async iterator over {@link StreamEvent}
    * compatible with the worker's existing streaming pipeline.
@@ -112,7 +269,8 @@ export class LlmClient {
       stream = this.client.messages.stream({
         model: this.model,
         max_tokens: this.maxOutputTokens,
-        thinking: { type: 'adaptive' },
+        ...this.samplingParams(),
+        ...this.effortParams(),
         system: prompt.system,
         messages: [
           {
@@ -128,7 +286,7 @@ export class LlmClient {
       throw err;
     }
 
-    const iterator = consumeStream(stream);
+    const iterator = consumeStream(stream, this.streamStallTimeoutMs);
     return {
       kind: 'active',
       iterator,
@@ -166,19 +324,127 @@ function extractText(content: Anthropic.Messages.ContentBlock[]): string {
     .join('\n');
 }
 
+/**
+ * Tracks how many bytes of the current SSE frame are buffered. A frame ends
+ * at a blank line (`\n\n`, CRs ignored), which is when the SDK's parser can
+ * release it; until then everything read sits in its buffer.
+ */
+export class SseFrameGuard {
+  private pending = 0;
+  private lastWasNewline = false;
+
+  constructor(private readonly maxBytes: number) {}
+
+  /** Bytes received since the last frame boundary. */
+  bufferLen(): number {
+    return this.pending;
+  }
+
+  /** Account for `chunk`; returns `false` once the open frame exceeds the cap. */
+  push(chunk: Uint8Array): boolean {
+    for (const byte of chunk) {
+      if (byte === 0x0a) {
+        if (this.lastWasNewline) {
+          this.pending = 0;
+          this.lastWasNewline = false;
+          continue;
+        }
+        this.lastWasNewline = true;
+      } else if (byte !== 0x0d) {
+        this.lastWasNewline = false;
+      }
+      this.pending += 1;
+    }
+    return this.pending <= this.maxBytes;
+  }
+}
+
+/**
+ * Wrap `fetchImpl` so `text/event-stream` bodies error out with
+ * {@link STREAM_BUFFER_OVERFLOW_MESSAGE} once a frame outgrows `maxBytes`.
+ * Other responses pass through untouched.
+ */
+function boundedSseFetch(fetchImpl: typeof fetch, maxBytes: number): typeof fetch {
+  return async (input, init) => {
+    const response = await fetchImpl(input, init);
+    const contentType = response.headers.get('content-type') ?? '';
+    if (!response.body || !contentType.includes('text/event-stream')) {
+      return response;
+    }
+    const guard = new SseFrameGuard(maxBytes);
+    const body = response.body.pipeThrough(
+      new TransformStream<Uint8Array, Uint8Array>({
+        transform(chunk, controller): void {
+          if (!guard.push(chunk)) {
+            controller.error(new Error(STREAM_BUFFER_OVERFLOW_MESSAGE));
+            return;
+          }
+          controller.enqueue(chunk);
+        },
+      })
+    );
+    return new Response(body, {
+      status: response.status,
+      statusText: response.statusText,
+      headers: response.headers,
+    });
+  };
+}
+
+const STALLED = Symbol('stalled');
+
+/**
+ * Await the next stream event, or {@link STALLED} if none arrives within
+ * `timeoutMs`. Already-buffered events resolve immediately, so the timer only
+ * ever fires while we're actually waiting on the network.
+ */
+async function nextWithin<T>(
+  iterator: AsyncIterator<T>,
+  timeoutMs: number | null
+): Promise<IteratorResult<T> | typeof STALLED> {
+  if (timeoutMs === null) {
+    return iterator.next();
+  }
+  let timer: ReturnType<typeof setTimeout> | undefined;
+  const stalled = new Promise<typeof STALLED>((resolve) => {
+    timer = setTimeout(() => resolve(STALLED), timeoutMs);
+  });
+  try {
+    return await Promise.race([iterator.next(), stalled]);
+  } finally {
+    clearTimeout(timer);
+  }
+}
+
 /**
  * Adapt the Anthropic SDK MessageStream into our internal `StreamEvent` shape.
- * We only surface text deltas, a single `completed` event when streaming ends,
- * and `failed` for errors — the worker pipeline doesn't care about tool use or
- * thinking blocks for summarisation.
+ * We only surface text deltas, a single `completed` (or `refused`) event when
+ * streaming ends, and `failed` for errors — the worker pipeline doesn't care
+ * about tool use or thinking blocks for summarisation. A stream that goes
+ * quiet for `stallTimeoutMs` is aborted and reported as
+ * {@link STREAM_STALLED_MESSAGE} so the caller's cleanup runs; one that trips
+ * the frame buffer cap surfaces as `failed` through the SDK's read error.
  */
 async function* consumeStream(
   stream: AsyncIterable<MessageStreamEvent> & {
-    finalMessage(): Promise<unknown>;
-  }
+    finalMessage(): Promise<{ stop_reason?: string | null }>;
+    abort(): void;
+  },
+  stallTimeoutMs: number | null
 ): AsyncGenerator<StreamEvent, void, void> {
   try {
-    for await (const event of stream) {
+    const events = stream[Symbol.asyncIterator]();
+    while (true) {
+      const next = await nextWithin(events, stallTimeoutMs);
+      if (next === STALLED) {
+        stream.abort();
+        yield { kind: 'failed', message: STREAM_STALLED_MESSAGE, timedOut: true };
+        return;
+      }
+      if (next.done) {
+        break;
+      }
+      const event = next.value;
       if (event.type === 'content_block_delta' && event.delta.type === 'text_delta') {
         yield { kind: 'text_delta', delta: event.delta.text };
       }
@@ -189,10 +455,14 @@ async function* consumeStream(
     }
     // Surfacing finalMessage() so that any deferred error on the stream is
     // raised here as a thrown exception (handled in the outer catch).
-    await stream.finalMessage();
+    const final = await stream.finalMessage();
+    if (final.stop_reason === REFUSAL_STOP_REASON) {
+      yield { kind: 'refused', reason: DEFAULT_REFUSAL_REASON };
+      return;
+    }
     yield { kind: 'completed' };
   } catch (err) {
     const message = err instanceof Error ? err.message : String(err);
-    yield { kind: 'failed', message };
+    yield { kind: 'failed', message, timedOut: isLlmTimeoutError(err) };
   }
 }
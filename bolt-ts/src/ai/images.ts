@@ -29,6 +29,45 @@ export function isAllowedImageMime(mime: string): boolean {
   return ALLOWED_IMAGE_MIME.has(canonicalizeMime(mime));
 }
 
+/** Content-Types that say nothing about the format; the bytes have to decide. */
+const GENERIC_MIME = new Set(['application/octet-stream', 'binary/octet-stream']);
+
+export function isGenericMime(mime: string): boolean {
+  return GENERIC_MIME.has(canonicalizeMime(mime));
+}
+
+function startsWith(bytes: Uint8Array, offset: number, signature: number[] | string): boolean {
+  const sig = typeof signature === 'string' ? [...signature].map((c) => c.charCodeAt(0)) : signature;
+  if (bytes.length < offset + sig.length) {
+    return false;
+  }
+  return sig.every((b, i) => bytes[offset + i] === b);
+}
+
+/**
+ * Identify an image format from its leading magic bytes. Returns `null` for
+ * anything unrecognised. AVIF is detected so it can be rejected explicitly —
+ * Anthropic doesn't accept it.
+ */
+export function sniffImageMime(bytes: Uint8Array): string | null {
+  if (startsWith(bytes, 0, [0xff, 0xd8, 0xff])) {
+    return 'image/jpeg';
+  }
+  if (startsWith(bytes, 0, [0x89, 0x50, 0x4e, 0x47, 0x0d, 0x0a, 0x1a, 0x0a])) {
+    return 'image/png';
+  }
+  if (startsWith(bytes, 0, 'GIF87a') || startsWith(bytes, 0, 'GIF89a')) {
+    return 'image/gif';
+  }
+  if (startsWith(bytes, 0, 'RIFF') && startsWith(bytes, 8, 'WEBP')) {
+    return 'image/webp';
+  }
+  if (startsWith(bytes, 4, 'ftypavif') || startsWith(bytes, 4, 'ftypavis')) {
+    return 'image/avif';
+  }
+  return null;
+}
+
 /**
  * Build an Anthropic image content block from raw bytes + a MIME hint. Throws
  * if the MIME is unsupported — callers should pre-filter with
@@ -1,30 +1,38 @@
 /**
  * TLDR Slack AI App — Bolt app factory.
  *
- * Wires the Assistant middleware, the style modal, and the per-summary
- * interactive buttons. All work runs inline (no SQS); long-running summary
+ * Wires the Assistant middleware, the style modal, the per-summary
+ * interactive buttons, channel @-mentions, the "Summarize Thread" message shortcut, and the Workflow Builder "Summarize channel" step. All work runs inline (no SQS); long-running summary
  * generation is streamed back into the assistant thread via
  * `worker/streaming.ts`.
  */
 
 import { App, LogLevel, Receiver } from '@slack/bolt';
 import { AppConfig } from './config';
+import { slackClientOptions } from './slack/retry';
 import {
   registerActionHandlers,
+  registerAppMentionHandlers,
   registerAssistantHandlers,
+  registerShortcutHandlers,
   registerStyleHandlers,
+  registerWorkflowHandlers,
 } from './handlers';
 
 export function createApp(config: AppConfig, receiver: Receiver): App {
   const app = new App({
     token: config.slackBotToken,
     receiver,
+    clientOptions: slackClientOptions(config),
     logLevel: process.env.LOG_LEVEL === 'debug' ? LogLevel.DEBUG : LogLevel.INFO,
   });
 
   registerAssistantHandlers(app, config);
-  registerStyleHandlers(app);
+  registerStyleHandlers(app, config);
   registerActionHandlers(app, config);
+  registerWorkflowHandlers(app, config);
+  registerAppMentionHandlers(app, config);
+  registerShortcutHandlers(app, config);
 
   return app;
 }
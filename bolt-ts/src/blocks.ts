@@ -12,7 +12,10 @@
 
 import { types } from '@slack/bolt';
 import type { View } from '@slack/types';
-import { normalizeMessageCount } from './security';
+import { SUMMARY_TONES } from './ai/prompt';
+import { MAX_CUSTOM_STYLE_LENGTH, normalizeMessageCount } from './security';
+import type { SummaryTone } from './types';
+import { splitIntoChunks } from './worker/chunks';
 
 type KnownBlock = types.KnownBlock;
 
@@ -21,6 +24,17 @@ export const ACTION_SELECT_MESSAGE_COUNT = 'select_message_count';
 export const MODAL_CALLBACK_SET_STYLE = 'set_style_modal';
 export const INPUT_BLOCK_STYLE = 'style_input_block';
 export const INPUT_ACTION_STYLE = 'style_input_action';
+export const INPUT_BLOCK_TONE = 'tone_input_block';
+export const INPUT_ACTION_TONE = 'tone_input_action';
+export const MODAL_CALLBACK_SHARE_PREVIEW = 'share_preview_modal';
+
+/**
+ * Codepoints per preview section. Slack caps section text at 3,000 UTF-16
+ * units, so this stays safe even when every character is an emoji.
+ */
+const PREVIEW_SECTION_CHARS = 1_500;
+/** Modals allow 100 blocks; leave room for the header blocks. */
+const MAX_PREVIEW_SECTIONS = 90;
 
 export const MESSAGE_COUNT_OPTIONS = [5, 10, 15, 20, 25, 30, 40, 50, 75, 100, 150, 200, 300, 500];
 
@@ -138,7 +152,26 @@ export function buildHelpBlocks(): KnownBlock[] {
           '• `summarize` — last 50 messages (or your chosen default).\n' +
           '• `summarize last 100` — explicit count.\n' +
           '• `summarize <#C123|general>` — pick a different channel.\n' +
-          '• `summarize with style: write as a haiku` — one-off style override.',
+          '• `summarize <message link>` — summarize that message\'s thread.\n' +
+          '• `summarize with style: write as a haiku` — one-off style override.\n' +
+          '• `summarize post to <#C123|leads>` — publish the summary in another channel.\n' +
+          '• `summarize post to <#C123|leads> --notify-me` — also DM you a link once it is posted.\n' +
+          '• `summarize post to <#C123|leads> --bookmark` — keep a channel bookmark on the latest one.\n' +
+          '• `summarize by thread` — per-topic digest that includes thread replies.\n' +
+          '• `summarize include bots` — keep alert / integration posts that are normally skipped.\n' +
+          '• `summarize template=standup` — per-person Yesterday / Today / Blockers digest.\n' +
+          '• `summarize pinned` — summarize the channel\'s pinned messages.\n' +
+          '• `summarize canvas` — summarize the channel\'s canvas document.\n' +
+          '• `summarize contains=deploy` — only messages mentioning a word (quote phrases).\n' +
+          '• `summarize tz=America/New_York` — date the summary in another time zone.\n' +
+          '• `summarize before=<message link>` — summarize what led up to that message.\n' +
+          '• `summarize after=<message link>` — summarize everything since that message.\n' +
+          '• `summarize --no-images` — text-only summary, skipping images this time.\n' +
+          '• `summarize --since-last-summary` — only what is new since the last summary here.\n' +
+          '• `summarize --allow-mentions` — let people the summary @-mentions get notified.\n' +
+          '• `describe the images <message link>` — describe just the images on that message.\n' +
+          '• `summarize my mentions` — digest of recent messages that @-mention you.\n' +
+          '• `refresh` / `refresh last 200` — regenerate my last summary here, in place.',
       },
     },
     { type: 'divider' },
@@ -148,7 +181,7 @@ export function buildHelpBlocks(): KnownBlock[] {
         type: 'mrkdwn',
         text:
           '*🎨 Persistent style for this thread*\n' +
-          '• Click *🎨 Set style* in the welcome message for a multi-line editor.\n' +
+          '• Click *🎨 Set style* in the welcome message for a multi-line editor and tone presets.\n' +
           '• Or type `style: be hyper-critical and roast everyone`.\n' +
           '• `clear style` to remove it.',
       },
@@ -161,6 +194,7 @@ export function buildHelpBlocks(): KnownBlock[] {
         text:
           '*⚡ Tips*\n' +
           '• Each summary comes with *Share*, *Roast*, and *Receipts* buttons.\n' +
+          '• Its ⋯ menu reposts it to the channel or saves it to a canvas.\n' +
           '• Styles only apply to this thread — start a new one to reset.\n' +
           '• I can only summarize channels *you* are a member of.',
       },
@@ -177,6 +211,18 @@ export function buildHelpBlocks(): KnownBlock[] {
   ];
 }
 
+/** Dropdown labels for the tone presets. */
+const TONE_LABELS: Record<SummaryTone, string> = {
+  neutral: 'Neutral',
+  friendly: 'Friendly',
+  formal: 'Formal',
+  executive: 'Executive brief',
+};
+
+function toneOption(tone: SummaryTone): types.PlainTextOption {
+  return { text: { type: 'plain_text', text: TONE_LABELS[tone] }, value: tone };
+}
+
 export interface StyleModalPrivateMetadata {
   assistantChannelId: string;
   assistantThreadTs: string;
@@ -184,7 +230,9 @@ export interface StyleModalPrivateMetadata {
 
 export function buildStyleModal(
   currentStyle: string | null,
-  privateMetadata: StyleModalPrivateMetadata
+  privateMetadata: StyleModalPrivateMetadata,
+  maxChars: number = MAX_CUSTOM_STYLE_LENGTH,
+  currentTone: SummaryTone | null = null
 ): View {
   return {
     type: 'modal',
@@ -212,7 +260,9 @@ export function buildStyleModal(
           type: 'plain_text_input',
           action_id: INPUT_ACTION_STYLE,
           multiline: true,
-          max_length: 4000,
+          // Stays at the hard cap so an older, longer saved style still
+          // loads as the initial value; `maxChars` is enforced on submit.
+          max_length: MAX_CUSTOM_STYLE_LENGTH,
           placeholder: {
             type: 'plain_text',
             text: 'e.g., "Write as a haiku" or "Be extremely concise and funny"',
@@ -222,15 +272,89 @@ export function buildStyleModal(
         label: { type: 'plain_text', text: 'Custom Style Instructions', emoji: true },
         hint: {
           type: 'plain_text',
-          text: 'Applied to every summary in this thread (up to 4 000 chars).',
+          text: `Applied to every summary in this thread (up to ${maxChars.toLocaleString('en-US')} chars).`,
+        },
+      },
+      {
+        type: 'input',
+        block_id: INPUT_BLOCK_TONE,
+        optional: true,
+        element: {
+          type: 'static_select',
+          action_id: INPUT_ACTION_TONE,
+          placeholder: { type: 'plain_text', text: 'Default' },
+          options: SUMMARY_TONES.map(toneOption),
+          ...(currentTone ? { initial_option: toneOption(currentTone) } : {}),
+        },
+        label: { type: 'plain_text', text: 'Tone', emoji: true },
+        hint: {
+          type: 'plain_text',
+          text: 'A preset voice for the summary. Custom instructions above take precedence.',
         },
       },
     ],
   };
 }
 
-export function buildStyleConfirmationBlocks(style: string | null): KnownBlock[] {
-  if (!style) {
+/**
+ * Where a previewed share goes and which message holds the summary. The
+ * summary itself is re-read on submit: it can outgrow `private_metadata`.
+ */
+export interface SharePreviewPrivateMetadata {
+  sourceChannelId: string;
+  count: number;
+  style: string | null;
+  assistantChannelId: string;
+  assistantThreadTs: string;
+  summaryTs: string;
+}
+
+/**
+ * Modal shown by the Share button: the summary exactly as it will be posted,
+ * with "Post" (submit) and "Cancel" (close).
+ */
+export function buildSharePreviewModal(
+  attribution: string,
+  summaryText: string,
+  privateMetadata: SharePreviewPrivateMetadata
+): View {
+  const sections = splitIntoChunks(summaryText, PREVIEW_SECTION_CHARS);
+  const shown = sections.slice(0, MAX_PREVIEW_SECTIONS);
+  const blocks: KnownBlock[] = [
+    {
+      type: 'context',
+      elements: [
+        { type: 'mrkdwn', text: `This will be posted to <#${privateMetadata.sourceChannelId}>:` },
+      ],
+    },
+    { type: 'section', text: { type: 'mrkdwn', text: attribution } },
+    { type: 'divider' },
+    ...shown.map((text): KnownBlock => ({ type: 'section', text: { type: 'mrkdwn', text } })),
+  ];
+  if (shown.length < sections.length) {
+    blocks.push({
+      type: 'context',
+      elements: [{ type: 'mrkdwn', text: '_Preview shortened; the full summary will be posted._' }],
+    });
+  }
+  return {
+    type: 'modal',
+    callback_id: MODAL_CALLBACK_SHARE_PREVIEW,
+    private_metadata: JSON.stringify(privateMetadata),
+    title: { type: 'plain_text', text: 'Share summary', emoji: true },
+    submit: { type: 'plain_text', text: 'Post', emoji: true },
+    close: { type: 'plain_text', text: 'Cancel', emoji: true },
+    // Slack only sends view_closed for Cancel when asked to.
+    notify_on_close: true,
+    blocks,
+  };
+}
+
+export function buildStyleConfirmationBlocks(
+  style: string | null,
+  tone: SummaryTone | null = null
+): KnownBlock[] {
+  if (!style && !tone) {
     return [
       {
         type: 'section',
@@ -239,6 +363,13 @@ export function buildStyleConfirmationBlocks(style: string | null): KnownBlock[]
     ];
   }
 
+  const details: string[] = [];
+  if (tone) {
+    details.push(`🗣️ Tone: ${TONE_LABELS[tone]}`);
+  }
+  if (style) {
+    details.push(`🎨 Active style: ${truncateStyle(style)}`);
+  }
   return [
     {
       type: 'section',
@@ -246,9 +377,7 @@ export function buildStyleConfirmationBlocks(style: string | null): KnownBlock[]
     },
     {
       type: 'context',
-      elements: [
-        { type: 'mrkdwn', text: `🎨 Active style: ${truncateStyle(style)}` },
-      ],
+      elements: details.map((text) => ({ type: 'mrkdwn' as const, text })),
     },
   ];
 }
@@ -5,19 +5,133 @@
  */
 
 import { GetParameterCommand, SSMClient } from '@aws-sdk/client-ssm';
-import { DEFAULT_MAX_OUTPUT_TOKENS, DEFAULT_MODEL } from './ai/anthropic';
+import {
+  DEFAULT_MAX_OUTPUT_TOKENS,
+  DEFAULT_MODEL,
+  MAX_TEMPERATURE,
+  REASONING_EFFORTS,
+  isReasoningEffort,
+  type ReasoningEffort,
+} from './ai/anthropic';
+import {
+  DEFAULT_ASSISTANT_NAME,
+  DEFAULT_SUMMARY_SECTIONS,
+  FILES_SHARED_SECTION,
+} from './ai/prompt';
+import { DEFAULT_MAX_CUSTOM_STYLE_CHARS, MAX_CUSTOM_STYLE_LENGTH } from './security';
+import { DEFAULT_TIMEZONE, isValidTimeZone } from './time';
 
 export interface AppConfig {
   slackBotToken: string;
   slackSigningSecret: string;
+  /**
+   * Optional user OAuth token with `search:read`. Slack only exposes
+   * `search.messages` to user tokens, so the "my mentions" digest is disabled
+   * when this is unset.
+   */
+  slackUserToken: string | null;
+  /**
+   * Optional app-level token (`xapp-…`, `connections:write`) for Socket Mode
+   * deployments started via `socket.ts`. The Lambda entry point ignores it.
+   */
+  slackAppToken: string | null;
   anthropicApiKey: string;
   anthropicModel: string;
   anthropicMaxOutputTokens: number;
+  /** Anthropic API root override for proxies / gateways; `null` uses the SDK default. */
+  anthropicBaseUrl: string | null;
+  /** Sampling temperature; `null` keeps adaptive thinking on instead. */
+  anthropicTemperature: number | null;
+  /** Temperature used when a custom style is active; falls back to `anthropicTemperature`. */
+  anthropicStyleTemperature: number | null;
+  /** `output_config.effort` for models that support it; `null` keeps the model default. */
+  anthropicReasoningEffort: ReasoningEffort | null;
   enableStreaming: boolean;
   streamMaxChunkChars: number;
   streamMinAppendIntervalMs: number;
+  /** Abort an Anthropic stream after this long without a new event. */
+  streamStallTimeoutMs: number;
+  /** Fail an Anthropic stream whose current SSE frame grows past this many bytes. */
+  streamMaxBufferBytes: number;
+  /**
+   * Before streaming, close the bot's unfinished replies in the thread that
+   * are older than this (a run killed before `stopStream`). 0 disables.
+   */
+  staleStreamMaxAgeSecs: number;
+  /** Fetch `chat.getPermalink` receipts for each summary. Off saves up to 12 API calls per run. */
+  enableReceipts: boolean;
+  /** Include link-unfurl / attachment titles and text in the prompt. */
+  includeAttachmentText: boolean;
+  /**
+   * Never fetch or send images: no HEAD / GET to Slack's file CDN and no
+   * image blocks in the prompt. For deployments that must not ship images
+   * to the model provider.
+   */
+  disableImages: boolean;
+  /** Ask the model to cite a receipt permalink after each major claim in the Summary. */
+  citeInline: boolean;
+  /** Sections every summary must have, in order; the safety net adds missing ones. */
+  summarySections: string[];
+  /** Write summaries in the conversation's language when it is confidently not English. */
+  detectSummaryLanguage: boolean;
+  /**
+   * Publish "post to #channel" summaries with `slackUserToken` (needs the
+   * `chat:write` user scope) so they appear from that user. Without a user
+   * token the bot still posts.
+   */
+  postAsUser: boolean;
+  /** Let Slack unfurl the links and media a posted summary lists. Off keeps summaries compact. */
+  unfurlSummaryLinks: boolean;
+  /** Number the sections of summaries saved to a canvas and add a contents list. */
+  canvasToc: boolean;
+  /**
+   * Footer shown under each summary; `{model}`, `{count}`, and `{channel}` are
+   * filled in. Defaults to {@link DEFAULT_SUMMARY_FOOTER}; `null` shows none.
+   */
+  summaryFooter: string | null;
+  /** Reject HTTP requests whose raw body is longer than this with a 413. */
+  maxRequestBodyBytes: number;
+  /** Max inline-image downloads in flight per summary. */
+  imageDownloadConcurrency: number;
+  /** Images larger than this (bytes) are left out of the prompt. */
+  inlineImageMaxBytes: number;
+  /** Max `chat.getPermalink` calls in flight while collecting receipts. */
+  permalinkConcurrency: number;
+  /** Max summaries this process runs at once; later ones wait for a slot. */
+  maxConcurrentSummaries: number;
+  /** Most messages any one summary reads, whatever the request asked for. */
+  hardMessageCap: number;
+  /** User or bot ids (CI, alerting integrations) whose messages are never summarised. */
+  ignoredUserIds: string[];
+  /** IANA zone for summary header dates when the requester's own zone is unknown. */
+  defaultTimezone: string;
+  /** Longest custom style (codepoints) accepted from `style:`, overrides, and the modal. */
+  maxCustomStyleChars: number;
+  /** Reply posted when there is nothing to summarise, on every delivery path. */
+  noMessagesMessage: string;
+  /** Replaces the built-in system prompt verbatim; see `buildPrompt`. */
+  systemPromptOverride: string | null;
+  /** Name the built-in system prompt gives the assistant (`ASSISTANT_NAME`). */
+  assistantName: string;
+  /** Budget for one summarisation run; it aborts cleanly before the Lambda is killed. */
+  taskDeadlineSecs: number;
+  /** Retries per Slack Web API call after the first attempt; see `slack/retry.ts`. */
+  slackMaxRetries: number;
+  /** Backoff before the first Slack retry; doubles per attempt up to `slackRetryMaxDelayMs`. */
+  slackRetryBaseDelayMs: number;
+  slackRetryMaxDelayMs: number;
+  /**
+   * Extra attempts at posting a finished summary after a transient Slack
+   * failure, on top of the client's own retries; see `deliverOrDmRequester`.
+   */
+  deliveryMaxRetries: number;
 }
 
+export const DEFAULT_NO_MESSAGES_MESSAGE = 'No messages found to summarize.';
+
+/** Footer used when `SUMMARY_FOOTER` is unset; `SUMMARY_FOOTER=off` turns it off. */
+export const DEFAULT_SUMMARY_FOOTER = '_{model} · {count} messages from {channel}_';
+
 /** Slack's documented per-call character limit for `markdown_text` in chat.*Stream APIs. */
 export const STREAM_MARKDOWN_TEXT_LIMIT = 12_000;
 /**
@@ -32,6 +146,96 @@ const DEFAULT_STREAM_MAX_CHUNK_CHARS = 8_000;
  * the stream feeling live.
  */
 const DEFAULT_STREAM_MIN_APPEND_INTERVAL_MS = 500;
+/**
+ * Default stall timeout for Anthropic streams. Adaptive thinking can pause
+ * text output for a while, so this is generous — it only exists so a dead
+ * connection fails fast instead of holding the Lambda until its timeout.
+ */
+const DEFAULT_STREAM_STALL_TIMEOUT_MS = 120_000;
+/**
+ * Default cap on one buffered SSE frame. Real frames are a few hundred bytes
+ * (a text delta or a usage update); 1 MB only trips on a broken upstream.
+ */
+const DEFAULT_STREAM_MAX_BUFFER_BYTES = 1_048_576;
+/** A run can't outlive the 15 min Lambda limit, so an older open stream is orphaned. */
+const DEFAULT_STALE_STREAM_MAX_AGE_SECS = 900;
+/**
+ * Default ceiling on an incoming request body. Slack payloads are a few KB;
+ * 1 MB is far above anything legitimate while still bounding the work done
+ * before signature verification.
+ */
+const DEFAULT_MAX_REQUEST_BODY_BYTES = 1_048_576;
+/**
+ * Default parallelism for inline-image downloads. Enough to hide per-file
+ * latency on image-heavy channels without bursting Slack's file endpoints.
+ */
+const DEFAULT_IMAGE_DOWNLOAD_CONCURRENCY = 3;
+/**
+ * Default per-image inline cap. Comfortably fits phone screenshots and
+ * exported diagrams; `ANTHROPIC_IMAGE_MAX_BYTES` is the hard ceiling.
+ */
+const DEFAULT_INLINE_IMAGE_MAX_BYTES = 4 * 1024 * 1024;
+/** Anthropic rejects larger images, so a higher cap would only fail the request. */
+const ANTHROPIC_IMAGE_MAX_BYTES = 5 * 1024 * 1024;
+/**
+ * Default parallelism for receipt permalink lookups. A run needs at most a
+ * dozen, so four in flight keeps it to a few round trips.
+ */
+const DEFAULT_PERMALINK_CONCURRENCY = 4;
+/**
+ * Default cap on concurrent summaries per process. Each holds an Anthropic
+ * stream open for up to a few minutes; more than this at once mostly buys
+ * rate-limit errors.
+ */
+const DEFAULT_MAX_CONCURRENT_SUMMARIES = 4;
+/**
+ * Default ceiling on messages read per summary. Matches the most a single
+ * `conversations.history` page returns, so it only bites when lowered.
+ */
+const DEFAULT_HARD_MESSAGE_CAP = 1000;
+/**
+ * Default task deadline. The Lambda timeout is 15 minutes; stopping a minute
+ * early leaves room to close a stream and post the failure message.
+ */
+const DEFAULT_TASK_DEADLINE_SECS = 840;
+/**
+ * Shortest accepted `SYSTEM_PROMPT_OVERRIDE`. Anything under this can't
+ * restate the four-section output contract and is almost certainly a typo.
+ */
+export const MIN_SYSTEM_PROMPT_OVERRIDE_CHARS = 200;
+
+/** Longest `ASSISTANT_NAME` kept; longer values are truncated. */
+export const MAX_ASSISTANT_NAME_CHARS = 60;
+/** Longest name accepted in `SUMMARY_SECTIONS`; it becomes a bold header. */
+const MAX_SUMMARY_SECTION_CHARS = 40;
+/** Lambda's hard ceiling; a longer deadline could never fire. */
+const MAX_TASK_DEADLINE_SECS = 900;
+/**
+ * Default Slack retry policy: five retries from 100 ms, doubling with jitter
+ * and capped at 5 s, so a flaky call settles in well under a minute.
+ */
+const DEFAULT_SLACK_MAX_RETRIES = 5;
+const DEFAULT_SLACK_RETRY_BASE_DELAY_MS = 100;
+const DEFAULT_SLACK_RETRY_MAX_DELAY_MS = 5_000;
+/** More retries than this can't finish inside the task deadline anyway. */
+const MAX_SLACK_RETRIES = 10;
+/**
+ * Default delivery retries. Each one already sits behind the client's own
+ * retries, so two more attempts are plenty before falling back to a DM.
+ */
+const DEFAULT_DELIVERY_MAX_RETRIES = 2;
+const MAX_DELIVERY_MAX_RETRIES = 5;
+
+/**
+ * Every missing or invalid setting found while loading config, reported
+ * together so a failed cold start is debuggable from one log line.
+ */
+export class ConfigError extends Error {
+  constructor(readonly problems: string[]) {
+    super(`Invalid configuration: ${problems.join('; ')}`);
+    this.name = 'ConfigError';
+  }
+}
 
 let ssmClient: SSMClient | null = null;
 let cachedConfig: AppConfig | null = null;
@@ -63,6 +267,25 @@ async function loadSensitiveValue(envName: string, parameterEnvName: string): Pr
   return value;
 }
 
+/** Like {@link loadSensitiveValue}, but returns `null` when neither source is configured. */
+async function loadOptionalSensitiveValue(
+  envName: string,
+  parameterEnvName: string
+): Promise<string | null> {
+  if (!process.env[envName] && !process.env[parameterEnvName]?.trim()) {
+    return null;
+  }
+  return loadSensitiveValue(envName, parameterEnvName);
+}
+
+/** Split a comma- or whitespace-separated list, dropping blanks. */
+function parseList(raw: string | undefined): string[] {
+  if (!raw) {
+    return [];
+  }
+  return raw.split(/[\s,]+/).filter((item) => item.length > 0);
+}
+
 function parseBool(raw: string | undefined): boolean {
   if (raw === undefined) {
     return false;
@@ -70,12 +293,44 @@ function parseBool(raw: string | undefined): boolean {
   return ['1', 'true', 'yes', 'y', 'on'].includes(raw.trim().toLowerCase());
 }
 
-function parsePositiveInt(raw: string | undefined, fallback: number, max?: number): number {
+/**
+ * Read a positive integer env var. Unset or blank uses `fallback`; anything
+ * that isn't a positive integer is recorded in `problems` with the raw value.
+ */
+function parsePositiveInt(
+  envName: string,
+  fallback: number,
+  problems: string[],
+  max?: number
+): number {
+  return parseBoundedInt(envName, fallback, problems, 1, max);
+}
+
+/** {@link parsePositiveInt}, but `0` is accepted too. */
+function parseNonNegativeInt(
+  envName: string,
+  fallback: number,
+  problems: string[],
+  max?: number
+): number {
+  return parseBoundedInt(envName, fallback, problems, 0, max);
+}
+
+function parseBoundedInt(
+  envName: string,
+  fallback: number,
+  problems: string[],
+  min: 0 | 1,
+  max?: number
+): number {
+  const raw = process.env[envName];
   if (raw === undefined || raw.trim() === '') {
     return fallback;
   }
-  const parsed = Number.parseInt(raw, 10);
-  if (!Number.isFinite(parsed) || parsed <= 0) {
+  const parsed = Number(raw.trim());
+  if (!Number.isInteger(parsed) || parsed < min) {
+    const kind = min === 0 ? 'non-negative' : 'positive';
+    problems.push(`${envName} must be a ${kind} integer (got "${raw}")`);
     return fallback;
   }
   if (max !== undefined && parsed > max) {
@@ -85,58 +340,287 @@ function parsePositiveInt(raw: string | undefined, fallback: number, max?: numbe
 }
 
 /**
- * Load configuration from environment variables and SSM. Validates required inputs.
- *
- * @throws Error if required configuration is missing.
+ * Accept an absolute http(s) URL, minus any trailing slash. Anything else is
+ * recorded in `problems`: falling back to the public API would quietly route
+ * prompts around the proxy the deployment asked for.
  */
-export async function loadConfig(): Promise<AppConfig> {
-  const missingSensitive: string[] = [];
-  if (!process.env.SLACK_BOT_TOKEN && !process.env.SLACK_BOT_TOKEN_PARAMETER_NAME) {
-    missingSensitive.push('SLACK_BOT_TOKEN or SLACK_BOT_TOKEN_PARAMETER_NAME');
+function parseBaseUrl(raw: string | undefined, problems: string[]): string | null {
+  const trimmed = raw?.trim();
+  if (!trimmed) {
+    return null;
   }
-  if (!process.env.SLACK_SIGNING_SECRET && !process.env.SLACK_SIGNING_SECRET_PARAMETER_NAME) {
-    missingSensitive.push('SLACK_SIGNING_SECRET or SLACK_SIGNING_SECRET_PARAMETER_NAME');
+  let protocol: string | null = null;
+  try {
+    protocol = new URL(trimmed).protocol;
+  } catch {
+    protocol = null;
   }
-  if (!process.env.ANTHROPIC_API_KEY && !process.env.ANTHROPIC_API_KEY_PARAMETER_NAME) {
-    missingSensitive.push('ANTHROPIC_API_KEY or ANTHROPIC_API_KEY_PARAMETER_NAME');
+  if (protocol !== 'https:' && protocol !== 'http:') {
+    problems.push(`ANTHROPIC_BASE_URL must be an absolute http(s) URL (got "${trimmed}")`);
+    return null;
   }
+  return trimmed.replace(/\/+$/, '');
+}
 
-  if (missingSensitive.length > 0) {
-    throw new Error(`Missing required environment variables: ${missingSensitive.join(', ')}`);
+/**
+ * Comma-separated section names, deduplicated case-insensitively. Blank or
+ * unset keeps the defaults. Names end up as `*bold*` headers and inside the
+ * prompt, so markup characters are refused.
+ */
+function parseSummarySections(raw: string | undefined, problems: string[]): string[] {
+  const names = (raw ?? '')
+    .split(',')
+    .map((name) => name.replace(/\s+/g, ' ').trim())
+    .filter((name) => name.length > 0);
+  if (names.length === 0) {
+    return [...DEFAULT_SUMMARY_SECTIONS];
+  }
+  const sections: string[] = [];
+  for (const name of names) {
+    if (/[*<>`|]/.test(name) || name.length > MAX_SUMMARY_SECTION_CHARS) {
+      problems.push(
+        `SUMMARY_SECTIONS names must be plain text up to ${MAX_SUMMARY_SECTION_CHARS} characters (got "${name}")`
+      );
+    } else if (name.toLowerCase() === FILES_SHARED_SECTION.toLowerCase()) {
+      problems.push(
+        `SUMMARY_SECTIONS can't list "${FILES_SHARED_SECTION}"; it's added when files were shared`
+      );
+    } else if (!sections.some((s) => s.toLowerCase() === name.toLowerCase())) {
+      sections.push(name);
+    }
+  }
+  return sections;
+}
+
+function parseSummaryFooter(raw: string | undefined): string | null {
+  const footer = raw?.trim() || DEFAULT_SUMMARY_FOOTER;
+  return /^(?:off|none)$/i.test(footer) ? null : footer;
+}
+
+/** One line of the system prompt: collapse whitespace and cap the length. */
+function parseAssistantName(raw: string | undefined): string {
+  const name = raw?.replace(/\s+/g, ' ').trim().slice(0, MAX_ASSISTANT_NAME_CHARS).trim();
+  return name || DEFAULT_ASSISTANT_NAME;
+}
+
+/** A temperature in [0, {@link MAX_TEMPERATURE}]; anything else is recorded in `problems`. */
+function parseTemperature(envName: string, problems: string[]): number | null {
+  const raw = process.env[envName];
+  if (raw === undefined || raw.trim() === '') {
+    return null;
   }
+  const parsed = Number(raw.trim());
+  if (!Number.isFinite(parsed) || parsed < 0 || parsed > MAX_TEMPERATURE) {
+    problems.push(`${envName} must be a number from 0 to ${MAX_TEMPERATURE} (got "${raw}")`);
+    return null;
+  }
+  return parsed;
+}
 
-  const [slackBotToken, slackSigningSecret, anthropicApiKey] = await Promise.all([
-    loadSensitiveValue('SLACK_BOT_TOKEN', 'SLACK_BOT_TOKEN_PARAMETER_NAME'),
-    loadSensitiveValue('SLACK_SIGNING_SECRET', 'SLACK_SIGNING_SECRET_PARAMETER_NAME'),
-    loadSensitiveValue('ANTHROPIC_API_KEY', 'ANTHROPIC_API_KEY_PARAMETER_NAME'),
-  ]);
+/**
+ * Load configuration from environment variables and SSM. Validates required inputs.
+ *
+ * @throws ConfigError listing every missing required variable and every
+ *   unparsable numeric variable (with the offending value).
+ */
+export async function loadConfig(): Promise<AppConfig> {
+  const problems: string[] = [];
+  for (const [envName, parameterEnvName] of [
+    ['SLACK_BOT_TOKEN', 'SLACK_BOT_TOKEN_PARAMETER_NAME'],
+    ['SLACK_SIGNING_SECRET', 'SLACK_SIGNING_SECRET_PARAMETER_NAME'],
+    ['ANTHROPIC_API_KEY', 'ANTHROPIC_API_KEY_PARAMETER_NAME'],
+  ]) {
+    if (!process.env[envName] && !process.env[parameterEnvName]) {
+      problems.push(`missing ${envName} or ${parameterEnvName}`);
+    }
+  }
 
   const streamMaxChunkChars = parsePositiveInt(
-    process.env.STREAM_MAX_CHUNK_CHARS,
+    'STREAM_MAX_CHUNK_CHARS',
     DEFAULT_STREAM_MAX_CHUNK_CHARS,
+    problems,
     STREAM_MARKDOWN_TEXT_LIMIT
   );
   const streamMinAppendIntervalMs = parsePositiveInt(
-    process.env.STREAM_MIN_APPEND_INTERVAL_MS,
-    DEFAULT_STREAM_MIN_APPEND_INTERVAL_MS
+    'STREAM_MIN_APPEND_INTERVAL_MS',
+    DEFAULT_STREAM_MIN_APPEND_INTERVAL_MS,
+    problems
+  );
+  const streamStallTimeoutMs = parsePositiveInt(
+    'STREAM_STALL_TIMEOUT_MS',
+    DEFAULT_STREAM_STALL_TIMEOUT_MS,
+    problems
+  );
+  const streamMaxBufferBytes = parsePositiveInt(
+    'STREAM_MAX_BUFFER_BYTES',
+    DEFAULT_STREAM_MAX_BUFFER_BYTES,
+    problems
   );
+  const staleStreamMaxAgeSecs = parseNonNegativeInt(
+    'STALE_STREAM_MAX_AGE_SECS',
+    DEFAULT_STALE_STREAM_MAX_AGE_SECS,
+    problems
+  );
+  const maxRequestBodyBytes = parsePositiveInt(
+    'MAX_REQUEST_BODY_BYTES',
+    DEFAULT_MAX_REQUEST_BODY_BYTES,
+    problems
+  );
+  const imageDownloadConcurrency = parsePositiveInt(
+    'IMAGE_DOWNLOAD_CONCURRENCY',
+    DEFAULT_IMAGE_DOWNLOAD_CONCURRENCY,
+    problems
+  );
+  const inlineImageMaxBytes = parsePositiveInt(
+    'INLINE_IMAGE_MAX_BYTES',
+    DEFAULT_INLINE_IMAGE_MAX_BYTES,
+    problems,
+    ANTHROPIC_IMAGE_MAX_BYTES
+  );
+  const permalinkConcurrency = parsePositiveInt(
+    'PERMALINK_CONCURRENCY',
+    DEFAULT_PERMALINK_CONCURRENCY,
+    problems
+  );
+  const maxConcurrentSummaries = parsePositiveInt(
+    'MAX_CONCURRENT_SUMMARIES',
+    DEFAULT_MAX_CONCURRENT_SUMMARIES,
+    problems
+  );
+  const hardMessageCap = parsePositiveInt('HARD_MESSAGE_CAP', DEFAULT_HARD_MESSAGE_CAP, problems);
+  const defaultTimezone = process.env.DEFAULT_TIMEZONE?.trim() || DEFAULT_TIMEZONE;
+  if (!isValidTimeZone(defaultTimezone)) {
+    problems.push(`DEFAULT_TIMEZONE must be an IANA time zone (got "${defaultTimezone}")`);
+  }
+  const maxCustomStyleChars = parsePositiveInt(
+    'MAX_CUSTOM_STYLE_CHARS',
+    DEFAULT_MAX_CUSTOM_STYLE_CHARS,
+    problems,
+    MAX_CUSTOM_STYLE_LENGTH
+  );
+  const taskDeadlineSecs = parsePositiveInt(
+    'TASK_DEADLINE_SECS',
+    DEFAULT_TASK_DEADLINE_SECS,
+    problems,
+    MAX_TASK_DEADLINE_SECS
+  );
+  const slackMaxRetries = parseNonNegativeInt(
+    'SLACK_MAX_RETRIES',
+    DEFAULT_SLACK_MAX_RETRIES,
+    problems,
+    MAX_SLACK_RETRIES
+  );
+  const slackRetryBaseDelayMs = parsePositiveInt(
+    'SLACK_RETRY_BASE_DELAY_MS',
+    DEFAULT_SLACK_RETRY_BASE_DELAY_MS,
+    problems
+  );
+  const slackRetryMaxDelayMs = parsePositiveInt(
+    'SLACK_RETRY_MAX_DELAY_MS',
+    DEFAULT_SLACK_RETRY_MAX_DELAY_MS,
+    problems
+  );
+  const deliveryMaxRetries = parseNonNegativeInt(
+    'DELIVERY_MAX_RETRIES',
+    DEFAULT_DELIVERY_MAX_RETRIES,
+    problems,
+    MAX_DELIVERY_MAX_RETRIES
+  );
+  const systemPromptOverride = process.env.SYSTEM_PROMPT_OVERRIDE?.trim() || null;
+  if (
+    systemPromptOverride !== null &&
+    systemPromptOverride.length < MIN_SYSTEM_PROMPT_OVERRIDE_CHARS
+  ) {
+    problems.push(
+      `SYSTEM_PROMPT_OVERRIDE must be at least ${MIN_SYSTEM_PROMPT_OVERRIDE_CHARS} characters (got ${systemPromptOverride.length})`
+    );
+  }
+  const rawReasoningEffort = process.env.ANTHROPIC_REASONING_EFFORT?.trim().toLowerCase() || null;
+  if (rawReasoningEffort !== null && !isReasoningEffort(rawReasoningEffort)) {
+    problems.push(
+      `ANTHROPIC_REASONING_EFFORT must be one of ${REASONING_EFFORTS.join(', ')} (got "${rawReasoningEffort}")`
+    );
+  }
+  const summarySections = parseSummarySections(process.env.SUMMARY_SECTIONS, problems);
   const anthropicMaxOutputTokens = parsePositiveInt(
-    process.env.ANTHROPIC_MAX_OUTPUT_TOKENS,
+    'ANTHROPIC_MAX_OUTPUT_TOKENS',
     DEFAULT_MAX_OUTPUT_TOKENS,
+    problems,
     64_000
   );
+  const anthropicBaseUrl = parseBaseUrl(process.env.ANTHROPIC_BASE_URL, problems);
+  const anthropicTemperature = parseTemperature('ANTHROPIC_TEMPERATURE', problems);
+  const anthropicStyleTemperature = parseTemperature('ANTHROPIC_STYLE_TEMPERATURE', problems);
+
+  if (problems.length > 0) {
+    throw new ConfigError(problems);
+  }
+
+  const [slackBotToken, slackSigningSecret, anthropicApiKey, slackUserToken, slackAppToken] =
+    await Promise.all([
+      loadSensitiveValue('SLACK_BOT_TOKEN', 'SLACK_BOT_TOKEN_PARAMETER_NAME'),
+      loadSensitiveValue('SLACK_SIGNING_SECRET', 'SLACK_SIGNING_SECRET_PARAMETER_NAME'),
+      loadSensitiveValue('ANTHROPIC_API_KEY', 'ANTHROPIC_API_KEY_PARAMETER_NAME'),
+      loadOptionalSensitiveValue('SLACK_USER_TOKEN', 'SLACK_USER_TOKEN_PARAMETER_NAME'),
+      loadOptionalSensitiveValue('SLACK_APP_TOKEN', 'SLACK_APP_TOKEN_PARAMETER_NAME'),
+    ]);
 
   return {
     slackBotToken,
     slackSigningSecret,
+    slackUserToken,
+    slackAppToken,
     anthropicApiKey,
     anthropicModel: process.env.ANTHROPIC_MODEL?.trim() || DEFAULT_MODEL,
     anthropicMaxOutputTokens,
+    anthropicBaseUrl,
+    anthropicTemperature,
+    anthropicStyleTemperature,
+    anthropicReasoningEffort:
+      rawReasoningEffort !== null && isReasoningEffort(rawReasoningEffort)
+        ? rawReasoningEffort
+        : null,
     enableStreaming: process.env.ENABLE_STREAMING === undefined
       ? true
       : parseBool(process.env.ENABLE_STREAMING),
     streamMaxChunkChars,
     streamMinAppendIntervalMs,
+    streamStallTimeoutMs,
+    streamMaxBufferBytes,
+    staleStreamMaxAgeSecs,
+    enableReceipts: process.env.ENABLE_RECEIPTS === undefined
+      ? true
+      : parseBool(process.env.ENABLE_RECEIPTS),
+    includeAttachmentText: process.env.INCLUDE_ATTACHMENT_TEXT === undefined
+      ? true
+      : parseBool(process.env.INCLUDE_ATTACHMENT_TEXT),
+    disableImages: parseBool(process.env.DISABLE_IMAGES),
+    citeInline: parseBool(process.env.CITE_INLINE),
+    summarySections,
+    detectSummaryLanguage: process.env.DETECT_SUMMARY_LANGUAGE === undefined
+      ? true
+      : parseBool(process.env.DETECT_SUMMARY_LANGUAGE),
+    postAsUser: parseBool(process.env.POST_AS_USER),
+    unfurlSummaryLinks: parseBool(process.env.UNFURL_SUMMARY_LINKS),
+    canvasToc: parseBool(process.env.CANVAS_TOC),
+    summaryFooter: parseSummaryFooter(process.env.SUMMARY_FOOTER),
+    maxRequestBodyBytes,
+    imageDownloadConcurrency,
+    inlineImageMaxBytes,
+    permalinkConcurrency,
+    maxConcurrentSummaries,
+    hardMessageCap,
+    ignoredUserIds: parseList(process.env.IGNORED_USER_IDS),
+    defaultTimezone,
+    maxCustomStyleChars,
+    noMessagesMessage: process.env.NO_MESSAGES_MESSAGE?.trim() || DEFAULT_NO_MESSAGES_MESSAGE,
+    systemPromptOverride,
+    assistantName: parseAssistantName(process.env.ASSISTANT_NAME),
+    taskDeadlineSecs,
+    slackMaxRetries,
+    slackRetryBaseDelayMs,
+    slackRetryMaxDelayMs,
+    deliveryMaxRetries,
   };
 }
 
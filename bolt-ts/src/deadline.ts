@@ -0,0 +1,92 @@
+/**
+ * Task-level deadline for one summarisation run.
+ *
+ * Sub-operations (Slack calls, image downloads, the Anthropic stream) each
+ * have their own timeouts and retries, so together they can outlast the
+ * Lambda. The handler starts a {@link Deadline} per invocation and binds it
+ * with {@link withDeadline}; the worker runs each step within it (see
+ * {@link runWithinDeadline}), checks it again before posting, and aborts with
+ * {@link DeadlineExceededError} while there is still time to clean up.
+ */
+
+import { AsyncLocalStorage } from 'async_hooks';
+
+/** Time kept back from the Lambda's own remaining time for failure cleanup. */
+export const CLEANUP_RESERVE_MS = 10_000;
+
+export class DeadlineExceededError extends Error {
+  constructor() {
+    super('Task deadline exceeded');
+    this.name = 'DeadlineExceededError';
+  }
+}
+
+export interface Deadline {
+  /** Epoch ms after which the run must stop. */
+  expiresAt: number;
+  remainingMs(): number;
+  /** Throw {@link DeadlineExceededError} if the deadline has passed. */
+  check(): void;
+}
+
+export function createDeadline(budgetMs: number, now: number = Date.now()): Deadline {
+  const expiresAt = now + Math.max(budgetMs, 0);
+  const remainingMs = (): number => Math.max(expiresAt - Date.now(), 0);
+  return {
+    expiresAt,
+    remainingMs,
+    check: (): void => {
+      if (remainingMs() <= 0) {
+        throw new DeadlineExceededError();
+      }
+    },
+  };
+}
+
+/**
+ * Budget for a run: `taskDeadlineMs`, shortened when the Lambda itself has
+ * less time left than that (minus {@link CLEANUP_RESERVE_MS}).
+ */
+export function taskBudgetMs(taskDeadlineMs: number, lambdaRemainingMs: number | null): number {
+  if (lambdaRemainingMs === null) {
+    return taskDeadlineMs;
+  }
+  return Math.min(taskDeadlineMs, lambdaRemainingMs - CLEANUP_RESERVE_MS);
+}
+
+/**
+ * Start `start()` and settle with it, or reject with
+ * {@link DeadlineExceededError} once `deadline` passes. Never starts the
+ * work when the deadline has already passed; without a deadline it just
+ * awaits `start()`.
+ */
+export async function runWithinDeadline<T>(
+  deadline: Deadline | undefined,
+  start: () => Promise<T>
+): Promise<T> {
+  if (!deadline) {
+    return start();
+  }
+  deadline.check();
+  let timer: ReturnType<typeof setTimeout> | undefined;
+  const expired = new Promise<never>((_, reject) => {
+    timer = setTimeout(() => reject(new DeadlineExceededError()), deadline.remainingMs());
+  });
+  try {
+    return await Promise.race([start(), expired]);
+  } finally {
+    clearTimeout(timer);
+  }
+}
+
+const deadlineStore = new AsyncLocalStorage<Deadline>();
+
+/** Run `fn` with `deadline` visible to {@link currentDeadline} in its call tree. */
+export function withDeadline<T>(deadline: Deadline, fn: () => Promise<T>): Promise<T> {
+  return deadlineStore.run(deadline, fn);
+}
+
+/** The deadline bound by the enclosing invocation, if any. */
+export function currentDeadline(): Deadline | undefined {
+  return deadlineStore.getStore();
+}
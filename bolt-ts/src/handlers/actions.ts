@@ -1,22 +1,35 @@
 /**
  * Action handlers for the interactive buttons that appear under a summary.
  *
- * Handlers ACK immediately, then either repost a message (Share) or kick off a
- * fresh summarisation inline (Roast, Receipts, message-count selector).
+ * Handlers ACK immediately, then either open a preview of the public post
+ * (Share, which posts once the preview modal is submitted) or kick off a
+ * fresh summarisation inline (Summarize again, Roast, Receipts, message-count
+ * selector). The overflow menu re-delivers the summary as already posted —
+ * "Repost to channel" opens the Share preview, "Save to Canvas" copies it
+ * into a canvas. The "Jump to channel" link button only needs its ACK.
  */
 
 import { App, BlockAction } from '@slack/bolt';
+import type { WebClient } from '@slack/web-api';
 import { v4 as uuidv4 } from 'uuid';
 import {
   checkSummarizeRateLimit,
   isUserMemberOfChannel,
   isValidSlackChannelId,
+  isValidSlackTimestamp,
   normalizeMessageCount,
   sanitizeGeneratedSlackText,
   type ConversationsMembersClient,
+  type SecurityLogger,
 } from '../security';
 import type { ThreadContext } from '../types';
-import { ACTION_SELECT_MESSAGE_COUNT, buildWelcomeBlocks } from '../blocks';
+import {
+  ACTION_SELECT_MESSAGE_COUNT,
+  MODAL_CALLBACK_SHARE_PREVIEW,
+  buildSharePreviewModal,
+  buildWelcomeBlocks,
+  type SharePreviewPrivateMetadata,
+} from '../blocks';
 import {
   buildThreadStateMetadata,
   findThreadStateMessage,
@@ -26,19 +39,85 @@ import {
   type SlackWebApiClient,
 } from '../thread_state';
 import type { AppConfig } from '../config';
+import {
+  MODAL_BUSY_MESSAGE,
+  ModalOpenError,
+  createCanvasForUser,
+  getSingleMessage,
+  openModal,
+} from '../slack/client';
+import { numberSummarySections } from '../slack/message_formatter';
+import { extractWorkspaceIds } from '../slack/workspace';
+import {
+  ACTION_SUMMARY_DESTINATION,
+  DESTINATION_CANVAS,
+  DESTINATION_REPOST,
+  findShareButtonValue,
+  type ShareButtonValue,
+} from '../worker/deliver';
 import { runSummarization } from '../worker/summarize';
 
-interface ShareButtonValue {
-  action: 'share_summary';
-  sourceChannelId: string;
+interface RerunButtonValue {
+  action: 'rerun_summary' | 'rerun_roast' | 'rerun_receipts';
+  channelId: string;
   count: number;
-  style: string | null;
+  style?: string | null;
 }
 
-interface RerunButtonValue {
-  action: 'rerun_roast' | 'rerun_receipts';
+export interface ParsedRerunValue {
   channelId: string;
   count: number;
+  style: string | null;
+}
+
+/**
+ * Parse a rerun button's JSON value. Returns `null` for malformed payloads or
+ * an invalid channel id; the count is clamped like every other entry point.
+ */
+export function parseRerunButtonValue(raw: string | undefined): ParsedRerunValue | null {
+  let value: Partial<RerunButtonValue>;
+  try {
+    value = JSON.parse(raw || '{}');
+  } catch {
+    return null;
+  }
+  if (!value || typeof value !== 'object' || !isValidSlackChannelId(value.channelId)) {
+    return null;
+  }
+  const style = typeof value.style === 'string' && value.style.trim() ? value.style : null;
+  return { channelId: value.channelId, count: normalizeMessageCount(value.count), style };
+}
+
+/**
+ * Parse the share preview modal's `private_metadata`. Returns `null` for
+ * malformed JSON or any invalid channel id / timestamp.
+ */
+export function parseSharePreviewMetadata(raw: string | undefined): SharePreviewPrivateMetadata | null {
+  let value: Partial<SharePreviewPrivateMetadata>;
+  try {
+    value = JSON.parse(raw || '{}');
+  } catch {
+    return null;
+  }
+  if (
+    !value ||
+    typeof value !== 'object' ||
+    !isValidSlackChannelId(value.sourceChannelId) ||
+    !isValidSlackChannelId(value.assistantChannelId) ||
+    !isValidSlackTimestamp(value.assistantThreadTs) ||
+    !isValidSlackTimestamp(value.summaryTs)
+  ) {
+    return null;
+  }
+  const style = typeof value.style === 'string' && value.style.trim() ? value.style : null;
+  return {
+    sourceChannelId: value.sourceChannelId,
+    count: normalizeMessageCount(value.count),
+    style,
+    assistantChannelId: value.assistantChannelId,
+    assistantThreadTs: value.assistantThreadTs,
+    summaryTs: value.summaryTs,
+  };
 }
 
 const ROAST_STYLE =
@@ -55,48 +134,98 @@ export function registerActionHandlers(app: App, config: AppConfig): void {
       }
       // eslint-disable-next-line @typescript-eslint/no-explicit-any
       const buttonValue: ShareButtonValue = JSON.parse((action as any).value || '{}');
-      const { sourceChannelId, count: rawCount, style } = buttonValue;
-      const count = normalizeMessageCount(rawCount);
-      if (!isValidSlackChannelId(sourceChannelId)) {
-        return;
+      await openSharePreview({
+        body,
+        client: client as unknown as WebClient,
+        logger,
+        share: buttonValue,
+      });
+    } catch (error) {
+      logger.error('Failed to handle share_summary action:', error);
+    }
+  });
+
+  app.action<BlockAction>(
+    ACTION_SUMMARY_DESTINATION,
+    async ({ ack, body, action, client, logger }) => {
+      await ack();
+      try {
+        if (!action || typeof action !== 'object' || !('type' in action) || action.type !== 'overflow') {
+          return;
+        }
+        const message = 'message' in body ? body.message : null;
+        const share = findShareButtonValue(message?.blocks);
+        if (!share) {
+          return;
+        }
+        const destination = action.selected_option?.value;
+        if (destination === DESTINATION_REPOST) {
+          // Same preview as Share; the summary is reposted, not regenerated.
+          await openSharePreview({ body, client: client as unknown as WebClient, logger, share });
+        } else if (destination === DESTINATION_CANVAS) {
+          const metadata = summaryLocation(body, share);
+          if (metadata) {
+            await saveSummaryToCanvas(client as unknown as WebClient, body.user.id, metadata, {
+              toc: config.canvasToc,
+            });
+          }
+        }
+      } catch (error) {
+        logger.error('Failed to handle summary destination menu:', error);
       }
-      const message = 'message' in body ? body.message : null;
-      const channel = 'channel' in body ? body.channel : null;
-      if (!message || !channel) {
+    }
+  );
+
+  app.view(MODAL_CALLBACK_SHARE_PREVIEW, async ({ ack, body, view, client, logger }) => {
+    await ack();
+    try {
+      const metadata = parseSharePreviewMetadata(view.private_metadata);
+      if (!metadata) {
+        logger.warn('Rejected share preview submission with invalid metadata');
         return;
       }
-      const assistantChannelId = channel.id;
-      const threadTs = message.thread_ts ?? message.ts;
-
+      // Membership may have changed while the preview was open.
       const canRead = await isUserMemberOfChannel({
         client: client as unknown as ConversationsMembersClient,
-        channelId: sourceChannelId,
+        channelId: metadata.sourceChannelId,
         userId: body.user.id,
         logger,
       });
       if (!canRead) {
         await client.chat.postMessage({
-          channel: assistantChannelId,
-          thread_ts: threadTs,
+          channel: metadata.assistantChannelId,
+          thread_ts: metadata.assistantThreadTs,
           text: "I can only share summaries for channels you're a member of.",
         });
         return;
       }
+      await postSharedSummary(client as unknown as WebClient, body.user.id, metadata);
+    } catch (error) {
+      logger.error('Failed to handle share preview submission:', error);
+    }
+  });
 
-      const summaryText = sanitizeGeneratedSlackText(message.text || '');
-      const attribution = buildShareAttribution(body.user.id, count, style);
-      await client.chat.postMessage({
-        channel: sourceChannelId,
-        text: `${attribution}\n\n${summaryText}`,
-      });
-      await client.chat.postMessage({
-        channel: assistantChannelId,
-        thread_ts: threadTs,
-        text: `✅ Shared to <#${sourceChannelId}>`,
+  // Cancel / ✕ on the preview. Nothing was posted and the preview's state
+  // lives only in the closed view's private_metadata, so ACK and note it.
+  app.view(
+    { callback_id: MODAL_CALLBACK_SHARE_PREVIEW, type: 'view_closed' },
+    async ({ ack, body, view, logger }) => {
+      await ack();
+      logger.debug('Share preview closed without posting', {
+        viewId: view.id,
+        userId: body.user.id,
       });
-    } catch (error) {
-      logger.error('Failed to handle share_summary action:', error);
     }
+  );
+
+  app.action<BlockAction>('rerun_summary', async (args) =>
+    handleRerun({ ...args, config, label: '🔄 Summarizing again...' })
+  );
+
+  // URL buttons still send a block_actions payload; ACK it so Slack doesn't
+  // flag the click as failed.
+  app.action<BlockAction>('jump_to_channel', async ({ ack }) => {
+    await ack();
   });
 
   app.action<BlockAction>('rerun_roast', async (args) =>
@@ -139,6 +268,7 @@ export function registerActionHandlers(app: App, config: AppConfig): void {
         let currentState: ThreadContext = {
           viewingChannelId: null,
           customStyle: null,
+          tone: null,
           defaultMessageCount: null,
         };
         const cached = getCachedThreadState(threadKey);
@@ -184,6 +314,157 @@ export function registerActionHandlers(app: App, config: AppConfig): void {
   );
 }
 
+/**
+ * Where the clicked summary lives and what it summarised, in the share
+ * preview's metadata shape. `null` when the payload lacks a message or the
+ * share value is invalid.
+ */
+function summaryLocation(
+  body: BlockAction,
+  share: Partial<ShareButtonValue>
+): SharePreviewPrivateMetadata | null {
+  const message = 'message' in body ? body.message : null;
+  const channel = 'channel' in body ? body.channel : null;
+  if (!message || !channel || !isValidSlackChannelId(share.sourceChannelId)) {
+    return null;
+  }
+  return {
+    sourceChannelId: share.sourceChannelId,
+    count: normalizeMessageCount(share.count),
+    style: share.style ?? null,
+    assistantChannelId: channel.id,
+    assistantThreadTs: message.thread_ts ?? message.ts,
+    summaryTs: message.ts,
+  };
+}
+
+/**
+ * Open the Share preview for the summary the user clicked on, after checking
+ * they can read its source channel. The post happens when the modal is
+ * submitted.
+ */
+async function openSharePreview(args: {
+  body: BlockAction;
+  client: WebClient;
+  logger: SecurityLogger;
+  share: Partial<ShareButtonValue>;
+}): Promise<void> {
+  const { body, client, logger } = args;
+  const metadata = summaryLocation(body, args.share);
+  const message = 'message' in body ? body.message : null;
+  if (!metadata || !message) {
+    return;
+  }
+
+  const canRead = await isUserMemberOfChannel({
+    client: client as unknown as ConversationsMembersClient,
+    channelId: metadata.sourceChannelId,
+    userId: body.user.id,
+    logger,
+  });
+  if (!canRead) {
+    await client.chat.postMessage({
+      channel: metadata.assistantChannelId,
+      thread_ts: metadata.assistantThreadTs,
+      text: "I can only share summaries for channels you're a member of.",
+    });
+    return;
+  }
+
+  const summaryText = sanitizeGeneratedSlackText(message.text || '');
+  try {
+    await openModal(client, {
+      triggerId: body.trigger_id,
+      view: buildSharePreviewModal(
+        buildShareAttribution(body.user.id, metadata.count, metadata.style),
+        summaryText,
+        metadata
+      ),
+    });
+  } catch (error) {
+    if (!(error instanceof ModalOpenError)) {
+      throw error;
+    }
+    logger.warn('Share preview not opened', { error: error.message });
+    await client.chat.postMessage({
+      channel: metadata.assistantChannelId,
+      thread_ts: metadata.assistantThreadTs,
+      text: MODAL_BUSY_MESSAGE,
+    });
+  }
+}
+
+/**
+ * Post the previewed summary to its source channel and confirm in the
+ * assistant thread. The summary is re-read from the thread so the post
+ * matches what was previewed, whatever its length.
+ */
+export async function postSharedSummary(
+  client: WebClient,
+  userId: string,
+  metadata: SharePreviewPrivateMetadata
+): Promise<void> {
+  const summary = await getSingleMessage(client, metadata.assistantChannelId, metadata.summaryTs);
+  if (!summary) {
+    await client.chat.postMessage({
+      channel: metadata.assistantChannelId,
+      thread_ts: metadata.assistantThreadTs,
+      text: "I couldn't find that summary anymore, so nothing was shared.",
+    });
+    return;
+  }
+  const summaryText = sanitizeGeneratedSlackText(summary.text);
+  const attribution = buildShareAttribution(userId, metadata.count, metadata.style);
+  await client.chat.postMessage({
+    channel: metadata.sourceChannelId,
+    text: `${attribution}\n\n${summaryText}`,
+  });
+  await client.chat.postMessage({
+    channel: metadata.assistantChannelId,
+    thread_ts: metadata.assistantThreadTs,
+    text: `✅ Shared to <#${metadata.sourceChannelId}>`,
+  });
+}
+
+/**
+ * Copy a summary into a new canvas owned by the requester and link it in the
+ * thread. Like a share, the text is re-read from the thread rather than
+ * regenerated. With `toc`, sections become numbered headings under a
+ * contents list (see `numberSummarySections`).
+ */
+export async function saveSummaryToCanvas(
+  client: WebClient,
+  userId: string,
+  metadata: SharePreviewPrivateMetadata,
+  options: { toc?: boolean } = {}
+): Promise<void> {
+  const summary = await getSingleMessage(client, metadata.assistantChannelId, metadata.summaryTs);
+  if (!summary) {
+    await client.chat.postMessage({
+      channel: metadata.assistantChannelId,
+      thread_ts: metadata.assistantThreadTs,
+      text: "I couldn't find that summary anymore, so no canvas was created.",
+    });
+    return;
+  }
+  const title = `TLDR: last ${metadata.count} messages`;
+  const sanitized = sanitizeGeneratedSlackText(summary.text);
+  const summaryText = options.toc ? numberSummarySections(sanitized, 'canvas') : sanitized;
+  const canvas = await createCanvasForUser(client, {
+    title,
+    // Canvas markdown renders `![](#C…)` as a channel mention.
+    markdown: `Summary of ![](#${metadata.sourceChannelId})\n\n${summaryText}`,
+    userId,
+  });
+  await client.chat.postMessage({
+    channel: metadata.assistantChannelId,
+    thread_ts: metadata.assistantThreadTs,
+    text: canvas.permalink
+      ? `📝 Saved to a canvas: <${canvas.permalink}|${title}>`
+      : `📝 Saved to a canvas: ${title}`,
+  });
+}
+
 function buildShareAttribution(userId: string, count: number, style: string | null): string {
   const lower = style?.toLowerCase() ?? '';
   if (lower.includes('roast')) {
@@ -198,7 +479,8 @@ function buildShareAttribution(userId: string, count: number, style: string | nu
 // eslint-disable-next-line @typescript-eslint/no-explicit-any
 type RerunArgs = any & {
   config: AppConfig;
-  style: string;
+  /** Fixed style for this rerun; omitted to reuse the style from the button value. */
+  style?: string;
   label: string;
 };
 
@@ -209,12 +491,11 @@ async function handleRerun(args: RerunArgs): Promise<void> {
     if (!action || typeof action !== 'object' || !('type' in action) || action.type !== 'button') {
       return;
     }
-    const buttonValue: RerunButtonValue = JSON.parse(action.value || '{}');
-    const { channelId, count: rawCount } = buttonValue;
-    const count = normalizeMessageCount(rawCount);
-    if (!isValidSlackChannelId(channelId)) {
+    const parsed = parseRerunButtonValue(action.value);
+    if (!parsed) {
       return;
     }
+    const { channelId, count } = parsed;
     const message = 'message' in body ? body.message : null;
     const channel = 'channel' in body ? body.channel : null;
     if (!message || !channel) {
@@ -263,7 +544,27 @@ async function handleRerun(args: RerunArgs): Promise<void> {
         originChannelId: assistantChannelId,
         threadTs,
         messageCount: count,
-        customStyle: style,
+        customStyle: style ?? parsed.style,
+        tone: null,
+        sourceThreadTs: null,
+        groupByThread: false,
+        includeBots: false,
+        filesOnly: false,
+        template: null,
+        notifyMe: false,
+        keywordFilter: null,
+        pinnedOnly: false,
+        canvasOnly: false,
+        ...extractWorkspaceIds(body),
+        postChannelId: null,
+        replaceTs: null,
+        timezone: null,
+        beforeTs: null,
+        afterTs: null,
+        sinceLastSummary: false,
+        skipImages: false,
+        bookmark: false,
+        allowMentions: false,
       },
     });
   } catch (error) {
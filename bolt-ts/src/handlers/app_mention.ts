@@ -0,0 +1,151 @@
+/**
+ * Channel @-mentions: "@TLDR summarize last 50".
+ *
+ * Slack sends `app_mention` when someone mentions the bot in a channel or
+ * group DM it's in. We strip the leading bot mention, run the rest through the same intent
+ * parser as the assistant thread, and reply in the mention's thread. Only
+ * summarize intents are acted on; style and refresh need assistant-thread
+ * state, so anything else is ignored.
+ */
+
+import { App } from '@slack/bolt';
+import { v4 as uuidv4 } from 'uuid';
+import type { AppConfig } from '../config';
+import { invalidBoundMessage, parseUserIntent } from '../intent';
+import {
+  checkSummarizeRateLimit,
+  claimMessageOnce,
+  isUserMemberOfChannel,
+  isValidSlackChannelId,
+  normalizeMessageCount,
+  validateAndSanitizeStyle,
+  type ConversationsMembersClient,
+} from '../security';
+import type { UserIntent } from '../types';
+import { extractWorkspaceIds } from '../slack/workspace';
+import { runSummarization } from '../worker/summarize';
+import { isValidTimeZone, unknownTimeZoneMessage } from '../time';
+
+type SummarizeIntent = Extract<UserIntent, { type: 'summarize' }>;
+
+/** Drop the `<@U…>` mention(s) Slack prefixes onto `app_mention` text. */
+export function stripBotMention(text: string): string {
+  return text.replace(/^(?:\s*<@[A-Z0-9]+(?:\|[^>]*)?>)+/, '').trim();
+}
+
+/** The summarize intent in a mention's text, or `null` when there isn't one. */
+export function parseMentionIntent(text: string): SummarizeIntent | null {
+  const intent = parseUserIntent(stripBotMention(text));
+  return intent.type === 'summarize' ? intent : null;
+}
+
+export function registerAppMentionHandlers(app: App, config: AppConfig): void {
+  // Chat streaming outside an assistant thread needs recipient ids we don't
+  // track, so mention replies are posted in one message.
+  const mentionConfig: AppConfig = { ...config, enableStreaming: false };
+
+  app.event('app_mention', async ({ event, body, client, logger }) => {
+    const userId = event.user;
+    if (!userId || event.bot_id) {
+      return;
+    }
+    const channelId = event.channel;
+    const threadTs = event.thread_ts ?? event.ts;
+
+    const intent = parseMentionIntent(event.text);
+    if (!intent) {
+      return;
+    }
+    if (!claimMessageOnce(`${channelId}:${event.ts}`)) {
+      logger.info(`Skipping duplicate delivery of ${channelId}:${event.ts}`);
+      return;
+    }
+
+    const reply = async (text: string): Promise<void> => {
+      await client.chat.postMessage({ channel: channelId, thread_ts: threadTs, text });
+    };
+
+    const targetChannelId = intent.targetChannel ?? channelId;
+    if (!isValidSlackChannelId(targetChannelId)) {
+      await reply("I can't summarize that channel identifier.");
+      return;
+    }
+    if (!checkSummarizeRateLimit(userId)) {
+      await reply('Please wait a minute before starting more summaries.');
+      return;
+    }
+    // The reply is visible to everyone here, so another channel's history
+    // needs the same membership check as "post to".
+    const userCanReadChannel = await isUserMemberOfChannel({
+      client: client as unknown as ConversationsMembersClient,
+      channelId: targetChannelId,
+      userId,
+      logger,
+    });
+    if (!userCanReadChannel) {
+      await reply("I can only summarize channels you're a member of.");
+      return;
+    }
+
+    const sanitizedStyle = validateAndSanitizeStyle(
+      intent.styleOverride,
+      config.maxCustomStyleChars
+    );
+    if (!sanitizedStyle.ok) {
+      await reply(sanitizedStyle.reason);
+      return;
+    }
+    if (intent.timezone !== null && !isValidTimeZone(intent.timezone)) {
+      await reply(unknownTimeZoneMessage(intent.timezone));
+      return;
+    }
+    if (intent.invalidBefore !== null) {
+      await reply(invalidBoundMessage('before', intent.invalidBefore));
+      return;
+    }
+    if (intent.invalidAfter !== null) {
+      await reply(invalidBoundMessage('after', intent.invalidAfter));
+      return;
+    }
+
+    const correlationId = uuidv4();
+    try {
+      await runSummarization({
+        config: mentionConfig,
+        client,
+        request: {
+          correlationId,
+          userId,
+          channelId: targetChannelId,
+          originChannelId: channelId,
+          threadTs,
+          messageCount: normalizeMessageCount(intent.count),
+          customStyle: sanitizedStyle.value,
+          tone: null,
+          sourceThreadTs: intent.threadTs,
+          groupByThread: intent.groupByThread,
+          includeBots: intent.includeBots,
+          filesOnly: intent.filesOnly,
+          template: intent.template,
+          notifyMe: false,
+          keywordFilter: intent.keywordFilter,
+          pinnedOnly: intent.pinnedOnly,
+          canvasOnly: intent.canvasOnly,
+          ...extractWorkspaceIds(body),
+          postChannelId: null,
+          replaceTs: null,
+          timezone: intent.timezone,
+          beforeTs: intent.beforeTs,
+          afterTs: intent.afterTs,
+          sinceLastSummary: intent.sinceLastSummary,
+          skipImages: intent.skipImages,
+          bookmark: false,
+          allowMentions: intent.allowMentions,
+        },
+      });
+      logger.info(`Completed mention summarize (corr_id=${correlationId})`);
+    } catch (error) {
+      logger.error('Mention summarization failed:', error);
+    }
+  });
+}
@@ -17,10 +17,11 @@ import {
   buildStyleConfirmationBlocks,
   buildWelcomeBlocks,
 } from '../blocks';
-import { parseUserIntent } from '../intent';
+import { invalidBoundMessage, parseUserIntent } from '../intent';
 import { buildSummarizeLoadingMessages } from '../loading_messages';
 import {
   checkSummarizeRateLimit,
+  claimMessageOnce,
   isUserMemberOfChannel,
   isValidSlackChannelId,
   normalizeMessageCount,
@@ -37,7 +38,11 @@ import {
   type SlackWebApiClient,
 } from '../thread_state';
 import type { AppConfig } from '../config';
+import { extractWorkspaceIds } from '../slack/workspace';
+import { findRefreshTarget } from '../worker/deliver';
 import { runSummarization } from '../worker/summarize';
+import { runMentionsDigest } from '../worker/mentions';
+import { isValidTimeZone, unknownTimeZoneMessage } from '../time';
 
 const WELCOME_TEXT = 'Welcome to TLDR';
 const CANONICAL_FAILURE_MESSAGE =
@@ -87,6 +92,7 @@ export function createAssistant(config: AppConfig): Assistant {
       const initialState: ThreadContext = {
         viewingChannelId: assistantThread.context?.channel_id ?? null,
         customStyle: null,
+        tone: null,
         defaultMessageCount: null,
       };
 
@@ -148,6 +154,7 @@ export function createAssistant(config: AppConfig): Assistant {
       const nextState: ThreadContext = {
         viewingChannelId,
         customStyle: cached?.state.customStyle ?? null,
+        tone: cached?.state.tone ?? null,
         defaultMessageCount: cached?.state.defaultMessageCount ?? null,
       };
 
@@ -195,7 +202,7 @@ export function createAssistant(config: AppConfig): Assistant {
         .catch((err) => logger.error('Failed to persist thread context:', err));
     },
 
-    userMessage: async ({ client, message, logger, setStatus }): Promise<void> => {
+    userMessage: async ({ body, client, message, logger, setStatus }): Promise<void> => {
       // eslint-disable-next-line @typescript-eslint/no-explicit-any
       const msg = message as any;
 
@@ -212,6 +219,13 @@ export function createAssistant(config: AppConfig): Assistant {
       if (!channelId || !userId || !threadTs) {
         return;
       }
+      const workspace = extractWorkspaceIds(body);
+
+      // Slack redelivers events it thinks we missed; don't summarize twice.
+      if (!claimMessageOnce(`${channelId}:${msg.ts as string}`)) {
+        logger.info(`Skipping duplicate delivery of ${channelId}:${msg.ts as string}`);
+        return;
+      }
 
       const intent = parseUserIntent(text);
       const threadKey = makeThreadKey(channelId, threadTs);
@@ -225,7 +239,12 @@ export function createAssistant(config: AppConfig): Assistant {
           return { state: cached.state, stateMessageTs: cached.state_message_ts };
         }
         return {
-          state: { viewingChannelId: null, customStyle: null, defaultMessageCount: null },
+          state: {
+            viewingChannelId: null,
+            customStyle: null,
+            tone: null,
+            defaultMessageCount: null,
+          },
           stateMessageTs: null,
         };
       };
@@ -246,7 +265,7 @@ export function createAssistant(config: AppConfig): Assistant {
           case 'clear_style': {
             const sanitizedStyle =
               intent.type === 'style'
-                ? validateAndSanitizeStyle(intent.instructions)
+                ? validateAndSanitizeStyle(intent.instructions, config.maxCustomStyleChars)
                 : { ok: true as const, value: null };
             if (!sanitizedStyle.ok) {
               await client.chat.postMessage({
@@ -277,6 +296,7 @@ export function createAssistant(config: AppConfig): Assistant {
             const nextState: ThreadContext = {
               viewingChannelId: state.viewingChannelId,
               customStyle: sanitizedStyle.value,
+              tone: state.tone,
               defaultMessageCount: state.defaultMessageCount,
             };
 
@@ -346,8 +366,33 @@ export function createAssistant(config: AppConfig): Assistant {
               return;
             }
 
-            const effectiveStyleRaw = intent.styleOverride ?? state.customStyle;
-            const sanitizedStyle = validateAndSanitizeStyle(effectiveStyleRaw);
+            // "post to <#C>" publishes elsewhere; "post here" publishes into the source channel.
+            const postChannelId = intent.postChannel ?? (intent.postHere ? targetChannelId : null);
+            if (postChannelId && postChannelId !== targetChannelId) {
+              const userCanPost =
+                isValidSlackChannelId(postChannelId) &&
+                (await isUserMemberOfChannel({
+                  client: client as unknown as ConversationsMembersClient,
+                  channelId: postChannelId,
+                  userId,
+                  logger,
+                }));
+              if (!userCanPost) {
+                await client.chat.postMessage({
+                  channel: channelId,
+                  thread_ts: threadTs,
+                  text: "I can only post summaries to channels you're a member of.",
+                });
+                return;
+              }
+            }
+
+            // Only a newly typed override is held to the configured limit; the
+            // saved thread style was already checked when it was set.
+            const sanitizedStyle =
+              intent.styleOverride !== null
+                ? validateAndSanitizeStyle(intent.styleOverride, config.maxCustomStyleChars)
+                : validateAndSanitizeStyle(state.customStyle);
             if (!sanitizedStyle.ok) {
               await client.chat.postMessage({
                 channel: channelId,
@@ -356,6 +401,30 @@ export function createAssistant(config: AppConfig): Assistant {
               });
               return;
             }
+            if (intent.timezone !== null && !isValidTimeZone(intent.timezone)) {
+              await client.chat.postMessage({
+                channel: channelId,
+                thread_ts: threadTs,
+                text: unknownTimeZoneMessage(intent.timezone),
+              });
+              return;
+            }
+            if (intent.invalidBefore !== null) {
+              await client.chat.postMessage({
+                channel: channelId,
+                thread_ts: threadTs,
+                text: invalidBoundMessage('before', intent.invalidBefore),
+              });
+              return;
+            }
+            if (intent.invalidAfter !== null) {
+              await client.chat.postMessage({
+                channel: channelId,
+                thread_ts: threadTs,
+                text: invalidBoundMessage('after', intent.invalidAfter),
+              });
+              return;
+            }
             const effectiveStyle = sanitizedStyle.value;
             const effectiveCount = normalizeMessageCount(
               intent.count,
@@ -383,6 +452,26 @@ export function createAssistant(config: AppConfig): Assistant {
                   threadTs,
                   messageCount: effectiveCount,
                   customStyle: effectiveStyle,
+                  tone: state.tone,
+                  sourceThreadTs: intent.threadTs,
+                  groupByThread: intent.groupByThread,
+                  includeBots: intent.includeBots,
+                  filesOnly: intent.filesOnly,
+                  template: intent.template,
+                  notifyMe: intent.notifyMe,
+                  keywordFilter: intent.keywordFilter,
+                  pinnedOnly: intent.pinnedOnly,
+                  canvasOnly: intent.canvasOnly,
+                  ...workspace,
+                  postChannelId,
+                  replaceTs: null,
+                  timezone: intent.timezone,
+                  beforeTs: intent.beforeTs,
+                  afterTs: intent.afterTs,
+                  sinceLastSummary: intent.sinceLastSummary,
+                  skipImages: intent.skipImages,
+                  bookmark: intent.bookmark,
+                  allowMentions: intent.allowMentions,
                 },
               });
               logger.info(`Completed summarize (corr_id=${correlationId})`);
@@ -401,6 +490,122 @@ export function createAssistant(config: AppConfig): Assistant {
             break;
           }
 
+          case 'refresh': {
+            const refreshTarget = await findRefreshTarget(client, channelId, threadTs);
+            if (!refreshTarget) {
+              await client.chat.postMessage({
+                channel: channelId,
+                thread_ts: threadTs,
+                text: "I couldn't find an earlier summary in this thread to refresh. Try `summarize` instead.",
+              });
+              return;
+            }
+
+            if (!checkSummarizeRateLimit(userId)) {
+              await client.chat.postMessage({
+                channel: channelId,
+                thread_ts: threadTs,
+                text: 'Please wait a minute before starting more summaries.',
+              });
+              return;
+            }
+
+            const sourceChannelId = refreshTarget.sourceChannelId;
+            const userCanRead =
+              isValidSlackChannelId(sourceChannelId) &&
+              (await isUserMemberOfChannel({
+                client: client as unknown as ConversationsMembersClient,
+                channelId: sourceChannelId,
+                userId,
+                logger,
+              }));
+            if (!userCanRead) {
+              await client.chat.postMessage({
+                channel: channelId,
+                thread_ts: threadTs,
+                text: "I can only summarize channels you're a member of.",
+              });
+              return;
+            }
+
+            const refreshStyle = validateAndSanitizeStyle(refreshTarget.style);
+            const refreshCount = normalizeMessageCount(intent.count ?? refreshTarget.messageCount);
+
+            await setStatus({ status: 'Refreshing summary...' });
+
+            const correlationId = uuidv4();
+            await runSummarization({
+              config,
+              client,
+              request: {
+                correlationId,
+                userId,
+                channelId: sourceChannelId,
+                originChannelId: channelId,
+                threadTs,
+                messageCount: refreshCount,
+                customStyle: refreshStyle.ok ? refreshStyle.value : null,
+                tone: null,
+                sourceThreadTs: null,
+                groupByThread: false,
+                includeBots: false,
+                filesOnly: false,
+                template: null,
+                notifyMe: false,
+                keywordFilter: null,
+                pinnedOnly: false,
+                canvasOnly: false,
+                ...workspace,
+                postChannelId: null,
+                replaceTs: refreshTarget.ts,
+                timezone: null,
+                beforeTs: null,
+                afterTs: null,
+                sinceLastSummary: false,
+                skipImages: false,
+                bookmark: false,
+                allowMentions: false,
+              },
+            });
+            logger.info(`Completed refresh (corr_id=${correlationId})`);
+            break;
+          }
+
+          case 'mentions': {
+            const { state } = getCachedOrEmpty();
+            if (!checkSummarizeRateLimit(userId)) {
+              await client.chat.postMessage({
+                channel: channelId,
+                thread_ts: threadTs,
+                text: 'Please wait a minute before starting more summaries.',
+              });
+              return;
+            }
+
+            await setStatus({ status: 'Searching your mentions...' });
+
+            const correlationId = uuidv4();
+            await runMentionsDigest({
+              config,
+              client,
+              request: {
+                correlationId,
+                userId,
+                originChannelId: channelId,
+                threadTs,
+                messageCount: normalizeMessageCount(
+                  intent.count,
+                  normalizeMessageCount(state.defaultMessageCount)
+                ),
+                customStyle: state.customStyle,
+                ...workspace,
+                allowMentions: intent.allowMentions,
+              },
+            });
+            logger.info(`Completed mentions digest (corr_id=${correlationId})`);
+            break;
+          }
+
           case 'unknown':
           default:
             break;
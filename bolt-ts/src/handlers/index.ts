@@ -12,3 +12,6 @@
 export { registerAssistantHandlers } from './assistant';
 export { registerStyleHandlers } from './style';
 export { registerActionHandlers } from './actions';
+export { registerWorkflowHandlers } from './workflow';
+export { registerAppMentionHandlers } from './app_mention';
+export { registerShortcutHandlers } from './shortcuts';
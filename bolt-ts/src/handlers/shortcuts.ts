@@ -0,0 +1,142 @@
+/**
+ * Message shortcut: "Summarize Thread".
+ *
+ * Slack sends a `message_action` payload when someone picks the shortcut on
+ * a message. We summarise that message's thread and reply in the same
+ * thread, so the summary lands next to the conversation it covers.
+ */
+
+import { App } from '@slack/bolt';
+import { v4 as uuidv4 } from 'uuid';
+import type { AppConfig } from '../config';
+import {
+  checkSummarizeRateLimit,
+  isUserMemberOfChannel,
+  isValidSlackChannelId,
+  isValidSlackTimestamp,
+  normalizeMessageCount,
+  type ConversationsMembersClient,
+} from '../security';
+import { extractWorkspaceIds } from '../slack/workspace';
+import { runSummarization } from '../worker/summarize';
+
+/** `callback_id` of the message shortcut in the app manifest. */
+export const SHORTCUT_SUMMARIZE_THREAD = 'summarize_thread';
+
+export interface ThreadShortcutTarget {
+  userId: string;
+  channelId: string;
+  /** The message the shortcut was invoked on. */
+  messageTs: string;
+  /** Root of its thread; equal to `messageTs` for a top-level message. */
+  threadTs: string;
+}
+
+/**
+ * Pull the selected message out of a `message_action` payload. Returns `null`
+ * when the channel or timestamps are missing or malformed.
+ */
+export function parseThreadShortcut(body: unknown): ThreadShortcutTarget | null {
+  if (!body || typeof body !== 'object') {
+    return null;
+  }
+  const payload = body as {
+    user?: { id?: unknown };
+    channel?: { id?: unknown };
+    message_ts?: unknown;
+    message?: { ts?: unknown; thread_ts?: unknown };
+  };
+  const userId = typeof payload.user?.id === 'string' ? payload.user.id : null;
+  const channelId = typeof payload.channel?.id === 'string' ? payload.channel.id : null;
+  const rawTs = payload.message_ts ?? payload.message?.ts;
+  const messageTs = typeof rawTs === 'string' ? rawTs : null;
+  const rawThreadTs = payload.message?.thread_ts;
+  const threadTs = typeof rawThreadTs === 'string' ? rawThreadTs : messageTs;
+  if (
+    !userId ||
+    !isValidSlackChannelId(channelId) ||
+    !isValidSlackTimestamp(messageTs) ||
+    !isValidSlackTimestamp(threadTs)
+  ) {
+    return null;
+  }
+  return { userId, channelId, messageTs, threadTs };
+}
+
+export function registerShortcutHandlers(app: App, config: AppConfig): void {
+  // Chat streaming outside an assistant thread needs recipient ids we don't
+  // track, so thread replies are posted in one message.
+  const threadReplyConfig: AppConfig = { ...config, enableStreaming: false };
+
+  app.shortcut(SHORTCUT_SUMMARIZE_THREAD, async ({ ack, body, client, logger }) => {
+    await ack();
+    const target = parseThreadShortcut(body);
+    if (!target) {
+      logger.warn('Ignoring Summarize Thread shortcut without a usable message');
+      return;
+    }
+    const tellUser = async (text: string): Promise<void> => {
+      await client.chat.postEphemeral({
+        channel: target.channelId,
+        user: target.userId,
+        thread_ts: target.threadTs,
+        text,
+      });
+    };
+
+    if (!checkSummarizeRateLimit(target.userId)) {
+      await tellUser('Please wait a minute before starting more summaries.');
+      return;
+    }
+    const canRead = await isUserMemberOfChannel({
+      client: client as unknown as ConversationsMembersClient,
+      channelId: target.channelId,
+      userId: target.userId,
+      logger,
+    });
+    if (!canRead) {
+      await tellUser("I can only summarize channels you're a member of.");
+      return;
+    }
+
+    const correlationId = uuidv4();
+    try {
+      await runSummarization({
+        config: threadReplyConfig,
+        client,
+        request: {
+          correlationId,
+          userId: target.userId,
+          channelId: target.channelId,
+          originChannelId: target.channelId,
+          threadTs: target.threadTs,
+          messageCount: normalizeMessageCount(null),
+          customStyle: null,
+          tone: null,
+          sourceThreadTs: target.threadTs,
+          groupByThread: false,
+          includeBots: false,
+          filesOnly: false,
+          template: null,
+          notifyMe: false,
+          keywordFilter: null,
+          pinnedOnly: false,
+          canvasOnly: false,
+          ...extractWorkspaceIds(body),
+          postChannelId: null,
+          replaceTs: null,
+          timezone: null,
+          beforeTs: null,
+          afterTs: null,
+          sinceLastSummary: false,
+          skipImages: false,
+          bookmark: false,
+          allowMentions: false,
+        },
+      });
+      logger.info(`Completed thread shortcut summarize (corr_id=${correlationId})`);
+    } catch (error) {
+      logger.error('Thread shortcut summarization failed:', error);
+    }
+  });
+}
@@ -7,11 +7,14 @@
  */
 
 import { App, BlockAction } from '@slack/bolt';
+import type { WebClient } from '@slack/web-api';
 import {
   ACTION_OPEN_STYLE_MODAL,
   MODAL_CALLBACK_SET_STYLE,
   INPUT_BLOCK_STYLE,
   INPUT_ACTION_STYLE,
+  INPUT_BLOCK_TONE,
+  INPUT_ACTION_TONE,
   buildStyleModal,
   buildStyleConfirmationBlocks,
   buildWelcomeBlocks,
@@ -25,7 +28,10 @@ import {
   setCachedThreadState,
   type SlackWebApiClient,
 } from '../thread_state';
-import type { ThreadContext } from '../types';
+import { isSummaryTone } from '../ai/prompt';
+import { MODAL_BUSY_MESSAGE, ModalOpenError, openModal } from '../slack/client';
+import type { AppConfig } from '../config';
+import type { SummaryTone, ThreadContext } from '../types';
 import {
   isUserMemberOfChannel,
   isValidSlackChannelId,
@@ -36,12 +42,96 @@ import {
 
 const WELCOME_TEXT = 'Welcome to TLDR';
 
+/** `view_submission` ack body that shows a field-level error under the style input. */
+export interface StyleInputErrorResponse {
+  response_action: 'errors';
+  errors: Record<string, string>;
+}
+
+/** `view.state.values` as submitted: block id -> action id -> input state. */
+export type ViewStateValues = Record<
+  string,
+  | Record<
+      string,
+      { value?: string | null; selected_option?: { value: string } | null } | undefined
+    >
+  | undefined
+>;
+
+/** An input a submitted view must contain; a stale or hand-built view may lack it. */
+export interface ExpectedViewField {
+  blockId: string;
+  actionId: string;
+}
+
+export const STALE_VIEW_MESSAGE = 'This form is out of date. Close it and open it again.';
+
+export const UNKNOWN_TONE_MESSAGE = 'Pick one of the listed tones.';
+
+const STYLE_MODAL_FIELDS: ExpectedViewField[] = [
+  { blockId: INPUT_BLOCK_STYLE, actionId: INPUT_ACTION_STYLE },
+];
+
+/**
+ * Field errors for every expected input missing from a submission, keyed by
+ * block id so Slack shows them all at once. Empty when nothing is missing.
+ */
+export function findMissingViewFields(
+  values: ViewStateValues,
+  fields: ExpectedViewField[]
+): Record<string, string> {
+  const errors: Record<string, string> = {};
+  for (const field of fields) {
+    if (!values[field.blockId]?.[field.actionId]) {
+      errors[field.blockId] = STALE_VIEW_MESSAGE;
+    }
+  }
+  return errors;
+}
+
+/**
+ * The tone picked in a style modal submission, or `null` for none (or a
+ * value that isn't one of the presets; {@link validateStyleSubmission} rejects those).
+ */
+export function readToneSelection(values: ViewStateValues): SummaryTone | null {
+  const selected = values[INPUT_BLOCK_TONE]?.[INPUT_ACTION_TONE]?.selected_option?.value;
+  return selected && isSummaryTone(selected) ? selected : null;
+}
+
+/**
+ * Every field error for a style modal submission — a missing input,
+ * instructions that are too long or disallowed, or a tone outside the
+ * presets — or `null` when it's valid.
+ * Checked before the ack so Slack keeps the modal open with the errors shown.
+ */
+export function validateStyleSubmission(
+  values: ViewStateValues,
+  maxChars: number
+): StyleInputErrorResponse | null {
+  const errors = findMissingViewFields(values, STYLE_MODAL_FIELDS);
+  if (!errors[INPUT_BLOCK_STYLE]) {
+    const validation = validateAndSanitizeStyle(
+      values[INPUT_BLOCK_STYLE]?.[INPUT_ACTION_STYLE]?.value ?? null,
+      maxChars
+    );
+    if (!validation.ok) {
+      errors[INPUT_BLOCK_STYLE] = validation.reason;
+    }
+  }
+  const tone = values[INPUT_BLOCK_TONE]?.[INPUT_ACTION_TONE]?.selected_option?.value;
+  if (tone && !isSummaryTone(tone)) {
+    errors[INPUT_BLOCK_TONE] = UNKNOWN_TONE_MESSAGE;
+  }
+  return Object.keys(errors).length === 0 ? null : { response_action: 'errors', errors };
+}
+
 /**
  * Register style-related action and view handlers.
  *
  * @param app - The Bolt app instance
+ * @param config - App config (custom style length limit)
  */
-export function registerStyleHandlers(app: App): void {
+export function registerStyleHandlers(app: App, config: AppConfig): void {
   // Handle "Set style" button click - opens the style modal
   app.action<BlockAction>(ACTION_OPEN_STYLE_MODAL, async ({ ack, body, client, logger }) => {
     // Acknowledge immediately (Slack requires response within 3 seconds)
@@ -90,6 +180,7 @@ export function registerStyleHandlers(app: App): void {
     }
 
     const currentStyle = cached?.state.customStyle ?? null;
+    const currentTone = cached?.state.tone ?? null;
 
     const privateMetadata: StyleModalPrivateMetadata = {
       assistantChannelId: channelId,
@@ -97,19 +188,37 @@ export function registerStyleHandlers(app: App): void {
     };
 
     try {
-      await client.views.open({
-        trigger_id: triggerId,
-        view: buildStyleModal(currentStyle, privateMetadata),
+      await openModal(client as unknown as WebClient, {
+        triggerId,
+        view: buildStyleModal(
+          currentStyle,
+          privateMetadata,
+          config.maxCustomStyleChars,
+          currentTone
+        ),
       });
     } catch (error) {
       logger.error('Failed to open style modal:', error);
+      if (error instanceof ModalOpenError) {
+        await client.chat
+          .postMessage({ channel: channelId, thread_ts: threadTs, text: MODAL_BUSY_MESSAGE })
+          .catch((postError) => logger.warn('Failed to post modal busy notice:', postError));
+      }
     }
   });
 
   // Handle style modal submission
   app.view(MODAL_CALLBACK_SET_STYLE, async ({ ack, body, view, client, logger }) => {
-    // Acknowledge immediately
+    // Invalid or stale submissions are rejected in the ack itself so the modal
+    // stays open with errors under the fields; everything else acks immediately.
+    const values = view.state.values as ViewStateValues;
+    const submissionErrors = validateStyleSubmission(values, config.maxCustomStyleChars);
+    if (submissionErrors) {
+      await ack(submissionErrors);
+      return;
+    }
     await ack();
+    const styleInput = values[INPUT_BLOCK_STYLE]?.[INPUT_ACTION_STYLE];
 
     // Parse private metadata to get thread context
     let privateMetadata: StyleModalPrivateMetadata;
@@ -148,22 +257,16 @@ export function registerStyleHandlers(app: App): void {
       return;
     }
 
-    // Extract the style value from the submission
-    const styleInput = view.state.values[INPUT_BLOCK_STYLE]?.[INPUT_ACTION_STYLE];
-    const styleValidation = validateAndSanitizeStyle(styleInput?.value ?? null);
+    const styleValidation = validateAndSanitizeStyle(
+      styleInput?.value ?? null,
+      config.maxCustomStyleChars
+    );
     if (!styleValidation.ok) {
-      try {
-        await client.chat.postMessage({
-          channel: assistantChannelId,
-          thread_ts: assistantThreadTs,
-          text: styleValidation.reason,
-        });
-      } catch (error) {
-        logger.error('Failed to post style validation error:', error);
-      }
+      // Already rejected in the ack above; kept so `value` is narrowed.
       return;
     }
     const newStyle = styleValidation.value;
+    const newTone = readToneSelection(values);
 
     const threadKey = makeThreadKey(assistantChannelId, assistantThreadTs);
 
@@ -185,6 +288,7 @@ export function registerStyleHandlers(app: App): void {
     const nextState: ThreadContext = {
       viewingChannelId: cached?.state.viewingChannelId ?? null,
       customStyle: newStyle,
+      tone: newTone,
       defaultMessageCount: cached?.state.defaultMessageCount ?? null,
     };
 
@@ -234,13 +338,13 @@ export function registerStyleHandlers(app: App): void {
       await client.chat.postMessage({
         channel: assistantChannelId,
         thread_ts: assistantThreadTs,
-        text: newStyle ? 'Style saved for this thread.' : 'Style cleared.',
-        blocks: buildStyleConfirmationBlocks(newStyle),
+        text: newStyle || newTone ? 'Style saved for this thread.' : 'Style cleared.',
+        blocks: buildStyleConfirmationBlocks(newStyle, newTone),
       });
     } catch (error) {
       logger.error('Failed to post style confirmation:', error);
     }
 
-    logger.info(`Style ${newStyle ? 'set' : 'cleared'} for thread ${assistantChannelId}:${assistantThreadTs}`);
+    logger.info(`Style ${newStyle || newTone ? 'set' : 'cleared'} for thread ${assistantChannelId}:${assistantThreadTs}`);
   });
 }
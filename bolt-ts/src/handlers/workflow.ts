@@ -0,0 +1,124 @@
+/**
+ * Workflow Builder custom step: "Summarize channel".
+ *
+ * Slack sends `function_executed` when a workflow reaches the step. We read
+ * the step inputs, summarise inline (non-streaming — there's no thread to
+ * stream into), and report the summary back as the step's `summary` output
+ * via `functions.completeSuccess`, or `functions.completeError` on failure.
+ */
+
+import { App } from '@slack/bolt';
+import type { WebClient } from '@slack/web-api';
+import type { AppConfig } from '../config';
+import { withCorrelationId } from '../logging';
+import { extractWorkspaceIds } from '../slack/workspace';
+import {
+  checkSummarizeRateLimit,
+  isUserMemberOfChannel,
+  isValidSlackChannelId,
+  normalizeMessageCount,
+  type ConversationsMembersClient,
+} from '../security';
+import { sanitizeGeneratedSlackMrkdwn } from '../slack/sanitize';
+import { functionCompleteError, functionCompleteSuccess } from '../slack/client';
+import { createLlmClient, generateSummaryText } from '../worker/summarize';
+import { failureMessageFor } from '../worker/streaming';
+
+/** `callback_id` of the custom step in the app manifest. */
+export const WORKFLOW_SUMMARIZE_CALLBACK_ID = 'summarize_channel';
+
+export type WorkflowInputs =
+  | { ok: true; channelId: string; userId: string; messageCount: number }
+  | { ok: false; error: string };
+
+/**
+ * Validate the step inputs. `channel_id` and `user_id` are required (the
+ * manifest defaults `user_id` to the person running the workflow);
+ * `message_count` is optional and clamped like every other count.
+ */
+export function parseWorkflowInputs(inputs: Record<string, unknown> | undefined): WorkflowInputs {
+  const channelId = typeof inputs?.channel_id === 'string' ? inputs.channel_id : null;
+  const userId = typeof inputs?.user_id === 'string' ? inputs.user_id : null;
+  if (!isValidSlackChannelId(channelId)) {
+    return { ok: false, error: 'A valid channel is required.' };
+  }
+  if (!userId) {
+    return { ok: false, error: 'A user is required.' };
+  }
+  const rawCount = inputs?.message_count;
+  const parsedCount =
+    typeof rawCount === 'number'
+      ? rawCount
+      : typeof rawCount === 'string' && rawCount.trim() !== ''
+        ? Number(rawCount)
+        : null;
+  return {
+    ok: true,
+    channelId,
+    userId,
+    messageCount: normalizeMessageCount(
+      parsedCount !== null && Number.isFinite(parsedCount) ? parsedCount : null
+    ),
+  };
+}
+
+export function registerWorkflowHandlers(app: App, config: AppConfig): void {
+  app.function(WORKFLOW_SUMMARIZE_CALLBACK_ID, async ({ inputs, body, event, client, logger }) => {
+    const executionId = event.function_execution_id;
+    const webClient = client as unknown as WebClient;
+    const fail = async (error: string): Promise<void> => {
+      try {
+        await functionCompleteError(webClient, executionId, error);
+      } catch (err) {
+        logger.error('Failed to report workflow step error:', err);
+      }
+    };
+
+    const parsed = parseWorkflowInputs(inputs as Record<string, unknown>);
+    if (!parsed.ok) {
+      await fail(parsed.error);
+      return;
+    }
+    if (!checkSummarizeRateLimit(parsed.userId)) {
+      await fail('Please wait a minute before starting more summaries.');
+      return;
+    }
+    const canRead = await isUserMemberOfChannel({
+      client: client as unknown as ConversationsMembersClient,
+      channelId: parsed.channelId,
+      userId: parsed.userId,
+      logger,
+    });
+    if (!canRead) {
+      await fail("I can only summarize channels you're a member of.");
+      return;
+    }
+
+    try {
+      const workspace = extractWorkspaceIds(body);
+      // The workflow execution id doubles as the run's correlation id.
+      const summary = await withCorrelationId(
+        executionId,
+        () =>
+          generateSummaryText({
+            config,
+            client: webClient,
+            llm: createLlmClient(config, null),
+            channelId: parsed.channelId,
+            messageCount: parsed.messageCount,
+            customStyle: null,
+            sourceThreadTs: null,
+            teamId: workspace.teamId,
+          }),
+        workspace
+      );
+      await functionCompleteSuccess(webClient, executionId, {
+        summary:
+          summary === null ? config.noMessagesMessage : sanitizeGeneratedSlackMrkdwn(summary.text),
+      });
+    } catch (err) {
+      logger.error('Workflow summarize step failed:', err);
+      await fail(failureMessageFor(err));
+    }
+  });
+}
@@ -6,6 +6,12 @@
  * the Lambda HTTP response is sent promptly, while the rest of the handler
  * (including Anthropic streaming and Slack streaming-message posting) continues
  * to run inline within the Lambda invocation.
+ *
+ * Oversized request bodies are rejected with a 413 before Bolt sees them, so
+ * no signature verification or parsing work is spent on them.
+ *
+ * Each invocation binds a task deadline (see `deadline.ts`) so a long run
+ * aborts cleanly before the Lambda is force-killed.
  */
 
 import { AwsLambdaReceiver } from '@slack/bolt';
@@ -16,6 +22,24 @@ import type {
 } from '@slack/bolt/dist/receivers/AwsLambdaReceiver';
 import { loadConfigCached } from './config';
 import { createApp } from './app';
+import { createDeadline, taskBudgetMs, withDeadline } from './deadline';
+import { log } from './logging';
+
+export const PAYLOAD_TOO_LARGE_RESPONSE: AwsResponse = {
+  statusCode: 413,
+  body: 'Payload Too Large',
+};
+
+/** `context.getRemainingTimeInMillis()` when running on Lambda, else `null`. */
+export function lambdaRemainingMs(context: unknown): number | null {
+  const fn = (context as { getRemainingTimeInMillis?: unknown } | null)?.getRemainingTimeInMillis;
+  return typeof fn === 'function' ? (fn as () => number).call(context) : null;
+}
+
+/** True when the raw (still-encoded) body is longer than `maxLength`. */
+export function isBodyTooLarge(event: Pick<AwsEvent, 'body'>, maxLength: number): boolean {
+  return typeof event.body === 'string' && event.body.length > maxLength;
+}
 
 let receiver: AwsLambdaReceiver | null = null;
 let receiverPromise: Promise<AwsLambdaReceiver> | null = null;
@@ -51,7 +75,18 @@ export const handler = async (
   context: unknown,
   callback: AwsCallback
 ): Promise<AwsResponse> => {
+  const config = await loadConfigCached();
+  if (isBodyTooLarge(event, config.maxRequestBodyBytes)) {
+    log.warn('Rejected oversized request body', {
+      length: event.body?.length,
+      limit: config.maxRequestBodyBytes,
+    });
+    return PAYLOAD_TOO_LARGE_RESPONSE;
+  }
+  const deadline = createDeadline(
+    taskBudgetMs(config.taskDeadlineSecs * 1000, lambdaRemainingMs(context))
+  );
   const awsReceiver = await initialize();
   const boltHandler = awsReceiver.toHandler();
-  return boltHandler(event, context, callback);
+  return withDeadline(deadline, () => boltHandler(event, context, callback));
 };
@@ -4,7 +4,31 @@
  * Parses natural language commands from assistant thread messages.
  */
 
-import { UserIntent } from './types';
+import { SummaryTemplate, UserIntent } from './types';
+import { parseSlackPermalink } from './worker/links';
+
+const SLACK_PERMALINK_RE = /https?:\/\/[^\s<>|]+\/archives\/[^\s<>|]+/;
+const POST_TO_CHANNEL_RE = /\b(?:post|share)\s+(?:to|in)\s+<#([A-Z0-9]+)(?:\|[^>]*)?>/i;
+// Slack's composer may turn straight quotes into curly ones.
+const CONTAINS_KEYWORD_RE = /\bcontains\s*[=:]\s*(?:["“]([^"“”]+)["”]|([^\s"“”]+))/i;
+const PINNED_MODE_RE =
+  /(?:--pinned|\bpinned\s+messages|\bpins\s+only|\bsummari[sz]e\s+(?:the\s+)?pin(?:ned|s))\b/;
+const CANVAS_MODE_RE = /(?:\bsummari[sz]e\s+(?:the\s+|this\s+)?(?:channel\s+)?canvas\b|--canvas\b)/;
+const TIMEZONE_PARAM_RE = /(?:--tz[\s=]+|\b(?:tz|timezone)\s*[=:]\s*)([^\s"“”<>]+)/i;
+const NO_IMAGES_RE = /(?:--no-images\b|\b(?:without|no)\s+images\b|\btext[-\s]only\b)/;
+const ALLOW_MENTIONS_RE = /--allow-mentions\b/;
+const SINCE_LAST_SUMMARY_RE = /(?:--since-last-summary\b|\bsince\s+(?:the\s+)?last\s+summary\b)/;
+// Both capture any value, so a mistyped one can be reported rather than ignored.
+const BEFORE_PARAM_RE = /(?:--before[\s=]+|\bbefore\s*[=:]\s*)(<[^>\s]+>|[^\s<>]+)/i;
+const AFTER_PARAM_RE = /(?:--after[\s=]+|\bafter\s*[=:]\s*)(<[^>\s]+>|[^\s<>]+)/i;
+
+/** Reply for a `before=` / `after=` value that names no message. */
+export function invalidBoundMessage(param: 'before' | 'after', raw: string): string {
+  return (
+    `I couldn't find a message in "${param}=${raw}". ` +
+    'Paste a message link (⋯ → Copy link) or a Slack timestamp like 1700000000.000100.'
+  );
+}
 
 /**
  * Parse user intent from message text.
@@ -33,7 +57,8 @@ export function parseUserIntent(text: string): UserIntent {
   // Examples:
   // - "style: write as a haiku"
   // - "style : extremely concise"
-  const styleMatch = text.match(/^\s*style\s*:\s*(.+?)\s*$/i);
+  // - "style: tone: formal\nfocus: blockers" (one stacked directive per line)
+  const styleMatch = text.match(/^\s*style\s*:\s*([\s\S]+?)\s*$/i);
   if (styleMatch) {
     const instructions = styleMatch[1]?.trim() ?? '';
     if (instructions.length > 0) {
@@ -42,40 +67,197 @@ export function parseUserIntent(text: string): UserIntent {
     return { type: 'help' };
   }
 
-  // Parse summarize intent
-  const postHere = textLower.includes('post here') || textLower.includes('public');
-
   // Parse per-run style override (doesn't persist)
   // Examples:
   // - "summarize with style: be funny"
   // - "summarize last 50 with style: write as haiku"
   let styleOverride: string | null = null;
-  const styleOverrideMatch = text.match(/with\s+style\s*:\s*(.+?)$/i);
+  const styleOverrideMatch = text.match(/with\s+style\s*:\s*([\s\S]+?)$/i);
   if (styleOverrideMatch) {
     styleOverride = styleOverrideMatch[1]?.trim() || null;
   }
 
-  // Parse "last N" pattern
-  const words = textLower.split(/\s+/);
-  let count: number | null = null;
-  for (let i = 0; i < words.length - 1; i++) {
-    if (words[i] === 'last') {
-      const parsed = parseInt(words[i + 1], 10);
-      if (!isNaN(parsed)) {
-        count = parsed;
-        break;
-      }
-    }
+  // Only messages mentioning a keyword
+  // Examples:
+  // - "summarize contains=deploy"
+  // - 'summarize last 200 contains:"release train"'
+  const keywordMatch = text.match(CONTAINS_KEYWORD_RE);
+  const keywordFilter = keywordMatch
+    ? (keywordMatch[1] ?? keywordMatch[2] ?? '').trim() || null
+    : null;
+
+  // Flags and parameters are read without the free-form style override and
+  // keyword, so "with style: focus on the pinned decision" doesn't become a
+  // pins-only run. `flagSource` keeps its case for case-sensitive values.
+  const flagSource = [styleOverrideMatch, keywordMatch].reduce<string>(
+    (rest, match) => (match ? rest.replace(match[0], ' ') : rest),
+    text
+  );
+  const flagText = flagSource.toLowerCase();
+
+  // Personal mentions digest (searches across channels, not the viewed one)
+  // Examples:
+  // - "summarize my mentions"
+  // - "my mentions last 20"
+  if (/\bmy\s+mentions\b/.test(flagText)) {
+    return {
+      type: 'mentions',
+      count: parseLastCount(textLower),
+      allowMentions: ALLOW_MENTIONS_RE.test(flagText),
+    };
   }
 
+  // Regenerate the last summary in this thread, editing it in place
+  // Examples:
+  // - "refresh"
+  // - "refresh last 200"
+  if (/^\s*refresh\b/i.test(text)) {
+    return { type: 'refresh', count: parseLastCount(textLower) };
+  }
+
+  // Parse summarize intent
+  // Publish into another channel
+  // Examples:
+  // - "summarize <#C1|eng> post to <#C2|leads>"
+  // - "summarize share in <#C2|leads>"
+  let postChannel: string | null = null;
+  const postToMatch = text.match(POST_TO_CHANNEL_RE);
+  if (postToMatch) {
+    postChannel = postToMatch[1];
+  }
+  // Summarize what came before a message rather than the latest history.
+  // Removed from the text so its permalink isn't read as a thread to summarize.
+  // Examples:
+  // - "summarize before=https://acme.slack.com/archives/C1/p1700000000000100"
+  // - "summarize last 50 before=1700000000.000100"
+  const beforeMatch = flagSource.match(BEFORE_PARAM_RE);
+  const before = beforeMatch ? parseMessageBound(beforeMatch[1]) : null;
+  // Everything after a message, the same way. A value that names no message
+  // is kept as `invalidBefore` / `invalidAfter` so handlers can say so.
+  // Examples:
+  // - "summarize after=https://acme.slack.com/archives/C1/p1700000000000100"
+  // - "summarize last 100 --after 1700000000.000100"
+  const afterMatch = flagSource.match(AFTER_PARAM_RE);
+  const after = afterMatch ? parseMessageBound(afterMatch[1]) : null;
+  const withoutBounds = [beforeMatch, afterMatch].reduce<string>(
+    (rest, match) => (match ? rest.replace(match[0], ' ') : rest),
+    text
+  );
+  const sourceText = postToMatch ? withoutBounds.replace(postToMatch[0], ' ') : withoutBounds;
+
+  // Parse "last N" pattern
+  const count = parseLastCount(textLower);
+
   // Extract channel mention like <#C123|name>
   let targetChannel: string | null = null;
-  const channelMatch = text.match(/<#([A-Z0-9]+)\|[^>]+>/);
+  const channelMatch = sourceText.match(/<#([A-Z0-9]+)\|[^>]+>/);
   if (channelMatch) {
     targetChannel = channelMatch[1];
   }
 
-  const askedToRun = textLower.includes('summarize') || count !== null;
+  // A pasted message permalink summarizes that message's thread. The
+  // permalink also names the channel unless one was mentioned explicitly.
+  let threadTs: string | null = null;
+  const permalinkMatch = withoutBounds.match(SLACK_PERMALINK_RE);
+  const permalink = permalinkMatch ? parseSlackPermalink(permalinkMatch[0]) : null;
+
+  // Describe a single message's images rather than its conversation. Needs a
+  // permalink; `threadTs` then names that message itself.
+  // Examples:
+  // - "describe the images https://acme.slack.com/archives/C1/p1700000000000100"
+  // - "summarize files only <permalink>"
+  const filesOnly =
+    permalink !== null &&
+    /\b(?:describe\s+(?:the\s+|these\s+)?(?:images?|files?|attachments?)|files?\s+only)\b/.test(
+      textLower
+    );
+  if (permalink) {
+    threadTs = filesOnly ? permalink.ts : permalink.threadTs ?? permalink.ts;
+    targetChannel = targetChannel ?? permalink.channelId;
+  }
+  targetChannel = targetChannel ?? before?.channelId ?? after?.channelId ?? null;
+
+  // Publish into the source channel
+  // Examples:
+  // - "summarize post here"
+  // - "summarize last 50 --public"
+  const postHere = /(?:--public\b|\bpost\s+here\b)/.test(flagText);
+
+  // Thread-aware digest
+  // Examples:
+  // - "summarize by thread"
+  // - "summarize last 100 grouped by threads"
+  const groupByThread = /\bby\s+threads?\b/.test(flagText);
+
+  // Keep bot / integration posts (e.g. an incident channel full of alerts)
+  // Examples:
+  // - "summarize include bots"
+  // - "summarize last 200 with bots"
+  // - "summarize --include-bots"
+  const includeBots = /(?:--include-bots\b|\b(?:include|including|with)\s+bots\b)/.test(flagText);
+
+  // Per-person standup digest
+  // Examples:
+  // - "summarize template=standup"
+  // - "summarize last 50 as a standup"
+  // - "summarize --standup"
+  const template: SummaryTemplate | null =
+    /(?:\btemplate\s*[=:]\s*standup\b|--standup\b|\bas\s+(?:an?\s+)?standup\b)/.test(flagText)
+      ? 'standup'
+      : null;
+
+  // DM me a link once the summary is published elsewhere
+  // Examples:
+  // - "summarize post to #leads --notify-me"
+  // - "summarize post here and notify me"
+  const notifyMe = /(?:--notify-me\b|\bnotify\s+me\b)/.test(flagText);
+
+  // The channel's pinned messages instead of recent history
+  // Examples:
+  // - "summarize pinned"
+  // - "summarize the pins in <#C1|eng>"
+  // - "summarize last 20 pinned messages"
+  const pinnedOnly = PINNED_MODE_RE.test(flagText);
+
+  // The channel's canvas, read as one document
+  // Examples:
+  // - "summarize canvas"
+  // - "summarize the canvas in <#C1|eng>"
+  const canvasOnly = CANVAS_MODE_RE.test(flagText);
+
+  // Render dates in this zone instead of the requester's Slack zone. Kept
+  // verbatim (zone names are case-sensitive); handlers validate it.
+  // Examples:
+  // - "summarize tz=America/New_York"
+  // - "summarize last 50 --tz Europe/Berlin"
+  const timezoneMatch = flagSource.match(TIMEZONE_PARAM_RE);
+  const timezone = timezoneMatch ? timezoneMatch[1] : null;
+
+  // Only what's new since this thread's last summary of the channel
+  // Examples:
+  // - "summarize --since-last-summary"
+  // - "summarize since the last summary"
+  const sinceLastSummary = SINCE_LAST_SUMMARY_RE.test(flagText);
+
+  // Text-only run: skip this run's image downloads. Meaningless when the run
+  // is about the images, so files-only runs ignore it.
+  // Examples:
+  // - "summarize --no-images"
+  // - "summarize last 200 without images"
+  const skipImages = !filesOnly && NO_IMAGES_RE.test(flagText);
+
+  // Bookmark the published summary in the channel it was posted to
+  // Examples:
+  // - "summarize post to #leads --bookmark"
+  // - "summarize post here and bookmark it"
+  const bookmark = /(?:--bookmark\b|\bbookmark\s+it\b)/.test(flagText);
+
+  // Let user mentions the summary repeats ping those people
+  // Examples:
+  // - "summarize --allow-mentions"
+  const allowMentions = ALLOW_MENTIONS_RE.test(flagText);
+
+  const askedToRun = textLower.includes('summarize') || count !== null || filesOnly;
 
   if (askedToRun) {
     return {
@@ -83,9 +265,54 @@ export function parseUserIntent(text: string): UserIntent {
       count,
       targetChannel,
       postHere,
+      postChannel,
       styleOverride,
+      threadTs,
+      groupByThread,
+      includeBots,
+      filesOnly,
+      template,
+      notifyMe,
+      keywordFilter,
+      pinnedOnly,
+      canvasOnly,
+      timezone,
+      beforeTs: before?.ts ?? null,
+      afterTs: after?.ts ?? null,
+      invalidBefore: beforeMatch && !before ? beforeMatch[1] : null,
+      invalidAfter: afterMatch && !after ? afterMatch[1] : null,
+      sinceLastSummary,
+      skipImages,
+      bookmark,
+      allowMentions,
     };
   }
 
   return { type: 'unknown' };
 }
+
+/**
+ * Decode a `before=` / `after=` value: a message permalink (which also names its
+ * channel) or a bare Slack ts, with or without the microseconds.
+ */
+function parseMessageBound(raw: string): { ts: string; channelId: string | null } | null {
+  if (/^\d{10}(?:\.\d{6})?$/.test(raw)) {
+    return { ts: raw.includes('.') ? raw : `${raw}.000000`, channelId: null };
+  }
+  const permalink = parseSlackPermalink(raw);
+  return permalink ? { ts: permalink.ts, channelId: permalink.channelId } : null;
+}
+
+/** Extract N from a "last N" phrase in already-lowercased text. */
+function parseLastCount(textLower: string): number | null {
+  const words = textLower.split(/\s+/);
+  for (let i = 0; i < words.length - 1; i++) {
+    if (words[i] === 'last') {
+      const parsed = parseInt(words[i + 1], 10);
+      if (!isNaN(parsed)) {
+        return parsed;
+      }
+    }
+  }
+  return null;
+}
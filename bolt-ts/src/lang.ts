@@ -0,0 +1,123 @@
+/**
+ * Lightweight language detection for conversation text.
+ *
+ * Good enough to notice that a channel talks Spanish or Japanese, not to
+ * label a single sentence: non-Latin scripts are identified by their Unicode
+ * block, Latin-script languages by how often their common function words
+ * appear. Anything uncertain returns `null`, so callers fall back to their
+ * default rather than guessing.
+ */
+
+/** Fewest letters a sample needs before any language is reported. */
+const MIN_DETECT_LETTERS = 40;
+/** Fewest words a Latin-script sample needs for the stopword vote. */
+const MIN_LATIN_WORDS = 12;
+/** Share of letters a non-Latin script needs to claim the sample. */
+const SCRIPT_SHARE = 0.6;
+/** Share of words the winning language's stopwords must cover. */
+const MIN_STOPWORD_SHARE = 0.15;
+/** How far the winning stopword count must lead the runner-up. */
+const STOPWORD_MARGIN = 1.5;
+
+/** Scripts that name their language outright (Han is handled with kana). */
+const SCRIPTS: Array<{ lang: string; re: RegExp }> = [
+  { lang: 'ko', re: /\p{Script=Hangul}/gu },
+  { lang: 'ru', re: /\p{Script=Cyrillic}/gu },
+  { lang: 'el', re: /\p{Script=Greek}/gu },
+  { lang: 'ar', re: /\p{Script=Arabic}/gu },
+  { lang: 'he', re: /\p{Script=Hebrew}/gu },
+  { lang: 'th', re: /\p{Script=Thai}/gu },
+  { lang: 'hi', re: /\p{Script=Devanagari}/gu },
+];
+
+/** Space-separated function words, one list per Latin-script language. */
+const STOPWORDS: Record<string, ReadonlySet<string>> = Object.fromEntries(
+  Object.entries({
+    en:
+      'the and is are was to of that it for with this ' +
+      'we you have be on not will can but they just',
+    es:
+      'el la los las que de y en es un una por para ' +
+      'con no se lo del al pero está como muy también hay',
+    fr:
+      'le la les des et est que de un une pour pas ' +
+      'dans sur avec ce il nous vous je mais du au sont',
+    de:
+      'der die das und ist nicht ich wir sie es ein ' +
+      'eine zu mit auf für den dem auch noch aber sind',
+    pt:
+      'o a os as que de e em um uma não para ' +
+      'com por mais do da no na está são mas também',
+    it:
+      'il la che di e è un una per non con ' +
+      'sono del della nel ma anche lo gli ho questo',
+    nl:
+      'de het een en is van dat niet ik we ' +
+      'je op voor met zijn maar ook er dit wel',
+  }).map(([lang, list]) => [lang, new Set(list.split(' '))])
+);
+
+/** Drop Slack markup that isn't prose: links, mentions, emoji codes, code. */
+function stripMarkup(sample: string): string {
+  return sample
+    .replace(/```[\s\S]*?```/g, ' ')
+    .replace(/`[^`]*`/g, ' ')
+    .replace(/<[^>]*>/g, ' ')
+    .replace(/https?:\/\/\S+/g, ' ')
+    .replace(/:[a-z0-9_+-]+:/g, ' ');
+}
+
+/**
+ * Detect the dominant language of `sample` as an ISO 639-1 code (`"es"`,
+ * `"ja"`), or `null` when the sample is too short or too mixed to call.
+ */
+export function detectLanguage(sample: string): string | null {
+  const text = stripMarkup(sample);
+  const letters = text.match(/\p{L}/gu)?.length ?? 0;
+  if (letters < MIN_DETECT_LETTERS) {
+    return null;
+  }
+
+  const kana = text.match(/[\p{Script=Hiragana}\p{Script=Katakana}]/gu)?.length ?? 0;
+  const han = text.match(/\p{Script=Han}/gu)?.length ?? 0;
+  if ((kana + han) / letters >= SCRIPT_SHARE) {
+    // Japanese mixes kana into kanji; Chinese has none.
+    return kana > 0 ? 'ja' : 'zh';
+  }
+  for (const { lang, re } of SCRIPTS) {
+    const count = text.match(re)?.length ?? 0;
+    if (count / letters >= SCRIPT_SHARE) {
+      return lang === 'ru' && /[іїєґ]/iu.test(text) ? 'uk' : lang;
+    }
+  }
+
+  const latin = text.match(/\p{Script=Latin}/gu)?.length ?? 0;
+  if (latin / letters < SCRIPT_SHARE) {
+    return null;
+  }
+  const words = text.toLowerCase().match(/\p{L}+/gu) ?? [];
+  if (words.length < MIN_LATIN_WORDS) {
+    return null;
+  }
+  const scores = Object.entries(STOPWORDS)
+    .map(([lang, stopwords]) => ({
+      lang,
+      hits: words.filter((w) => stopwords.has(w)).length,
+    }))
+    .sort((a, b) => b.hits - a.hits);
+  const [best, runnerUp] = scores;
+  const share = best.hits / words.length;
+  if (share < MIN_STOPWORD_SHARE || best.hits < runnerUp.hits * STOPWORD_MARGIN) {
+    return null;
+  }
+  return best.lang;
+}
+
+/** English name for an ISO 639-1 code, e.g. `"es"` → `"Spanish"`; the code itself if unknown. */
+export function languageName(code: string): string {
+  try {
+    return new Intl.DisplayNames(['en'], { type: 'language' }).of(code) ?? code;
+  } catch {
+    return code;
+  }
+}
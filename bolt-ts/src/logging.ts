@@ -0,0 +1,65 @@
+/**
+ * Correlation-aware logging for worker runs.
+ *
+ * Each summarisation run gets a correlation id; {@link withCorrelationId}
+ * binds it to the async call tree so every {@link log} line emitted from
+ * downstream helpers (Slack client, Anthropic client, streaming) carries
+ * `corr_id` without threading the id through each call signature. The run's
+ * workspace ids ride along as `team_id` / `enterprise_id`, which tell apart
+ * runs on different Enterprise Grid workspaces.
+ */
+
+import { AsyncLocalStorage } from 'async_hooks';
+import type { WorkspaceIds } from './slack/workspace';
+
+export interface Logger {
+  debug(message: string, meta?: Record<string, unknown>): void;
+  info(message: string, meta?: Record<string, unknown>): void;
+  warn(message: string, meta?: Record<string, unknown>): void;
+  error(message: string, meta?: Record<string, unknown>): void;
+}
+
+interface RunLogContext {
+  correlationId: string;
+  workspace?: WorkspaceIds;
+}
+
+const correlationStore = new AsyncLocalStorage<RunLogContext>();
+
+/** Run `fn` with `correlationId` (and workspace ids) attached to every log line it emits. */
+export function withCorrelationId<T>(
+  correlationId: string,
+  fn: () => Promise<T>,
+  workspace?: WorkspaceIds
+): Promise<T> {
+  return correlationStore.run({ correlationId, workspace }, fn);
+}
+
+/** The correlation id of the enclosing run, if any. */
+export function currentCorrelationId(): string | undefined {
+  return correlationStore.getStore()?.correlationId;
+}
+
+/**
+ * Merge the current `corr_id`, plus `team_id` / `enterprise_id` when known,
+ * into `meta`. Explicit fields win.
+ */
+export function withCorrelation(meta?: Record<string, unknown>): Record<string, unknown> {
+  const context = correlationStore.getStore();
+  if (context === undefined) {
+    return meta ?? {};
+  }
+  return {
+    corr_id: context.correlationId,
+    ...(context.workspace?.teamId ? { team_id: context.workspace.teamId } : {}),
+    ...(context.workspace?.enterpriseId ? { enterprise_id: context.workspace.enterpriseId } : {}),
+    ...meta,
+  };
+}
+
+export const log: Logger = {
+  debug: (message, meta) => console.debug(message, withCorrelation(meta)),
+  info: (message, meta) => console.log(message, withCorrelation(meta)),
+  warn: (message, meta) => console.warn(message, withCorrelation(meta)),
+  error: (message, meta) => console.error(message, withCorrelation(meta)),
+};
@@ -0,0 +1,185 @@
+/**
+ * CloudWatch Embedded Metric Format (EMF) emission.
+ *
+ * Lambda ships stdout to CloudWatch Logs, which turns any line shaped like an
+ * EMF document into metrics — so dashboards need no extra infrastructure.
+ * See https://docs.aws.amazon.com/AmazonCloudWatch/latest/monitoring/CloudWatch_Embedded_Metric_Format_Specification.html
+ */
+
+import type { PromptPayload } from './ai/prompt';
+import { log } from './logging';
+
+export const METRICS_NAMESPACE = 'TLDR';
+
+export type MetricUnit = 'Milliseconds' | 'Count' | 'None';
+
+export interface MetricDatum {
+  name: string;
+  value: number;
+  unit?: MetricUnit;
+}
+
+export interface EmfDocument {
+  _aws: {
+    Timestamp: number;
+    CloudWatchMetrics: Array<{
+      Namespace: string;
+      Dimensions: string[][];
+      Metrics: Array<{ Name: string; Unit: MetricUnit }>;
+    }>;
+  };
+  [key: string]: unknown;
+}
+
+/** Build one EMF document carrying every metric under the same dimensions. */
+export function buildEmfDocument(
+  metrics: MetricDatum[],
+  dimensions: Record<string, string> = {},
+  timestamp: number = Date.now()
+): EmfDocument {
+  const doc: EmfDocument = {
+    _aws: {
+      Timestamp: timestamp,
+      CloudWatchMetrics: [
+        {
+          Namespace: METRICS_NAMESPACE,
+          Dimensions: [Object.keys(dimensions)],
+          Metrics: metrics.map((m) => ({ Name: m.name, Unit: m.unit ?? 'None' })),
+        },
+      ],
+    },
+    ...dimensions,
+  };
+  for (const m of metrics) {
+    doc[m.name] = m.value;
+  }
+  return doc;
+}
+
+/** Write several metrics as a single EMF log line. Returns the document for tests. */
+export function emitMetrics(
+  metrics: MetricDatum[],
+  dimensions: Record<string, string> = {}
+): EmfDocument {
+  const doc = buildEmfDocument(metrics, dimensions);
+  console.log(JSON.stringify(doc));
+  return doc;
+}
+
+/** Write one metric as an EMF log line. */
+export function emitMetric(
+  name: string,
+  value: number,
+  dimensions: Record<string, string> = {},
+  unit: MetricUnit = 'None'
+): EmfDocument {
+  return emitMetrics([{ name, value, unit }], dimensions);
+}
+
+/** Per-run counters filled in while a summary is built and generated. */
+export interface SummaryRunStats {
+  messages: number;
+  images: number;
+  inputTokens: number;
+  outputTokens: number;
+}
+
+export function newSummaryRunStats(): SummaryRunStats {
+  return { messages: 0, images: 0, inputTokens: 0, outputTokens: 0 };
+}
+
+/** Rough per-image token cost; Anthropic bills ~1.6k tokens for a ~1 MP image. */
+const IMAGE_TOKEN_ESTIMATE = 1600;
+
+/** Rough token estimate (~4 characters per token) — good enough for trend lines. */
+export function estimateTokens(text: string): number {
+  return Math.ceil(text.length / 4);
+}
+
+/** Record the inputs of a built prompt on `stats`; a no-op without stats. */
+export function recordPromptStats(
+  stats: SummaryRunStats | undefined,
+  messageCount: number,
+  prompt: PromptPayload
+): void {
+  if (!stats) {
+    return;
+  }
+  stats.messages = messageCount;
+  stats.images = prompt.userContent.filter((block) => block.type === 'image').length;
+  stats.inputTokens = estimatePromptTokens(prompt);
+}
+
+/** Estimated input tokens for a prompt: its text plus a flat cost per image. */
+export function estimatePromptTokens(prompt: PromptPayload): number {
+  let tokens = estimateTokens(prompt.system);
+  for (const block of prompt.userContent) {
+    tokens += block.type === 'text' ? estimateTokens(block.text) : IMAGE_TOKEN_ESTIMATE;
+  }
+  return tokens;
+}
+
+/**
+ * Anthropic's context window for the summary models. Input and `max_tokens`
+ * share it, so a prompt overflows once the two together exceed this.
+ */
+export const MAX_CONTEXT_TOKENS = 200_000;
+
+/**
+ * Estimated tokens by which `prompt` plus `maxOutputTokens` overruns
+ * {@link MAX_CONTEXT_TOKENS}; `0` when it fits.
+ */
+export function promptOverflowTokens(prompt: PromptPayload, maxOutputTokens: number): number {
+  return Math.max(0, estimatePromptTokens(prompt) + maxOutputTokens - MAX_CONTEXT_TOKENS);
+}
+
+/**
+ * Log and emit `PromptOverflowTokens` when `prompt` looks too big for one
+ * call, before it is sent. Anthropic only says "too long", so this is the
+ * number operators tune default message counts against. Returns the overflow.
+ */
+export function recordPromptOverflow(
+  prompt: PromptPayload,
+  maxOutputTokens: number,
+  path: 'streaming' | 'non_streaming'
+): number {
+  const overflow = promptOverflowTokens(prompt, maxOutputTokens);
+  if (overflow > 0) {
+    log.warn('Prompt is likely over the context window', {
+      estimatedInputTokens: estimatePromptTokens(prompt),
+      maxOutputTokens,
+      maxContextTokens: MAX_CONTEXT_TOKENS,
+      overflowTokens: overflow,
+      path,
+    });
+    emitMetric('PromptOverflowTokens', overflow, { Path: path }, 'Count');
+  }
+  return overflow;
+}
+
+/** Record the generated summary on `stats`; a no-op without stats. */
+export function recordOutputStats(stats: SummaryRunStats | undefined, output: string): void {
+  if (stats) {
+    stats.outputTokens = estimateTokens(output);
+  }
+}
+
+/** Emit the end-of-run metrics for one summary. */
+export function emitSummaryMetrics(args: {
+  stats: SummaryRunStats;
+  latencyMs: number;
+  success: boolean;
+  delivery: string;
+}): EmfDocument {
+  return emitMetrics(
+    [
+      { name: 'SummaryLatency', value: args.latencyMs, unit: 'Milliseconds' },
+      { name: 'InputTokens', value: args.stats.inputTokens, unit: 'Count' },
+      { name: 'OutputTokens', value: args.stats.outputTokens, unit: 'Count' },
+      { name: 'MessageCount', value: args.stats.messages, unit: 'Count' },
+      { name: 'ImageCount', value: args.stats.images, unit: 'Count' },
+      { name: 'SummarySuccess', value: args.success ? 1 : 0, unit: 'Count' },
+    ],
+    { Delivery: args.delivery }
+  );
+}
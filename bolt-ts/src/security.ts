@@ -14,9 +14,21 @@ export const MAX_MESSAGE_COUNT = 500;
  * so the Slack input modal `max_length` and the LLM-side truncation agree.
  */
 export const MAX_CUSTOM_STYLE_LENGTH = 4000;
+/**
+ * Default limit for newly submitted custom instructions (`MAX_CUSTOM_STYLE_CHARS`).
+ * Tighter than the hard cap so a single style can't crowd out the transcript.
+ */
+export const DEFAULT_MAX_CUSTOM_STYLE_CHARS = 500;
+
+/** User-facing rejection for custom instructions over `maxChars`. */
+export function customStyleTooLongMessage(maxChars: number): string {
+  return `Custom instructions too long (max ${maxChars}).`;
+}
 
 const RATE_LIMIT_WINDOW_MS = 60_000;
 const RATE_LIMIT_MAX_REQUESTS = 5;
+const DEDUPE_TTL_MS = 5 * 60_000;
+const DEDUPE_MAX_ENTRIES = 1000;
 const MAX_MEMBERSHIP_PAGES = 20;
 const MEMBERSHIP_PAGE_SIZE = 1000;
 
@@ -28,6 +40,8 @@ interface RateLimitBucket {
 }
 
 const rateLimitBuckets = new Map<string, RateLimitBucket>();
+/** Message key -> expiry. Map insertion order doubles as oldest-first eviction order. */
+const seenMessages = new Map<string, number>();
 
 export interface SecurityLogger {
   warn(message: string, ...args: unknown[]): void;
@@ -67,17 +81,26 @@ export function normalizeMessageCount(
   return whole;
 }
 
-export function validateAndSanitizeStyle(raw: string | null | undefined):
+/**
+ * Strip control characters and validate user-supplied style instructions.
+ * `maxChars` (codepoints) defaults to the hard cap; request paths pass the
+ * configured `maxCustomStyleChars` for newly submitted instructions.
+ */
+export function validateAndSanitizeStyle(
+  raw: string | null | undefined,
+  maxChars: number = MAX_CUSTOM_STYLE_LENGTH
+):
   | { ok: true; value: string | null }
   | { ok: false; reason: string } {
   if (raw === null || raw === undefined) {
     return { ok: true, value: null };
   }
 
+  // Newlines survive: each line is a separate stacked directive.
   const trimmed = Array.from(raw)
     .filter((char) => {
       const code = char.charCodeAt(0);
-      return code >= 32 && code !== 127;
+      return char === '\n' || (code >= 32 && code !== 127);
     })
     .join('')
     .trim();
@@ -86,10 +109,10 @@ export function validateAndSanitizeStyle(raw: string | null | undefined):
     return { ok: true, value: null };
   }
 
-  if (trimmed.length > MAX_CUSTOM_STYLE_LENGTH) {
+  if (Array.from(trimmed).length > Math.min(maxChars, MAX_CUSTOM_STYLE_LENGTH)) {
     return {
       ok: false,
-      reason: `Style instructions must be ${MAX_CUSTOM_STYLE_LENGTH} characters or fewer.`,
+      reason: customStyleTooLongMessage(Math.min(maxChars, MAX_CUSTOM_STYLE_LENGTH)),
     };
   }
 
@@ -138,6 +161,35 @@ export function resetRateLimitForTests(): void {
   rateLimitBuckets.clear();
 }
 
+/**
+ * Return true the first time `key` is seen within the dedupe window, false for
+ * repeats. Guards against Slack redelivering the same event (retries after a
+ * slow ACK) and producing a second summary.
+ *
+ * Best-effort only: the set lives in one warm Lambda container, so a retry
+ * that lands on a different container is not caught.
+ */
+export function claimMessageOnce(key: string, now = Date.now()): boolean {
+  for (const [seenKey, expiresAt] of seenMessages) {
+    if (expiresAt > now && seenMessages.size <= DEDUPE_MAX_ENTRIES) {
+      break;
+    }
+    seenMessages.delete(seenKey);
+  }
+
+  const expiresAt = seenMessages.get(key);
+  if (expiresAt !== undefined && expiresAt > now) {
+    return false;
+  }
+  seenMessages.delete(key);
+  seenMessages.set(key, now + DEDUPE_TTL_MS);
+  return true;
+}
+
+export function resetMessageDedupeForTests(): void {
+  seenMessages.clear();
+}
+
 export function sanitizeGeneratedSlackText(text: string): string {
   return text
     .replace(/<!(channel|here|everyone)>/g, '`$&`')
@@ -175,7 +227,9 @@ export async function isUserMemberOfChannel(args: {
       }
       cursor = nextCursor;
     } catch (error) {
-      logger.warn('Failed to verify Slack channel membership before enqueueing work:', error);
+      logger.warn('Failed to verify Slack channel membership before enqueueing work', {
+        error: error instanceof Error ? error.message : String(error),
+      });
       return false;
     }
   }
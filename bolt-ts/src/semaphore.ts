@@ -0,0 +1,58 @@
+/**
+ * Counting semaphore for bounding concurrent async work.
+ *
+ * One warm process can be handed several summaries at once (Socket Mode, or a
+ * Lambda container reused while an earlier run is still streaming). Each run
+ * makes a long Anthropic call, so the worker caps how many proceed together
+ * and queues the rest in arrival order.
+ */
+
+export class Semaphore {
+  private active = 0;
+  private readonly waiters: Array<() => void> = [];
+
+  constructor(readonly limit: number) {}
+
+  /** Runs holding a slot right now. */
+  get inFlight(): number {
+    return this.active;
+  }
+
+  /** Runs waiting for a slot. */
+  get queued(): number {
+    return this.waiters.length;
+  }
+
+  /** Wait for a slot; call the returned function exactly once to give it back. */
+  async acquire(): Promise<() => void> {
+    if (this.active < this.limit) {
+      this.active += 1;
+    } else {
+      // The releasing run hands its slot straight over, so `active` is unchanged.
+      await new Promise<void>((resolve) => this.waiters.push(resolve));
+    }
+    let released = false;
+    return (): void => {
+      if (released) {
+        return;
+      }
+      released = true;
+      const next = this.waiters.shift();
+      if (next) {
+        next();
+      } else {
+        this.active -= 1;
+      }
+    };
+  }
+
+  /** Run `fn` while holding a slot, releasing it however `fn` settles. */
+  async run<T>(fn: () => Promise<T>): Promise<T> {
+    const release = await this.acquire();
+    try {
+      return await fn();
+    } finally {
+      release();
+    }
+  }
+}
@@ -7,7 +7,9 @@
  * (rate-limit handling, image downloads, "not in streaming state" detection).
  */
 
+import type { MessageMetadata, View } from '@slack/types';
 import type { WebClient } from '@slack/web-api';
+import { log } from '../logging';
 
 /** Slack-documented per-call character limit for `markdown_text` in chat.*Stream APIs. */
 export const STREAM_MARKDOWN_TEXT_LIMIT = 12_000;
@@ -15,6 +17,24 @@ export const STREAM_MARKDOWN_TEXT_LIMIT = 12_000;
 /** Sentinel error message returned by Slack when a streaming message has been finalised. */
 export const ERROR_MESSAGE_NOT_IN_STREAMING_STATE = 'message_not_in_streaming_state';
 
+/** Returned by `chat.postMessage` when the bot isn't a member of the channel. */
+export const ERROR_NOT_IN_CHANNEL = 'not_in_channel';
+
+/** Returned by `chat.postMessage` when `text` exceeds Slack's per-message limit. */
+export const ERROR_MSG_TOO_LONG = 'msg_too_long';
+
+/**
+ * Errors `chat.startStream` returns when the workspace (or token) can't use
+ * the streaming APIs at all, as opposed to a transient failure.
+ */
+export const STREAMING_UNAVAILABLE_ERRORS = [
+  'method_not_supported',
+  'unknown_method',
+  'not_allowed',
+  'not_allowed_token_type',
+  'feature_not_enabled',
+] as const;
+
 /**
  * Result of `appendStream`. `Ok` means the append succeeded; `Closed` means the
  * Slack message left streaming state and the caller should stop appending.
@@ -24,10 +44,23 @@ export type AppendStreamResult = { kind: 'ok' } | { kind: 'closed' };
 export interface RecentMessage {
   ts: string;
   user: string | null;
+  /** Set on messages posted by an app or integration (`B…` id). */
+  botId?: string | null;
+  /** Workspace of the author (`user_team`); differs per org in Slack Connect channels. */
+  userTeam?: string | null;
   text: string;
   files: SlackFile[];
   blocks?: unknown;
   attachments?: unknown;
+  reactions?: Array<{ name: string; count: number }>;
+  /** Root ts of the thread this message belongs to (equal to `ts` on a root). */
+  threadTs?: string | null;
+  /** Number of thread replies; only set on thread roots. */
+  replyCount?: number;
+  /** True when the author revised the message after posting it. */
+  edited?: boolean;
+  /** A thread reply also sent to the channel (`subtype: thread_broadcast`). */
+  broadcast?: boolean;
 }
 
 export interface SlackFile {
@@ -37,6 +70,20 @@ export interface SlackFile {
   urlPrivate: string | null;
   /** MIME type if Slack provided one. */
   mimeType: string | null;
+  /** Title (or file name) shown in Slack. */
+  name?: string | null;
+  /** Slack's short type, e.g. `pdf`, `gdoc`, `python`. */
+  fileType?: string | null;
+}
+
+/** A single `search.messages` hit, flattened to the fields the digest needs. */
+export interface SearchMatch {
+  ts: string;
+  user: string | null;
+  text: string;
+  channelId: string | null;
+  channelName: string | null;
+  permalink: string | null;
 }
 
 export interface ImageHead {
@@ -49,45 +96,457 @@ interface RawHistoryMessage {
   user?: string;
   text?: string;
   bot_id?: string;
+  user_team?: string;
+  team?: string;
   subtype?: string;
   files?: Array<{
     url_private_download?: string;
     url_private?: string;
     mimetype?: string;
+    name?: string;
+    title?: string;
+    filetype?: string;
   }>;
   blocks?: unknown;
   attachments?: unknown;
+  reactions?: Array<{ name?: string; count?: number }>;
+  thread_ts?: string;
+  reply_count?: number;
+  edited?: { user?: string; ts?: string };
 }
 
-/** Fetch the latest `count` messages in a channel. */
+/** Exclusive ts bounds for a `conversations.history` read. */
+export interface HistoryBounds {
+  /** Only messages posted before this ts. */
+  latest?: string | null;
+  /** Only messages posted after this ts. */
+  oldest?: string | null;
+}
+
+/**
+ * Fetch the latest `count` messages in a channel, or the latest `count`
+ * within `bounds` (e.g. just before a ts, or since the last summary).
+ */
 export async function getRecentMessages(
   client: WebClient,
   channelId: string,
+  count: number,
+  bounds: HistoryBounds = {}
+): Promise<RecentMessage[]> {
+  const limit = Math.min(Math.max(count, 1), 1000);
+  const response = await client.conversations.history({
+    channel: channelId,
+    limit,
+    ...(bounds.latest ? { latest: bounds.latest } : {}),
+    ...(bounds.oldest ? { oldest: bounds.oldest } : {}),
+  });
+  const messages = (response.messages ?? []) as RawHistoryMessage[];
+  return messages.map(toRecentMessage);
+}
+
+/**
+ * Fetch up to `count` messages from a thread via `conversations.replies`.
+ * Slack returns the root first, followed by replies oldest → newest.
+ */
+export async function getThreadMessages(
+  client: WebClient,
+  channelId: string,
+  threadTs: string,
   count: number
 ): Promise<RecentMessage[]> {
   const limit = Math.min(Math.max(count, 1), 1000);
-  const response = await client.conversations.history({ channel: channelId, limit });
+  const response = await client.conversations.replies({ channel: channelId, ts: threadTs, limit });
   const messages = (response.messages ?? []) as RawHistoryMessage[];
   return messages.map(toRecentMessage);
 }
 
+/**
+ * Fetch one message by ts, whether it's top-level or a thread reply. Returns
+ * `null` when Slack doesn't return it (deleted, or no access).
+ */
+export async function getSingleMessage(
+  client: WebClient,
+  channelId: string,
+  ts: string
+): Promise<RecentMessage | null> {
+  const response = await client.conversations.replies({
+    channel: channelId,
+    ts,
+    oldest: ts,
+    latest: ts,
+    inclusive: true,
+  });
+  const messages = (response.messages ?? []) as RawHistoryMessage[];
+  const match = messages.find((m) => m.ts === ts);
+  return match ? toRecentMessage(match) : null;
+}
+
+interface RawPinnedItem {
+  type?: string;
+  message?: RawHistoryMessage;
+}
+
+/**
+ * Fetch a channel's pinned messages via `pins.list`, oldest first. Pinned
+ * files without a message are skipped.
+ */
+export async function getPinnedMessages(
+  client: WebClient,
+  channelId: string
+): Promise<RecentMessage[]> {
+  const response = (await client.pins.list({ channel: channelId })) as { items?: RawPinnedItem[] };
+  return (response.items ?? [])
+    .filter((item): item is RawPinnedItem & { message: RawHistoryMessage } =>
+      item.type === 'message' && item.message !== undefined
+    )
+    .map((item) => toRecentMessage(item.message))
+    .sort((a, b) => Number(a.ts) - Number(b.ts));
+}
+
+/**
+ * Time allowed for `views.open`, retries included. A `trigger_id` is only
+ * valid for three seconds, so anything later fails with `expired_trigger_id`.
+ */
+export const MODAL_OPEN_BUDGET_MS = 2_500;
+/** Wait before retrying a 429 that came without `Retry-After`. */
+const MODAL_RATE_LIMIT_FALLBACK_MS = 500;
+
+/** Shown in the thread when a modal couldn't be opened in time. */
+export const MODAL_BUSY_MESSAGE =
+  "Slack is rate-limiting me right now, so I couldn't open that form. Try again in a moment.";
+
+/** `views.open` was rate-limited (or stalled) past the `trigger_id`'s lifetime. */
+export class ModalOpenError extends Error {
+  constructor(message: string) {
+    super(message);
+    this.name = 'ModalOpenError';
+  }
+}
+
+/** `Retry-After` in ms for a rate-limited Web API error, or `null` if `err` isn't one. */
+function rateLimitDelayMs(err: unknown): number | null {
+  const limited = err as { code?: unknown; retryAfter?: unknown } | null;
+  if (
+    limited?.code === 'slack_webapi_rate_limited_error' ||
+    hasSlackErrorCode(err, 'ratelimited')
+  ) {
+    return typeof limited?.retryAfter === 'number'
+      ? limited.retryAfter * 1000
+      : MODAL_RATE_LIMIT_FALLBACK_MS;
+  }
+  return null;
+}
+
+/**
+ * `views.open` that respects the `trigger_id`'s short life. A 429 is retried
+ * after its `Retry-After` only while that still lands inside `budgetMs`, and
+ * a call still pending at the end of the budget (the Web API client sitting
+ * out a long `Retry-After` itself) is abandoned. Both throw
+ * {@link ModalOpenError} so the caller can tell the user; other errors
+ * propagate unchanged.
+ */
+export async function openModal(
+  client: WebClient,
+  args: { triggerId: string; view: View },
+  options: { budgetMs?: number; sleep?: (ms: number) => Promise<void> } = {}
+): Promise<void> {
+  const budgetMs = options.budgetMs ?? MODAL_OPEN_BUDGET_MS;
+  const sleep = options.sleep ?? ((ms: number) => new Promise<void>((r) => setTimeout(r, ms)));
+  const startedAt = Date.now();
+  while (true) {
+    const remaining = budgetMs - (Date.now() - startedAt);
+    let timer: ReturnType<typeof setTimeout> | undefined;
+    const expired = new Promise<'expired'>((resolve) => {
+      timer = setTimeout(() => resolve('expired'), Math.max(remaining, 0));
+    });
+    try {
+      const outcome = await Promise.race([
+        client.views.open({ trigger_id: args.triggerId, view: args.view }),
+        expired,
+      ]);
+      if (outcome === 'expired') {
+        throw new ModalOpenError('views.open did not finish before the trigger_id expired');
+      }
+      return;
+    } catch (err) {
+      const delayMs = rateLimitDelayMs(err);
+      if (delayMs === null) {
+        throw err;
+      }
+      if (Date.now() - startedAt + delayMs >= budgetMs) {
+        throw new ModalOpenError('views.open was rate limited past the trigger_id lifetime');
+      }
+      log.warn('views.open rate limited; retrying', { retryAfterMs: delayMs });
+      await sleep(delayMs);
+    } finally {
+      clearTimeout(timer);
+    }
+  }
+}
+
+/**
+ * Point the link bookmark titled `title` in `channelId` at `link`, adding it
+ * when there is none. Matching on title means re-running edits the one
+ * bookmark instead of piling up duplicates.
+ */
+export async function addBookmark(
+  client: WebClient,
+  args: { channelId: string; title: string; link: string }
+): Promise<'added' | 'updated'> {
+  const listed = await client.bookmarks.list({ channel_id: args.channelId });
+  const existing = listed.bookmarks?.find((b) => b.type === 'link' && b.title === args.title);
+  if (existing?.id) {
+    await client.bookmarks.edit({
+      channel_id: args.channelId,
+      bookmark_id: existing.id,
+      link: args.link,
+    });
+    return 'updated';
+  }
+  await client.bookmarks.add({
+    channel_id: args.channelId,
+    title: args.title,
+    type: 'link',
+    link: args.link,
+  });
+  return 'added';
+}
+
+export interface CreatedCanvas {
+  canvasId: string;
+  /** Link to open the canvas; `null` when `files.info` didn't return one. */
+  permalink: string | null;
+}
+
+/**
+ * Create a standalone canvas from markdown and give `userId` write access.
+ * The bot owns canvases it creates, so without the grant the requester
+ * couldn't open it.
+ */
+export async function createCanvasForUser(
+  client: WebClient,
+  args: { title: string; markdown: string; userId: string }
+): Promise<CreatedCanvas> {
+  const created = await client.canvases.create({
+    title: args.title,
+    document_content: { type: 'markdown', markdown: args.markdown },
+  });
+  if (!created.canvas_id) {
+    throw new Error('canvases.create returned no canvas_id');
+  }
+  await client.canvases.access.set({
+    canvas_id: created.canvas_id,
+    access_level: 'write',
+    user_ids: [args.userId],
+  });
+  let permalink: string | null = null;
+  try {
+    const info = await client.files.info({ file: created.canvas_id });
+    permalink = info.file?.permalink ?? null;
+  } catch {
+    // The canvas exists either way; the caller falls back to plain text.
+  }
+  return { canvasId: created.canvas_id, permalink };
+}
+
+/** A canvas's current content, flattened to markdown-ish text for the prompt. */
+export interface CanvasContent {
+  canvasId: string;
+  title: string | null;
+  markdown: string;
+  /** Last edit (else creation) time as epoch seconds, `null` when unknown. */
+  updated: number | null;
+}
+
+/**
+ * Id of the canvas attached to a channel (`conversations.info`
+ * `properties.canvas`), or `null` when the channel has none.
+ */
+export async function getChannelCanvasId(
+  client: WebClient,
+  channelId: string
+): Promise<string | null> {
+  const resp = await client.conversations.info({ channel: channelId });
+  const channel = resp.channel as { properties?: { canvas?: { file_id?: string } } } | undefined;
+  return channel?.properties?.canvas?.file_id ?? null;
+}
+
+/**
+ * Read a canvas's current content. Slack has no markdown export, so the
+ * canvas file (HTML) is downloaded with the bot token and flattened by
+ * `canvasHtmlToMarkdown`. Returns `null` when the file has no download URL
+ * or the download fails.
+ */
+export async function getCanvasContent(
+  client: WebClient,
+  args: { canvasId: string; botToken: string; fetchImpl?: typeof fetch }
+): Promise<CanvasContent | null> {
+  const info = await client.files.info({ file: args.canvasId });
+  const file = info.file as
+    | {
+        title?: string;
+        name?: string;
+        created?: number;
+        updated?: number;
+        url_private_download?: string;
+        url_private?: string;
+      }
+    | undefined;
+  const url = file?.url_private_download ?? file?.url_private;
+  if (!file || !url) {
+    return null;
+  }
+  const impl = args.fetchImpl ?? fetch;
+  const resp = await impl(url, { headers: { Authorization: `Bearer ${args.botToken}` } });
+  if (!resp.ok) {
+    log.warn('Canvas download failed', { canvasId: args.canvasId, status: resp.status });
+    return null;
+  }
+  return {
+    canvasId: args.canvasId,
+    title: file.title ?? file.name ?? null,
+    markdown: canvasHtmlToMarkdown(await resp.text()),
+    updated: file.updated ?? file.created ?? null,
+  };
+}
+
+const HTML_ENTITIES: Record<string, string> = {
+  amp: '&',
+  lt: '<',
+  gt: '>',
+  quot: '"',
+  apos: "'",
+  nbsp: ' ',
+};
+
+const MAX_CODE_POINT = 0x10ffff;
+
+/**
+ * Flatten canvas HTML to markdown-ish text: headings become `#` lines, list
+ * items `- ` lines, links `text (url)`, and everything else plain text.
+ */
+export function canvasHtmlToMarkdown(html: string): string {
+  return html
+    .replace(/<(script|style)\b[\s\S]*?<\/\1>/gi, '')
+    .replace(/<h([1-6])\b[^>]*>/gi, (_m, level: string) => `\n${'#'.repeat(Number(level))} `)
+    .replace(/<li\b[^>]*>/gi, '\n- ')
+    .replace(/<a\b[^>]*href="([^"]*)"[^>]*>([\s\S]*?)<\/a>/gi, (_m, href: string, text: string) =>
+      text.trim() && text.trim() !== href ? `${text} (${href})` : href
+    )
+    .replace(/<br\s*\/?>|<\/(?:p|div|h[1-6]|ul|ol|tr|table|blockquote)>/gi, '\n')
+    .replace(/<[^>]+>/g, '')
+    .replace(/&(#\d+|#x[0-9a-f]+|[a-z]+);/gi, (entity, code: string) => {
+      if (code.startsWith('#')) {
+        const hex = code.startsWith('#x') || code.startsWith('#X');
+        const point = Number.parseInt(code.slice(hex ? 2 : 1), hex ? 16 : 10);
+        // Out-of-range references would make fromCodePoint throw; keep them as written.
+        return Number.isFinite(point) && point <= MAX_CODE_POINT ? String.fromCodePoint(point) : entity;
+      }
+      return HTML_ENTITIES[code.toLowerCase()] ?? entity;
+    })
+    .replace(/[ \t]+\n/g, '\n')
+    .replace(/\n{3,}/g, '\n\n')
+    .trim();
+}
+
 function toRecentMessage(raw: RawHistoryMessage): RecentMessage {
   return {
     ts: raw.ts ?? '',
     user: raw.user ?? null,
+    botId: raw.bot_id ?? null,
+    userTeam: raw.user_team ?? raw.team ?? null,
     text: raw.text ?? '',
     files: (raw.files ?? []).map((f) => ({
       urlPrivateDownload: f.url_private_download ?? null,
       urlPrivate: f.url_private ?? null,
       mimeType: f.mimetype ?? null,
+      name: f.title ?? f.name ?? null,
+      fileType: f.filetype ?? null,
     })),
     blocks: raw.blocks,
     attachments: raw.attachments,
+    reactions: raw.reactions?.map((r) => ({ name: r.name ?? '', count: r.count ?? 0 })),
+    threadTs: raw.thread_ts ?? null,
+    replyCount: raw.reply_count ?? 0,
+    edited: raw.edited !== undefined,
+    broadcast: raw.subtype === 'thread_broadcast',
   };
 }
 
-/** Fetch the bot's own user ID via `auth.test`. */
-export async function getBotUserId(client: WebClient): Promise<string | null> {
+/** `search.messages` caps `count` at 100 per page. */
+const SEARCH_PAGE_SIZE = 100;
+/** Hard stop on pagination so a noisy workspace can't stall the digest. */
+const MAX_SEARCH_PAGES = 5;
+
+interface RawSearchMatch {
+  ts?: string;
+  user?: string;
+  text?: string;
+  permalink?: string;
+  channel?: { id?: string; name?: string };
+}
+
+/**
+ * Build a `search.messages` query matching messages that @-mention `userId`.
+ * Slack's `@me` shorthand resolves to the token owner, which for a shared
+ * user token isn't the requester — so we spell out the mention markup.
+ */
+export function buildMentionSearchQuery(userId: string): string {
+  return `<@${userId}>`;
+}
+
+/**
+ * Run `search.messages`, newest first, paginating until `maxResults` matches
+ * are collected. `client` must be authenticated with a user token carrying
+ * `search:read`; Slack rejects bot tokens for this method. `teamId` scopes
+ * the search on an Enterprise Grid org, where org-level tokens require it.
+ */
+export async function searchMessages(
+  client: WebClient,
+  query: string,
+  maxResults: number,
+  teamId: string | null = null
+): Promise<SearchMatch[]> {
+  const matches: SearchMatch[] = [];
+  const count = Math.min(Math.max(maxResults, 1), SEARCH_PAGE_SIZE);
+  for (let page = 1; page <= MAX_SEARCH_PAGES; page += 1) {
+    const resp = await client.search.messages({
+      query,
+      count,
+      page,
+      sort: 'timestamp',
+      sort_dir: 'desc',
+      ...(teamId ? { team_id: teamId } : {}),
+    });
+    const raw = (resp.messages?.matches ?? []) as RawSearchMatch[];
+    for (const m of raw) {
+      matches.push({
+        ts: m.ts ?? '',
+        user: m.user ?? null,
+        text: m.text ?? '',
+        channelId: m.channel?.id ?? null,
+        channelName: m.channel?.name ?? null,
+        permalink: m.permalink ?? null,
+      });
+      if (matches.length >= maxResults) {
+        return matches;
+      }
+    }
+    const pages = resp.messages?.paging?.pages ?? 1;
+    if (raw.length === 0 || page >= pages) {
+      break;
+    }
+  }
+  return matches;
+}
+
+/**
+ * Bot user IDs by token. The ID never changes for a token, so one `auth.test`
+ * per warm process is enough; the promise is stored so concurrent callers share it.
+ */
+const botUserIdCache = new Map<string, Promise<string | null>>();
+
+async function fetchBotUserId(client: WebClient): Promise<string | null> {
   try {
     const resp = await client.auth.test();
     return resp.user_id ?? null;
@@ -96,6 +555,34 @@ export async function getBotUserId(client: WebClient): Promise<string | null> {
   }
 }
 
+/**
+ * Fetch the bot's own user ID via `auth.test`, cached per token. A failed
+ * lookup is dropped from the cache so the next call tries again; a client
+ * without a token is never cached.
+ */
+export async function getBotUserId(client: WebClient): Promise<string | null> {
+  const token = client.token;
+  if (!token) {
+    return fetchBotUserId(client);
+  }
+  let pending = botUserIdCache.get(token);
+  if (!pending) {
+    pending = fetchBotUserId(client).then((userId) => {
+      if (userId === null) {
+        botUserIdCache.delete(token);
+      }
+      return userId;
+    });
+    botUserIdCache.set(token, pending);
+  }
+  return pending;
+}
+
+/** For tests. */
+export function resetBotUserIdCacheForTests(): void {
+  botUserIdCache.clear();
+}
+
 /** Fetch a real-name (or display-name) for a user, falling back to the userId. */
 export async function getUserDisplayName(client: WebClient, userId: string): Promise<string> {
   try {
@@ -107,17 +594,215 @@ export async function getUserDisplayName(client: WebClient, userId: string): Pro
   }
 }
 
-/** Fetch the channel name (without leading `#`). Returns the channel ID on failure. */
+/** Default `users.info` calls in flight for {@link getUserDisplayNames}. */
+export const DEFAULT_USER_LOOKUP_CONCURRENCY = 4;
+
+/**
+ * Resolve names for many users with at most `concurrency` `users.info` calls
+ * at once, so a busy channel doesn't burst into Slack's rate limit. Each id
+ * maps to its real (or display) name; to the raw id when the lookup still
+ * fails transiently after the client's retries (see `slack/retry.ts`); or to
+ * `null` when the profile can't be read at all, e.g. a Slack Connect user
+ * from another org. Never rejects.
+ */
+export async function getUserDisplayNames(
+  client: WebClient,
+  userIds: string[],
+  options: { concurrency?: number } = {}
+): Promise<Map<string, string | null>> {
+  const ids = [...new Set(userIds)];
+  const names = new Map<string, string | null>();
+  let degraded = 0;
+  let next = 0;
+  const worker = async (): Promise<void> => {
+    while (next < ids.length) {
+      const id = ids[next];
+      next += 1;
+      try {
+        const resp = await client.users.info({ user: id });
+        const profile = resp.user?.profile;
+        names.set(id, profile?.real_name || profile?.display_name || null);
+      } catch (err) {
+        const transient = isTransientSlackError(err);
+        degraded += transient ? 1 : 0;
+        names.set(id, transient ? id : null);
+      }
+    }
+  };
+  const concurrency = options.concurrency ?? DEFAULT_USER_LOOKUP_CONCURRENCY;
+  const workers = Math.max(1, Math.min(concurrency, ids.length));
+  await Promise.all(Array.from({ length: workers }, worker));
+  if (degraded > 0) {
+    log.warn('Some user names could not be resolved; using raw ids', {
+      unresolved: degraded,
+      total: ids.length,
+    });
+  }
+  return names;
+}
+
+/** Fetch a user's IANA time zone from `users.info`. Returns `null` when unset or on failure. */
+export async function getUserTimeZone(client: WebClient, userId: string): Promise<string | null> {
+  try {
+    const resp = await client.users.info({ user: userId });
+    return resp.user?.tz ?? null;
+  } catch {
+    return null;
+  }
+}
+
+/** Members listed in a group DM's name before the rest are counted instead. */
+const GROUP_DM_NAME_MEMBER_LIMIT = 8;
+
+/** How long a `conversations.list` snapshot of channel names is trusted. */
+export const CHANNEL_NAME_CACHE_TTL_MS = 10 * 60_000;
+/** `conversations.list` page size; Slack caps it at 1000. */
+const CHANNEL_LIST_PAGE_SIZE = 1000;
+/** Pages read per refresh. Channels past them resolve through `conversations.info`. */
+const CHANNEL_LIST_MAX_PAGES = 10;
+
+/** Id → name snapshots keyed by token, so workspaces never answer for each other. */
+const channelNameCache = new Map<string, { names: Map<string, string>; loadedAt: number }>();
+/** Refreshes in flight by token, shared so concurrent lookups list the workspace once. */
+const channelNameRefresh = new Map<string, Promise<Map<string, string>>>();
+
+/**
+ * Every public and private channel the bot can see, id → name, read from
+ * `conversations.list` page by page (up to {@link CHANNEL_LIST_MAX_PAGES}).
+ */
+export async function listChannelNames(client: WebClient): Promise<Map<string, string>> {
+  const names = new Map<string, string>();
+  let cursor: string | undefined;
+  for (let page = 0; page < CHANNEL_LIST_MAX_PAGES; page++) {
+    const resp = await client.conversations.list({
+      types: 'public_channel,private_channel',
+      exclude_archived: true,
+      limit: CHANNEL_LIST_PAGE_SIZE,
+      ...(cursor ? { cursor } : {}),
+    });
+    for (const channel of resp.channels ?? []) {
+      if (channel.id && channel.name) {
+        names.set(channel.id, channel.name);
+      }
+    }
+    cursor = resp.response_metadata?.next_cursor || undefined;
+    if (!cursor) {
+      break;
+    }
+  }
+  return names;
+}
+
+/**
+ * The token's cached channel-name snapshot, listing the workspace again when
+ * it is missing or older than {@link CHANNEL_NAME_CACHE_TTL_MS}. A failed
+ * listing is cached as empty for the same TTL so lookups fall back to
+ * `conversations.info` instead of retrying the list on every call.
+ */
+async function cachedChannelNames(client: WebClient, token: string): Promise<Map<string, string>> {
+  const cached = channelNameCache.get(token);
+  if (cached && Date.now() - cached.loadedAt < CHANNEL_NAME_CACHE_TTL_MS) {
+    return cached.names;
+  }
+  let pending = channelNameRefresh.get(token);
+  if (!pending) {
+    pending = listChannelNames(client)
+      .catch((err: unknown) => {
+        log.warn('Could not list channels; resolving names one by one', {
+          error: err instanceof Error ? err.message : String(err),
+        });
+        return new Map<string, string>();
+      })
+      .then((names) => {
+        channelNameCache.set(token, { names, loadedAt: Date.now() });
+        return names;
+      })
+      .finally(() => {
+        channelNameRefresh.delete(token);
+      });
+    channelNameRefresh.set(token, pending);
+  }
+  return pending;
+}
+
+/** For tests. */
+export function resetChannelNameCacheForTests(): void {
+  channelNameCache.clear();
+  channelNameRefresh.clear();
+}
+
+/**
+ * Names for several channels at once (without leading `#`), for callers that
+ * label many channels in one run. Names come from the token's cached
+ * `conversations.list` snapshot; channels it doesn't have (group DMs, ones
+ * joined since the snapshot) and clients without a token fall back to
+ * {@link getChannelName}. A single lookup should call that directly instead.
+ */
+export async function getChannelNames(
+  client: WebClient,
+  channelIds: string[]
+): Promise<Map<string, string>> {
+  const ids = [...new Set(channelIds)];
+  const listed = client.token
+    ? await cachedChannelNames(client, client.token)
+    : new Map<string, string>();
+  const entries = await Promise.all(
+    ids.map(async (id) => [id, listed.get(id) ?? (await getChannelName(client, id))] as const)
+  );
+  return new Map(entries);
+}
+
+/**
+ * Fetch the channel name (without leading `#`) via `conversations.info`.
+ * Returns the channel ID on failure.
+ *
+ * Group DMs (MPIMs) have no name worth showing, only Slack's generated
+ * `mpdm-alice--bob-1`, so they are named after their members' display names
+ * instead; see {@link getGroupDmName}.
+ */
 export async function getChannelName(client: WebClient, channelId: string): Promise<string> {
   try {
     const resp = await client.conversations.info({ channel: channelId });
-    const name = resp.channel && 'name' in resp.channel ? (resp.channel.name as string | undefined) : undefined;
-    return name ?? channelId;
+    const channel = resp.channel as { name?: string; is_mpim?: boolean } | undefined;
+    if (channel?.is_mpim || (channel && !channel.name)) {
+      return (await getGroupDmName(client, channelId)) ?? channel.name ?? channelId;
+    }
+    return channel?.name ?? channelId;
   } catch {
     return channelId;
   }
 }
 
+/**
+ * Name a conversation after its members, e.g. "Ana, Bo, Cy and 2 others".
+ * Members whose names can't be resolved are left out. Returns `null` when
+ * `conversations.members` fails or no name resolves.
+ */
+async function getGroupDmName(client: WebClient, channelId: string): Promise<string | null> {
+  let memberIds: string[];
+  try {
+    const resp = await client.conversations.members({ channel: channelId, limit: 100 });
+    memberIds = resp.members ?? [];
+  } catch {
+    return null;
+  }
+  const shown = memberIds.slice(0, GROUP_DM_NAME_MEMBER_LIMIT);
+  const names = await getUserDisplayNames(client, shown);
+  // A lookup that degraded to the raw id names no one, so it is dropped too.
+  const resolved = shown.flatMap((id) => {
+    const name = names.get(id);
+    return name && name !== id ? [name] : [];
+  });
+  if (resolved.length === 0) {
+    return null;
+  }
+  const more = memberIds.length - shown.length;
+  if (more === 0) {
+    return resolved.join(', ');
+  }
+  return `${resolved.join(', ')} and ${more} other${more === 1 ? '' : 's'}`;
+}
+
 /** Fetch a permalink for a specific message. Returns null if Slack errors. */
 export async function getMessagePermalink(
   client: WebClient,
@@ -132,8 +817,304 @@ export async function getMessagePermalink(
   }
 }
 
+/** Default `chat.getPermalink` calls in flight for {@link getMessagePermalinks}. */
+export const DEFAULT_PERMALINK_CONCURRENCY = 4;
+/** Shared budget for a whole {@link getMessagePermalinks} batch. */
+export const PERMALINK_BATCH_TIMEOUT_MS = 5_000;
+
+/**
+ * Fetch permalinks for several messages in one channel. Slack has no batch
+ * API, so this runs at most `concurrency` `chat.getPermalink` calls at once.
+ * Results keep the order of `messageTs`; a failed lookup, or one still
+ * pending when the shared `timeoutMs` runs out, is `null` rather than
+ * failing the batch.
+ */
+export async function getMessagePermalinks(
+  client: WebClient,
+  channelId: string,
+  messageTs: string[],
+  options: { concurrency?: number; timeoutMs?: number } = {}
+): Promise<Array<{ ts: string; permalink: string | null }>> {
+  const results = messageTs.map((ts) => ({ ts, permalink: null as string | null }));
+  let next = 0;
+  let timedOut = false;
+  const worker = async (): Promise<void> => {
+    while (next < messageTs.length && !timedOut) {
+      const index = next;
+      next += 1;
+      results[index].permalink = await getMessagePermalink(client, channelId, messageTs[index]);
+    }
+  };
+  const concurrency = options.concurrency ?? DEFAULT_PERMALINK_CONCURRENCY;
+  const workers = Math.max(1, Math.min(concurrency, messageTs.length));
+  let timer: ReturnType<typeof setTimeout> | undefined;
+  const expired = new Promise<void>((resolve) => {
+    timer = setTimeout(() => {
+      timedOut = true;
+      resolve();
+    }, options.timeoutMs ?? PERMALINK_BATCH_TIMEOUT_MS);
+  });
+  try {
+    await Promise.race([Promise.all(Array.from({ length: workers }, worker)), expired]);
+  } finally {
+    clearTimeout(timer);
+  }
+  // Lookups that land after the timeout must not change what we returned.
+  return results.map((r) => ({ ...r }));
+}
+
+/** Build the `assistant.threads.setStatus` payload. An empty status clears it. */
+export function buildAssistantStatusPayload(
+  channel: string,
+  threadTs: string,
+  status: string
+): { channel_id: string; thread_ts: string; status: string } {
+  return { channel_id: channel, thread_ts: threadTs, status };
+}
+
+/**
+ * Set the "is working…" status shown under an assistant thread. Status is
+ * cosmetic, so failures — including channels that aren't assistant threads —
+ * are swallowed; returns whether Slack accepted it.
+ */
+export async function setAssistantStatus(
+  client: WebClient,
+  channel: string,
+  threadTs: string,
+  status: string
+): Promise<boolean> {
+  try {
+    await client.assistant.threads.setStatus(buildAssistantStatusPayload(channel, threadTs, status));
+    return true;
+  } catch (err) {
+    log.warn('Failed to set assistant thread status', {
+      error: err instanceof Error ? err.message : String(err),
+    });
+    return false;
+  }
+}
+
+/** Build the `functions.completeSuccess` payload for a Workflow Builder step. */
+export function buildFunctionCompleteSuccessPayload(
+  functionExecutionId: string,
+  outputs: Record<string, unknown>
+): { function_execution_id: string; outputs: Record<string, unknown> } {
+  return { function_execution_id: functionExecutionId, outputs };
+}
+
+/** Build the `functions.completeError` payload for a Workflow Builder step. */
+export function buildFunctionCompleteErrorPayload(
+  functionExecutionId: string,
+  error: string
+): { function_execution_id: string; error: string } {
+  return { function_execution_id: functionExecutionId, error };
+}
+
+/** Mark a Workflow Builder step as succeeded with the given outputs. */
+export async function functionCompleteSuccess(
+  client: WebClient,
+  functionExecutionId: string,
+  outputs: Record<string, unknown>
+): Promise<void> {
+  await client.functions.completeSuccess(
+    buildFunctionCompleteSuccessPayload(functionExecutionId, outputs) as never
+  );
+}
+
+/** Mark a Workflow Builder step as failed; `error` is shown to the workflow owner. */
+export async function functionCompleteError(
+  client: WebClient,
+  functionExecutionId: string,
+  error: string
+): Promise<void> {
+  await client.functions.completeError(buildFunctionCompleteErrorPayload(functionExecutionId, error));
+}
+
+/** `event_type` of the metadata our streams carry from `startStream` to `stopStream`. */
+export const TLDR_STREAM_EVENT_TYPE = 'tldr_stream';
+
+/** Stamped by `startStream`, so `stopStaleStreams` can find streams a killed run left open. */
+export const STREAM_OPEN_METADATA = {
+  event_type: TLDR_STREAM_EVENT_TYPE,
+  event_payload: { closed: false },
+};
+
+/** Marks a streamed message as closed, so `stopStaleStreams` skips it later. */
+export const STREAM_CLOSED_METADATA = {
+  event_type: TLDR_STREAM_EVENT_TYPE,
+  event_payload: { closed: true },
+};
+
+/** At most this many stale candidates are stopped per run. */
+const MAX_STALE_STREAM_STOPS = 3;
+
+/** A thread reply, reduced to what the stale-stream check needs. */
+export interface ThreadReplySummary {
+  ts: string;
+  user: string | null;
+  /** Still carries {@link STREAM_OPEN_METADATA}: started but never stopped. */
+  openStream: boolean;
+}
+
 /**
- * Start a Slack streaming message. Bolt's WebClient generates the right API
+ * Pick the bot's replies that may be orphaned streams: posted by
+ * `botUserId`, older than `olderThanSecs`, and still marked open by
+ * `startStream`. Plain notices carry no marker, so they never crowd out a
+ * real orphan. Newest first, capped at `limit`.
+ */
+export function findStaleStreamTs(
+  replies: ThreadReplySummary[],
+  args: {
+    threadTs: string;
+    botUserId: string;
+    olderThanSecs: number;
+    nowSecs: number;
+    limit?: number;
+  }
+): string[] {
+  const cutoff = args.nowSecs - args.olderThanSecs;
+  return replies
+    .filter(
+      (reply) =>
+        reply.ts !== args.threadTs &&
+        reply.user === args.botUserId &&
+        reply.openStream &&
+        Number(reply.ts) <= cutoff
+    )
+    .sort((a, b) => Number(b.ts) - Number(a.ts))
+    .slice(0, args.limit ?? MAX_STALE_STREAM_STOPS)
+    .map((reply) => reply.ts);
+}
+
+/**
+ * Best-effort cleanup of streams a killed run never stopped: finalise the
+ * bot's replies in the thread still marked open and older than
+ * `olderThanSecs`. A stream Slack already timed out just returns
+ * `message_not_in_streaming_state`, which `stopStream` ignores. Resolves with
+ * the number of replies stopped.
+ */
+export async function stopStaleStreams(
+  client: WebClient,
+  args: {
+    channel: string;
+    threadTs: string;
+    botUserId: string;
+    olderThanSecs: number;
+    now?: number;
+  }
+): Promise<number> {
+  const resp = await client.conversations.replies({
+    channel: args.channel,
+    ts: args.threadTs,
+    include_all_metadata: true,
+  });
+  const replies = ((resp.messages ?? []) as Array<{
+    ts?: string;
+    user?: string;
+    metadata?: { event_type?: string; event_payload?: { closed?: unknown } };
+  }>).map((m) => ({
+    ts: m.ts ?? '',
+    user: m.user ?? null,
+    openStream:
+      m.metadata?.event_type === TLDR_STREAM_EVENT_TYPE &&
+      m.metadata.event_payload?.closed === false,
+  }));
+  const stale = findStaleStreamTs(replies, {
+    threadTs: args.threadTs,
+    botUserId: args.botUserId,
+    olderThanSecs: args.olderThanSecs,
+    nowSecs: (args.now ?? Date.now()) / 1000,
+  });
+  for (const ts of stale) {
+    await stopStream(client, { channel: args.channel, ts, metadata: STREAM_CLOSED_METADATA });
+  }
+  return stale.length;
+}
+
+/** Event type stamped on a summary with the channel-history range it covered. */
+export const TLDR_SUMMARY_EVENT_TYPE = 'tldr_summary';
+
+/** The slice of a channel's history one summary covered, by message ts. */
+export interface SummaryRange {
+  sourceChannelId: string;
+  oldestTs: string;
+  latestTs: string;
+}
+
+/** The oldest..latest ts span of `messages`, or `null` when there are none. */
+export function summaryRangeOf(
+  sourceChannelId: string,
+  messages: Array<{ ts: string }>
+): SummaryRange | null {
+  const sorted = messages.map((m) => m.ts).sort((a, b) => Number(a) - Number(b));
+  if (sorted.length === 0) {
+    return null;
+  }
+  return { sourceChannelId, oldestTs: sorted[0], latestTs: sorted[sorted.length - 1] };
+}
+
+/**
+ * Metadata recording a summary's range for `--since-last-summary`. It also
+ * counts as a close marker, so `stopStaleStreams` leaves the message alone.
+ */
+export function buildSummaryRangeMetadata(range: SummaryRange): MessageMetadata {
+  return {
+    event_type: TLDR_SUMMARY_EVENT_TYPE,
+    event_payload: {
+      closed: true,
+      source_channel_id: range.sourceChannelId,
+      oldest_ts: range.oldestTs,
+      latest_ts: range.latestTs,
+    },
+  };
+}
+
+/** The newest range stamped for `sourceChannelId` among thread `messages`, oldest first. */
+export function extractLastSummaryRange(
+  messages: Array<{ metadata?: { event_type?: string; event_payload?: unknown } }>,
+  sourceChannelId: string
+): SummaryRange | null {
+  for (let i = messages.length - 1; i >= 0; i--) {
+    const metadata = messages[i].metadata;
+    if (metadata?.event_type !== TLDR_SUMMARY_EVENT_TYPE) {
+      continue;
+    }
+    const payload = (metadata.event_payload ?? {}) as Record<string, unknown>;
+    const { oldest_ts: oldestTs, latest_ts: latestTs } = payload;
+    if (
+      payload.source_channel_id === sourceChannelId &&
+      typeof oldestTs === 'string' &&
+      typeof latestTs === 'string'
+    ) {
+      return { sourceChannelId, oldestTs, latestTs };
+    }
+  }
+  return null;
+}
+
+/**
+ * The history range of the latest summary of `sourceChannelId` posted in an
+ * assistant thread, or `null` when none carries one.
+ */
+export async function getLastSummaryRange(
+  client: WebClient,
+  args: { channel: string; threadTs: string; sourceChannelId: string }
+): Promise<SummaryRange | null> {
+  const resp = await client.conversations.replies({
+    channel: args.channel,
+    ts: args.threadTs,
+    limit: 1000,
+    include_all_metadata: true,
+  });
+  return extractLastSummaryRange(
+    (resp.messages ?? []) as Array<{ metadata?: { event_type?: string; event_payload?: unknown } }>,
+    args.sourceChannelId
+  );
+}
+
+/**
+ * Start a Slack streaming message, stamped with {@link STREAM_OPEN_METADATA}
+ * until `stopStream` replaces it. Bolt's WebClient generates the right API
  * call; we just normalise the response shape and surface the `ts` consumers need.
  */
 export async function startStream(
@@ -143,6 +1124,7 @@ export async function startStream(
   const params: Record<string, unknown> = {
     channel: args.channel,
     thread_ts: args.threadTs,
+    metadata: STREAM_OPEN_METADATA,
   };
   if (args.markdownText !== undefined) {
     params.markdown_text = args.markdownText;
@@ -208,17 +1190,88 @@ export async function stopStream(
   }
 }
 
-/** Detect Slack's `message_not_in_streaming_state` error. */
-export function isMessageNotInStreamingStateError(err: unknown): boolean {
+/**
+ * True when `err` is a Slack Web API error carrying `code`. Checks the
+ * structured `data.error` first, then falls back to the message text for
+ * errors that were rethrown as plain `Error`s.
+ */
+export function hasSlackErrorCode(err: unknown, code: string): boolean {
   if (!err || typeof err !== 'object') {
     return false;
   }
   const data = (err as { data?: { error?: string } }).data;
-  if (data?.error === ERROR_MESSAGE_NOT_IN_STREAMING_STATE) {
+  if (data?.error === code) {
     return true;
   }
   const message = (err as Error).message ?? '';
-  return message.includes(ERROR_MESSAGE_NOT_IN_STREAMING_STATE);
+  return message.includes(code);
+}
+
+/** Detect Slack's `message_not_in_streaming_state` error. */
+export function isMessageNotInStreamingStateError(err: unknown): boolean {
+  return hasSlackErrorCode(err, ERROR_MESSAGE_NOT_IN_STREAMING_STATE);
+}
+
+/** Detect Slack's `not_in_channel` error (bot isn't a member of the target channel). */
+export function isNotInChannelError(err: unknown): boolean {
+  return hasSlackErrorCode(err, ERROR_NOT_IN_CHANNEL);
+}
+
+/** Detect Slack's `msg_too_long` error (message text over the per-message limit). */
+export function isMsgTooLongError(err: unknown): boolean {
+  return hasSlackErrorCode(err, ERROR_MSG_TOO_LONG);
+}
+
+/** Detect a `chat.startStream` error meaning streaming isn't available here. */
+export function isStreamingUnavailableError(err: unknown): boolean {
+  return STREAMING_UNAVAILABLE_ERRORS.some((code) => hasSlackErrorCode(err, code));
+}
+
+const TIMEOUT_ERROR_NAMES = new Set(['TimeoutError', 'AbortError']);
+const TIMEOUT_ERROR_CODES = new Set(['ETIMEDOUT', 'ECONNABORTED', 'ESOCKETTIMEDOUT']);
+
+/**
+ * Detect a request that timed out rather than being rejected by Slack: a
+ * `fetch` aborted by a timeout signal, a socket timeout, or a Web API
+ * `slack_webapi_request_error` wrapping either.
+ */
+export function isSlackTimeoutError(err: unknown): boolean {
+  if (!err || typeof err !== 'object') {
+    return false;
+  }
+  const candidate = err as { name?: string; code?: string; original?: unknown };
+  if (candidate.name && TIMEOUT_ERROR_NAMES.has(candidate.name)) {
+    return true;
+  }
+  if (candidate.code && TIMEOUT_ERROR_CODES.has(candidate.code)) {
+    return true;
+  }
+  return candidate.original !== undefined && isSlackTimeoutError(candidate.original);
+}
+
+/** Slack-side errors that a later identical request can reasonably succeed at. */
+const TRANSIENT_ERROR_CODES = [
+  'internal_error',
+  'fatal_error',
+  'service_unavailable',
+  'request_timeout',
+  'ratelimited',
+];
+
+/**
+ * A failure worth retrying: a timeout, a network-level request error, or a
+ * Slack 5xx-style / rate-limit error code. Permanent errors such as
+ * `channel_not_found` are not.
+ */
+export function isTransientSlackError(err: unknown): boolean {
+  if (isSlackTimeoutError(err)) {
+    return true;
+  }
+  const code = (err as { code?: unknown } | null)?.code;
+  if (code === 'slack_webapi_request_error' || code === 'slack_webapi_rate_limited_error') {
+    return true;
+  }
+  return TRANSIENT_ERROR_CODES.some((candidate) => hasSlackErrorCode(err, candidate));
 }
 
 /** HEAD an image URL with bot auth to learn its content-type / size. */
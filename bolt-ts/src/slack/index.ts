@@ -1,2 +1,6 @@
 export * from './sanitize';
 export * from './client';
+export * from './rich_text';
+export * from './message_formatter';
+export * from './retry';
+export * from './workspace';
@@ -0,0 +1,73 @@
+/**
+ * Normalise standard Markdown emphasis into Slack mrkdwn.
+ *
+ * Models sometimes fall back to `**bold**` / `__italic__` even when asked for
+ * mrkdwn. `chat.postMessage` text is rendered as mrkdwn, where those show up
+ * literally, so non-streamed summaries pass through {@link toMrkdwn} first.
+ * Streaming doesn't need it: `markdown_text` is rendered as Markdown natively.
+ */
+
+/** Code fences, inline code, and Slack `<…>` links/mentions — never rewritten. */
+const PROTECTED_RE = /```[\s\S]*?```|`[^`\n]*`|<[^<>\n]+>/g;
+
+const BOLD_ITALIC_RE = /\*\*\*(?=\S)([\s\S]*?\S)\*\*\*/g;
+const BOLD_RE = /\*\*(?=\S)([\s\S]*?\S)\*\*/g;
+const UNDERSCORE_ITALIC_RE = /__(?=\S)([\s\S]*?\S)__/g;
+
+function convertEmphasis(text: string): string {
+  return text
+    .replace(BOLD_ITALIC_RE, '*_$1_*')
+    .replace(BOLD_RE, '*$1*')
+    .replace(UNDERSCORE_ITALIC_RE, '_$1_');
+}
+
+/**
+ * Convert `***x***` → `*_x_*`, `**x**` → `*x*`, and `__x__` → `_x_`, leaving
+ * code spans, code fences, and Slack links untouched. Existing mrkdwn
+ * (`*bold*`, `_italic_`) passes through unchanged.
+ */
+export function toMrkdwn(markdown: string): string {
+  let out = '';
+  let last = 0;
+  for (const match of markdown.matchAll(PROTECTED_RE)) {
+    const start = match.index ?? 0;
+    out += convertEmphasis(markdown.slice(last, start)) + match[0];
+    last = start + match[0].length;
+  }
+  return out + convertEmphasis(markdown.slice(last));
+}
+
+/** A line holding only a bold span (`*Summary*` / `**Summary**`): a section header. */
+const SECTION_HEADER_RE = /^(\*\*?)([^*\n]+?)\1[ \t]*$/;
+
+/** Where numbered sections are rendered: a canvas (Markdown) or a Slack message (mrkdwn). */
+export type SectionFormat = 'canvas' | 'mrkdwn';
+
+/**
+ * Number the summary's section headers ("1. Summary", "2. Links shared", …).
+ * For a canvas, headers become `## n. Title` headings, which canvases anchor,
+ * under a *Contents* list; for Slack they stay bold mrkdwn lines. The
+ * `*Summary from <#C…>*` title and anything inside code fences are left as is.
+ */
+export function numberSummarySections(text: string, format: SectionFormat): string {
+  const titles: string[] = [];
+  let inFence = false;
+  const lines = text.split('\n').map((line) => {
+    if (line.trimStart().startsWith('```')) {
+      inFence = !inFence;
+      return line;
+    }
+    const match = inFence ? null : SECTION_HEADER_RE.exec(line);
+    const title = match?.[2].trim();
+    if (!title || title.startsWith('Summary from ')) {
+      return line;
+    }
+    titles.push(title);
+    return format === 'canvas' ? `## ${titles.length}. ${title}` : `*${titles.length}. ${title}*`;
+  });
+  if (titles.length === 0 || format === 'mrkdwn') {
+    return lines.join('\n');
+  }
+  const contents = titles.map((title, i) => `${i + 1}. ${title}`).join('\n');
+  return `**Contents**\n${contents}\n\n${lines.join('\n')}`;
+}
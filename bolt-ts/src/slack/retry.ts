@@ -0,0 +1,48 @@
+/**
+ * Retry policy for Slack Web API calls.
+ *
+ * `@slack/web-api` retries failed calls for about thirty minutes by default,
+ * far past a Lambda invocation. Every `WebClient` we build (Bolt's and the
+ * user-token search client) gets a bounded exponential backoff instead,
+ * tunable for rate-limited workspaces.
+ */
+
+import type { RetryOptions, WebClientOptions } from '@slack/web-api';
+import type { AppConfig } from '../config';
+
+export interface RetryPolicy {
+  /** Retries after the first attempt; 0 makes every call a single attempt. */
+  maxRetries: number;
+  /** Delay before the first retry; each later one doubles, with jitter. */
+  baseDelayMs: number;
+  /** Ceiling on any single delay. */
+  maxDelayMs: number;
+}
+
+export function retryPolicyFromConfig(
+  config: Pick<AppConfig, 'slackMaxRetries' | 'slackRetryBaseDelayMs' | 'slackRetryMaxDelayMs'>
+): RetryPolicy {
+  return {
+    maxRetries: config.slackMaxRetries,
+    baseDelayMs: config.slackRetryBaseDelayMs,
+    maxDelayMs: config.slackRetryMaxDelayMs,
+  };
+}
+
+/** Map a policy onto the `retryConfig` shape `WebClient` accepts. */
+export function toRetryOptions(policy: RetryPolicy): RetryOptions {
+  return {
+    retries: policy.maxRetries,
+    factor: 2,
+    minTimeout: policy.baseDelayMs,
+    maxTimeout: Math.max(policy.maxDelayMs, policy.baseDelayMs),
+    randomize: true,
+  };
+}
+
+/** `WebClient` options carrying the configured retry policy. */
+export function slackClientOptions(
+  config: Pick<AppConfig, 'slackMaxRetries' | 'slackRetryBaseDelayMs' | 'slackRetryMaxDelayMs'>
+): WebClientOptions {
+  return { retryConfig: toRetryOptions(retryPolicyFromConfig(config)) };
+}
@@ -0,0 +1,103 @@
+/**
+ * Plain-text extraction from Slack Block Kit `rich_text` blocks.
+ *
+ * Some clients (workflows, forwarded posts, certain integrations) send a
+ * message whose content lives only in `blocks`, leaving `text` empty. This
+ * walks sections, lists, quotes, and preformatted runs so those messages still
+ * reach the prompt.
+ */
+
+interface RichElement {
+  type?: string;
+  text?: string | { text?: string };
+  url?: string;
+  user_id?: string;
+  channel_id?: string;
+  usergroup_id?: string;
+  name?: string;
+  range?: string;
+  style?: string;
+  indent?: number;
+  elements?: RichElement[];
+}
+
+/** Concatenate the readable text of every block in a message, one line per paragraph. */
+export function extractTextFromBlocks(blocks: unknown): string {
+  if (!Array.isArray(blocks)) {
+    return '';
+  }
+  const lines: string[] = [];
+  for (const block of blocks as RichElement[]) {
+    if (!block || typeof block !== 'object') {
+      continue;
+    }
+    if (block.type === 'rich_text') {
+      for (const child of block.elements ?? []) {
+        lines.push(...renderContainer(child));
+      }
+    } else if (block.type === 'section' && typeof block.text === 'object' && block.text?.text) {
+      lines.push(block.text.text);
+    }
+  }
+  return lines.filter((l) => l.trim().length > 0).join('\n');
+}
+
+function renderContainer(el: RichElement): string[] {
+  switch (el.type) {
+    case 'rich_text_section':
+      return [renderInline(el.elements)];
+    case 'rich_text_quote':
+      return renderInline(el.elements)
+        .split('\n')
+        .map((line) => `> ${line}`);
+    case 'rich_text_preformatted':
+      return ['```\n' + renderInline(el.elements) + '\n```'];
+    case 'rich_text_list': {
+      // Slack flattens nested lists into siblings; `indent` carries the depth.
+      const pad = '  '.repeat(el.indent ?? 0);
+      const ordered = el.style === 'ordered';
+      return (el.elements ?? []).map((item, i) => {
+        const marker = ordered ? `${i + 1}.` : '-';
+        const body =
+          item.type === 'rich_text_section'
+            ? renderInline(item.elements)
+            : renderContainer(item).join(' ');
+        return `${pad}${marker} ${body}`;
+      });
+    }
+    default:
+      return [];
+  }
+}
+
+function renderInline(elements: RichElement[] | undefined): string {
+  let out = '';
+  for (const el of elements ?? []) {
+    switch (el.type) {
+      case 'text':
+        out += typeof el.text === 'string' ? el.text : '';
+        break;
+      case 'link':
+        out += typeof el.text === 'string' && el.text ? `<${el.url}|${el.text}>` : `<${el.url}>`;
+        break;
+      case 'user':
+        out += `<@${el.user_id}>`;
+        break;
+      case 'channel':
+        out += `<#${el.channel_id}>`;
+        break;
+      case 'usergroup':
+        out += `<!subteam^${el.usergroup_id}>`;
+        break;
+      case 'broadcast':
+        out += `@${el.range}`;
+        break;
+      case 'emoji':
+        out += `:${el.name}:`;
+        break;
+      default:
+        break;
+    }
+  }
+  return out;
+}
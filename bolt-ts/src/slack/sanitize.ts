@@ -8,9 +8,20 @@ const BROADCAST_MENTION_RE = /<!(channel|here|everyone)>/g;
 const USER_GROUP_MENTION_RE = /<!subteam\^[^>]+>/g;
 const USER_MENTION_RE = /<@[UW][A-Z0-9]+>/g;
 
-export function sanitizeGeneratedSlackMrkdwn(text: string): string {
-  return text
+export interface SanitizeOptions {
+  /**
+   * Leave `<@U...>` mentions live ("--allow-mentions"). Broadcast and
+   * user-group mentions are neutralised regardless.
+   */
+  allowUserMentions?: boolean;
+}
+
+export function sanitizeGeneratedSlackMrkdwn(
+  text: string,
+  options: SanitizeOptions = {}
+): string {
+  const neutralised = text
     .replace(BROADCAST_MENTION_RE, '`$&`')
-    .replace(USER_GROUP_MENTION_RE, '`$&`')
-    .replace(USER_MENTION_RE, '`$&`');
+    .replace(USER_GROUP_MENTION_RE, '`$&`');
+  return options.allowUserMentions ? neutralised : neutralised.replace(USER_MENTION_RE, '`$&`');
 }
@@ -0,0 +1,53 @@
+/**
+ * Workspace and Enterprise Grid ids from incoming Slack payloads.
+ *
+ * On a Grid org, channel ids are only unique within a workspace, and
+ * org-level tokens need `team_id` on some Web API calls. Each entry point
+ * extracts these ids from the raw payload so runs carry them into logs and
+ * into calls that accept a `team_id`.
+ */
+
+export interface WorkspaceIds {
+  teamId: string | null;
+  /** Set only for Enterprise Grid workspaces. */
+  enterpriseId: string | null;
+}
+
+interface RawWorkspacePayload {
+  team_id?: unknown;
+  enterprise_id?: unknown;
+  team?: { id?: unknown; enterprise_id?: unknown } | null;
+  enterprise?: { id?: unknown } | null;
+  user?: { team_id?: unknown } | null;
+  authorizations?: Array<{ team_id?: unknown; enterprise_id?: unknown }> | null;
+}
+
+function asSlackId(value: unknown): string | null {
+  return typeof value === 'string' && /^[A-Z][A-Z0-9]{2,}$/.test(value) ? value : null;
+}
+
+/**
+ * Pull `team_id` / `enterprise_id` from an Events API envelope or an
+ * interactive payload (`block_actions`, `view_submission`). Org-wide installs
+ * can send `team: null`, so the acting user's workspace and the
+ * authorization are used as fallbacks.
+ */
+export function extractWorkspaceIds(payload: unknown): WorkspaceIds {
+  if (!payload || typeof payload !== 'object') {
+    return { teamId: null, enterpriseId: null };
+  }
+  const raw = payload as RawWorkspacePayload;
+  const auth = raw.authorizations?.[0];
+  return {
+    teamId:
+      asSlackId(raw.team_id) ??
+      asSlackId(raw.team?.id) ??
+      asSlackId(raw.user?.team_id) ??
+      asSlackId(auth?.team_id),
+    enterpriseId:
+      asSlackId(raw.enterprise_id) ??
+      asSlackId(raw.enterprise?.id) ??
+      asSlackId(raw.team?.enterprise_id) ??
+      asSlackId(auth?.enterprise_id),
+  };
+}
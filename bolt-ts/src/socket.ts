@@ -0,0 +1,43 @@
+/**
+ * Socket Mode entry point for self-hosted deployments.
+ *
+ * Runs the same Bolt app as the Lambda (`index.ts`) over a Socket Mode
+ * WebSocket instead of HTTP. Bolt's `SocketModeReceiver` unwraps each
+ * envelope (`envelope_id` + nested `payload`) into the same event, action,
+ * and view payloads the HTTP receiver produces — so every handler and
+ * routing path is shared — and acks with `{ envelope_id }` on our behalf.
+ *
+ * Requires `SLACK_APP_TOKEN` (or `SLACK_APP_TOKEN_PARAMETER_NAME`).
+ */
+
+import { SocketModeReceiver } from '@slack/bolt';
+import { loadConfigCached, type AppConfig } from './config';
+import { createApp } from './app';
+import { log } from './logging';
+
+/** Build the Socket Mode receiver, failing fast when no app-level token is configured. */
+export function createSocketModeReceiver(config: AppConfig): SocketModeReceiver {
+  if (!config.slackAppToken) {
+    throw new Error(
+      'Socket Mode needs an app-level token: set SLACK_APP_TOKEN or SLACK_APP_TOKEN_PARAMETER_NAME'
+    );
+  }
+  return new SocketModeReceiver({ appToken: config.slackAppToken });
+}
+
+/** Load config, wire the app onto a Socket Mode receiver, and connect. */
+export async function startSocketMode(): Promise<void> {
+  const config = await loadConfigCached();
+  const app = createApp(config, createSocketModeReceiver(config));
+  await app.start();
+  log.info('TLDR is running in Socket Mode');
+}
+
+if (require.main === module) {
+  startSocketMode().catch((err) => {
+    log.error('Failed to start Socket Mode', {
+      error: err instanceof Error ? err.message : String(err),
+    });
+    process.exit(1);
+  });
+}
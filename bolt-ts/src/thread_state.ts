@@ -8,6 +8,7 @@
 
 import type { ThreadContext } from './types';
 import type { MessageMetadata } from '@slack/types';
+import { isSummaryTone } from './ai/prompt';
 import {
   isValidSlackChannelId,
   normalizeMessageCount,
@@ -62,6 +63,9 @@ export function buildThreadStateMetadata(state: ThreadContext): SlackMessageMeta
   if (state.customStyle) {
     payload.custom_style = state.customStyle;
   }
+  if (state.tone) {
+    payload.tone = state.tone;
+  }
   if (state.defaultMessageCount !== null && state.defaultMessageCount !== undefined) {
     payload.default_message_count = state.defaultMessageCount;
   }
@@ -92,6 +96,7 @@ export function parseThreadContextFromMetadata(eventPayload: unknown): ThreadCon
   const defaultState: ThreadContext = {
     viewingChannelId: null,
     customStyle: null,
+    tone: null,
     defaultMessageCount: null,
   };
 
@@ -109,12 +114,14 @@ export function parseThreadContextFromMetadata(eventPayload: unknown): ThreadCon
   const rawCustomStyle = typeof payload.custom_style === 'string' ? payload.custom_style : null;
   const sanitizedStyle = validateAndSanitizeStyle(rawCustomStyle);
   const customStyle = sanitizedStyle.ok ? sanitizedStyle.value : null;
+  const tone =
+    typeof payload.tone === 'string' && isSummaryTone(payload.tone) ? payload.tone : null;
   const defaultMessageCount =
     typeof payload.default_message_count === 'number'
       ? normalizeMessageCount(payload.default_message_count)
       : null;
 
-  return { viewingChannelId, customStyle, defaultMessageCount };
+  return { viewingChannelId, customStyle, tone, defaultMessageCount };
 }
 
 export async function findThreadStateMessage(args: {
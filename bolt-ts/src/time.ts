@@ -0,0 +1,39 @@
+/**
+ * Timezone helpers for rendering dates in summaries.
+ *
+ * Uses the runtime's `Intl` tz database, so any IANA zone Node knows about
+ * works without bundling extra data.
+ */
+
+export const DEFAULT_TIMEZONE = 'UTC';
+
+/** True when `timeZone` is an IANA zone the runtime can format in. */
+export function isValidTimeZone(timeZone: string): boolean {
+  try {
+    new Intl.DateTimeFormat('en-US', { timeZone });
+    return true;
+  } catch {
+    return false;
+  }
+}
+
+/** Reply for a `tz=` value that isn't an IANA zone. */
+export function unknownTimeZoneMessage(timeZone: string): string {
+  return `I don't recognise the time zone "${timeZone}". Use an IANA name like America/New_York.`;
+}
+
+/**
+ * Format a Slack timestamp (`"1700000000.123456"`) or epoch seconds as a
+ * calendar date in `timeZone`, e.g. `Mon, Mar 9, 2026`. The local date (and
+ * any DST offset) is resolved by the zone, not a fixed UTC offset.
+ */
+export function formatInTimeZone(ts: string | number, timeZone: string): string {
+  const seconds = typeof ts === 'number' ? ts : Number.parseFloat(ts);
+  return new Intl.DateTimeFormat('en-US', {
+    timeZone,
+    weekday: 'short',
+    month: 'short',
+    day: 'numeric',
+    year: 'numeric',
+  }).format(new Date(seconds * 1000));
+}
@@ -6,6 +6,12 @@
  * `worker/summarize.ts`.
  */
 
+/** Alternate summary layouts; see `worker/standup.ts`. */
+export type SummaryTemplate = 'standup';
+
+/** Curated tone presets picked in the style modal; see `TONE_DIRECTIVES` in `ai/prompt.ts`. */
+export type SummaryTone = 'neutral' | 'friendly' | 'formal' | 'executive';
+
 /** Parsed user intent from message text. */
 export type UserIntent =
   | { type: 'help' }
@@ -16,8 +22,58 @@ export type UserIntent =
       count: number | null;
       targetChannel: string | null;
       postHere: boolean;
+      /** Channel to publish the summary in (from "post to <#C…>"). */
+      postChannel: string | null;
       /** Per-run style override (doesn't persist to thread state). */
       styleOverride: string | null;
+      /** Root ts of a thread to summarize instead of channel history (from a pasted permalink). */
+      threadTs: string | null;
+      /** Group channel history into topics with their thread replies ("by thread"). */
+      groupByThread: boolean;
+      /** Keep integration / bot messages that `IGNORED_USER_IDS` would drop ("include bots"). */
+      includeBots: boolean;
+      /** Describe the images on the pasted message instead of summarising its thread. */
+      filesOnly: boolean;
+      /** Alternate summary layout ("template=standup"). */
+      template: SummaryTemplate | null;
+      /** DM the requester a link when a published summary is ready ("--notify-me"). */
+      notifyMe: boolean;
+      /** Only summarize messages containing this word ("contains=deploy"). */
+      keywordFilter: string | null;
+      /** Summarize the channel's pinned messages instead of recent history ("pinned"). */
+      pinnedOnly: boolean;
+      /** Summarize the channel's canvas instead of its messages ("summarize canvas"). */
+      canvasOnly: boolean;
+      /** IANA zone for this run's dates ("tz=America/New_York"); not yet validated. */
+      timezone: string | null;
+      /** Only history posted before this message ts ("before=<permalink>"). */
+      beforeTs: string | null;
+      /** Only history posted after this message ts ("after=<permalink>"). */
+      afterTs: string | null;
+      /** The `before=` value when it named no message; handlers reply with an error. */
+      invalidBefore: string | null;
+      /** The `after=` value when it named no message; handlers reply with an error. */
+      invalidAfter: string | null;
+      /** Only history newer than the thread's last summary of it ("--since-last-summary"). */
+      sinceLastSummary: boolean;
+      /** Leave this run's images out of the prompt ("--no-images"). */
+      skipImages: boolean;
+      /** Bookmark a published summary in its channel ("--bookmark"). */
+      bookmark: boolean;
+      /** Let user mentions in the summary notify ("--allow-mentions"). */
+      allowMentions: boolean;
+    }
+  | {
+      type: 'refresh';
+      /** New message count; falls back to the count of the summary being refreshed. */
+      count: number | null;
+    }
+  | {
+      type: 'mentions';
+      /** Max number of mentions to digest; falls back to the thread default. */
+      count: number | null;
+      /** Let user mentions in the digest notify ("--allow-mentions"). */
+      allowMentions: boolean;
     }
   | { type: 'unknown' };
 
@@ -25,5 +81,7 @@ export type UserIntent =
 export interface ThreadContext {
   viewingChannelId: string | null;
   customStyle: string | null;
+  /** Tone preset for the thread's summaries; layered under `customStyle`. */
+  tone: SummaryTone | null;
   defaultMessageCount: number | null;
 }
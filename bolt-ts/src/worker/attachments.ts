@@ -0,0 +1,48 @@
+/**
+ * Readable text from Slack message attachments (link unfurls, app
+ * attachments). Unfurls carry the title and summary of shared articles, which
+ * gives the model context for a link without us fetching the URL.
+ */
+
+/** Per-attachment cap so one verbose unfurl can't crowd out the conversation. */
+export const MAX_ATTACHMENT_TEXT_CHARS = 300;
+
+interface RawAttachment {
+  title?: unknown;
+  text?: unknown;
+  fallback?: unknown;
+}
+
+function asText(value: unknown): string {
+  return typeof value === 'string' ? value.replace(/\s+/g, ' ').trim() : '';
+}
+
+function clip(text: string): string {
+  const chars = [...text];
+  return chars.length > MAX_ATTACHMENT_TEXT_CHARS
+    ? chars.slice(0, MAX_ATTACHMENT_TEXT_CHARS - 3).join('') + '...'
+    : text;
+}
+
+/**
+ * One `title — text` line per attachment. `fallback` is used only when an
+ * attachment has neither title nor text; empty attachments are skipped.
+ */
+export function extractAttachmentText(msg: { attachments?: unknown }): string[] {
+  if (!Array.isArray(msg.attachments)) {
+    return [];
+  }
+  const out: string[] = [];
+  for (const raw of msg.attachments as RawAttachment[]) {
+    if (!raw || typeof raw !== 'object') {
+      continue;
+    }
+    const title = asText(raw.title);
+    const text = asText(raw.text);
+    const line = title && text ? `${title} — ${text}` : title || text || asText(raw.fallback);
+    if (line) {
+      out.push(clip(line));
+    }
+  }
+  return out;
+}
@@ -1,5 +1,9 @@
 /**
- * UTF-8 safe streaming chunker for Slack's `chat.appendStream` API.
+ * UTF-8 safe chunker shared by both delivery paths: streaming
+ * (`chat.appendStream`) and the non-streaming `msg_too_long` splitter in
+ * `deliver.ts`. Chunks are cut on codepoint boundaries only, so a surrogate
+ * pair (emoji, rare CJK) is never split and concatenating the chunks always
+ * gives back the input.
  *
  * Split priority: paragraph (`\n\n`), line (`\n`), whitespace, then a hard
  * codepoint cap.
@@ -19,6 +23,8 @@ export function takeStreamChunk(
   if (buffer.length === 0) {
     return null;
   }
+  // A cap below one codepoint would return empty chunks forever.
+  maxChars = Math.max(1, Math.floor(maxChars));
 
   const codepoints = [...buffer];
   if (codepoints.length <= maxChars) {
@@ -65,6 +71,17 @@ export function takeStreamChunk(
   return { chunk, rest };
 }
 
+/** Split all of `text` into chunks of at most `maxChars` codepoints, in order. */
+export function splitIntoChunks(text: string, maxChars: number): string[] {
+  const chunks: string[] = [];
+  let rest = text;
+  for (let taken = takeStreamChunk(rest, maxChars); taken; taken = takeStreamChunk(rest, maxChars)) {
+    chunks.push(taken.chunk);
+    rest = taken.rest;
+  }
+  return chunks;
+}
+
 function findLastIndex(haystack: string, needle: string): number {
   return haystack.lastIndexOf(needle);
 }
@@ -1,12 +1,62 @@
 /**
- * Block Kit action button factory shared between non-streaming delivery and the
- * streaming finaliser. Renders the Share / Roast / Receipts buttons that
- * appear under every summary in the assistant thread.
+ * Summary delivery helpers.
+ *
+ * - Block Kit action button factory shared between non-streaming delivery and
+ *   the streaming finaliser. Renders the Share / Summarize again / Roast /
+ *   Receipts / Jump to channel buttons that appear under every summary in the
+ *   assistant thread, plus an overflow menu to repost the finished summary
+ *   or save it to a canvas.
+ * - Channel delivery for "post here" / "post to #channel" runs, which publish
+ *   the summary into a channel instead of the assistant thread (as the bot,
+ *   or as the token's user with `POST_AS_USER`), and the optional
+ *   `--notify-me` DM linking the requester to it.
+ * - Delivery retries: a finished summary whose post fails transiently is
+ *   retried, then DMed to the requester so the generated text isn't lost.
+ * - Oversized posts: when Slack rejects a summary with `msg_too_long`, it is
+ *   re-posted as numbered parts split on paragraph boundaries.
+ * - The optional configured footer (model / count disclaimer) rendered as a
+ *   context block under the summary.
+ * - Refresh support: locating the bot's last summary in a thread (via its
+ *   Share button payload) so a rerun can overwrite it in place.
  */
 
-import type { ActionsBlock, Button, KnownBlock } from '@slack/types';
+import type { WebClient } from '@slack/web-api';
+import type {
+  ActionsBlock,
+  Button,
+  ContextBlock,
+  KnownBlock,
+  MessageMetadata,
+  Overflow,
+} from '@slack/types';
+import type { AppConfig } from '../config';
+import { log } from '../logging';
+import {
+  addBookmark,
+  getBotUserId,
+  getChannelName,
+  getMessagePermalink,
+  getThreadMessages,
+  isMsgTooLongError,
+  isNotInChannelError,
+  isSlackTimeoutError,
+  isTransientSlackError,
+  type RecentMessage,
+} from '../slack/client';
+import { splitIntoChunks } from './chunks';
 
-interface ShareButtonValue {
+/** Slack's documented `chat.postMessage` text limit, in characters. */
+export const SLACK_MESSAGE_CHAR_LIMIT = 40_000;
+/** Room kept free in each part for its "(i/n) " label. */
+const PART_LABEL_RESERVE = 16;
+
+/** `action_id` of the overflow menu under each summary. */
+export const ACTION_SUMMARY_DESTINATION = 'summary_destination';
+/** Overflow option values; both re-deliver the summary as posted, without regenerating it. */
+export const DESTINATION_REPOST = 'repost_to_channel';
+export const DESTINATION_CANVAS = 'save_to_canvas';
+
+export interface ShareButtonValue {
   action: 'share_summary';
   sourceChannelId: string;
   count: number;
@@ -14,9 +64,31 @@ interface ShareButtonValue {
 }
 
 interface RerunButtonValue {
-  action: 'rerun_roast' | 'rerun_receipts';
+  action: 'rerun_summary' | 'rerun_roast' | 'rerun_receipts';
   channelId: string;
   count: number;
+  /** Only set for `rerun_summary`, which repeats the run with its original style. */
+  style?: string | null;
+}
+
+/** Slack rejects a button `value` longer than this. */
+export const SLACK_BUTTON_VALUE_MAX_CHARS = 2000;
+
+/**
+ * Serialise a Share / Summarize again payload. A style long enough to push
+ * the value past {@link SLACK_BUTTON_VALUE_MAX_CHARS} is left out, so the
+ * button still posts; clicking it then runs without the style.
+ */
+function styledButtonValue(value: ShareButtonValue | RerunButtonValue): string {
+  const full = JSON.stringify(value);
+  return full.length <= SLACK_BUTTON_VALUE_MAX_CHARS
+    ? full
+    : JSON.stringify({ ...value, style: null });
+}
+
+/** Deep link that opens a channel in the Slack client. */
+export function channelUrl(channelId: string): string {
+  return `https://slack.com/app_redirect?channel=${encodeURIComponent(channelId)}`;
 }
 
 export interface SummaryActionButtonsArgs {
@@ -27,9 +99,12 @@ export interface SummaryActionButtonsArgs {
 }
 
 /**
- * Build an `actions` block containing Share / Roast / Receipts buttons.
- * Roast and Receipts buttons are hidden when the current summary already uses
- * that style — keeps the row clean for the user.
+ * Build an `actions` block containing Share / Summarize again / Roast /
+ * Receipts buttons, a "Jump to channel" link to the source channel, and the
+ * destination overflow menu. Roast and Receipts buttons are hidden when the
+ * current summary already uses that style — keeps the row clean for the user.
+ * The overflow options carry no payload: the handler reads the summary's
+ * source from the Share button on the same message.
  */
 export function buildSummaryActionButtons(args: SummaryActionButtonsArgs): KnownBlock[] {
   const { sourceChannelId, messageCount, currentStyle } = args;
@@ -45,7 +120,20 @@ export function buildSummaryActionButtons(args: SummaryActionButtonsArgs): Known
     type: 'button',
     text: { type: 'plain_text', text: '📤 Share to channel', emoji: true },
     action_id: 'share_summary',
-    value: JSON.stringify(shareValue),
+    value: styledButtonValue(shareValue),
+  });
+
+  const againValue: RerunButtonValue = {
+    action: 'rerun_summary',
+    channelId: sourceChannelId,
+    count: messageCount,
+    style: currentStyle,
+  };
+  elements.push({
+    type: 'button',
+    text: { type: 'plain_text', text: '🔄 Summarize again', emoji: true },
+    action_id: 'rerun_summary',
+    value: styledButtonValue(againValue),
   });
 
   const styleLower = currentStyle?.toLowerCase() ?? '';
@@ -71,6 +159,501 @@ export function buildSummaryActionButtons(args: SummaryActionButtonsArgs): Known
     });
   }
 
-  const block: ActionsBlock = { type: 'actions', elements };
+  elements.push({
+    type: 'button',
+    text: { type: 'plain_text', text: '↗️ Jump to channel', emoji: true },
+    action_id: 'jump_to_channel',
+    url: channelUrl(sourceChannelId),
+  });
+
+  const destinations: Overflow = {
+    type: 'overflow',
+    action_id: ACTION_SUMMARY_DESTINATION,
+    options: [
+      { text: { type: 'plain_text', text: '📣 Repost to channel', emoji: true }, value: DESTINATION_REPOST },
+      { text: { type: 'plain_text', text: '📝 Save to Canvas', emoji: true }, value: DESTINATION_CANVAS },
+    ],
+  };
+
+  const block: ActionsBlock = { type: 'actions', elements: [...elements, destinations] };
+  return [block];
+}
+
+/** Values substituted into the configured summary footer. */
+export interface SummaryFooterVars {
+  model: string;
+  /** Requested message count for the run. */
+  count: number;
+  /** Summarized channel, rendered as a `<#C…>` link for `{channel}`. */
+  channelId?: string;
+}
+
+/** Slack's limit on a context block's text element. */
+export const CONTEXT_TEXT_MAX_CHARS = 3_000;
+
+/**
+ * Fill `{model}`, `{count}`, and `{channel}` in the configured footer
+ * template. Returns `null` when no footer is configured (or it renders blank).
+ */
+export function renderSummaryFooter(
+  template: string | null,
+  vars: SummaryFooterVars
+): string | null {
+  if (!template) {
+    return null;
+  }
+  const rendered = template
+    .replace(/\{model\}/g, vars.model)
+    .replace(/\{count\}/g, String(vars.count))
+    .replace(/\{channel\}/g, vars.channelId ? `<#${vars.channelId}>` : 'the channel')
+    .trim();
+  return rendered.length > 0 ? rendered : null;
+}
+
+/**
+ * Context block carrying the rendered footer, or nothing. Shared by the
+ * streaming finaliser and non-streaming delivery so the footer always sits
+ * directly under the summary, above the action buttons.
+ */
+export function buildSummaryFooterBlocks(footer: string | null): KnownBlock[] {
+  if (!footer) {
+    return [];
+  }
+  const text =
+    footer.length > CONTEXT_TEXT_MAX_CHARS
+      ? `${footer.slice(0, CONTEXT_TEXT_MAX_CHARS - 1)}…`
+      : footer;
+  const block: ContextBlock = {
+    type: 'context',
+    elements: [{ type: 'mrkdwn', text }],
+  };
   return [block];
 }
+
+/** Where a finished summary goes. */
+export type DeliveryTarget =
+  | { kind: 'assistant_thread' }
+  | { kind: 'replace'; ts: string }
+  | { kind: 'channel'; channelId: string; crossChannel: boolean };
+
+/**
+ * Decide where to post. A `replaceTs` overwrites that earlier summary in the
+ * assistant thread; no post channel keeps the summary in the assistant
+ * thread; otherwise it goes to that channel, flagged as cross-channel when it
+ * differs from the source.
+ */
+export function resolveDeliveryTarget(
+  sourceChannelId: string,
+  postChannelId: string | null,
+  replaceTs: string | null = null
+): DeliveryTarget {
+  if (replaceTs) {
+    return { kind: 'replace', ts: replaceTs };
+  }
+  if (!postChannelId) {
+    return { kind: 'assistant_thread' };
+  }
+  return {
+    kind: 'channel',
+    channelId: postChannelId,
+    crossChannel: postChannelId !== sourceChannelId,
+  };
+}
+
+/** Prefix a channel post with "Summary of #source" when it lands somewhere else. */
+export function buildChannelDeliveryText(
+  summary: string,
+  sourceChannelId: string,
+  target: { crossChannel: boolean }
+): string {
+  if (!target.crossChannel) {
+    return summary;
+  }
+  return `_Summary of <#${sourceChannelId}>_\n\n${summary}`;
+}
+
+/**
+ * Split `text` into "(i/n) "-labelled parts of at most `maxChars` codepoints,
+ * breaking on paragraphs first (see `takeStreamChunk`). Text that already fits
+ * comes back as a single unlabelled part.
+ */
+export function splitForSlackMessages(
+  text: string,
+  maxChars: number = SLACK_MESSAGE_CHAR_LIMIT
+): string[] {
+  if ([...text].length <= maxChars) {
+    return [text];
+  }
+  const parts = splitIntoChunks(text, maxChars - PART_LABEL_RESERVE)
+    .map((part) => part.trim())
+    .filter((part) => part.length > 0);
+  return parts.map((part, i) => `(${i + 1}/${parts.length}) ${part}`);
+}
+
+/**
+ * Unfurl flags for a summary post. A summary lists up to ten links, and
+ * unfurling each of them buries the summary, so both are off by default.
+ */
+export function summaryUnfurlOptions(unfurl = false): {
+  unfurl_links: boolean;
+  unfurl_media: boolean;
+} {
+  return { unfurl_links: unfurl, unfurl_media: unfurl };
+}
+
+/**
+ * How far a split {@link postLongMessage} got. Pass the same object to every
+ * attempt of a retried delivery so a retry resumes after the last part that
+ * was posted instead of posting the earlier parts again.
+ */
+export interface LongMessageProgress {
+  partsPosted: number;
+  firstTs: string | null;
+}
+
+export function newLongMessageProgress(): LongMessageProgress {
+  return { partsPosted: 0, firstTs: null };
+}
+
+export interface PostLongMessageArgs {
+  channel: string;
+  threadTs?: string;
+  text: string;
+  blocks?: KnownBlock[];
+  metadata?: MessageMetadata;
+  /** Let Slack unfurl the listed links; see `AppConfig.unfurlSummaryLinks`. */
+  unfurlLinks?: boolean;
+  /** Shared across retries; see {@link LongMessageProgress}. */
+  progress?: LongMessageProgress;
+}
+
+/**
+ * `chat.postMessage` for a summary that survives `msg_too_long`: on that
+ * error the text is re-posted as sequential numbered parts, with `blocks` and
+ * `metadata` on the last part only. Every part carries
+ * {@link summaryUnfurlOptions}. Resolves with the ts of the (first) posted
+ * message, when Slack returned one.
+ */
+export async function postLongMessage(
+  client: WebClient,
+  args: PostLongMessageArgs
+): Promise<string | null> {
+  const unfurl = summaryUnfurlOptions(args.unfurlLinks);
+  const progress = args.progress ?? newLongMessageProgress();
+  if (progress.partsPosted > 0) {
+    return postRemainingParts(client, args, progress);
+  }
+  try {
+    const resp = await client.chat.postMessage({
+      channel: args.channel,
+      thread_ts: args.threadTs,
+      text: args.text,
+      blocks: args.blocks,
+      ...unfurl,
+      ...(args.metadata ? { metadata: args.metadata } : {}),
+    });
+    return resp?.ts ?? null;
+  } catch (err) {
+    if (!isMsgTooLongError(err)) {
+      throw err;
+    }
+    // Nothing to split by our count; don't retry the same payload.
+    if (splitForSlackMessages(args.text).length === 1) {
+      throw err;
+    }
+  }
+  return postRemainingParts(client, args, progress);
+}
+
+/** Post the numbered parts of `args.text` that `progress` hasn't seen posted yet. */
+async function postRemainingParts(
+  client: WebClient,
+  args: PostLongMessageArgs,
+  progress: LongMessageProgress
+): Promise<string | null> {
+  const unfurl = summaryUnfurlOptions(args.unfurlLinks);
+  const parts = splitForSlackMessages(args.text);
+  for (let i = progress.partsPosted; i < parts.length; i++) {
+    const last = i === parts.length - 1;
+    const resp = await client.chat.postMessage({
+      channel: args.channel,
+      thread_ts: args.threadTs,
+      text: parts[i],
+      blocks: last ? args.blocks : undefined,
+      ...unfurl,
+      ...(last && args.metadata ? { metadata: args.metadata } : {}),
+    });
+    progress.firstTs = progress.firstTs ?? resp?.ts ?? null;
+    progress.partsPosted = i + 1;
+  }
+  return progress.firstTs;
+}
+
+export type ChannelDeliveryResult =
+  | { ok: true; ts: string | null }
+  | { ok: false; reason: 'not_in_channel' };
+
+/**
+ * Token for publishing a summary into a channel: the configured user token
+ * when `postAsUser` is on, so the post appears from that user; otherwise, or
+ * when no user token is configured, the bot token.
+ */
+export function selectPostingToken(
+  config: Pick<AppConfig, 'postAsUser' | 'slackUserToken' | 'slackBotToken'>
+): string {
+  return config.postAsUser && config.slackUserToken ? config.slackUserToken : config.slackBotToken;
+}
+
+/**
+ * Post a summary into a channel. Slack's `not_in_channel` is reported back to
+ * the caller (so it can ask the user to invite the bot); other errors throw.
+ */
+export async function postSummaryToChannel(
+  client: WebClient,
+  channelId: string,
+  text: string,
+  unfurlLinks = false,
+  progress?: LongMessageProgress
+): Promise<ChannelDeliveryResult> {
+  try {
+    const ts = await postLongMessage(client, { channel: channelId, text, unfurlLinks, progress });
+    return { ok: true, ts };
+  } catch (err) {
+    if (isNotInChannelError(err)) {
+      return { ok: false, reason: 'not_in_channel' };
+    }
+    throw err;
+  }
+}
+
+/** DM text for `--notify-me`; falls back to the channel mention without a permalink. */
+export function buildNotifyMeText(channelId: string, permalink: string | null): string {
+  return permalink
+    ? `Your summary is ready in <#${channelId}>: ${permalink}`
+    : `Your summary is ready in <#${channelId}>.`;
+}
+
+/**
+ * DM `userId` a link to the summary posted at `ts` in `channelId`. Best
+ * effort: the summary is already delivered, so failures are only logged.
+ */
+export async function notifyRequester(
+  client: WebClient,
+  userId: string,
+  channelId: string,
+  ts: string | null
+): Promise<void> {
+  try {
+    const permalink = ts ? await getMessagePermalink(client, channelId, ts) : null;
+    await client.chat.postMessage({ channel: userId, text: buildNotifyMeText(channelId, permalink) });
+  } catch (err) {
+    log.warn('Failed to notify requester', {
+      error: err instanceof Error ? err.message : String(err),
+    });
+  }
+}
+
+/** Bookmark title for the latest published summary of `sourceName`. */
+export function summaryBookmarkTitle(sourceName: string): string {
+  return `Latest summary: #${sourceName}`;
+}
+
+/**
+ * Bookmark the summary posted at `ts` in `channelId`, one bookmark per source
+ * channel so it always points at the newest summary. Best effort, like
+ * {@link notifyRequester}.
+ */
+export async function bookmarkSummary(
+  client: WebClient,
+  args: { channelId: string; sourceChannelId: string; ts: string | null }
+): Promise<void> {
+  try {
+    const permalink = args.ts ? await getMessagePermalink(client, args.channelId, args.ts) : null;
+    if (!permalink) {
+      log.warn('No permalink for the published summary; skipping bookmark');
+      return;
+    }
+    const sourceName = await getChannelName(client, args.sourceChannelId);
+    await addBookmark(client, {
+      channelId: args.channelId,
+      title: summaryBookmarkTitle(sourceName),
+      link: permalink,
+    });
+  } catch (err) {
+    log.warn('Failed to bookmark summary', {
+      error: err instanceof Error ? err.message : String(err),
+    });
+  }
+}
+
+/**
+ * Reply shown in the assistant thread when the bot can't post to the target.
+ * The invite names `assistantName` (`AppConfig.assistantName`), or "TLDR".
+ */
+export function notInChannelMessage(channelId: string, assistantName?: string | null): string {
+  const name = assistantName?.trim() || 'TLDR';
+  return `I'm not a member of <#${channelId}>. Invite me with \`/invite @${name}\` and try again.`;
+}
+
+/** Pause before delivery retry `n` (1-based); grows linearly. */
+const DELIVERY_RETRY_DELAY_MS = 1_000;
+
+/**
+ * Run `deliver`, retrying up to `retries` more times while it fails with a
+ * transient Slack error. Permanent errors and the last failure are rethrown,
+ * and so are timeouts: a timed-out post may already have landed, and posting
+ * it again would duplicate the summary.
+ */
+export async function retryTransientDelivery<T>(
+  deliver: () => Promise<T>,
+  args: { retries: number; sleep?: (ms: number) => Promise<void> }
+): Promise<T> {
+  const sleep = args.sleep ?? ((ms: number) => new Promise<void>((r) => setTimeout(r, ms)));
+  for (let attempt = 0; ; attempt++) {
+    try {
+      return await deliver();
+    } catch (err) {
+      if (attempt >= args.retries || !isTransientSlackError(err) || isSlackTimeoutError(err)) {
+        throw err;
+      }
+      log.warn('Summary delivery failed; retrying', {
+        attempt: attempt + 1,
+        error: err instanceof Error ? err.message : String(err),
+      });
+      await sleep(DELIVERY_RETRY_DELAY_MS * (attempt + 1));
+    }
+  }
+}
+
+/** DM sent when the summary couldn't be posted where it was requested. */
+export function buildDeliveryFallbackText(summary: string): string {
+  return `I couldn't post your summary where you asked for it, so here it is:\n\n${summary}`;
+}
+
+/** Reply in the assistant thread when a channel post fell back to a DM. */
+export function dmFallbackNotice(channelId: string): string {
+  return `I couldn't post to <#${channelId}>, so I sent the summary to you by DM.`;
+}
+
+/** What `deliverOrDmRequester` did: `deliver`'s result, or the DM fallback. */
+export type DeliveryOutcome<T> = { dmed: false; result: T } | { dmed: true };
+
+/**
+ * Deliver a finished summary with `retryTransientDelivery`; if that still
+ * fails, DM the full text to `userId` instead. When the DM fails too, the
+ * original error is rethrown.
+ */
+export async function deliverOrDmRequester<T>(
+  client: WebClient,
+  args: {
+    deliver: () => Promise<T>;
+    userId: string;
+    summary: string;
+    retries: number;
+    sleep?: (ms: number) => Promise<void>;
+  }
+): Promise<DeliveryOutcome<T>> {
+  try {
+    const result = await retryTransientDelivery(args.deliver, {
+      retries: args.retries,
+      sleep: args.sleep,
+    });
+    return { dmed: false, result };
+  } catch (err) {
+    log.warn('Summary delivery failed; sending it to the requester by DM', {
+      error: err instanceof Error ? err.message : String(err),
+    });
+    try {
+      await postLongMessage(client, {
+        channel: args.userId,
+        text: buildDeliveryFallbackText(args.summary),
+      });
+    } catch (dmErr) {
+      log.warn('Fallback DM failed', {
+        error: dmErr instanceof Error ? dmErr.message : String(dmErr),
+      });
+      throw err;
+    }
+    return { dmed: true };
+  }
+}
+
+/** An earlier summary in the assistant thread, plus what it summarised. */
+export interface RefreshTarget {
+  /** ts of the summary message to overwrite. */
+  ts: string;
+  sourceChannelId: string;
+  messageCount: number;
+  style: string | null;
+}
+
+/**
+ * Pick the newest bot-authored summary in a thread. Summaries are recognised
+ * by their Share button, whose value already records the source channel,
+ * count, and style the summary was generated with.
+ */
+export function resolveRefreshTarget(
+  messages: Array<Pick<RecentMessage, 'ts' | 'user' | 'blocks'>>,
+  botUserId: string | null
+): RefreshTarget | null {
+  for (let i = messages.length - 1; i >= 0; i--) {
+    const msg = messages[i];
+    if (!msg.ts || (botUserId !== null && msg.user !== botUserId)) {
+      continue;
+    }
+    const share = findShareButtonValue(msg.blocks);
+    if (share) {
+      return {
+        ts: msg.ts,
+        sourceChannelId: share.sourceChannelId,
+        messageCount: share.count,
+        style: share.style,
+      };
+    }
+  }
+  return null;
+}
+
+/** Load the assistant thread and resolve the summary a `refresh` should replace. */
+export async function findRefreshTarget(
+  client: WebClient,
+  channelId: string,
+  threadTs: string
+): Promise<RefreshTarget | null> {
+  const [messages, botUserId] = await Promise.all([
+    getThreadMessages(client, channelId, threadTs, 1000),
+    getBotUserId(client),
+  ]);
+  return resolveRefreshTarget(messages, botUserId);
+}
+
+/** The Share button payload on a summary message's blocks, if it has one. */
+export function findShareButtonValue(blocks: unknown): ShareButtonValue | null {
+  if (!Array.isArray(blocks)) {
+    return null;
+  }
+  for (const block of blocks as Array<{ type?: string; elements?: unknown }>) {
+    if (block?.type !== 'actions' || !Array.isArray(block.elements)) {
+      continue;
+    }
+    for (const el of block.elements as Array<{ action_id?: string; value?: string }>) {
+      if (el.action_id !== 'share_summary' || typeof el.value !== 'string') {
+        continue;
+      }
+      try {
+        const parsed = JSON.parse(el.value) as Partial<ShareButtonValue>;
+        if (typeof parsed.sourceChannelId === 'string' && typeof parsed.count === 'number') {
+          return {
+            action: 'share_summary',
+            sourceChannelId: parsed.sourceChannelId,
+            count: parsed.count,
+            style: typeof parsed.style === 'string' ? parsed.style : null,
+          };
+        }
+      } catch {
+        // Not one of our payloads; keep looking.
+      }
+    }
+  }
+  return null;
+}
@@ -0,0 +1,51 @@
+/**
+ * Non-image files shared in a conversation (PDFs, docs, snippets). We never
+ * read their contents; the names and types go into the prompt so the summary
+ * can still mention them. Images are skipped here because they're inlined.
+ */
+
+import { canonicalizeMime, isAllowedImageMime, isGenericMime } from '../ai/images';
+import type { RecentMessage, SlackFile } from '../slack/client';
+
+/** Cap on files listed in the prompt and the safety-net section. */
+export const MAX_FILES_SHARED = 20;
+
+export interface SharedFile {
+  name: string;
+  /** Slack's `filetype` (e.g. `pdf`), else the MIME type, else `file`. */
+  type: string;
+}
+
+/**
+ * True when the file's MIME hint could be an allowed image. A missing or
+ * generic hint counts, since the download is sniffed before inlining.
+ */
+export function isImageCandidate(file: SlackFile): boolean {
+  const canonHint = canonicalizeMime(file.mimeType ?? '');
+  return canonHint === '' || isGenericMime(canonHint) || isAllowedImageMime(canonHint);
+}
+
+/** Named, non-image files in message order, deduplicated by name and type. */
+export function extractSharedFiles(messages: RecentMessage[]): SharedFile[] {
+  const out: SharedFile[] = [];
+  const seen = new Set<string>();
+  for (const msg of messages) {
+    for (const file of msg.files) {
+      const name = file.name?.trim();
+      if (!name || isImageCandidate(file)) {
+        continue;
+      }
+      const type = file.fileType?.trim() || canonicalizeMime(file.mimeType ?? '') || 'file';
+      const key = `${name}\u0000${type}`;
+      if (seen.has(key)) {
+        continue;
+      }
+      seen.add(key);
+      out.push({ name, type });
+      if (out.length >= MAX_FILES_SHARED) {
+        return out;
+      }
+    }
+  }
+  return out;
+}
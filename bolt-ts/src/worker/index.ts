@@ -1,6 +1,8 @@
 export * from './chunks';
 export * from './links';
+export * from './mentions';
 export * from './deliver';
 export * from './prompt_builder';
 export * from './streaming';
+export * from './structured';
 export * from './summarize';
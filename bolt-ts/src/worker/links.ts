@@ -3,6 +3,8 @@
  *  - Slack-link markup `<URL|label>` / `<URL>`
  *  - Raw URLs in message text and JSON-encoded blocks/attachments
  * and filter out Slack-permalink/file URLs (those are surfaced as "Receipts").
+ * Extracted links can then be classified by what they point at (GitHub PR,
+ * Jira ticket, Google Doc, …) so the prompt and safety net can tag them.
  */
 
 const SLACK_LINK_RE = /<(https?:\/\/[^>|\s>]+)(?:\|[^>]+)?>/g;
@@ -98,6 +100,11 @@ export function normaliseAndDedupe(rawLinks: Iterable<string>): string[] {
   return out;
 }
 
+/** `slack.com` or one of its subdomains; a bare suffix check would accept `evilslack.com`. */
+function isSlackHost(host: string): boolean {
+  return host === 'slack.com' || host.endsWith('.slack.com');
+}
+
 function normaliseLink(raw: string): string | null {
   let cleaned = raw.trim().replace(/^[<"']+|[>"']+$/g, '');
   if (!(cleaned.startsWith('http://') || cleaned.startsWith('https://'))) {
@@ -114,11 +121,11 @@ function normaliseLink(raw: string): string | null {
 
   const host = url.hostname.toLowerCase();
   const path = url.pathname;
-  const isMessagePermalink = host.endsWith('slack.com') && path.includes('/archives/');
+  const isMessagePermalink = isSlackHost(host) && path.includes('/archives/');
   const isFileUrl =
     host === 'slack-files.com' ||
     host === 'files.slack.com' ||
-    (host.endsWith('slack.com') && path.includes('/files-pri/'));
+    (isSlackHost(host) && path.includes('/files-pri/'));
 
   if (isMessagePermalink || isFileUrl) {
     return null;
@@ -131,6 +138,103 @@ function normaliseLink(raw: string): string | null {
   return cleaned;
 }
 
+/** What a shared link points at; drives the `[PR]`-style tag shown next to it. */
+export type LinkKind = 'github_pr' | 'github_issue' | 'jira' | 'google_doc' | 'generic';
+
+const LINK_KIND_TAGS: Record<LinkKind, string | null> = {
+  github_pr: 'PR',
+  github_issue: 'Issue',
+  jira: 'Jira',
+  google_doc: 'Doc',
+  generic: null,
+};
+
+const GITHUB_ITEM_PATH_RE = /^\/[^/]+\/[^/]+\/(pull|issues)\/\d+(?:\/|$)/;
+const JIRA_KEY_RE = /^[A-Z][A-Z0-9]+-\d+$/;
+const JIRA_BROWSE_PATH_RE = /\/browse\/([A-Z][A-Z0-9]+-\d+)\/?$/;
+const GOOGLE_DOC_PATH_RE = /^\/(document|spreadsheets|presentation|forms)\/d\//;
+
+/**
+ * Classify a link by host and path. Anything unrecognised — including
+ * shortened links (bit.ly, t.co, …), whose target we can't see without
+ * fetching them — is `generic`.
+ */
+export function classifyLink(raw: string): LinkKind {
+  let url: URL;
+  try {
+    url = new URL(raw);
+  } catch {
+    return 'generic';
+  }
+  const host = url.hostname.toLowerCase().replace(/^www\./, '');
+  const path = url.pathname;
+
+  if (host === 'github.com') {
+    const match = path.match(GITHUB_ITEM_PATH_RE);
+    if (match) {
+      return match[1] === 'pull' ? 'github_pr' : 'github_issue';
+    }
+    return 'generic';
+  }
+  if (host === 'docs.google.com' && GOOGLE_DOC_PATH_RE.test(path)) {
+    return 'google_doc';
+  }
+  if (JIRA_BROWSE_PATH_RE.test(path)) {
+    return 'jira';
+  }
+  const selectedIssue = url.searchParams.get('selectedIssue');
+  if (host.endsWith('.atlassian.net') && selectedIssue && JIRA_KEY_RE.test(selectedIssue)) {
+    return 'jira';
+  }
+  return 'generic';
+}
+
+/** `[PR] https://github.com/…` for a recognised link; generic links come back unchanged. */
+export function tagLink(link: string): string {
+  const tag = LINK_KIND_TAGS[classifyLink(link)];
+  return tag ? `[${tag}] ${link}` : link;
+}
+
+/** Channel + message coordinates decoded from a Slack message permalink. */
+export interface SlackPermalink {
+  channelId: string;
+  /** The linked message's ts. */
+  ts: string;
+  /** Parent thread ts when the link points at a reply, else `null`. */
+  threadTs: string | null;
+}
+
+const PERMALINK_PATH_RE = /\/archives\/([A-Z][A-Z0-9]+)\/p(\d{10})(\d{6})\/?$/;
+
+/**
+ * Decode a Slack message permalink, e.g.
+ * `https://acme.slack.com/archives/C123/p1700000000000100?thread_ts=1699999999.000200`.
+ * Accepts the `<url>` / `<url|label>` markup Slack wraps pasted links in.
+ * Returns `null` for anything that isn't a Slack message permalink.
+ */
+export function parseSlackPermalink(raw: string): SlackPermalink | null {
+  const unwrapped = raw.trim().replace(/^</, '').replace(/(\|[^>]*)?>$/, '');
+  let url: URL;
+  try {
+    url = new URL(unwrapped);
+  } catch {
+    return null;
+  }
+  if (!isSlackHost(url.hostname.toLowerCase())) {
+    return null;
+  }
+  const match = url.pathname.match(PERMALINK_PATH_RE);
+  if (!match) {
+    return null;
+  }
+  const threadTs = url.searchParams.get('thread_ts');
+  return {
+    channelId: match[1],
+    ts: `${match[2]}.${match[3]}`,
+    threadTs: threadTs && /^\d{10}\.\d{6}$/.test(threadTs) ? threadTs : null,
+  };
+}
+
 function trimTrailingPunct(value: string): string {
   return value.replace(TRAILING_PUNCT, '');
 }
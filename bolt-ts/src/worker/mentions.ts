@@ -0,0 +1,268 @@
+/**
+ * "My mentions" personal digest.
+ *
+ * Searches for messages that @-mention the requester across every channel
+ * they belong to, then summarises them with the standard prompt — each line
+ * labelled with its source channel so the model can group by conversation.
+ *
+ * `search.messages` only accepts user tokens, so this flow needs
+ * `AppConfig.slackUserToken`; without it we reply with a short explanation.
+ */
+
+import { WebClient } from '@slack/web-api';
+import type { LlmClient } from '../ai/anthropic';
+import { buildPrompt, type PromptPayload } from '../ai/prompt';
+import type { AppConfig } from '../config';
+import { createDeadline, currentDeadline, runWithinDeadline, type Deadline } from '../deadline';
+import { log } from '../logging';
+import {
+  emitSummaryMetrics,
+  newSummaryRunStats,
+  recordOutputStats,
+  recordPromptOverflow,
+  recordPromptStats,
+  type SummaryRunStats,
+} from '../metrics';
+import { isUserMemberOfChannel, type ConversationsMembersClient } from '../security';
+import { slackClientOptions } from '../slack/retry';
+import { sanitizeGeneratedSlackMrkdwn } from '../slack/sanitize';
+import {
+  buildMentionSearchQuery,
+  getChannelNames,
+  getUserDisplayNames,
+  searchMessages,
+  type SearchMatch,
+} from '../slack/client';
+import { extractLinksFromMessages } from './links';
+import { applySafetyNetSections } from './prompt_builder';
+import { failureMessageFor } from './streaming';
+import { createLlmClient, runInSummarySlot } from './summarize';
+
+export const MENTIONS_TOKEN_MISSING_MESSAGE =
+  "The mentions digest isn't enabled for this workspace — an admin needs to configure a Slack user token with `search:read`.";
+export const NO_MENTIONS_MESSAGE = 'No recent mentions found.';
+
+const MAX_RECEIPTS = 12;
+
+export interface MentionsDigestRequest {
+  correlationId: string;
+  userId: string;
+  /** Assistant DM channel where we'll reply. */
+  originChannelId: string;
+  /** Parent thread ts for replies. */
+  threadTs: string;
+  messageCount: number;
+  customStyle: string | null;
+  /** Workspace the request came from; org-level search tokens need it. */
+  teamId: string | null;
+  enterpriseId: string | null;
+  /** Leave user mentions in the digest live; see `sanitizeGeneratedSlackMrkdwn`. */
+  allowMentions: boolean;
+}
+
+interface RunMentionsArgs {
+  config: AppConfig;
+  client: WebClient;
+  request: MentionsDigestRequest;
+  /** Client authenticated with the user token. Built from config when omitted. */
+  searchClient?: WebClient;
+  llm?: LlmClient;
+  /** Defaults to the invocation's deadline, else `config.taskDeadlineSecs` from now. */
+  deadline?: Deadline;
+}
+
+/**
+ * Search, filter, summarise, and post the mentions digest into the assistant
+ * thread. Shares the summary slots, deadline, and metrics of `runSummarization`.
+ */
+export async function runMentionsDigest(args: RunMentionsArgs): Promise<void> {
+  return runInSummarySlot(args.config, args.request, () => digestMentions(args));
+}
+
+async function digestMentions(args: RunMentionsArgs): Promise<void> {
+  const stats = newSummaryRunStats();
+  const startedAt = Date.now();
+  let success = false;
+  try {
+    success = await deliverMentionsDigest(args, stats);
+  } finally {
+    emitSummaryMetrics({
+      stats,
+      latencyMs: Date.now() - startedAt,
+      success,
+      delivery: 'mentions',
+    });
+  }
+}
+
+/** Build and post the digest. Resolves `false` when it failed and the user was told so. */
+async function deliverMentionsDigest(
+  args: RunMentionsArgs,
+  stats: SummaryRunStats
+): Promise<boolean> {
+  const { config, client, request } = args;
+  const reply = async (text: string): Promise<void> => {
+    await client.chat.postMessage({
+      channel: request.originChannelId,
+      thread_ts: request.threadTs,
+      text,
+    });
+  };
+
+  const searchClient =
+    args.searchClient ??
+    (config.slackUserToken
+      ? new WebClient(config.slackUserToken, slackClientOptions(config))
+      : null);
+  if (!searchClient) {
+    await reply(MENTIONS_TOKEN_MISSING_MESSAGE);
+    return false;
+  }
+
+  const llm = args.llm ?? createLlmClient(config, request.customStyle);
+  const deadline =
+    args.deadline ?? currentDeadline() ?? createDeadline(config.taskDeadlineSecs * 1000);
+
+  try {
+    const digest = await runWithinDeadline(deadline, async () => {
+      const matches = await searchMessages(
+        searchClient,
+        buildMentionSearchQuery(request.userId),
+        Math.min(request.messageCount, config.hardMessageCap),
+        request.teamId
+      );
+      const visible = await filterToMemberChannels(client, matches, request.userId);
+      if (visible.length === 0) {
+        return null;
+      }
+      const promptData = await buildMentionsPromptData(
+        client,
+        visible,
+        request.customStyle,
+        config.systemPromptOverride,
+        config.assistantName,
+        config.summarySections
+      );
+      recordPromptStats(stats, visible.length, promptData.prompt);
+      recordPromptOverflow(promptData.prompt, llm.maxOutputTokens, 'non_streaming');
+      const summary = await llm.generateSummary(promptData.prompt);
+      recordOutputStats(stats, summary);
+      return applySafetyNetSections(summary, promptData);
+    });
+    if (digest === null) {
+      await reply(NO_MENTIONS_MESSAGE);
+      return true;
+    }
+    await reply(
+      sanitizeGeneratedSlackMrkdwn('*Your mentions digest*\n\n' + digest, {
+        allowUserMentions: request.allowMentions,
+      })
+    );
+    return true;
+  } catch (err) {
+    log.error('Mentions digest failed', {
+      error: err instanceof Error ? err.message : String(err),
+    });
+    try {
+      await reply(failureMessageFor(err));
+    } catch (followup) {
+      log.error('Failed to post canonical failure', {
+        error: followup instanceof Error ? followup.message : String(followup),
+      });
+    }
+    return false;
+  }
+}
+
+/** Format a search hit as a prompt line labelled with its source channel. */
+export function formatMentionLine(match: SearchMatch, author: string): string {
+  const channel = match.channelName
+    ? `#${match.channelName}`
+    : match.channelId ?? 'unknown channel';
+  return `[${match.ts}] ${channel} — ${author}: ${match.text}`;
+}
+
+/**
+ * Drop hits from channels the requester can't read. A shared user token may
+ * see more than the requester, so membership is re-checked per channel.
+ */
+async function filterToMemberChannels(
+  client: WebClient,
+  matches: SearchMatch[],
+  userId: string
+): Promise<SearchMatch[]> {
+  const channelIds = [...new Set(matches.map((m) => m.channelId).filter((id): id is string => !!id))];
+  const readable = new Set<string>();
+  await Promise.all(
+    channelIds.map(async (channelId) => {
+      const ok = await isUserMemberOfChannel({
+        client: client as unknown as ConversationsMembersClient,
+        channelId,
+        userId,
+        logger: log,
+      });
+      if (ok) {
+        readable.add(channelId);
+      }
+    })
+  );
+  return matches.filter((m) => m.channelId !== null && readable.has(m.channelId));
+}
+
+async function buildMentionsPromptData(
+  client: WebClient,
+  matches: SearchMatch[],
+  customStyle: string | null,
+  systemPrompt: string | null,
+  assistantName: string | null,
+  sections: readonly string[]
+): Promise<{
+  prompt: PromptPayload;
+  linksShared: string[];
+  receiptPermalinks: string[];
+  hasAnyImages: boolean;
+  sections: readonly string[];
+}> {
+  const userIds = [...new Set(matches.map((m) => m.user).filter((id): id is string => !!id))];
+  const names = await getUserDisplayNames(client, userIds);
+  // Hits span many channels, so names search left out come from one listing.
+  const unnamed = matches
+    .filter((m) => !m.channelName && m.channelId)
+    .map((m) => m.channelId as string);
+  const channelNames = unnamed.length > 0 ? await getChannelNames(client, unnamed) : null;
+  const channelNameOf = (m: SearchMatch): string | null => {
+    const resolved = m.channelId ? channelNames?.get(m.channelId) : undefined;
+    return m.channelName ?? (resolved && resolved !== m.channelId ? resolved : null);
+  };
+  const authorOf = (m: SearchMatch): string =>
+    m.user ? names.get(m.user) ?? m.user : 'Unknown User';
+
+  const linksShared = extractLinksFromMessages(matches);
+  // Search hits already carry permalinks, so receipts need no extra API calls.
+  const receipts = matches
+    .filter((m): m is SearchMatch & { permalink: string } => m.permalink !== null)
+    .slice(0, MAX_RECEIPTS)
+    .map((m) => ({ permalink: m.permalink, author: authorOf(m), snippet: '' }));
+
+  const prompt = buildPrompt({
+    channelName: 'mentions across your channels',
+    formattedMessages: matches.map((m) =>
+      formatMentionLine({ ...m, channelName: channelNameOf(m) }, authorOf(m))
+    ),
+    linksShared,
+    receipts,
+    images: [],
+    customStyle,
+    sections,
+    systemPrompt,
+    assistantName,
+  });
+
+  return {
+    prompt,
+    linksShared,
+    receiptPermalinks: receipts.map((r) => r.permalink),
+    hasAnyImages: false,
+    sections,
+  };
+}
@@ -2,42 +2,97 @@
  * Compose the "Slack messages → Anthropic prompt" pipeline.
  *
  *  1. Fetch the channel name and the unique senders' display names.
- *  2. Format each message as `[ts] author: text`.
+ *  2. Format each message as `[ts] author: text` (thread roots are labelled
+ *     `Thread topic:` so the model anchors on them).
  *  3. Extract shared links, drop Slack permalinks/files.
  *  4. Pick up to 12 receipt messages (preferring ones with files/links), fetch
  *     their permalinks.
- *  5. Download inline images (per-file size cap, MIME guard) and convert them
- *     into Anthropic image content blocks.
+ *  5. Download inline images (per-file size cap, MIME guard, bounded
+ *     concurrency) and convert them into Anthropic image content blocks.
  */
 
 import type { WebClient } from '@slack/web-api';
-import { buildPrompt as buildBasePrompt, type ImageBlock, type PromptPayload } from '../ai/prompt';
-import { canonicalizeMime, isAllowedImageMime, buildImageBlock } from '../ai/images';
+import {
+  buildPrompt as buildBasePrompt,
+  DEFAULT_SUMMARY_SECTIONS,
+  FILES_SHARED_SECTION,
+  type BuildPromptArgs,
+  type ImageBlock,
+  type PromptPayload,
+} from '../ai/prompt';
+import {
+  buildImageBlock,
+  canonicalizeMime,
+  isAllowedImageMime,
+  isGenericMime,
+  sniffImageMime,
+} from '../ai/images';
 import {
   downloadImageBytes,
   fetchImageHead,
+  getCanvasContent,
+  getChannelCanvasId,
   getChannelName,
-  getMessagePermalink,
-  getUserDisplayName,
+  getSingleMessage,
+  getMessagePermalinks,
+  getPinnedMessages,
+  getRecentMessages,
+  getThreadMessages,
+  getUserDisplayNames,
   pickFileDownloadUrl,
+  type HistoryBounds,
   type RecentMessage,
 } from '../slack/client';
-import { extractLinksFromMessage, extractLinksFromMessages } from './links';
+import { detectLanguage, languageName } from '../lang';
+import { extractTextFromBlocks } from '../slack/rich_text';
+import type { SummaryTemplate, SummaryTone } from '../types';
+import { extractAttachmentText } from './attachments';
+import { extractSharedFiles, isImageCandidate, type SharedFile } from './files';
+import { extractLinksFromMessage, extractLinksFromMessages, tagLink } from './links';
+import { formatAuthorGroups, groupMessagesByAuthor } from './standup';
+import { fetchThreadReplies, formatThreadGroups, groupMessagesByThread } from './threads';
+import { topReacted, totalReactions } from './reactions';
 
-/** Inline-image ceiling (bytes). Modern multimodal models accept larger
+/** Default inline-image ceiling (bytes) when the caller doesn't pass
+ *  `inlineImageMaxBytes`. Modern multimodal models accept larger
  *  attachments, but we keep an upper bound to protect Lambda memory and
  *  Anthropic per-request size limits. */
-export const INLINE_IMAGE_MAX_BYTES = 4 * 1024 * 1024; // 4 MiB
+export const DEFAULT_INLINE_IMAGE_MAX_BYTES = 4 * 1024 * 1024; // 4 MiB
 /** Conservative cap on inline images per summary to keep prompts focused. */
 export const MAX_IMAGES_TOTAL = 8;
+/** Parallel image downloads per summary when the caller doesn't say otherwise. */
+export const DEFAULT_IMAGE_DOWNLOAD_CONCURRENCY = 3;
+/** Reply posted instead of a summary when a pinned-only run finds no pins. */
+export const NO_PINNED_MESSAGES_MESSAGE =
+  'There are no pinned messages in that channel yet. Try `summarize` for recent history instead.';
+/** Reply posted instead of a summary when `--since-last-summary` finds nothing newer. */
+export const NO_NEW_MESSAGES_MESSAGE =
+  'Nothing new since the last summary of that channel. Try `summarize` to recap recent history.';
+/** Reply posted instead of a summary when a canvas run finds no channel canvas. */
+export const NO_CANVAS_MESSAGE =
+  "That channel doesn't have a canvas yet. Try `summarize` for recent history instead.";
+/** Reply posted instead of an image description when `disableImages` is on. */
+export const IMAGES_DISABLED_MESSAGE =
+  "Image processing is turned off for this workspace, so I can't describe those files.";
 const MAX_RECEIPTS = 12;
 const MAX_SNIPPET_CHARS = 100;
+const MAX_MOST_REACTED = 3;
 
 export interface SummarizePromptData {
   prompt: PromptPayload;
+  /** Inputs behind `prompt`, kept so an oversized run can be re-windowed for a rollup. */
+  promptArgs: BuildPromptArgs;
   linksShared: string[];
+  /** Non-image files shared in the conversation; see `extractSharedFiles`. */
+  filesShared: SharedFile[];
   receiptPermalinks: string[];
   hasAnyImages: boolean;
+  /** False when receipts were skipped by config; the safety net says so. */
+  receiptsEnabled?: boolean;
+  /** False when images were skipped by config (`disableImages`); likewise. */
+  imagesEnabled?: boolean;
+  /** Required sections the prompt asked for; the safety net fills in missing ones. */
+  sections?: readonly string[];
 }
 
 interface Receipt {
@@ -52,166 +107,662 @@ export interface BuildPromptDataArgs {
   channelId: string;
   messages: RecentMessage[];
   customStyle: string | null;
+  /** Tone preset layered under `customStyle`; see `TONE_DIRECTIVES`. */
+  tone?: SummaryTone | null;
+  /** Skip `chat.getPermalink` receipt lookups when false. Defaults to true. */
+  enableReceipts?: boolean;
+  /** Append link-unfurl / attachment text to each message line. Defaults to true. */
+  includeAttachmentText?: boolean;
+  /** Root ts when summarizing a single thread; `messages` then come from `conversations.replies`. */
+  threadTs?: string | null;
+  /**
+   * Fetch replies for recent threads and render the channel as topics with
+   * their replies (see `worker/threads.ts`). Ignored for single-thread runs.
+   */
+  groupByThread?: boolean;
+  /**
+   * Only inline the messages' images and ask the model to describe them; no
+   * transcript, links, or receipts are sent.
+   */
+  filesOnly?: boolean;
+  /**
+   * `standup` renders messages grouped by author (see `worker/standup.ts`)
+   * and asks for a per-person digest. Takes precedence over `groupByThread`.
+   */
+  template?: SummaryTemplate | null;
+  /** Keep only messages containing this word; see `filterMessagesByKeyword`. */
+  keywordFilter?: string | null;
+  /** `messages` are the channel's pins (`getPinnedMessages`), not its recent history. */
+  pinnedOnly?: boolean;
+  /**
+   * `messages` is the channel canvas as one document (`fetchCanvasMessages`).
+   * It is sent verbatim, without author / ts framing or receipts.
+   */
+  canvasOnly?: boolean;
+  /** Replaces the built-in system prompt; see `AppConfig.systemPromptOverride`. */
+  systemPromptOverride?: string | null;
+  /** Name the system prompt gives the assistant; see `AppConfig.assistantName`. */
+  assistantName?: string | null;
+  /** Max image downloads in flight. Defaults to `DEFAULT_IMAGE_DOWNLOAD_CONCURRENCY`. */
+  imageDownloadConcurrency?: number;
+  /** Skip images larger than this. Defaults to `DEFAULT_INLINE_IMAGE_MAX_BYTES`. */
+  inlineImageMaxBytes?: number;
+  /** Don't look at message images at all; see `AppConfig.disableImages`. */
+  disableImages?: boolean;
+  /** Cite receipts inline in the Summary; see `AppConfig.citeInline`. */
+  citeInline?: boolean;
+  /** Required summary sections in order; see `AppConfig.summarySections`. */
+  summarySections?: readonly string[];
+  /** Answer in the conversation's language; see `AppConfig.detectSummaryLanguage`. */
+  detectSummaryLanguage?: boolean;
+  /** Max `chat.getPermalink` calls in flight; see `getMessagePermalinks`. */
+  permalinkConcurrency?: number;
+  /** Workspace the run belongs to; authors from any other team are external. */
+  teamId?: string | null;
   /** Injected for tests. */
   fetchImpl?: typeof fetch;
 }
 
+/**
+ * Fetch the messages to summarize: a thread when `threadTs` is set, the
+ * channel's pins with `pinnedOnly`, else recent channel history within
+ * `bounds`. With `filesOnly`, `threadTs` names a single message and only that
+ * message is returned.
+ */
+export async function fetchSourceMessages(
+  client: WebClient,
+  channelId: string,
+  count: number,
+  threadTs: string | null,
+  filesOnly = false,
+  pinnedOnly = false,
+  bounds: HistoryBounds = {}
+): Promise<RecentMessage[]> {
+  if (filesOnly && threadTs) {
+    const message = await getSingleMessage(client, channelId, threadTs);
+    return message ? [message] : [];
+  }
+  if (threadTs) {
+    return getThreadMessages(client, channelId, threadTs, count);
+  }
+  if (pinnedOnly) {
+    return getPinnedMessages(client, channelId);
+  }
+  return getRecentMessages(client, channelId, count, bounds);
+}
+
+/**
+ * True when a run reads a slice of recent channel history rather than a
+ * thread, one message's files, the pins, or the canvas. Only these runs
+ * record the range they covered for `--since-last-summary`.
+ */
+export function isChannelHistoryRun(args: {
+  threadTs?: string | null;
+  filesOnly?: boolean;
+  pinnedOnly?: boolean;
+  canvasOnly?: boolean;
+}): boolean {
+  return !args.threadTs && !args.filesOnly && !args.pinnedOnly && !args.canvasOnly;
+}
+
+/**
+ * The channel's canvas as a single pseudo-message whose text is the canvas
+ * title and markdown, so it can flow through the normal summary pipeline.
+ * Empty when the channel has no canvas or its content can't be read.
+ */
+export async function fetchCanvasMessages(
+  client: WebClient,
+  channelId: string,
+  botToken: string,
+  fetchImpl?: typeof fetch
+): Promise<RecentMessage[]> {
+  const canvasId = await getChannelCanvasId(client, channelId);
+  if (!canvasId) {
+    return [];
+  }
+  const canvas = await getCanvasContent(client, { canvasId, botToken, fetchImpl });
+  if (!canvas || canvas.markdown.length === 0) {
+    return [];
+  }
+  return [
+    {
+      ts: canvas.updated !== null ? String(canvas.updated) : '',
+      user: null,
+      text: canvas.title ? `# ${canvas.title}\n\n${canvas.markdown}` : canvas.markdown,
+      files: [],
+    },
+  ];
+}
+
+/**
+ * A posted summary's opening: an optional `(1/2) ` part label from
+ * `postLongMessage`, an optional `_Style: …_` line, then the title from
+ * `buildStreamPrefix`.
+ */
+const SUMMARY_HEADER_RE = /^(?:\(\d+\/\d+\) )?(?:_Style: [^\n]*_\s*)?\*Summary from /;
+
+/** True when `msg` is an earlier summary, judged by its header alone. */
+export function isPriorSummary(msg: RecentMessage): boolean {
+  return SUMMARY_HEADER_RE.test(msg.text.trimStart());
+}
+
+/**
+ * Keep one copy of each thread broadcast. A reply sent "also to channel" can
+ * come back both as the reply and as its `thread_broadcast` copy with the same
+ * ts and thread ts; the reply is kept so it stays with its thread.
+ */
+export function collapseThreadBroadcasts(messages: RecentMessage[]): RecentMessage[] {
+  const key = (m: RecentMessage): string => `${m.threadTs ?? m.ts}:${m.ts}`;
+  const replies = new Set(messages.filter((m) => !m.broadcast).map(key));
+  const seen = new Set<string>();
+  return messages.filter((m) => {
+    if (!m.broadcast) {
+      return true;
+    }
+    const k = key(m);
+    if (replies.has(k) || seen.has(k)) {
+      return false;
+    }
+    seen.add(k);
+    return true;
+  });
+}
+
+/**
+ * Drop messages that shouldn't be summarised: the bot's own posts (so it never
+ * summarises itself), anything from `ignoredUserIds`, matched against both
+ * the author's user id and the posting integration's bot id, any earlier
+ * summary whoever posted it ({@link isPriorSummary}), and duplicate thread
+ * broadcasts ({@link collapseThreadBroadcasts}). Summaries published with
+ * `POST_AS_USER` or pasted by hand carry a person's id, so only the header
+ * keeps them out.
+ */
+export function filterUserMessages(
+  messages: RecentMessage[],
+  botUserId: string | null,
+  ignoredUserIds: readonly string[] = []
+): RecentMessage[] {
+  const ignored = new Set(ignoredUserIds);
+  if (botUserId) {
+    ignored.add(botUserId);
+  }
+  return collapseThreadBroadcasts(
+    messages.filter(
+      (m) =>
+        !(m.user !== null && ignored.has(m.user)) &&
+        !(m.botId && ignored.has(m.botId)) &&
+        !isPriorSummary(m)
+    )
+  );
+}
+
+/**
+ * Keep messages whose text contains `keyword`, case-insensitively. Rich-text
+ * only messages are matched on their block text, as in the prompt lines.
+ */
+export function filterMessagesByKeyword(
+  messages: RecentMessage[],
+  keyword: string | null
+): RecentMessage[] {
+  const needle = keyword?.trim().toLowerCase() ?? '';
+  if (needle.length === 0) {
+    return messages;
+  }
+  return messages.filter((m) => {
+    const body = m.text.trim().length > 0 ? m.text : extractTextFromBlocks(m.blocks);
+    return body.toLowerCase().includes(needle);
+  });
+}
+
+/** Reply posted instead of a summary when no message matched `contains=`. */
+export function noKeywordMatchesMessage(keyword: string): string {
+  return `No messages mentioning "${keyword}" turned up. Try a larger \`last N\` or a different word.`;
+}
+
+/**
+ * Format messages as `[ts] author: text` prompt lines. When `threadRootTs` is
+ * set, the root is labelled `Thread topic:` exactly once — Slack can return it
+ * both as `messages[0]` and again among the replies.
+ */
+export function formatMessagesForPrompt(
+  messages: RecentMessage[],
+  userNames: Map<string, string>,
+  threadRootTs: string | null = null,
+  includeAttachmentText = true
+): string[] {
+  const lines: string[] = [];
+  let rootSeen = false;
+  for (const msg of messages) {
+    const author = msg.user ? userNames.get(msg.user) ?? msg.user : 'Unknown User';
+    // Messages posted purely as rich_text blocks arrive with empty `text`.
+    let body = msg.text.trim().length > 0 ? msg.text : extractTextFromBlocks(msg.blocks);
+    if (msg.edited) {
+      body += ' (edited)';
+    }
+    if (includeAttachmentText) {
+      for (const preview of extractAttachmentText(msg)) {
+        body += ` [attachment: ${preview}]`;
+      }
+    }
+    const line = `[${msg.ts}] ${author}: ${body}`;
+    if (threadRootTs !== null && msg.ts === threadRootTs) {
+      if (rootSeen) {
+        continue;
+      }
+      rootSeen = true;
+      lines.push(`Thread topic: ${line}`);
+      continue;
+    }
+    lines.push(line);
+  }
+  return lines;
+}
+
+/** Characters of message text sampled for {@link detectSummaryLanguage}. */
+const LANGUAGE_SAMPLE_CHARS = 4_000;
+
+/**
+ * The language to write the summary in when it isn't English: detected from
+ * a sample of the messages' text, or `null` when they are English or the
+ * sample is too short or mixed to call.
+ */
+export function detectSummaryLanguage(messages: RecentMessage[]): string | null {
+  const sample = messages
+    .map((msg) => msg.text)
+    .join('\n')
+    .slice(0, LANGUAGE_SAMPLE_CHARS);
+  const detected = detectLanguage(sample);
+  return detected && detected !== 'en' ? languageName(detected) : null;
+}
+
 export async function buildSummarizePromptData(
   args: BuildPromptDataArgs
 ): Promise<SummarizePromptData> {
-  const { client, channelId, messages, customStyle } = args;
+  const { client, channelId, customStyle } = args;
   const fetchImpl = args.fetchImpl ?? fetch;
+  const messages = filterMessagesByKeyword(args.messages, args.keywordFilter ?? null);
+
+  if (args.filesOnly) {
+    return buildFilesOnlyPromptData(args, fetchImpl);
+  }
+
+  const standup = args.template === 'standup';
+  const repliesByRoot =
+    args.groupByThread && !args.threadTs && !standup
+      ? await fetchThreadReplies(client, channelId, messages)
+      : null;
+  const allReplies = repliesByRoot ? [...repliesByRoot.values()].flat() : [];
 
   const [channelName, userNames] = await Promise.all([
     getChannelName(client, channelId),
-    fetchUserNames(client, messages),
+    fetchUserNames(client, [...messages, ...allReplies], args.teamId ?? null),
   ]);
 
-  const formattedMessages = messages.map((msg) => {
-    const author = msg.user ? userNames.get(msg.user) ?? msg.user : 'Unknown User';
-    return `[${msg.ts}] ${author}: ${msg.text}`;
-  });
+  const includeAttachmentText = args.includeAttachmentText ?? true;
+  const formatBatch = (batch: RecentMessage[]): string[] =>
+    formatMessagesForPrompt(batch, userNames, null, includeAttachmentText);
+  const formattedMessages = args.canvasOnly
+    ? messages.map((msg) => msg.text)
+    : standup
+      ? formatAuthorGroups(groupMessagesByAuthor(messages, userNames), formatBatch)
+      : repliesByRoot
+        ? formatThreadGroups(groupMessagesByThread(messages, repliesByRoot), formatBatch)
+        : formatMessagesForPrompt(
+            messages,
+            userNames,
+            args.threadTs ?? null,
+            includeAttachmentText
+          );
 
-  const linksShared = extractLinksFromMessages(messages);
+  const linksShared = extractLinksFromMessages([...messages, ...allReplies]);
+  const filesShared = extractSharedFiles([...messages, ...allReplies]);
+
+  // A canvas has no message permalinks to cite.
+  const receiptsEnabled = (args.enableReceipts ?? true) && !args.canvasOnly;
+  const receiptSeeds = receiptsEnabled ? pickReceiptSeeds(messages, userNames) : [];
+  // Receipts and most-reacted messages often overlap, so their permalinks
+  // are fetched as one batch (and not at all when receipts are off).
+  const reacted = topReacted(messages, MAX_MOST_REACTED);
+  const permalinkTs = receiptsEnabled
+    ? [...new Set([...receiptSeeds.map((seed) => seed.ts), ...reacted.map((msg) => msg.ts)])]
+    : [];
+  const permalinkByTs = new Map<string, string>();
+  for (const { ts, permalink } of await getMessagePermalinks(client, channelId, permalinkTs, {
+    concurrency: args.permalinkConcurrency,
+  })) {
+    if (permalink !== null) {
+      permalinkByTs.set(ts, permalink);
+    }
+  }
 
-  const receiptSeeds = pickReceiptSeeds(messages, userNames);
-  const permalinkResults = await Promise.all(
-    receiptSeeds.map((seed) => getMessagePermalink(client, channelId, seed.ts))
-  );
   const receipts: Receipt[] = [];
-  for (let i = 0; i < receiptSeeds.length; i += 1) {
-    const link = permalinkResults[i];
-    if (link !== null) {
-      receipts.push({
-        permalink: link,
-        author: receiptSeeds[i].author,
-        snippet: receiptSeeds[i].snippet,
-      });
+  for (const seed of receiptSeeds) {
+    const link = permalinkByTs.get(seed.ts);
+    if (link !== undefined) {
+      receipts.push({ permalink: link, author: seed.author, snippet: seed.snippet });
     }
   }
   const receiptPermalinks = receipts.map((r) => r.permalink);
 
-  const images: ImageBlock[] = [];
-  for (const msg of messages) {
-    if (images.length >= MAX_IMAGES_TOTAL) {
-      break;
-    }
-    for (const file of msg.files) {
-      if (images.length >= MAX_IMAGES_TOTAL) {
-        break;
-      }
-      const url = pickFileDownloadUrl(file);
-      if (!url) {
-        continue;
-      }
-      const mimeHint = file.mimeType ?? '';
-      const canonHint = canonicalizeMime(mimeHint);
-      if (canonHint !== '' && !isAllowedImageMime(canonHint)) {
-        continue;
-      }
+  const mostReacted = reacted.map((msg) => {
+    const seed = toSeed(msg, userNames);
+    return {
+      permalink: permalinkByTs.get(msg.ts) ?? null,
+      author: seed.author,
+      snippet: seed.snippet,
+      reactionCount: totalReactions(msg),
+    };
+  });
 
-      try {
-        const head = await fetchImageHead({ url, botToken: args.botToken, fetchImpl });
-        if (head?.contentType) {
-          const headCanon = canonicalizeMime(head.contentType);
-          if (!headCanon.startsWith('image/') || !isAllowedImageMime(headCanon)) {
-            continue;
-          }
-        }
-        if (head?.contentLength && head.contentLength > INLINE_IMAGE_MAX_BYTES) {
-          continue;
-        }
-        const bytes = await downloadImageBytes({
-          url,
-          botToken: args.botToken,
-          maxBytes: INLINE_IMAGE_MAX_BYTES,
-          fetchImpl,
-        });
-        const finalMime = canonHint || 'image/png';
-        if (!isAllowedImageMime(finalMime)) {
-          continue;
-        }
-        images.push(buildImageBlock(finalMime, bytes));
-      } catch {
-        // Skip individual image failures — non-fatal.
-      }
-    }
-  }
+  const images = args.disableImages
+    ? []
+    : await downloadInlineImages({
+        messages,
+        botToken: args.botToken,
+        concurrency: args.imageDownloadConcurrency ?? DEFAULT_IMAGE_DOWNLOAD_CONCURRENCY,
+        maxBytes: args.inlineImageMaxBytes,
+        fetchImpl,
+      });
 
-  const prompt = buildBasePrompt({
+  const promptArgs: BuildPromptArgs = {
     channelName,
     formattedMessages,
     linksShared,
     receipts,
     images,
     customStyle,
-  });
+    tone: args.tone ?? null,
+    mostReacted,
+    filesShared,
+    template: args.template ?? null,
+    keywordFilter: args.keywordFilter ?? null,
+    pinnedOnly: args.pinnedOnly ?? false,
+    canvasOnly: args.canvasOnly ?? false,
+    citeInline: args.citeInline ?? false,
+    sections: args.summarySections,
+    summaryLanguage: args.detectSummaryLanguage ? detectSummaryLanguage(messages) : null,
+    systemPrompt: args.systemPromptOverride ?? null,
+    assistantName: args.assistantName ?? null,
+    imageFocused: isImageHeavy(images.length, messages),
+  };
+  const prompt = buildBasePrompt(promptArgs);
 
   return {
     prompt,
+    promptArgs,
     linksShared,
+    filesShared,
     receiptPermalinks,
     hasAnyImages: images.length > 0,
+    receiptsEnabled,
+    imagesEnabled: !args.disableImages,
+    sections: args.summarySections,
+  };
+}
+
+/**
+ * True when the inlined images outnumber the messages that say anything in
+ * text — a channel of screenshots, where the image-focused prompt does
+ * better. Counts images actually inlined, so failed downloads don't tip it.
+ */
+export function isImageHeavy(imageCount: number, messages: RecentMessage[]): boolean {
+  if (imageCount === 0) {
+    return false;
+  }
+  const textMessages = messages.filter((m) => m.text.trim().length > 0).length;
+  return imageCount > textMessages;
+}
+
+/** Files-only variant: the messages' images with an empty transcript and no links or receipts. */
+async function buildFilesOnlyPromptData(
+  args: BuildPromptDataArgs,
+  fetchImpl: typeof fetch
+): Promise<SummarizePromptData> {
+  const [channelName, images] = await Promise.all([
+    getChannelName(args.client, args.channelId),
+    args.disableImages
+      ? []
+      : downloadInlineImages({
+          messages: args.messages,
+          botToken: args.botToken,
+          concurrency: args.imageDownloadConcurrency ?? DEFAULT_IMAGE_DOWNLOAD_CONCURRENCY,
+          maxBytes: args.inlineImageMaxBytes,
+          fetchImpl,
+        }),
+  ]);
+  const promptArgs: BuildPromptArgs = {
+    channelName,
+    formattedMessages: [],
+    linksShared: [],
+    receipts: [],
+    images,
+    customStyle: args.customStyle,
+    filesOnly: true,
+    systemPrompt: args.systemPromptOverride ?? null,
+    assistantName: args.assistantName ?? null,
+  };
+  return {
+    prompt: buildBasePrompt(promptArgs),
+    promptArgs,
+    linksShared: [],
+    filesShared: [],
+    receiptPermalinks: [],
+    hasAnyImages: images.length > 0,
+    receiptsEnabled: false,
+    imagesEnabled: !args.disableImages,
+  };
+}
+
+/** Download URLs for every file whose MIME hint could be an allowed image, in message order. */
+function imageCandidateUrls(messages: RecentMessage[]): string[] {
+  const urls: string[] = [];
+  for (const msg of messages) {
+    for (const file of msg.files) {
+      const url = pickFileDownloadUrl(file);
+      if (!url) {
+        continue;
+      }
+      if (isImageCandidate(file)) {
+        urls.push(url);
+      }
+    }
+  }
+  return urls;
+}
+
+/**
+ * HEAD-check, download, and sniff one image. Returns `null` for anything that
+ * isn't an allowed image under `maxBytes`, or on any failure — individual
+ * images are non-fatal.
+ */
+async function fetchInlineImage(
+  url: string,
+  botToken: string,
+  maxBytes: number,
+  fetchImpl: typeof fetch
+): Promise<ImageBlock | null> {
+  try {
+    const head = await fetchImageHead({ url, botToken, fetchImpl });
+    if (head?.contentType) {
+      const headCanon = canonicalizeMime(head.contentType);
+      if (!isGenericMime(headCanon) && !isAllowedImageMime(headCanon)) {
+        return null;
+      }
+    }
+    if (head?.contentLength && head.contentLength > maxBytes) {
+      return null;
+    }
+    const bytes = await downloadImageBytes({
+      url,
+      botToken,
+      maxBytes,
+      fetchImpl,
+    });
+    // Headers can be generic or wrong; the bytes decide the final format.
+    const finalMime = sniffImageMime(bytes);
+    if (finalMime === null || !isAllowedImageMime(finalMime)) {
+      return null;
+    }
+    return buildImageBlock(finalMime, bytes);
+  } catch {
+    return null;
+  }
+}
+
+/**
+ * Download up to `MAX_IMAGES_TOTAL` inline images with at most `concurrency`
+ * in flight. Workers stop picking up new files once enough have succeeded;
+ * the result keeps message order regardless of which download finished first.
+ */
+export async function downloadInlineImages(args: {
+  messages: RecentMessage[];
+  botToken: string;
+  concurrency: number;
+  /** Per-image byte cap; defaults to `DEFAULT_INLINE_IMAGE_MAX_BYTES`. */
+  maxBytes?: number;
+  fetchImpl: typeof fetch;
+}): Promise<ImageBlock[]> {
+  const urls = imageCandidateUrls(args.messages);
+  const maxBytes = args.maxBytes ?? DEFAULT_INLINE_IMAGE_MAX_BYTES;
+  const results: Array<ImageBlock | null> = new Array(urls.length).fill(null);
+  let next = 0;
+  let succeeded = 0;
+  const worker = async (): Promise<void> => {
+    while (next < urls.length && succeeded < MAX_IMAGES_TOTAL) {
+      const index = next;
+      next += 1;
+      const image = await fetchInlineImage(urls[index], args.botToken, maxBytes, args.fetchImpl);
+      if (image !== null) {
+        results[index] = image;
+        succeeded += 1;
+      }
+    }
   };
+  const workers = Math.max(1, Math.min(args.concurrency, urls.length));
+  await Promise.all(Array.from({ length: workers }, worker));
+  return results.filter((image): image is ImageBlock => image !== null).slice(0, MAX_IMAGES_TOTAL);
 }
 
 /**
- * Safety-net: if the model omits required sections (`Links shared`, `Image
- * highlights`, `Receipts`), append minimal versions so the output is
- * consistent. Mutates the input string and returns the result.
+ * Safety-net: if the model omits a required section (each of `sections`
+ * after Summary, plus `Files shared` when files were shared), append a
+ * minimal version so the output is consistent. Returns the result.
  */
 export function applySafetyNetSections(
   summary: string,
-  data: { linksShared: string[]; receiptPermalinks: string[]; hasAnyImages: boolean }
+  data: {
+    linksShared: string[];
+    filesShared?: SharedFile[];
+    receiptPermalinks: string[];
+    hasAnyImages: boolean;
+    receiptsEnabled?: boolean;
+    imagesEnabled?: boolean;
+    /** Required sections in order; defaults to `DEFAULT_SUMMARY_SECTIONS`. */
+    sections?: readonly string[];
+  }
 ): string {
+  const sections = data.sections ?? DEFAULT_SUMMARY_SECTIONS;
   const lower = summary.toLowerCase();
   let out = summary;
 
-  if (!lower.includes('links shared')) {
-    out += '\n\n*Links shared*\n';
-    if (data.linksShared.length === 0) {
-      out += '- None\n';
-    } else {
-      for (const link of data.linksShared.slice(0, 30)) {
-        out += `- ${link}\n`;
+  const filesShared = data.filesShared ?? [];
+  const appendFilesShared = (): void => {
+    if (filesShared.length > 0 && !lower.includes('files shared')) {
+      out += `\n\n*${FILES_SHARED_SECTION}*\n`;
+      for (const file of filesShared) {
+        out += `- ${file.name} (${file.type})\n`;
       }
     }
-  }
+  };
 
-  if (!lower.includes('image highlights')) {
-    out += '\n\n*Image highlights*\n';
-    out += data.hasAnyImages ? '- (No image highlights provided.)\n' : '- None\n';
+  for (const section of sections) {
+    const key = section.toLowerCase();
+    // The model's reply is the summary; there's nothing to stand in for it.
+    if (key !== 'summary' && !lower.includes(key)) {
+      out += `\n\n*${section}*\n${fallbackSectionBody(key, data)}`;
+    }
+    if (key === 'links shared') {
+      appendFilesShared();
+    }
+  }
+  if (!sections.some((section) => section.toLowerCase() === 'links shared')) {
+    appendFilesShared();
   }
 
-  if (!lower.includes('receipts')) {
-    out += '\n\n*Receipts*\n';
-    if (data.receiptPermalinks.length === 0) {
-      out += '- None\n';
-    } else {
-      for (const link of data.receiptPermalinks.slice(0, MAX_RECEIPTS)) {
-        out += `- ${link}\n`;
+  return out;
+}
+
+/** Stand-in bullets for a missing section, keyed by its lower-cased name. */
+function fallbackSectionBody(
+  key: string,
+  data: {
+    linksShared: string[];
+    receiptPermalinks: string[];
+    hasAnyImages: boolean;
+    receiptsEnabled?: boolean;
+    imagesEnabled?: boolean;
+  }
+): string {
+  switch (key) {
+    case 'links shared':
+      return data.linksShared.length === 0
+        ? '- None\n'
+        : data.linksShared
+            .slice(0, 30)
+            .map((link) => `- ${tagLink(link)}\n`)
+            .join('');
+    case 'image highlights':
+      if (data.imagesEnabled === false) {
+        return '- Disabled\n';
       }
-    }
+      return data.hasAnyImages ? '- (No image highlights provided.)\n' : '- None\n';
+    case 'receipts':
+      if (data.receiptsEnabled === false) {
+        return '- Disabled\n';
+      }
+      return data.receiptPermalinks.length === 0
+        ? '- None\n'
+        : data.receiptPermalinks
+            .slice(0, MAX_RECEIPTS)
+            .map((link) => `- ${link}\n`)
+            .join('');
+    default:
+      return '- None\n';
   }
+}
 
-  return out;
+/** Label for an author whose profile can't be read, e.g. a Slack Connect user from another org. */
+export function externalUserLabel(team: string | null): string {
+  return team ? `External user (${team})` : 'External user';
 }
 
+/**
+ * Resolve display names for every author. Lookups fail per user, never for the
+ * batch: an unreadable profile of an author from another team becomes
+ * {@link externalUserLabel} so shared-channel authors don't leak as `U…` ids.
+ * Any other unresolved author, including a lookup that was rate-limited past
+ * its retries, keeps the raw id.
+ */
 async function fetchUserNames(
   client: WebClient,
-  messages: RecentMessage[]
+  messages: RecentMessage[],
+  teamId: string | null
 ): Promise<Map<string, string>> {
-  const userIds = new Set<string>();
+  const userTeams = new Map<string, string | null>();
   for (const msg of messages) {
-    if (msg.user && msg.user !== 'Unknown User') {
-      userIds.add(msg.user);
+    if (msg.user && msg.user !== 'Unknown User' && !userTeams.get(msg.user)) {
+      userTeams.set(msg.user, msg.userTeam ?? null);
     }
   }
-  const ids = [...userIds];
-  const pairs = await Promise.all(
-    ids.map(async (id) => [id, await getUserDisplayName(client, id)] as const)
+  const names = await getUserDisplayNames(client, [...userTeams.keys()]);
+  const isExternal = (team: string | null): boolean =>
+    team !== null && teamId !== null && team !== teamId;
+  return new Map(
+    [...userTeams].map(([id, team]) => {
+      const fallback = isExternal(team) ? externalUserLabel(team) : id;
+      return [id, names.get(id) ?? fallback] as const;
+    })
   );
-  return new Map(pairs);
 }
 
 function pickReceiptSeeds(
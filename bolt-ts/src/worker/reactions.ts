@@ -0,0 +1,33 @@
+/**
+ * Emoji-reaction ranking. The most-reacted messages are a cheap signal for
+ * what the channel cared about, so the prompt surfaces them alongside
+ * receipts.
+ */
+
+export interface SlackReaction {
+  name: string;
+  count: number;
+}
+
+export interface ReactableMessage {
+  ts: string;
+  reactions?: SlackReaction[];
+}
+
+/** Sum of every reaction's count on a message. */
+export function totalReactions(msg: ReactableMessage): number {
+  return (msg.reactions ?? []).reduce((sum, r) => sum + (r.count > 0 ? r.count : 0), 0);
+}
+
+/**
+ * Top `n` messages by total reaction count. Ties go to the earlier message.
+ * Messages without reactions are never returned.
+ */
+export function topReacted<T extends ReactableMessage>(messages: T[], n: number): T[] {
+  return messages
+    .map((msg) => ({ msg, total: totalReactions(msg) }))
+    .filter((entry) => entry.total > 0)
+    .sort((a, b) => b.total - a.total || Number(a.msg.ts) - Number(b.msg.ts))
+    .slice(0, n)
+    .map((entry) => entry.msg);
+}
@@ -0,0 +1,81 @@
+/**
+ * "Summary of summaries" rollup for conversations that exceed the model's
+ * context window.
+ *
+ * Message lines are packed into windows under {@link ROLLUP_WINDOW_TOKENS},
+ * each window is summarised on its own, and the partial summaries are folded
+ * into one final summary that keeps the run's links, receipts, images, and
+ * custom style.
+ */
+
+import type { LlmClient } from '../ai/anthropic';
+import { buildPrompt, type BuildPromptArgs } from '../ai/prompt';
+
+/**
+ * Token budget per window. Leaves room under the 200k context for the system
+ * prompt, the links / receipts blocks, and the output.
+ */
+export const ROLLUP_WINDOW_TOKENS = 60_000;
+
+/** Rough token estimate: ~4 characters per token for English chat text. */
+export function estimateTokens(text: string): number {
+  return Math.ceil(text.length / 4);
+}
+
+/**
+ * Greedily pack lines into windows whose estimated size stays within
+ * `maxTokens`. Order is preserved; a single line over the budget gets a
+ * window of its own rather than being split mid-message.
+ */
+export function windowMessagesByTokens(lines: string[], maxTokens: number): string[][] {
+  const windows: string[][] = [];
+  let current: string[] = [];
+  let currentTokens = 0;
+  for (const line of lines) {
+    // +1 for the joining newline.
+    const lineTokens = estimateTokens(line) + 1;
+    if (current.length > 0 && currentTokens + lineTokens > maxTokens) {
+      windows.push(current);
+      current = [];
+      currentTokens = 0;
+    }
+    current.push(line);
+    currentTokens += lineTokens;
+  }
+  if (current.length > 0) {
+    windows.push(current);
+  }
+  return windows;
+}
+
+/** Summarise an oversized run via map-reduce. Returns the final summary text. */
+export async function summarizeInWindows(
+  llm: LlmClient,
+  promptArgs: BuildPromptArgs,
+  maxTokens: number = ROLLUP_WINDOW_TOKENS
+): Promise<string> {
+  const windows = windowMessagesByTokens(promptArgs.formattedMessages, maxTokens);
+  // Map passes only see messages; links, receipts, images, and style are
+  // applied once in the reduce pass.
+  const windowPrompts = windows.map((lines) =>
+    buildPrompt({
+      channelName: promptArgs.channelName,
+      formattedMessages: lines,
+      linksShared: [],
+      receipts: [],
+      images: [],
+      customStyle: null,
+      sections: promptArgs.sections,
+      systemPrompt: promptArgs.systemPrompt,
+      assistantName: promptArgs.assistantName,
+    })
+  );
+  return llm.mapReduceSummarize(windowPrompts, (partials) =>
+    buildPrompt({
+      ...promptArgs,
+      formattedMessages: partials.map(
+        (partial, i) => `Partial summary ${i + 1} of ${partials.length}:\n${partial}`
+      ),
+    })
+  );
+}
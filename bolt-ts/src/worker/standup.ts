@@ -0,0 +1,61 @@
+/**
+ * Standup digests (`template=standup`).
+ *
+ * Standup channels are a stream of per-person updates, so instead of a
+ * chronological transcript the prompt lists each author's messages together
+ * and the model writes a Yesterday / Today / Blockers line per person.
+ */
+
+import type { RecentMessage } from '../slack/client';
+
+export interface AuthorGroup {
+  author: string;
+  messages: RecentMessage[];
+}
+
+/** Extra task instruction for the standup template. */
+export const STANDUP_TASK =
+  'This is a standup channel. Under *Summary*, write one bullet per person in <messages> as "- *Name* — Yesterday: … · Today: … · Blockers: …", using only that person\'s own messages. Write "—" for anything they did not mention.';
+
+/**
+ * Group messages by author, oldest first within each group. Authors appear in
+ * the order of their first message; messages without a user id share one
+ * "Unknown User" group.
+ */
+export function groupMessagesByAuthor(
+  messages: RecentMessage[],
+  userNames: Map<string, string>
+): AuthorGroup[] {
+  const sorted = [...messages].sort((a, b) => Number(a.ts) - Number(b.ts));
+  const groups = new Map<string, AuthorGroup>();
+  for (const msg of sorted) {
+    const key = msg.user ?? '';
+    let group = groups.get(key);
+    if (!group) {
+      const author = msg.user ? userNames.get(msg.user) ?? msg.user : 'Unknown User';
+      group = { author, messages: [] };
+      groups.set(key, group);
+    }
+    group.messages.push(msg);
+  }
+  return [...groups.values()];
+}
+
+/**
+ * Render groups as prompt lines: `Author: <name>` followed by that author's
+ * messages indented underneath. `formatLines` formats a batch the same way
+ * the flat prompt does.
+ */
+export function formatAuthorGroups(
+  groups: AuthorGroup[],
+  formatLines: (messages: RecentMessage[]) => string[]
+): string[] {
+  const lines: string[] = [];
+  for (const group of groups) {
+    lines.push(`Author: ${group.author}`);
+    for (const line of formatLines(group.messages)) {
+      lines.push(`  ${line}`);
+    }
+  }
+  return lines;
+}
@@ -4,7 +4,13 @@
  *  - Fetch messages, build prompt with images and link/receipt context.
  *  - Open an Anthropic Messages streaming request (Claude Sonnet 4.6).
  *  - For each text delta, chunk and append to the Slack streaming message via
- *    `chat.appendStream`.
+ *    `chat.appendStream`. After a 429 on any append, later appends wait out
+ *    the shared cooldown (buffering deltas meanwhile) instead of each hitting
+ *    the limit in turn.
+ *  - While writing, show a rough completion percentage in the assistant
+ *    status (see `estimateProgressPercent`).
+ *  - If the prompt is too large for one pass, fall back to a non-streamed
+ *    map-reduce rollup (`worker/rollup.ts`).
  *  - On completion, apply safety-net sections then call `chat.stopStream` with
  *    interactive action buttons.
  *  - On any failure, fall back to a canonical error message in-thread (and
@@ -12,28 +18,142 @@
  *    had already started).
  */
 
-import type { WebClient } from '@slack/web-api';
+import type { KnownBlock, MessageMetadata } from '@slack/types';
+import { WebClientEvent, type WebClient } from '@slack/web-api';
 import {
   LlmClient,
+  LlmTimeoutError,
+  RefusalError,
+  isLlmTimeoutError,
   type StreamingResponse,
-  TOO_LARGE_MESSAGE,
 } from '../ai/anthropic';
+import { DeadlineExceededError, runWithinDeadline, type Deadline } from '../deadline';
+import { log, type Logger } from '../logging';
+import {
+  estimatePromptTokens,
+  recordOutputStats,
+  recordPromptOverflow,
+  recordPromptStats,
+  type SummaryRunStats,
+} from '../metrics';
+import { toMrkdwn } from '../slack/message_formatter';
 import { sanitizeGeneratedSlackMrkdwn } from '../slack/sanitize';
+import type { SummaryTemplate, SummaryTone } from '../types';
 import {
+  STREAM_CLOSED_METADATA,
   STREAM_MARKDOWN_TEXT_LIMIT,
   appendStream,
+  buildSummaryRangeMetadata,
   getBotUserId,
-  getRecentMessages,
+  isSlackTimeoutError,
+  isStreamingUnavailableError,
+  setAssistantStatus,
   startStream,
+  stopStaleStreams,
   stopStream,
+  summaryRangeOf,
+  type HistoryBounds,
 } from '../slack/client';
 import { takeStreamChunk } from './chunks';
-import { applySafetyNetSections, buildSummarizePromptData } from './prompt_builder';
-import { buildSummaryActionButtons } from './deliver';
+import type { SharedFile } from './files';
+import {
+  applySafetyNetSections,
+  buildSummarizePromptData,
+  fetchCanvasMessages,
+  fetchSourceMessages,
+  filterMessagesByKeyword,
+  filterUserMessages,
+  isChannelHistoryRun,
+  noKeywordMatchesMessage,
+} from './prompt_builder';
+import { buildSummaryActionButtons, buildSummaryFooterBlocks, postLongMessage } from './deliver';
+import { summarizeInWindows } from './rollup';
+
+/** Assistant-thread statuses shown while a summary is in flight. */
+const STATUS_READING = 'Reading messages…';
+const STATUS_WRITING = 'Writing summary…';
+
+/** Minimum gap between progress status updates, to stay well inside `setStatus` limits. */
+export const PROGRESS_STATUS_INTERVAL_MS = 3_000;
+/** The percentage is only an estimate, so it never claims more than this before completion. */
+export const MAX_ESTIMATED_PROGRESS = 95;
+/** Summaries run a few hundred to ~1.5k tokens whatever the input, hence the clamp. */
+const MIN_EXPECTED_OUTPUT_TOKENS = 300;
+const MAX_EXPECTED_OUTPUT_TOKENS = 1_500;
+/** Longer inputs yield longer summaries, but far less than proportionally. */
+const OUTPUT_TO_INPUT_RATIO = 0.05;
+const CHARS_PER_TOKEN = 4;
 
 export const CANONICAL_FAILURE_MESSAGE =
   "Sorry, I couldn't generate a summary at this time. Please try again later.";
 
+export const DEADLINE_FAILURE_MESSAGE =
+  'Sorry, this summary took too long and was stopped. Try again with fewer messages.';
+
+/** Anthropic or Slack timed out on a single call, well inside the task deadline. */
+export const TIMEOUT_FAILURE_MESSAGE =
+  'The summary is taking longer than expected; please try a smaller message count.';
+
+/** User-facing text for a summarisation failure. Refusals and timeouts get their own wording. */
+export function failureMessageFor(err: unknown): string {
+  if (err instanceof RefusalError) {
+    return `I couldn't summarize this content: ${err.reason}.`;
+  }
+  if (err instanceof DeadlineExceededError) {
+    return DEADLINE_FAILURE_MESSAGE;
+  }
+  if (isLlmTimeoutError(err) || isSlackTimeoutError(err)) {
+    return TIMEOUT_FAILURE_MESSAGE;
+  }
+  return CANONICAL_FAILURE_MESSAGE;
+}
+
+/**
+ * Expected summary length in characters for a prompt of `inputTokens`:
+ * a small share of the input, clamped to a typical summary size and never
+ * above what `maxOutputTokens` allows.
+ */
+export function estimateExpectedOutputChars(inputTokens: number, maxOutputTokens: number): number {
+  const scaled = Math.round(inputTokens * OUTPUT_TO_INPUT_RATIO);
+  const typical = Math.max(MIN_EXPECTED_OUTPUT_TOKENS, scaled);
+  return Math.min(typical, MAX_EXPECTED_OUTPUT_TOKENS, maxOutputTokens) * CHARS_PER_TOKEN;
+}
+
+/** Percentage of `expectedChars` written so far, capped at {@link MAX_ESTIMATED_PROGRESS}. */
+export function estimateProgressPercent(collectedChars: number, expectedChars: number): number {
+  if (expectedChars <= 0) {
+    return 0;
+  }
+  return Math.min(MAX_ESTIMATED_PROGRESS, Math.floor((collectedChars / expectedChars) * 100));
+}
+
+/**
+ * Report streaming progress through `setStatus`, at most once per
+ * {@link PROGRESS_STATUS_INTERVAL_MS} and only when the percentage rises.
+ * Updates are fire-and-forget: a failed status never slows the stream.
+ */
+export function newProgressReporter(
+  setStatus: (status: string) => Promise<unknown>,
+  expectedChars: number,
+  now: () => number = Date.now
+): (collectedChars: number) => void {
+  let lastAt = now();
+  let lastPercent = 0;
+  return (collectedChars: number): void => {
+    const at = now();
+    if (at - lastAt < PROGRESS_STATUS_INTERVAL_MS) {
+      return;
+    }
+    const percent = estimateProgressPercent(collectedChars, expectedChars);
+    if (percent <= lastPercent) {
+      return;
+    }
+    lastAt = at;
+    lastPercent = percent;
+    void setStatus(`${STATUS_WRITING} ${percent}%`);
+  };
+}
+
 export interface StreamSummaryArgs {
   client: WebClient;
   llm: LlmClient;
@@ -45,91 +165,228 @@ export interface StreamSummaryArgs {
   assistantThreadTs: string;
   messageCount: number;
   customStyle: string | null;
+  /** Tone preset; see `BuildPromptArgs.tone`. */
+  tone?: SummaryTone | null;
+  /** Summarize this thread instead of recent channel history. */
+  sourceThreadTs: string | null;
+  /** Render channel history as topics with their thread replies. */
+  groupByThread: boolean;
+  /** Describe the images on the message at `sourceThreadTs` instead of summarising. */
+  filesOnly?: boolean;
+  /** Alternate summary layout; see `BuildPromptDataArgs.template`. */
+  template?: SummaryTemplate | null;
+  /** Only summarize messages containing this word; see `filterMessagesByKeyword`. */
+  keywordFilter?: string | null;
+  /** Summarize the channel's pinned messages instead of recent history. */
+  pinnedOnly?: boolean;
+  /** Summarize the channel's canvas as one document instead of its messages. */
+  canvasOnly?: boolean;
+  /** Exclusive ts bounds for channel history; see `fetchSourceMessages`. */
+  historyBounds?: HistoryBounds;
+  /** Replaces the built-in system prompt; see `AppConfig.systemPromptOverride`. */
+  systemPromptOverride?: string | null;
+  /** Name the system prompt gives the assistant; see `AppConfig.assistantName`. */
+  assistantName?: string | null;
+  enableReceipts: boolean;
+  includeAttachmentText: boolean;
+  /** Max inline-image downloads in flight; see `buildSummarizePromptData`. */
+  imageDownloadConcurrency?: number;
+  /** Per-image byte cap for inlined images. */
+  inlineImageMaxBytes?: number;
+  /** Skip every image download; see `AppConfig.disableImages`. */
+  disableImages?: boolean;
+  /** Cite receipts inline in the Summary; see `AppConfig.citeInline`. */
+  citeInline?: boolean;
+  /** Required summary sections in order; see `AppConfig.summarySections`. */
+  summarySections?: readonly string[];
+  /** Leave user mentions live; see `sanitizeGeneratedSlackMrkdwn`. */
+  allowMentions?: boolean;
+  /** Answer in the conversation's language; see `AppConfig.detectSummaryLanguage`. */
+  detectSummaryLanguage?: boolean;
+  /** Unfurl links in summaries posted whole; see `AppConfig.unfurlSummaryLinks`. */
+  unfurlSummaryLinks?: boolean;
+  /** Max `chat.getPermalink` calls in flight for receipts. */
+  permalinkConcurrency?: number;
+  /** Extra user / bot ids to drop alongside the bot's own messages. */
+  ignoredUserIds?: string[];
+  /** Workspace the run belongs to; see `BuildPromptDataArgs.teamId`. */
+  teamId?: string | null;
+  /** Date shown in the summary title, formatted in the requester's zone. */
+  headerDate?: string | null;
+  /** Rendered footer shown under the summary; see `renderSummaryFooter`. */
+  summaryFooter: string | null;
+  /** Reply posted when the source has no messages. */
+  noMessagesMessage: string;
   correlationId: string;
+  /**
+   * Close the bot's unmarked replies in the assistant thread older than
+   * this before streaming; see `stopStaleStreams`. 0 / unset skips it.
+   */
+  staleStreamMaxAgeSecs?: number;
   /** Streaming knobs. */
   streamMaxChunkChars: number;
   streamMinAppendIntervalMs: number;
+  /** Filled with message / image / token counts for the run's metrics. */
+  stats?: SummaryRunStats;
+  /** Abort (closing any started stream) once this passes. */
+  deadline?: Deadline;
   /** Test-injectable sleep. */
   sleep?: (ms: number) => Promise<void>;
   /** Test-injectable fetch (for image downloads). */
   fetchImpl?: typeof fetch;
 }
 
-interface Logger {
-  info(message: string, meta?: Record<string, unknown>): void;
-  warn(message: string, meta?: Record<string, unknown>): void;
-  error(message: string, meta?: Record<string, unknown>): void;
-}
-
-const defaultLogger: Logger = {
-  info: (message, meta) => console.log(message, meta ?? ''),
-  warn: (message, meta) => console.warn(message, meta ?? ''),
-  error: (message, meta) => console.error(message, meta ?? ''),
-};
-
 /**
- * Run the end-to-end streaming summary, including safety-net cleanup. Returns
- * normally on success; throws if cleanup fails fatally.
+ * Run the end-to-end streaming summary, including safety-net cleanup. Resolves
+ * `true` on success and `false` when a refusal or a missed deadline was
+ * already explained to the user; throws for any other failure.
  */
 export async function streamSummaryToAssistantThread(
   args: StreamSummaryArgs,
-  logger: Logger = defaultLogger
-): Promise<void> {
+  logger: Logger = log
+): Promise<boolean> {
   const sleep: (ms: number) => Promise<void> =
     args.sleep ?? ((ms: number): Promise<void> => new Promise((resolve) => setTimeout(resolve, ms)));
 
   let streamTs: string | null = null;
+  const setStatus = (status: string): Promise<boolean> =>
+    setAssistantStatus(args.client, args.assistantChannelId, args.assistantThreadTs, status);
 
   try {
-    const messages = await getRecentMessages(args.client, args.sourceChannelId, args.messageCount);
+    await setStatus(STATUS_READING);
+    // Each step before the stream is bounded by the task deadline, so a slow
+    // fetch fails while `ensureCanonicalFailure` still has time to report it.
+    const messages = await runWithinDeadline(args.deadline, () =>
+      args.canvasOnly
+        ? fetchCanvasMessages(args.client, args.sourceChannelId, args.botToken, args.fetchImpl)
+        : fetchSourceMessages(
+            args.client,
+            args.sourceChannelId,
+            args.messageCount,
+            args.sourceThreadTs,
+            args.filesOnly,
+            args.pinnedOnly,
+            args.historyBounds
+          )
+    );
     if (messages.length === 0) {
       await args.client.chat.postMessage({
         channel: args.assistantChannelId,
         thread_ts: args.assistantThreadTs,
-        text: 'No messages found to summarize.',
+        text: args.noMessagesMessage,
       });
-      return;
+      return true;
     }
 
     // Filter out bot's own messages so it doesn't summarize itself.
     const botUserId = await getBotUserId(args.client);
-    const userMessages = botUserId
-      ? messages.filter((m) => m.user !== botUserId)
-      : messages;
-
-    const promptData = await buildSummarizePromptData({
-      client: args.client,
-      botToken: args.botToken,
-      channelId: args.sourceChannelId,
-      messages: userMessages,
-      customStyle: args.customStyle,
-      fetchImpl: args.fetchImpl,
-    });
+    if (botUserId && args.staleStreamMaxAgeSecs) {
+      await runWithinDeadline(args.deadline, () => closeStaleStreams(args, botUserId, logger));
+    }
+    const userMessages = filterUserMessages(messages, botUserId, args.ignoredUserIds);
+    const matched = filterMessagesByKeyword(userMessages, args.keywordFilter ?? null);
+    if (matched.length === 0 && args.keywordFilter) {
+      await args.client.chat.postMessage({
+        channel: args.assistantChannelId,
+        thread_ts: args.assistantThreadTs,
+        text: noKeywordMatchesMessage(args.keywordFilter),
+      });
+      return true;
+    }
 
-    const prefix = buildStreamPrefix(args.sourceChannelId, args.customStyle);
-    const stream = await args.llm.generateSummaryStream(promptData.prompt);
+    const promptData = await runWithinDeadline(args.deadline, () =>
+      buildSummarizePromptData({
+        client: args.client,
+        botToken: args.botToken,
+        channelId: args.sourceChannelId,
+        messages: userMessages,
+        customStyle: args.customStyle,
+        tone: args.tone,
+        threadTs: args.sourceThreadTs,
+        groupByThread: args.groupByThread,
+        filesOnly: args.filesOnly,
+        template: args.template,
+        keywordFilter: args.keywordFilter,
+        pinnedOnly: args.pinnedOnly,
+        canvasOnly: args.canvasOnly,
+        systemPromptOverride: args.systemPromptOverride,
+        assistantName: args.assistantName,
+        enableReceipts: args.enableReceipts,
+        includeAttachmentText: args.includeAttachmentText,
+        imageDownloadConcurrency: args.imageDownloadConcurrency,
+        inlineImageMaxBytes: args.inlineImageMaxBytes,
+        disableImages: args.disableImages,
+        citeInline: args.citeInline,
+        summarySections: args.summarySections,
+        detectSummaryLanguage: args.detectSummaryLanguage,
+        permalinkConcurrency: args.permalinkConcurrency,
+        teamId: args.teamId,
+        fetchImpl: args.fetchImpl,
+      })
+    );
+    recordPromptStats(args.stats, matched.length, promptData.prompt);
+    recordPromptOverflow(promptData.prompt, args.llm.maxOutputTokens, 'streaming');
+
+    const prefix = buildStreamPrefix(args.sourceChannelId, args.customStyle, args.headerDate);
+    // Stamped on the summary so a later `--since-last-summary` run can start
+    // where this one ended.
+    const range = isChannelHistoryRun({
+      threadTs: args.sourceThreadTs,
+      filesOnly: args.filesOnly,
+      pinnedOnly: args.pinnedOnly,
+      canvasOnly: args.canvasOnly,
+    })
+      ? summaryRangeOf(args.sourceChannelId, messages)
+      : null;
+    const rangeMetadata = range ? buildSummaryRangeMetadata(range) : undefined;
+    await setStatus(STATUS_WRITING);
+    const stream = await runWithinDeadline(args.deadline, () =>
+      args.llm.generateSummaryStream(promptData.prompt)
+    );
 
     if (stream.kind === 'too_large') {
+      // Too big for one pass: roll up window summaries instead of streaming.
+      const rolledUp = await runWithinDeadline(args.deadline, () =>
+        summarizeInWindows(args.llm, promptData.promptArgs)
+      );
+      recordOutputStats(args.stats, rolledUp);
+      // Posted as mrkdwn, not streamed markdown_text, so normalise emphasis.
       const message = sanitizeGeneratedSlackMrkdwn(
-        prefix + applySafetyNetSections(TOO_LARGE_MESSAGE, promptData)
+        toMrkdwn(prefix + applySafetyNetSections(rolledUp, promptData)),
+        { allowUserMentions: args.allowMentions }
       );
-      await args.client.chat.postMessage({
+      // Checked, not raced: abandoning a post midway could leave half a summary.
+      args.deadline?.check();
+      await postLongMessage(args.client, {
         channel: args.assistantChannelId,
-        thread_ts: args.assistantThreadTs,
+        threadTs: args.assistantThreadTs,
         text: message,
+        blocks: buildPostedSummaryBlocks(args),
+        metadata: rangeMetadata,
+        unfurlLinks: args.unfurlSummaryLinks,
       });
-      return;
+      return true;
     }
 
+    const expectedChars = estimateExpectedOutputChars(
+      estimatePromptTokens(promptData.prompt),
+      args.llm.maxOutputTokens
+    );
     streamTs = await consumeStream({
       ...args,
       sleep,
       prefix,
       promptData,
+      rangeMetadata,
       stream,
+      onProgress: newProgressReporter(setStatus, expectedChars),
       streamTs: null,
+      onStreamStarted: (ts) => {
+        streamTs = ts;
+      },
       logger,
     });
+    return true;
   } catch (err) {
     logger.error('Streaming summary failed', {
       corr_id: args.correlationId,
@@ -141,27 +398,148 @@ export async function streamSummaryToAssistantThread(
       assistantThreadTs: args.assistantThreadTs,
       streamTs,
       correlationId: args.correlationId,
+      text: failureMessageFor(err),
       logger,
     });
+    // A refusal is a final answer, not a fault, and a missed deadline has
+    // already been reported — don't let callers layer a generic failure on top.
+    if (err instanceof RefusalError || err instanceof DeadlineExceededError) {
+      return false;
+    }
     throw err;
+  } finally {
+    // Slack clears the status when the stream's message is posted; a
+    // percentage set after that would otherwise linger under the summary.
+    await setStatus('');
+  }
+}
+
+/**
+ * Finalise streams an earlier, killed run left open in this thread, so they
+ * don't spin as "streaming" forever. Failures are only logged.
+ */
+async function closeStaleStreams(
+  args: StreamSummaryArgs,
+  botUserId: string,
+  logger: Logger
+): Promise<void> {
+  try {
+    const stopped = await stopStaleStreams(args.client, {
+      channel: args.assistantChannelId,
+      threadTs: args.assistantThreadTs,
+      botUserId,
+      olderThanSecs: args.staleStreamMaxAgeSecs ?? 0,
+    });
+    if (stopped > 0) {
+      logger.info('Closed stale streams', { corr_id: args.correlationId, count: stopped });
+    }
+  } catch (err) {
+    logger.warn('Stale stream cleanup failed', {
+      corr_id: args.correlationId,
+      error: err instanceof Error ? err.message : String(err),
+    });
   }
 }
 
 interface ConsumeStreamArgs extends StreamSummaryArgs {
   prefix: string;
-  promptData: { linksShared: string[]; receiptPermalinks: string[]; hasAnyImages: boolean };
+  promptData: {
+    linksShared: string[];
+    filesShared?: SharedFile[];
+    receiptPermalinks: string[];
+    hasAnyImages: boolean;
+    receiptsEnabled?: boolean;
+    imagesEnabled?: boolean;
+  };
+  /** The summary's history range, stamped when the stream is closed. */
+  rangeMetadata?: MessageMetadata;
   stream: Extract<StreamingResponse, { kind: 'active' }>;
   streamTs: string | null;
+  /** Called once `chat.startStream` succeeds, so a later failure can close the stream. */
+  onStreamStarted?: (ts: string) => void;
+  /** Called with the summary length so far after each text delta. */
+  onProgress?: (collectedChars: number) => void;
   sleep: (ms: number) => Promise<void>;
   logger: Logger;
 }
 
-async function consumeStream(args: ConsumeStreamArgs): Promise<string> {
+/**
+ * Rate-limit state shared by every append in one stream: when the last 429
+ * arrived and how long Slack asked us to back off.
+ */
+export interface AppendCooldown {
+  last429At: number | null;
+  retryAfterMs: number;
+}
+
+export function newAppendCooldown(): AppendCooldown {
+  return { last429At: null, retryAfterMs: 0 };
+}
+
+/** Record a 429 with its `Retry-After` (seconds). */
+export function noteRateLimited(
+  cooldown: AppendCooldown,
+  retryAfterSec: number,
+  now: number = Date.now()
+): void {
+  cooldown.last429At = now;
+  cooldown.retryAfterMs = Math.max(0, retryAfterSec * 1000);
+}
+
+/** Milliseconds left before the next append may fire; 0 when not cooling down. */
+export function cooldownRemainingMs(cooldown: AppendCooldown, now: number = Date.now()): number {
+  if (cooldown.last429At === null) {
+    return 0;
+  }
+  return Math.max(0, cooldown.last429At + cooldown.retryAfterMs - now);
+}
+
+/**
+ * Feed `chat.appendStream` 429s seen by `client` into `cooldown`. The Web API
+ * client retries the limited call itself and emits `rate_limited`; we only
+ * need to hear about it. Returns the unsubscribe function.
+ */
+export function watchAppendRateLimits(client: WebClient, cooldown: AppendCooldown): () => void {
+  // Test fakes aren't event emitters.
+  if (typeof client.on !== 'function') {
+    return () => undefined;
+  }
+  const listener = (retryAfterSec: number, meta?: { url?: string }): void => {
+    if (meta?.url === undefined || meta.url.includes('chat.appendStream')) {
+      noteRateLimited(cooldown, retryAfterSec);
+    }
+  };
+  client.on(WebClientEvent.RATE_LIMITED, listener);
+  return () => {
+    client.off(WebClientEvent.RATE_LIMITED, listener);
+  };
+}
+
+/**
+ * Pump the Anthropic stream into a Slack streaming message. Returns the
+ * streaming message ts, or `null` when `chat.startStream` isn't available in
+ * this workspace and the collected text was posted as one message instead.
+ */
+async function consumeStream(args: ConsumeStreamArgs): Promise<string | null> {
+  const cooldown = newAppendCooldown();
+  const unwatchRateLimits = watchAppendRateLimits(args.client, cooldown);
+  try {
+    return await pumpStream(args, cooldown);
+  } finally {
+    unwatchRateLimits();
+  }
+}
+
+async function pumpStream(
+  args: ConsumeStreamArgs,
+  cooldown: AppendCooldown
+): Promise<string | null> {
   let streamTs: string | null = args.streamTs;
   let pending = '';
   let collected = '';
   let lastAppendAt: number | null = null;
   let canAppend = true;
+  let streamingUnavailable = false;
 
   const flushAll = async (ts: string): Promise<void> => {
     while (pending.length > 0) {
@@ -178,6 +556,9 @@ async function consumeStream(args: ConsumeStreamArgs): Promise<string> {
         ts,
         pending,
         maxChunkChars: args.streamMaxChunkChars,
+        allowMentions: args.allowMentions,
+        cooldown,
+        sleep: args.sleep,
         correlationId: args.correlationId,
         logger: args.logger,
       });
@@ -192,13 +573,16 @@ async function consumeStream(args: ConsumeStreamArgs): Promise<string> {
 
   try {
     while (true) {
-      const next = await args.stream.iterator.next();
+      const next = await runWithinDeadline(args.deadline, () => args.stream.iterator.next());
       if (next.done) {
         break;
       }
       const event = next.value;
       if (event.kind === 'failed') {
-        throw new Error(event.message);
+        throw event.timedOut ? new LlmTimeoutError(event.message) : new Error(event.message);
+      }
+      if (event.kind === 'refused') {
+        throw new RefusalError(event.reason);
       }
       if (event.kind === 'completed') {
         break;
@@ -208,8 +592,12 @@ async function consumeStream(args: ConsumeStreamArgs): Promise<string> {
       }
       pending += event.delta;
       collected += event.delta;
+      args.onProgress?.(collected.length);
 
       if (streamTs === null) {
+        if (streamingUnavailable) {
+          continue;
+        }
         const prefixChars = [...args.prefix].length;
         if (prefixChars >= STREAM_MARKDOWN_TEXT_LIMIT) {
           throw new Error('Streaming prefix exceeds Slack markdown limit');
@@ -222,18 +610,39 @@ async function consumeStream(args: ConsumeStreamArgs): Promise<string> {
         if (!taken) {
           continue;
         }
-        const initialText = sanitizeGeneratedSlackMrkdwn(args.prefix + taken.chunk);
-        streamTs = await startStream(args.client, {
-          channel: args.assistantChannelId,
-          threadTs: args.assistantThreadTs,
-          markdownText: initialText,
+        const initialText = sanitizeGeneratedSlackMrkdwn(args.prefix + taken.chunk, {
+          allowUserMentions: args.allowMentions,
         });
+        try {
+          streamTs = await startStream(args.client, {
+            channel: args.assistantChannelId,
+            threadTs: args.assistantThreadTs,
+            markdownText: initialText,
+          });
+          args.onStreamStarted?.(streamTs);
+        } catch (err) {
+          if (!isStreamingUnavailableError(err)) {
+            throw err;
+          }
+          args.logger.warn('chat.startStream unavailable; collecting the summary for one post', {
+            corr_id: args.correlationId,
+            error: err instanceof Error ? err.message : String(err),
+          });
+          streamingUnavailable = true;
+          continue;
+        }
         pending = taken.rest;
         lastAppendAt = Date.now();
         continue;
       }
 
-      if (!canAppend || pending.length === 0 || lastAppendAt === null) {
+      // Keep buffering while cooling down; the next append carries it all.
+      if (
+        !canAppend ||
+        pending.length === 0 ||
+        lastAppendAt === null ||
+        cooldownRemainingMs(cooldown) > 0
+      ) {
         continue;
       }
       const elapsed = Date.now() - lastAppendAt;
@@ -244,6 +653,9 @@ async function consumeStream(args: ConsumeStreamArgs): Promise<string> {
           ts: streamTs,
           pending,
           maxChunkChars: args.streamMaxChunkChars,
+          allowMentions: args.allowMentions,
+          cooldown,
+          sleep: args.sleep,
           correlationId: args.correlationId,
           logger: args.logger,
         });
@@ -261,6 +673,15 @@ async function consumeStream(args: ConsumeStreamArgs): Promise<string> {
       void args.stream.cancel();
     }
   }
+  recordOutputStats(args.stats, collected);
+
+  if (streamingUnavailable) {
+    // Nothing is on screen yet, so a missed deadline can still be reported
+    // instead. A started stream already has content and is always finalised.
+    args.deadline?.check();
+    await postCollectedSummary(args, collected);
+    return null;
+  }
 
   if (streamTs === null) {
     throw new Error('Anthropic stream completed without any output');
@@ -288,24 +709,63 @@ async function consumeStream(args: ConsumeStreamArgs): Promise<string> {
       sourceChannelId: args.sourceChannelId,
       messageCount: args.messageCount,
       customStyle: args.customStyle,
+      summaryFooter: args.summaryFooter,
+      metadata: args.rangeMetadata,
     });
   }
 
   return streamTs;
 }
 
+/**
+ * Fallback for workspaces without the streaming APIs: post the whole
+ * collected summary (safety net, footer, and buttons included) at once.
+ */
+async function postCollectedSummary(args: ConsumeStreamArgs, collected: string): Promise<void> {
+  // Posted as mrkdwn, not streamed markdown_text, so normalise emphasis.
+  const text = sanitizeGeneratedSlackMrkdwn(
+    toMrkdwn(args.prefix + applySafetyNetSections(collected, args.promptData)),
+    { allowUserMentions: args.allowMentions }
+  );
+  await postLongMessage(args.client, {
+    channel: args.assistantChannelId,
+    threadTs: args.assistantThreadTs,
+    text,
+    blocks: buildPostedSummaryBlocks(args),
+    metadata: args.rangeMetadata,
+    unfurlLinks: args.unfurlSummaryLinks,
+  });
+}
+
+/** Footer and action buttons for a summary posted whole rather than streamed. */
+function buildPostedSummaryBlocks(args: StreamSummaryArgs): KnownBlock[] {
+  return [
+    ...buildSummaryFooterBlocks(args.summaryFooter),
+    ...buildSummaryActionButtons({
+      sourceChannelId: args.sourceChannelId,
+      messageCount: args.messageCount,
+      currentStyle: args.customStyle,
+    }),
+  ];
+}
+
 interface AppendOneChunkArgs {
   client: WebClient;
   channel: string;
   ts: string;
   pending: string;
   maxChunkChars: number;
+  allowMentions?: boolean;
+  /** Shared with the stream's other appends; see `AppendCooldown`. */
+  cooldown: AppendCooldown;
+  sleep: (ms: number) => Promise<void>;
   correlationId: string;
   logger: Logger;
 }
 
 /**
- * Take one chunk off `pending` and post it. Returns the updated buffer state,
+ * Take one chunk off `pending` and post it, first waiting out any 429
+ * cooldown another append started. Returns the updated buffer state,
  * or `null` when the Slack message has left streaming state.
  */
 async function appendOneChunk(
@@ -315,7 +775,13 @@ async function appendOneChunk(
   if (!taken) {
     return { rest: '' };
   }
-  const sanitised = sanitizeGeneratedSlackMrkdwn(taken.chunk);
+  const wait = cooldownRemainingMs(args.cooldown);
+  if (wait > 0) {
+    await args.sleep(wait);
+  }
+  const sanitised = sanitizeGeneratedSlackMrkdwn(taken.chunk, {
+    allowUserMentions: args.allowMentions,
+  });
   const result = await appendStream(args.client, {
     channel: args.channel,
     ts: args.ts,
@@ -338,16 +804,23 @@ async function finalizeStreamSuccess(args: {
   sourceChannelId: string;
   messageCount: number;
   customStyle: string | null;
+  summaryFooter: string | null;
+  /** Defaults to {@link STREAM_CLOSED_METADATA}. */
+  metadata?: MessageMetadata;
 }): Promise<void> {
-  const blocks = buildSummaryActionButtons({
-    sourceChannelId: args.sourceChannelId,
-    messageCount: args.messageCount,
-    currentStyle: args.customStyle,
-  });
+  const blocks = [
+    ...buildSummaryFooterBlocks(args.summaryFooter),
+    ...buildSummaryActionButtons({
+      sourceChannelId: args.sourceChannelId,
+      messageCount: args.messageCount,
+      currentStyle: args.customStyle,
+    }),
+  ];
   await stopStream(args.client, {
     channel: args.channel,
     ts: args.streamTs,
     blocks,
+    metadata: args.metadata ?? STREAM_CLOSED_METADATA,
   });
 }
 
@@ -357,6 +830,8 @@ interface EnsureCanonicalFailureArgs {
   assistantThreadTs: string;
   streamTs: string | null;
   correlationId: string;
+  /** Message to show in place of the summary. */
+  text: string;
   logger: Logger;
 }
 
@@ -366,7 +841,7 @@ async function ensureCanonicalFailure(args: EnsureCanonicalFailureArgs): Promise
       await args.client.chat.postMessage({
         channel: args.assistantChannelId,
         thread_ts: args.assistantThreadTs,
-        text: CANONICAL_FAILURE_MESSAGE,
+        text: args.text,
       });
     } catch (err) {
       args.logger.error('Failed to post canonical failure message', {
@@ -379,7 +854,11 @@ async function ensureCanonicalFailure(args: EnsureCanonicalFailureArgs): Promise
 
   // Streaming started — stop it, then overwrite the body with the error message.
   try {
-    await stopStream(args.client, { channel: args.assistantChannelId, ts: args.streamTs });
+    await stopStream(args.client, {
+      channel: args.assistantChannelId,
+      ts: args.streamTs,
+      metadata: STREAM_CLOSED_METADATA,
+    });
   } catch (err) {
     args.logger.warn('Failed to stop stream during cleanup', {
       corr_id: args.correlationId,
@@ -390,7 +869,7 @@ async function ensureCanonicalFailure(args: EnsureCanonicalFailureArgs): Promise
     await args.client.chat.update({
       channel: args.assistantChannelId,
       ts: args.streamTs,
-      text: CANONICAL_FAILURE_MESSAGE,
+      text: args.text,
       blocks: [],
     });
     return;
@@ -413,7 +892,7 @@ async function ensureCanonicalFailure(args: EnsureCanonicalFailureArgs): Promise
     await args.client.chat.postMessage({
       channel: args.assistantChannelId,
       thread_ts: args.assistantThreadTs,
-      text: CANONICAL_FAILURE_MESSAGE,
+      text: args.text,
     });
   } catch (err) {
     args.logger.error('Failed to post fallback canonical failure message', {
@@ -423,19 +902,31 @@ async function ensureCanonicalFailure(args: EnsureCanonicalFailureArgs): Promise
   }
 }
 
-/** Build the streaming prefix shown above the LLM-streamed body. */
-export function buildStreamPrefix(channelId: string, customStyle: string | null): string {
+/**
+ * Build the streaming prefix shown above the LLM-streamed body. `headerDate`
+ * (already formatted in the reader's zone) is appended to the title line.
+ */
+export function buildStreamPrefix(
+  channelId: string,
+  customStyle: string | null,
+  headerDate: string | null = null
+): string {
   let prefix = '';
   const stylePrefix = buildStylePrefix(customStyle);
   if (stylePrefix) {
     prefix += stylePrefix;
   }
-  prefix += `*Summary from <#${channelId}>*\n\n`;
+  prefix += `*Summary from <#${channelId}>*`;
+  if (headerDate) {
+    prefix += ` · ${headerDate}`;
+  }
+  prefix += '\n\n';
   return prefix;
 }
 
 function buildStylePrefix(customStyle: string | null): string | null {
-  const trimmed = customStyle?.trim();
+  // Stacked directives arrive one per line; keep the italic prefix on one line.
+  const trimmed = customStyle?.trim().replace(/\s*\n\s*/g, ' · ');
   if (!trimmed) {
     return null;
   }
@@ -0,0 +1,88 @@
+/**
+ * Structured view of a generated summary.
+ *
+ * The model writes mrkdwn with one bold header per section (see the output
+ * contract in `ai/prompt.ts`). Parsing that once here gives the Slack, canvas,
+ * and any JSON renderers the same sections instead of each re-splitting the
+ * text.
+ */
+
+export interface StructuredSummary {
+  /** Prose under *Summary* (bullets included, e.g. for standups). */
+  summary: string;
+  /** Bullets under an *Action items* section, when the model wrote one. */
+  actionItems: string[];
+  links: string[];
+  filesShared: string[];
+  imageHighlights: string[];
+  receipts: string[];
+}
+
+type ListSection = Exclude<keyof StructuredSummary, 'summary'>;
+
+const SECTION_BY_HEADER: Record<string, 'summary' | ListSection> = {
+  summary: 'summary',
+  'action items': 'actionItems',
+  'links shared': 'links',
+  links: 'links',
+  'files shared': 'filesShared',
+  'image highlights': 'imageHighlights',
+  receipts: 'receipts',
+};
+
+/** `*Header*`, `**Header**`, or `# Header`, optionally followed by a colon. */
+const HEADER_RE = /^\s*(?:#{1,6}\s+)?\*{0,2}([^*\n]+?)\*{0,2}:?\s*$/;
+const BULLET_RE = /^\s*(?:[-•]|\*(?!\*))\s+(.*)$/;
+/** Placeholder bullets the prompt and safety net use for an empty section. */
+const EMPTY_BULLETS = new Set(['none', 'disabled', '(no image highlights provided.)']);
+
+/** Section key for a header line, or `null` for any other line. */
+function sectionForLine(line: string): 'summary' | ListSection | null {
+  const trimmed = line.trim();
+  if (!trimmed.startsWith('*') && !trimmed.startsWith('#')) {
+    return null;
+  }
+  const match = trimmed.match(HEADER_RE);
+  if (!match) {
+    return null;
+  }
+  return SECTION_BY_HEADER[match[1].trim().toLowerCase()] ?? null;
+}
+
+/**
+ * Split a summary into its sections by header. Text before the first header
+ * and under unknown headers counts as summary prose; placeholder bullets
+ * such as "- None" are dropped from the lists.
+ */
+export function parseStructuredSummary(text: string): StructuredSummary {
+  const result: StructuredSummary = {
+    summary: '',
+    actionItems: [],
+    links: [],
+    filesShared: [],
+    imageHighlights: [],
+    receipts: [],
+  };
+  const summaryLines: string[] = [];
+  let current: 'summary' | ListSection = 'summary';
+
+  for (const line of text.split('\n')) {
+    const section = sectionForLine(line);
+    if (section !== null) {
+      current = section;
+      continue;
+    }
+    if (current === 'summary') {
+      summaryLines.push(line);
+      continue;
+    }
+    const bullet = line.match(BULLET_RE);
+    const item = (bullet ? bullet[1] : line).trim();
+    if (item.length > 0 && !EMPTY_BULLETS.has(item.toLowerCase())) {
+      result[current].push(item);
+    }
+  }
+
+  result.summary = summaryLines.join('\n').trim();
+  return result;
+}
@@ -5,16 +5,71 @@
  * orchestrates the work end-to-end.
  */
 
-import type { WebClient } from '@slack/web-api';
-import { LlmClient } from '../ai/anthropic';
+import { WebClient } from '@slack/web-api';
+import { LlmClient, TOO_LARGE_MESSAGE } from '../ai/anthropic';
 import type { AppConfig } from '../config';
+import { createDeadline, currentDeadline, runWithinDeadline, type Deadline } from '../deadline';
+import { log, withCorrelationId } from '../logging';
+import {
+  emitSummaryMetrics,
+  newSummaryRunStats,
+  recordOutputStats,
+  recordPromptOverflow,
+  recordPromptStats,
+  type SummaryRunStats,
+} from '../metrics';
+import { toMrkdwn } from '../slack/message_formatter';
 import { sanitizeGeneratedSlackMrkdwn } from '../slack/sanitize';
-import { getRecentMessages, getBotUserId } from '../slack/client';
-import { applySafetyNetSections, buildSummarizePromptData } from './prompt_builder';
-import { buildSummaryActionButtons } from './deliver';
 import {
-  CANONICAL_FAILURE_MESSAGE,
+  buildSummaryRangeMetadata,
+  getBotUserId,
+  getLastSummaryRange,
+  getUserTimeZone,
+  isSlackTimeoutError,
+  summaryRangeOf,
+  type HistoryBounds,
+  type SummaryRange,
+} from '../slack/client';
+import { slackClientOptions } from '../slack/retry';
+import { Semaphore } from '../semaphore';
+import { formatInTimeZone, isValidTimeZone } from '../time';
+import type { SummaryTemplate, SummaryTone } from '../types';
+import {
+  applySafetyNetSections,
+  buildSummarizePromptData,
+  IMAGES_DISABLED_MESSAGE,
+  NO_CANVAS_MESSAGE,
+  NO_NEW_MESSAGES_MESSAGE,
+  NO_PINNED_MESSAGES_MESSAGE,
+  fetchCanvasMessages,
+  fetchSourceMessages,
+  filterMessagesByKeyword,
+  filterUserMessages,
+  isChannelHistoryRun,
+  noKeywordMatchesMessage,
+} from './prompt_builder';
+import {
+  buildChannelDeliveryText,
+  bookmarkSummary,
+  buildSummaryActionButtons,
+  buildSummaryFooterBlocks,
+  deliverOrDmRequester,
+  dmFallbackNotice,
+  newLongMessageProgress,
+  notInChannelMessage,
+  notifyRequester,
+  postLongMessage,
+  postSummaryToChannel,
+  renderSummaryFooter,
+  resolveDeliveryTarget,
+  selectPostingToken,
+  type DeliveryTarget,
+} from './deliver';
+import { summarizeInWindows } from './rollup';
+import { parseStructuredSummary, type StructuredSummary } from './structured';
+import {
   buildStreamPrefix,
+  failureMessageFor,
   streamSummaryToAssistantThread,
 } from './streaming';
 
@@ -29,6 +84,51 @@ export interface SummarizeRequest {
   threadTs: string;
   messageCount: number;
   customStyle: string | null;
+  /** Tone preset saved on the assistant thread; see `TONE_DIRECTIVES`. */
+  tone: SummaryTone | null;
+  /** Summarize this thread in `channelId` instead of recent channel history. */
+  sourceThreadTs: string | null;
+  /** Group channel history by thread, with replies fetched per topic. */
+  groupByThread: boolean;
+  /**
+   * Skip the `ignoredUserIds` filter so bot / integration posts are summarised.
+   * The bot's own messages are still dropped to avoid summarising summaries.
+   */
+  includeBots: boolean;
+  /** Describe the images on the message at `sourceThreadTs` instead of summarising. */
+  filesOnly: boolean;
+  /** Alternate summary layout, e.g. a per-person standup digest. */
+  template: SummaryTemplate | null;
+  /** DM the requester a link once a `postChannelId` summary is published. */
+  notifyMe: boolean;
+  /** Only summarize messages containing this word; see `filterMessagesByKeyword`. */
+  keywordFilter: string | null;
+  /** Summarize the source channel's pinned messages instead of its history. */
+  pinnedOnly: boolean;
+  /** Workspace the request came from; see `slack/workspace.ts`. */
+  teamId: string | null;
+  /** Enterprise Grid org, when there is one. */
+  enterpriseId: string | null;
+  /** Publish the summary in this channel instead of the assistant thread. */
+  postChannelId: string | null;
+  /** Overwrite this earlier summary message in the assistant thread ("refresh"). */
+  replaceTs: string | null;
+  /** Summarize the channel's canvas as one document instead of its messages. */
+  canvasOnly: boolean;
+  /** Validated IANA zone from "tz=…"; overrides the requester's Slack zone. */
+  timezone: string | null;
+  /** Read channel history from just before this ts ("before=<permalink>"). */
+  beforeTs: string | null;
+  /** Read channel history from just after this ts ("after=<permalink>"). */
+  afterTs: string | null;
+  /** Start channel history after the range the thread's last summary covered. */
+  sinceLastSummary: boolean;
+  /** Leave images out of this run only; `config.disableImages` turns them off everywhere. */
+  skipImages: boolean;
+  /** Bookmark a `postChannelId` summary in that channel once it is published. */
+  bookmark: boolean;
+  /** Leave user mentions in the summary live; see `sanitizeGeneratedSlackMrkdwn`. */
+  allowMentions: boolean;
 }
 
 interface RunArgs {
@@ -36,26 +136,374 @@ interface RunArgs {
   client: WebClient;
   request: SummarizeRequest;
   llm?: LlmClient;
+  /** Defaults to the invocation's deadline, else `config.taskDeadlineSecs` from now. */
+  deadline?: Deadline;
   fetchImpl?: typeof fetch;
+  /** Pause between delivery retries; tests inject a no-op. */
+  sleep?: (ms: number) => Promise<void>;
+}
+
+/**
+ * Build the Anthropic client for one run. A custom style gets
+ * `anthropicStyleTemperature` (if set) so personas can be more playful than
+ * the default summary voice.
+ */
+export function createLlmClient(config: AppConfig, customStyle: string | null): LlmClient {
+  const hasStyle = customStyle !== null && customStyle.trim().length > 0;
+  return new LlmClient({
+    apiKey: config.anthropicApiKey,
+    model: config.anthropicModel,
+    maxOutputTokens: config.anthropicMaxOutputTokens,
+    baseUrl: config.anthropicBaseUrl,
+    streamStallTimeoutMs: config.streamStallTimeoutMs,
+    streamMaxBufferBytes: config.streamMaxBufferBytes,
+    reasoningEffort: config.anthropicReasoningEffort,
+    temperature: hasStyle
+      ? config.anthropicStyleTemperature ?? config.anthropicTemperature
+      : config.anthropicTemperature,
+  });
+}
+
+/**
+ * Today's date for the summary title: in the run's `tz=` zone when one was
+ * given, else the requester's Slack time zone when `users.info` exposes one,
+ * else `config.defaultTimezone`.
+ */
+export async function resolveHeaderDate(
+  client: WebClient,
+  request: Pick<SummarizeRequest, 'userId' | 'timezone'>,
+  defaultTimezone: string,
+  now: Date = new Date()
+): Promise<string> {
+  if (request.timezone && isValidTimeZone(request.timezone)) {
+    return formatInTimeZone(now.getTime() / 1000, request.timezone);
+  }
+  const userZone = await getUserTimeZone(client, request.userId);
+  const zone = userZone && isValidTimeZone(userZone) ? userZone : defaultTimezone;
+  return formatInTimeZone(now.getTime() / 1000, zone);
+}
+
+interface GenerateSummaryTextArgs {
+  config: AppConfig;
+  client: WebClient;
+  llm: LlmClient;
+  channelId: string;
+  messageCount: number;
+  customStyle: string | null;
+  tone?: SummaryTone | null;
+  sourceThreadTs: string | null;
+  groupByThread?: boolean;
+  /** Keep messages from `config.ignoredUserIds`; the bot's own are always dropped. */
+  includeBots?: boolean;
+  /** Describe the images on the message at `sourceThreadTs`; see `BuildPromptDataArgs`. */
+  filesOnly?: boolean;
+  template?: SummaryTemplate | null;
+  keywordFilter?: string | null;
+  pinnedOnly?: boolean;
+  /** Summarize the channel canvas instead of messages; see `fetchCanvasMessages`. */
+  canvasOnly?: boolean;
+  /** Exclusive ts bounds for channel history; see `fetchSourceMessages`. */
+  bounds?: HistoryBounds;
+  /** Skip images for this run on top of `config.disableImages`. */
+  skipImages?: boolean;
+  /** Workspace the run belongs to; see `BuildPromptDataArgs.teamId`. */
+  teamId?: string | null;
+  /** Filled with message / image / token counts for the run's metrics. */
+  stats?: SummaryRunStats;
+  fetchImpl?: typeof fetch;
+}
+
+/** A generated summary as text plus its parsed sections. */
+export interface GeneratedSummary {
+  /** Safety-netted mrkdwn, not yet sanitised. */
+  text: string;
+  structured: StructuredSummary;
+  /** History the summary covered; `null` unless `isChannelHistoryRun`. */
+  range: SummaryRange | null;
+}
+
+/** The later of two optional Slack timestamps. */
+function laterTs(a: string | null, b: string | null): string | null {
+  if (a === null || b === null) {
+    return a ?? b;
+  }
+  return Number(b) > Number(a) ? b : a;
+}
+
+/**
+ * For `--since-last-summary`: where the thread's previous summary of this
+ * channel stopped. `null` when the flag is off, the run doesn't read channel
+ * history, or no earlier summary recorded a range, so the run reads the
+ * latest history as usual.
+ */
+async function resolveSinceLastSummaryTs(
+  client: WebClient,
+  request: SummarizeRequest
+): Promise<string | null> {
+  const historyRun = isChannelHistoryRun({
+    threadTs: request.sourceThreadTs,
+    filesOnly: request.filesOnly,
+    pinnedOnly: request.pinnedOnly,
+    canvasOnly: request.canvasOnly,
+  });
+  if (!request.sinceLastSummary || !historyRun) {
+    return null;
+  }
+  try {
+    const range = await getLastSummaryRange(client, {
+      channel: request.originChannelId,
+      threadTs: request.threadTs,
+      sourceChannelId: request.channelId,
+    });
+    return range?.latestTs ?? null;
+  } catch (err) {
+    log.warn('Could not read the last summary range; summarizing recent history', {
+      error: err instanceof Error ? err.message : String(err),
+    });
+    return null;
+  }
+}
+
+/**
+ * Fetch, prompt, and summarise without posting anywhere. Returns the
+ * safety-netted (not yet sanitised) summary with its sections parsed, or
+ * `null` when there was nothing to summarise (including when no message
+ * matched `keywordFilter`). Oversized prompts fall back to a windowed rollup.
+ */
+export async function generateSummaryText(
+  args: GenerateSummaryTextArgs
+): Promise<GeneratedSummary | null> {
+  const { config, client, llm } = args;
+  const messages = args.canvasOnly
+    ? await fetchCanvasMessages(client, args.channelId, config.slackBotToken, args.fetchImpl)
+    : await fetchSourceMessages(
+        client,
+        args.channelId,
+        // Runs from `summarize` are already capped; a workflow step lands here directly.
+        Math.min(args.messageCount, config.hardMessageCap),
+        args.sourceThreadTs,
+        args.filesOnly,
+        args.pinnedOnly,
+        args.bounds
+      );
+  if (messages.length === 0) {
+    return null;
+  }
+  const historyRun = isChannelHistoryRun({
+    threadTs: args.sourceThreadTs,
+    filesOnly: args.filesOnly,
+    pinnedOnly: args.pinnedOnly,
+    canvasOnly: args.canvasOnly,
+  });
+  const range = historyRun ? summaryRangeOf(args.channelId, messages) : null;
+  const botUserId = await getBotUserId(client);
+  const userMessages = filterUserMessages(
+    messages,
+    botUserId,
+    args.includeBots ? [] : config.ignoredUserIds
+  );
+  const matched = filterMessagesByKeyword(userMessages, args.keywordFilter ?? null);
+  if (matched.length === 0 && args.keywordFilter) {
+    return null;
+  }
+  const promptData = await buildSummarizePromptData({
+    client,
+    botToken: config.slackBotToken,
+    channelId: args.channelId,
+    messages: userMessages,
+    customStyle: args.customStyle,
+    tone: args.tone,
+    threadTs: args.sourceThreadTs,
+    groupByThread: args.groupByThread,
+    filesOnly: args.filesOnly,
+    template: args.template,
+    keywordFilter: args.keywordFilter,
+    pinnedOnly: args.pinnedOnly,
+    canvasOnly: args.canvasOnly,
+    systemPromptOverride: config.systemPromptOverride,
+    assistantName: config.assistantName,
+    enableReceipts: config.enableReceipts,
+    includeAttachmentText: config.includeAttachmentText,
+    imageDownloadConcurrency: config.imageDownloadConcurrency,
+    inlineImageMaxBytes: config.inlineImageMaxBytes,
+    disableImages: config.disableImages || (args.skipImages ?? false),
+    citeInline: config.citeInline,
+    summarySections: config.summarySections,
+    detectSummaryLanguage: config.detectSummaryLanguage,
+    permalinkConcurrency: config.permalinkConcurrency,
+    teamId: args.teamId,
+    fetchImpl: args.fetchImpl,
+  });
+  recordPromptStats(args.stats, matched.length, promptData.prompt);
+  recordPromptOverflow(promptData.prompt, llm.maxOutputTokens, 'non_streaming');
+  let summary = await llm.generateSummary(promptData.prompt);
+  if (summary === TOO_LARGE_MESSAGE) {
+    summary = await summarizeInWindows(llm, promptData.promptArgs);
+  }
+  recordOutputStats(args.stats, summary);
+  const text = applySafetyNetSections(summary, promptData);
+  return { text, structured: parseStructuredSummary(text), range };
+}
+
+/** Process-wide cap on summaries in flight; see `AppConfig.maxConcurrentSummaries`. */
+let summarySlots: Semaphore | null = null;
+
+/** The shared semaphore, rebuilt only if the configured limit changes. */
+export function summarySemaphore(limit: number): Semaphore {
+  if (summarySlots === null || summarySlots.limit !== limit) {
+    summarySlots = new Semaphore(limit);
+  }
+  return summarySlots;
 }
 
 /**
  * Summarise the requested channel and post the result back into the assistant
  * thread. Streams the response when `config.enableStreaming` is set; otherwise
- * makes a single Anthropic call and posts the result.
+ * makes a single Anthropic call and posts the result. Runs with a
+ * `postChannelId` always take the non-streaming path and publish to that
+ * channel, leaving a confirmation in the assistant thread. Runs with a
+ * `replaceTs` also skip streaming and edit that message in place.
  */
 export async function runSummarization(args: RunArgs): Promise<void> {
+  return runInSummarySlot(args.config, args.request, () => summarize(args));
+}
+
+/**
+ * Run one summary job under its correlation id once the shared
+ * {@link summarySemaphore} has a free slot. Every kind of summary goes
+ * through here so `AppConfig.maxConcurrentSummaries` bounds them all.
+ */
+export function runInSummarySlot(
+  config: AppConfig,
+  request: { correlationId: string; teamId: string | null; enterpriseId: string | null },
+  work: () => Promise<void>
+): Promise<void> {
+  const { correlationId, teamId, enterpriseId } = request;
+  return withCorrelationId(
+    correlationId,
+    () => {
+      const slots = summarySemaphore(config.maxConcurrentSummaries);
+      if (slots.inFlight >= slots.limit) {
+        log.info('Waiting for a free summary slot', { limit: slots.limit, queued: slots.queued });
+      }
+      return slots.run(work);
+    },
+    { teamId, enterpriseId }
+  );
+}
+
+/** Reply noting that a request asked for more than `AppConfig.hardMessageCap`. */
+export function messageCapNotice(requested: number, cap: number): string {
+  return (
+    `Summaries here read at most ${cap} messages, ` +
+    `so I'm using the latest ${cap} instead of ${requested}.`
+  );
+}
+
+/**
+ * Cut `request.messageCount` down to `config.hardMessageCap`. Every entry point
+ * (assistant thread, mention, shortcut, workflow, button) ends up here, so the
+ * cap holds however the run started. The requester is told when it changed a
+ * count that would actually have been read.
+ */
+async function applyHardMessageCap(args: RunArgs): Promise<RunArgs> {
   const { config, client, request } = args;
-  const llm =
-    args.llm ??
-    new LlmClient({
-      apiKey: config.anthropicApiKey,
-      model: config.anthropicModel,
-      maxOutputTokens: config.anthropicMaxOutputTokens,
+  const cap = config.hardMessageCap;
+  if (request.messageCount <= cap) {
+    return args;
+  }
+  log.info('Clamped message count to the hard cap', {
+    requested: request.messageCount,
+    cap,
+  });
+  if (!request.filesOnly && !request.pinnedOnly && !request.canvasOnly) {
+    try {
+      await client.chat.postMessage({
+        channel: request.originChannelId,
+        thread_ts: request.threadTs,
+        text: messageCapNotice(request.messageCount, cap),
+      });
+    } catch (err) {
+      log.warn('Failed to post the message cap notice', {
+        error: err instanceof Error ? err.message : String(err),
+      });
+    }
+  }
+  return { ...args, request: { ...request, messageCount: cap } };
+}
+
+async function summarize(requested: RunArgs): Promise<void> {
+  const args = await applyHardMessageCap(requested);
+  const { config, request } = args;
+  const stats = newSummaryRunStats();
+  const startedAt = Date.now();
+  const target = resolveDeliveryTarget(
+    request.channelId,
+    request.postChannelId,
+    request.replaceTs
+  );
+  if (target.kind === 'channel' && !target.crossChannel) {
+    // Summaries posted here feed the next run of this channel; filterUserMessages
+    // drops them by header, which is worth knowing when a summary looks thin.
+    log.debug('Summarizing the channel summaries are posted to; earlier summaries are skipped', {
+      channelId: request.channelId,
+    });
+  }
+  let success = false;
+  try {
+    success = await deliverSummary(args, target, stats);
+  } finally {
+    emitSummaryMetrics({
+      stats,
+      latencyMs: Date.now() - startedAt,
+      success,
+      delivery:
+        config.enableStreaming && target.kind === 'assistant_thread' ? 'streaming' : target.kind,
+    });
+  }
+}
+
+/** Generate and deliver one summary. Resolves `false` when it failed and the user was told so. */
+async function deliverSummary(
+  args: RunArgs,
+  target: DeliveryTarget,
+  stats: SummaryRunStats
+): Promise<boolean> {
+  const { config, client, request } = args;
+  const llm = args.llm ?? createLlmClient(config, request.customStyle);
+  const deadline =
+    args.deadline ?? currentDeadline() ?? createDeadline(config.taskDeadlineSecs * 1000);
+  const summaryFooter = renderSummaryFooter(config.summaryFooter, {
+    model: config.anthropicModel,
+    count: request.messageCount,
+    channelId: request.channelId,
+  });
+
+  if (request.filesOnly && config.disableImages) {
+    await client.chat.postMessage({
+      channel: request.originChannelId,
+      thread_ts: request.threadTs,
+      text: IMAGES_DISABLED_MESSAGE,
     });
+    return true;
+  }
 
-  if (config.enableStreaming) {
-    await streamSummaryToAssistantThread({
+  const headerDate = await resolveHeaderDate(client, request, config.defaultTimezone);
+  const sinceTs = await resolveSinceLastSummaryTs(client, request);
+  const bounds: HistoryBounds = {
+    latest: request.beforeTs,
+    oldest: laterTs(sinceTs, request.afterTs),
+  };
+  const noMessagesMessage = request.canvasOnly
+    ? NO_CANVAS_MESSAGE
+    : request.pinnedOnly
+      ? NO_PINNED_MESSAGES_MESSAGE
+      : sinceTs
+        ? NO_NEW_MESSAGES_MESSAGE
+        : config.noMessagesMessage;
+
+  if (config.enableStreaming && target.kind === 'assistant_thread') {
+    return streamSummaryToAssistantThread({
       client,
       llm,
       botToken: config.slackBotToken,
@@ -64,63 +512,190 @@ export async function runSummarization(args: RunArgs): Promise<void> {
       assistantThreadTs: request.threadTs,
       messageCount: request.messageCount,
       customStyle: request.customStyle,
+      tone: request.tone,
+      sourceThreadTs: request.sourceThreadTs,
+      groupByThread: request.groupByThread,
+      filesOnly: request.filesOnly,
+      template: request.template,
+      keywordFilter: request.keywordFilter,
+      pinnedOnly: request.pinnedOnly,
+      canvasOnly: request.canvasOnly,
+      historyBounds: bounds,
+      systemPromptOverride: config.systemPromptOverride,
+      assistantName: config.assistantName,
+      enableReceipts: config.enableReceipts,
+      includeAttachmentText: config.includeAttachmentText,
+      imageDownloadConcurrency: config.imageDownloadConcurrency,
+      inlineImageMaxBytes: config.inlineImageMaxBytes,
+      disableImages: config.disableImages || request.skipImages,
+      citeInline: config.citeInline,
+      summarySections: config.summarySections,
+      allowMentions: request.allowMentions,
+      detectSummaryLanguage: config.detectSummaryLanguage,
+      unfurlSummaryLinks: config.unfurlSummaryLinks,
+      permalinkConcurrency: config.permalinkConcurrency,
+      ignoredUserIds: request.includeBots ? [] : config.ignoredUserIds,
+      teamId: request.teamId,
+      headerDate,
+      summaryFooter,
+      noMessagesMessage,
       correlationId: request.correlationId,
+      staleStreamMaxAgeSecs: config.staleStreamMaxAgeSecs,
       streamMaxChunkChars: config.streamMaxChunkChars,
       streamMinAppendIntervalMs: config.streamMinAppendIntervalMs,
+      stats,
+      deadline,
       fetchImpl: args.fetchImpl,
     });
-    return;
   }
 
   try {
-    const messages = await getRecentMessages(client, request.channelId, request.messageCount);
-    if (messages.length === 0) {
+    const generated = await runWithinDeadline(deadline, () =>
+      generateSummaryText({
+        config,
+        client,
+        llm,
+        channelId: request.channelId,
+        messageCount: request.messageCount,
+        customStyle: request.customStyle,
+        tone: request.tone,
+        sourceThreadTs: request.sourceThreadTs,
+        groupByThread: request.groupByThread,
+        includeBots: request.includeBots,
+        filesOnly: request.filesOnly,
+        template: request.template,
+        keywordFilter: request.keywordFilter,
+        pinnedOnly: request.pinnedOnly,
+        canvasOnly: request.canvasOnly,
+        bounds,
+        skipImages: request.skipImages,
+        teamId: request.teamId,
+        stats,
+        fetchImpl: args.fetchImpl,
+      })
+    );
+    if (generated === null) {
       await client.chat.postMessage({
         channel: request.originChannelId,
         thread_ts: request.threadTs,
-        text: 'No messages found to summarize.',
+        text: request.keywordFilter
+          ? noKeywordMatchesMessage(request.keywordFilter)
+          : noMessagesMessage,
       });
-      return;
+      return true;
     }
-    const botUserId = await getBotUserId(client);
-    const userMessages = botUserId ? messages.filter((m) => m.user !== botUserId) : messages;
-    const promptData = await buildSummarizePromptData({
-      client,
-      botToken: config.slackBotToken,
-      channelId: request.channelId,
-      messages: userMessages,
-      customStyle: request.customStyle,
-      fetchImpl: args.fetchImpl,
-    });
-    const summary = await llm.generateSummary(promptData.prompt);
-    const safetyNetted = applySafetyNetSections(summary, promptData);
+    const safetyNetted = toMrkdwn(generated.text);
+
+    if (target.kind === 'channel') {
+      const channelText = sanitizeGeneratedSlackMrkdwn(
+        buildChannelDeliveryText(safetyNetted, request.channelId, target),
+        { allowUserMentions: request.allowMentions }
+      );
+      const postingToken = selectPostingToken(config);
+      const poster =
+        postingToken === config.slackBotToken
+          ? client
+          : new WebClient(postingToken, slackClientOptions(config));
+      const progress = newLongMessageProgress();
+      const outcome = await deliverOrDmRequester(client, {
+        deliver: () =>
+          postSummaryToChannel(
+            poster,
+            target.channelId,
+            channelText,
+            config.unfurlSummaryLinks,
+            progress
+          ),
+        userId: request.userId,
+        summary: channelText,
+        retries: config.deliveryMaxRetries,
+        sleep: args.sleep,
+      });
+      if (outcome.dmed) {
+        await client.chat.postMessage({
+          channel: request.originChannelId,
+          thread_ts: request.threadTs,
+          text: dmFallbackNotice(target.channelId),
+        });
+        return true;
+      }
+      const delivered = outcome.result;
+      await client.chat.postMessage({
+        channel: request.originChannelId,
+        thread_ts: request.threadTs,
+        text: delivered.ok
+          ? `✅ Posted to <#${target.channelId}>`
+          : notInChannelMessage(target.channelId, config.assistantName),
+      });
+      if (delivered.ok && request.notifyMe) {
+        await notifyRequester(client, request.userId, target.channelId, delivered.ts);
+      }
+      if (delivered.ok && request.bookmark) {
+        await bookmarkSummary(client, {
+          channelId: target.channelId,
+          sourceChannelId: request.channelId,
+          ts: delivered.ts,
+        });
+      }
+      return delivered.ok;
+    }
+
     const text = sanitizeGeneratedSlackMrkdwn(
-      buildStreamPrefix(request.channelId, request.customStyle) + safetyNetted
+      buildStreamPrefix(request.channelId, request.customStyle, headerDate) + safetyNetted,
+      { allowUserMentions: request.allowMentions }
     );
-    const blocks = buildSummaryActionButtons({
-      sourceChannelId: request.channelId,
-      messageCount: request.messageCount,
-      currentStyle: request.customStyle,
-    });
-    await client.chat.postMessage({
-      channel: request.originChannelId,
-      thread_ts: request.threadTs,
-      text,
-      blocks,
+    const blocks = [
+      ...buildSummaryFooterBlocks(summaryFooter),
+      ...buildSummaryActionButtons({
+        sourceChannelId: request.channelId,
+        messageCount: request.messageCount,
+        currentStyle: request.customStyle,
+      }),
+    ];
+    // Stamped so a later `--since-last-summary` run can start where this one ended.
+    const metadata = generated.range ? buildSummaryRangeMetadata(generated.range) : undefined;
+    const progress = newLongMessageProgress();
+    await deliverOrDmRequester(client, {
+      deliver: () =>
+        target.kind === 'replace'
+          ? client.chat.update({
+              channel: request.originChannelId,
+              ts: target.ts,
+              text,
+              blocks,
+              ...(metadata ? { metadata } : {}),
+            })
+          : postLongMessage(client, {
+              channel: request.originChannelId,
+              threadTs: request.threadTs,
+              text,
+              blocks,
+              metadata,
+              unfurlLinks: config.unfurlSummaryLinks,
+              progress,
+            }),
+      userId: request.userId,
+      summary: text,
+      retries: config.deliveryMaxRetries,
+      sleep: args.sleep,
     });
+    return true;
   } catch (err) {
-    console.error('Non-streaming summarization failed', {
-      corr_id: request.correlationId,
+    log.error('Non-streaming summarization failed', {
       error: err instanceof Error ? err.message : String(err),
+      timeout: isSlackTimeoutError(err),
     });
     try {
       await client.chat.postMessage({
         channel: request.originChannelId,
         thread_ts: request.threadTs,
-        text: CANONICAL_FAILURE_MESSAGE,
+        text: failureMessageFor(err),
       });
     } catch (followup) {
-      console.error('Failed to post canonical failure', followup);
+      log.error('Failed to post canonical failure', {
+        error: followup instanceof Error ? followup.message : String(followup),
+      });
     }
+    return false;
   }
 }
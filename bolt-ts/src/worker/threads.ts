@@ -0,0 +1,105 @@
+/**
+ * Thread-aware channel summaries ("summarize by thread").
+ *
+ * Channel history only returns thread roots, so a flat summary sees the first
+ * message of every thread and none of the discussion. For the opt-in grouped
+ * mode we fetch replies for a bounded number of threads and render each one
+ * as a topic with its replies indented underneath, so the model can write a
+ * per-topic digest.
+ */
+
+import type { WebClient } from '@slack/web-api';
+import { getThreadMessages, type RecentMessage } from '../slack/client';
+
+/** Threads whose replies we fetch per run (one `conversations.replies` call each). */
+export const MAX_GROUPED_THREADS = 10;
+/** Replies kept per thread, newest last. */
+export const MAX_REPLIES_PER_THREAD = 50;
+
+export interface ThreadGroup {
+  root: RecentMessage;
+  replies: RecentMessage[];
+}
+
+/**
+ * Fetch replies for the most recent threaded roots in `messages`, keyed by
+ * root ts. Roots without replies, and any beyond `maxThreads`, are skipped.
+ */
+export async function fetchThreadReplies(
+  client: WebClient,
+  channelId: string,
+  messages: RecentMessage[],
+  maxThreads: number = MAX_GROUPED_THREADS
+): Promise<Map<string, RecentMessage[]>> {
+  const roots = messages
+    .filter((m) => (m.replyCount ?? 0) > 0 && (m.threadTs ?? m.ts) === m.ts)
+    .sort((a, b) => Number(b.ts) - Number(a.ts))
+    .slice(0, maxThreads);
+  const fetched = await Promise.all(
+    roots.map((root) =>
+      // +1: Slack returns the root itself as the first message.
+      getThreadMessages(client, channelId, root.ts, MAX_REPLIES_PER_THREAD + 1)
+    )
+  );
+  const repliesByRoot = new Map<string, RecentMessage[]>();
+  roots.forEach((root, i) => {
+    repliesByRoot.set(root.ts, fetched[i].filter((m) => m.ts !== root.ts));
+  });
+  return repliesByRoot;
+}
+
+/**
+ * Group channel messages into topics, oldest first. Each top-level message
+ * becomes a group; fetched replies (and any thread broadcasts that also
+ * appeared in history) attach to their root. A broadcast whose root isn't in
+ * the window stands alone.
+ */
+export function groupMessagesByThread(
+  messages: RecentMessage[],
+  repliesByRoot: Map<string, RecentMessage[]>
+): ThreadGroup[] {
+  const sorted = [...messages].sort((a, b) => Number(a.ts) - Number(b.ts));
+  const groups = new Map<string, ThreadGroup>();
+  for (const msg of sorted) {
+    const root = groups.get(msg.threadTs ?? msg.ts);
+    if (root && root.root !== msg) {
+      root.replies.push(msg);
+    } else {
+      groups.set(msg.ts, { root: msg, replies: [] });
+    }
+  }
+  for (const [rootTs, replies] of repliesByRoot) {
+    const group = groups.get(rootTs);
+    if (!group) {
+      continue;
+    }
+    const seen = new Set(group.replies.map((r) => r.ts));
+    group.replies.push(...replies.filter((r) => !seen.has(r.ts)));
+    group.replies.sort((a, b) => Number(a.ts) - Number(b.ts));
+  }
+  return [...groups.values()];
+}
+
+/**
+ * Render groups as prompt lines: `Topic: <root line>` followed by indented
+ * `reply: <line>` entries. `formatLines` formats a batch of messages the same
+ * way the flat prompt does (author names, rich_text fallback, attachments).
+ */
+export function formatThreadGroups(
+  groups: ThreadGroup[],
+  formatLines: (messages: RecentMessage[]) => string[]
+): string[] {
+  const lines: string[] = [];
+  for (const group of groups) {
+    const [rootLine] = formatLines([group.root]);
+    if (group.replies.length === 0) {
+      lines.push(rootLine);
+      continue;
+    }
+    lines.push(`Topic: ${rootLine}`);
+    for (const reply of formatLines(group.replies)) {
+      lines.push(`  reply: ${reply}`);
+    }
+  }
+  return lines;
+}
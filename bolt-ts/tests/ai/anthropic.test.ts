@@ -1,7 +1,12 @@
 import {
   LlmClient,
+  RefusalError,
+  STREAM_BUFFER_OVERFLOW_MESSAGE,
+  STREAM_STALLED_MESSAGE,
+  SseFrameGuard,
   TOO_LARGE_MESSAGE,
   isPromptTooLargeError,
+  type StreamEvent,
 } from '../../src/ai/anthropic';
 import { buildPrompt } from '../../src/ai/prompt';
 
@@ -52,6 +57,92 @@ describe('LlmClient.generateSummary', () => {
     expect(requestUrl).toContain('/v1/messages');
   });
 
+  it('sends the configured temperature instead of adaptive thinking', async () => {
+    const fetchImpl = jest.fn().mockResolvedValue(
+      new Response(JSON.stringify({ content: [{ type: 'text', text: 'ok' }] }), {
+        status: 200,
+        headers: { 'Content-Type': 'application/json' },
+      })
+    );
+    const client = new LlmClient({
+      apiKey: 'sk-ant-test',
+      model: 'claude-test',
+      temperature: 0.3,
+      fetchImpl: fetchImpl as unknown as typeof fetch,
+    });
+    await client.generateSummary(makePrompt());
+    const body = JSON.parse(String(fetchImpl.mock.calls[0][1].body));
+    expect(body.temperature).toBe(0.3);
+    expect(body.thinking).toBeUndefined();
+  });
+
+  it('sends the configured reasoning effort to models that support it', async () => {
+    const fetchImpl = jest.fn().mockImplementation(
+      async () =>
+        new Response(JSON.stringify({ content: [{ type: 'text', text: 'ok' }] }), {
+          status: 200,
+          headers: { 'Content-Type': 'application/json' },
+        })
+    );
+    const supported = new LlmClient({
+      apiKey: 'sk-ant-test',
+      model: 'claude-sonnet-4-6',
+      reasoningEffort: 'low',
+      fetchImpl: fetchImpl as unknown as typeof fetch,
+    });
+    await supported.generateSummary(makePrompt());
+    const body = JSON.parse(String(fetchImpl.mock.calls[0][1].body));
+    expect(body.output_config).toEqual({ effort: 'low' });
+    expect(body.thinking).toEqual({ type: 'adaptive' });
+
+    const unsupported = new LlmClient({
+      apiKey: 'sk-ant-test',
+      model: 'claude-test',
+      reasoningEffort: 'low',
+      fetchImpl: fetchImpl as unknown as typeof fetch,
+    });
+    await unsupported.generateSummary(makePrompt());
+    expect(JSON.parse(String(fetchImpl.mock.calls[1][1].body)).output_config).toBeUndefined();
+  });
+
+  it('sends requests to the configured base URL', async () => {
+    const fetchImpl = jest.fn().mockResolvedValue(
+      new Response(JSON.stringify({ content: [{ type: 'text', text: 'ok' }] }), {
+        status: 200,
+        headers: { 'Content-Type': 'application/json' },
+      })
+    );
+    const client = new LlmClient({
+      apiKey: 'sk-ant-test',
+      model: 'claude-test',
+      baseUrl: 'https://gateway.example.com/anthropic',
+      fetchImpl: fetchImpl as unknown as typeof fetch,
+    });
+    await client.generateSummary(makePrompt());
+    const requestUrl = String(fetchImpl.mock.calls[0][0]);
+    expect(requestUrl).toBe('https://gateway.example.com/anthropic/v1/messages');
+    const headers = new Headers(fetchImpl.mock.calls[0][1].headers);
+    expect(headers.get('x-api-key')).toBe('sk-ant-test');
+  });
+
+  it('keeps adaptive thinking when no temperature is configured', async () => {
+    const fetchImpl = jest.fn().mockResolvedValue(
+      new Response(JSON.stringify({ content: [{ type: 'text', text: 'ok' }] }), {
+        status: 200,
+        headers: { 'Content-Type': 'application/json' },
+      })
+    );
+    const client = new LlmClient({
+      apiKey: 'sk-ant-test',
+      model: 'claude-test',
+      fetchImpl: fetchImpl as unknown as typeof fetch,
+    });
+    await client.generateSummary(makePrompt());
+    const body = JSON.parse(String(fetchImpl.mock.calls[0][1].body));
+    expect(body.temperature).toBeUndefined();
+    expect(body.thinking).toEqual({ type: 'adaptive' });
+  });
+
   it('returns the friendly TOO_LARGE_MESSAGE when Anthropic rejects an oversize prompt', async () => {
     const errorBody = JSON.stringify({
       type: 'error',
@@ -69,6 +160,22 @@ describe('LlmClient.generateSummary', () => {
     expect(result).toBe(TOO_LARGE_MESSAGE);
   });
 
+  it('raises RefusalError when Anthropic stops with a refusal', async () => {
+    const response = { content: [], stop_reason: 'refusal' };
+    const fetchImpl = jest.fn().mockResolvedValue(
+      new Response(JSON.stringify(response), {
+        status: 200,
+        headers: { 'Content-Type': 'application/json' },
+      })
+    );
+    const client = new LlmClient({
+      apiKey: 'sk-ant-test',
+      model: 'claude-test',
+      fetchImpl: fetchImpl as unknown as typeof fetch,
+    });
+    await expect(client.generateSummary(makePrompt())).rejects.toBeInstanceOf(RefusalError);
+  });
+
   it('rethrows non-too-large errors', async () => {
     const errorBody = JSON.stringify({
       type: 'error',
@@ -161,4 +268,109 @@ describe('LlmClient.generateSummaryStream', () => {
       { kind: 'completed', delta: undefined },
     ]);
   });
+
+  it('fails a stream that stops sending events once the stall timeout passes', async () => {
+    const opening = [
+      'event: message_start',
+      'data: {"type":"message_start","message":{"id":"m_1","type":"message","role":"assistant","model":"claude-test","content":[],"stop_reason":null,"stop_sequence":null,"usage":{"input_tokens":0,"output_tokens":0}}}',
+      '',
+      'event: content_block_delta',
+      'data: {"type":"content_block_delta","index":0,"delta":{"type":"text_delta","text":"Hello"}}',
+      '',
+      '',
+    ].join('\n');
+    // Sends one delta, then never yields again (and never closes).
+    const stream = new ReadableStream<Uint8Array>({
+      start(controller) {
+        controller.enqueue(new TextEncoder().encode(opening));
+      },
+    });
+    const fetchImpl = jest.fn().mockResolvedValue(
+      new Response(stream, { status: 200, headers: { 'Content-Type': 'text/event-stream' } })
+    );
+
+    const client = new LlmClient({
+      apiKey: 'sk-ant-test',
+      model: 'claude-test',
+      streamStallTimeoutMs: 50,
+      fetchImpl: fetchImpl as unknown as typeof fetch,
+    });
+    const streaming = await client.generateSummaryStream(makePrompt());
+    if (streaming.kind !== 'active') {
+      throw new Error('expected an active stream');
+    }
+    const events: StreamEvent[] = [];
+    while (true) {
+      const { value, done } = await streaming.iterator.next();
+      if (done) {
+        break;
+      }
+      events.push(value);
+    }
+    expect(events).toEqual([
+      { kind: 'text_delta', delta: 'Hello' },
+      { kind: 'failed', message: STREAM_STALLED_MESSAGE, timedOut: true },
+    ]);
+  });
+
+  it('fails a stream whose SSE frame outgrows the buffer limit', async () => {
+    const opening = [
+      'event: message_start',
+      'data: {"type":"message_start","message":{"id":"m_1","type":"message","role":"assistant","model":"claude-test","content":[],"stop_reason":null,"stop_sequence":null,"usage":{"input_tokens":0,"output_tokens":0}}}',
+      '',
+      '',
+    ].join('\n');
+    // One giant line with no frame boundary, as a broken upstream might send.
+    const giant = `data: ${'x'.repeat(4096)}`;
+    const stream = new ReadableStream<Uint8Array>({
+      start(controller) {
+        controller.enqueue(new TextEncoder().encode(opening));
+        controller.enqueue(new TextEncoder().encode(giant));
+      },
+    });
+    const fetchImpl = jest.fn().mockResolvedValue(
+      new Response(stream, { status: 200, headers: { 'Content-Type': 'text/event-stream' } })
+    );
+
+    const client = new LlmClient({
+      apiKey: 'sk-ant-test',
+      model: 'claude-test',
+      streamMaxBufferBytes: 1024,
+      fetchImpl: fetchImpl as unknown as typeof fetch,
+    });
+    const streaming = await client.generateSummaryStream(makePrompt());
+    if (streaming.kind !== 'active') {
+      throw new Error('expected an active stream');
+    }
+    const events: StreamEvent[] = [];
+    while (true) {
+      const { value, done } = await streaming.iterator.next();
+      if (done) {
+        break;
+      }
+      events.push(value);
+    }
+    expect(events).toEqual([
+      { kind: 'failed', message: STREAM_BUFFER_OVERFLOW_MESSAGE, timedOut: false },
+    ]);
+  });
+});
+
+describe('SseFrameGuard', () => {
+  const bytes = (text: string): Uint8Array => new TextEncoder().encode(text);
+
+  it('resets the buffered length at each blank-line frame boundary', () => {
+    const guard = new SseFrameGuard(64);
+    expect(guard.push(bytes('event: ping\ndata: {}'))).toBe(true);
+    expect(guard.bufferLen()).toBe(20);
+    expect(guard.push(bytes('\r\n\r\nevent: a'))).toBe(true);
+    expect(guard.bufferLen()).toBe(8);
+  });
+
+  it('trips once an open frame exceeds the cap', () => {
+    const guard = new SseFrameGuard(16);
+    expect(guard.push(bytes('data: 0123456789'))).toBe(true);
+    expect(guard.push(bytes('x'))).toBe(false);
+    expect(guard.bufferLen()).toBe(17);
+  });
 });
@@ -1,4 +1,10 @@
-import { buildImageBlock, canonicalizeMime, isAllowedImageMime } from '../../src/ai/images';
+import {
+  buildImageBlock,
+  canonicalizeMime,
+  isAllowedImageMime,
+  isGenericMime,
+  sniffImageMime,
+} from '../../src/ai/images';
 
 describe('canonicalizeMime', () => {
   it('lowercases and trims parameters', () => {
@@ -52,3 +58,46 @@ describe('buildImageBlock', () => {
     );
   });
 });
+
+describe('sniffImageMime', () => {
+  const ascii = (text: string): number[] => [...text].map((c) => c.charCodeAt(0));
+  const bytes = (...parts: number[][]): Uint8Array => new Uint8Array(parts.flat());
+
+  it('detects JPEG', () => {
+    expect(sniffImageMime(bytes([0xff, 0xd8, 0xff, 0xe0, 0x00]))).toBe('image/jpeg');
+  });
+
+  it('detects PNG', () => {
+    expect(sniffImageMime(bytes([0x89, 0x50, 0x4e, 0x47, 0x0d, 0x0a, 0x1a, 0x0a, 0x00]))).toBe('image/png');
+  });
+
+  it('detects GIF87a and GIF89a', () => {
+    expect(sniffImageMime(bytes(ascii('GIF87a')))).toBe('image/gif');
+    expect(sniffImageMime(bytes(ascii('GIF89a'), [0x01]))).toBe('image/gif');
+  });
+
+  it('detects WebP only with both RIFF and WEBP markers', () => {
+    expect(sniffImageMime(bytes(ascii('RIFF'), [0, 0, 0, 0], ascii('WEBPVP8 ')))).toBe('image/webp');
+    expect(sniffImageMime(bytes(ascii('RIFF'), [0, 0, 0, 0], ascii('WAVEfmt ')))).toBeNull();
+  });
+
+  it('detects AVIF, which is not an allowed upload type', () => {
+    const avif = sniffImageMime(bytes([0, 0, 0, 0x1c], ascii('ftypavif')));
+    expect(avif).toBe('image/avif');
+    expect(isAllowedImageMime(avif!)).toBe(false);
+  });
+
+  it('returns null for unknown or truncated input', () => {
+    expect(sniffImageMime(bytes(ascii('%PDF-1.7')))).toBeNull();
+    expect(sniffImageMime(bytes([0xff, 0xd8]))).toBeNull();
+    expect(sniffImageMime(new Uint8Array())).toBeNull();
+  });
+});
+
+describe('isGenericMime', () => {
+  it('recognises octet-stream content types', () => {
+    expect(isGenericMime('application/octet-stream')).toBe(true);
+    expect(isGenericMime('Binary/Octet-Stream; charset=x')).toBe(true);
+    expect(isGenericMime('image/png')).toBe(false);
+  });
+});
@@ -6,12 +6,17 @@ import {
   buildWelcomeBlocks,
   buildHelpBlocks,
   buildStyleModal,
+  buildSharePreviewModal,
+  MODAL_CALLBACK_SHARE_PREVIEW,
   buildStyleConfirmationBlocks,
   ACTION_OPEN_STYLE_MODAL,
   MODAL_CALLBACK_SET_STYLE,
   INPUT_BLOCK_STYLE,
   INPUT_ACTION_STYLE,
+  INPUT_BLOCK_TONE,
+  INPUT_ACTION_TONE,
 } from '../src/blocks';
+import type { SummaryTone } from '../src/types';
 
 describe('Block Kit builders', () => {
   describe('buildWelcomeBlocks', () => {
@@ -127,6 +132,45 @@ describe('Block Kit builders', () => {
     });
   });
 
+  describe('buildSharePreviewModal', () => {
+    const metadata = {
+      sourceChannelId: 'C11111111',
+      count: 50,
+      style: null,
+      assistantChannelId: 'D11111111',
+      assistantThreadTs: '1700000000.000100',
+      summaryTs: '1700000000.000200',
+    };
+    const sectionTexts = (blocks: unknown[]): string[] =>
+      (blocks as Array<{ type: string; text?: { text: string } }>)
+        .filter((b) => b.type === 'section')
+        .map((b) => b.text!.text);
+
+    it('shows the attribution and summary with Post / Cancel', () => {
+      const modal = buildSharePreviewModal('<@U1> asked TLDR:', '*Summary*\nthings', metadata);
+      expect(modal.type).toBe('modal');
+      expect(modal.callback_id).toBe(MODAL_CALLBACK_SHARE_PREVIEW);
+      expect(modal.submit?.text).toBe('Post');
+      expect(modal.close?.text).toBe('Cancel');
+      expect(modal.notify_on_close).toBe(true);
+      expect(JSON.parse(modal.private_metadata!)).toEqual(metadata);
+      expect(JSON.stringify(modal.blocks)).toContain('<#C11111111>');
+      expect(sectionTexts(modal.blocks)).toEqual(['<@U1> asked TLDR:', '*Summary*\nthings']);
+    });
+
+    it('splits a long summary into sections under Slack limits without losing text', () => {
+      const summary = '🎉'.repeat(4_000);
+      const modal = buildSharePreviewModal('attribution', summary, metadata);
+      const [, ...parts] = sectionTexts(modal.blocks);
+      expect(parts.length).toBeGreaterThan(1);
+      for (const part of parts) {
+        expect(part.length).toBeLessThanOrEqual(3_000);
+      }
+      expect(parts.join('')).toBe(summary);
+      expect(modal.blocks.length).toBeLessThanOrEqual(100);
+    });
+  });
+
   describe('buildStyleModal', () => {
     it('should return a modal view', () => {
       const modal = buildStyleModal(null, {
@@ -162,6 +206,29 @@ describe('Block Kit builders', () => {
       }
     });
 
+    it('should offer the tone presets and preselect the current tone', () => {
+      const metadata = { assistantChannelId: 'D123', assistantThreadTs: '1700000000.000100' };
+      const toneBlock = (tone: SummaryTone | null): unknown =>
+        buildStyleModal(null, metadata, 500, tone).blocks.find(
+          (b) => b.type === 'input' && 'block_id' in b && b.block_id === INPUT_BLOCK_TONE
+        );
+      expect(toneBlock(null)).toMatchObject({
+        optional: true,
+        element: {
+          type: 'static_select',
+          action_id: INPUT_ACTION_TONE,
+          options: [
+            { value: 'neutral' },
+            { value: 'friendly' },
+            { value: 'formal' },
+            { value: 'executive' },
+          ],
+        },
+      });
+      expect(toneBlock(null)).not.toHaveProperty('element.initial_option');
+      expect(toneBlock('formal')).toHaveProperty('element.initial_option.value', 'formal');
+    });
+
     it('should store private metadata as JSON', () => {
       const metadata = {
         assistantChannelId: 'D123',
@@ -195,6 +262,15 @@ describe('Block Kit builders', () => {
       }
     });
 
+    it('should confirm a tone saved without custom instructions', () => {
+      const blocks = buildStyleConfirmationBlocks(null, 'executive');
+      expect(blocks[0]).toMatchObject({ text: { text: '✅ *Style saved for this thread.*' } });
+      expect(blocks[1]).toEqual({
+        type: 'context',
+        elements: [{ type: 'mrkdwn', text: '🗣️ Tone: Executive brief' }],
+      });
+    });
+
     it('should return cleared message when style is null', () => {
       const blocks = buildStyleConfirmationBlocks(null);
       expect(blocks.length).toBeGreaterThan(0);
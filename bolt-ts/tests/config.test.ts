@@ -2,7 +2,12 @@
  * Tests for configuration loading.
  */
 
-import { loadConfig, resetConfigCacheForTests } from '../src/config';
+import {
+  ConfigError,
+  DEFAULT_SUMMARY_FOOTER,
+  loadConfig,
+  resetConfigCacheForTests,
+} from '../src/config';
 
 describe('loadConfig', () => {
   const originalEnv = process.env;
@@ -34,6 +39,46 @@ describe('loadConfig', () => {
     expect(config.enableStreaming).toBe(true);
     expect(config.streamMaxChunkChars).toBeGreaterThan(0);
     expect(config.streamMinAppendIntervalMs).toBeGreaterThan(0);
+    expect(config.streamStallTimeoutMs).toBe(120_000);
+    expect(config.streamMaxBufferBytes).toBe(1_048_576);
+    expect(config.staleStreamMaxAgeSecs).toBe(900);
+    expect(config.maxRequestBodyBytes).toBe(1_048_576);
+    expect(config.imageDownloadConcurrency).toBe(3);
+    expect(config.inlineImageMaxBytes).toBe(4 * 1024 * 1024);
+    expect(config.permalinkConcurrency).toBe(4);
+    expect(config.maxConcurrentSummaries).toBe(4);
+    expect(config.hardMessageCap).toBe(1000);
+    expect(config.ignoredUserIds).toEqual([]);
+    expect(config.defaultTimezone).toBe('UTC');
+    expect(config.maxCustomStyleChars).toBe(500);
+    expect(config.noMessagesMessage).toBe('No messages found to summarize.');
+    expect(config.summaryFooter).toBe(DEFAULT_SUMMARY_FOOTER);
+    expect(config.systemPromptOverride).toBeNull();
+    expect(config.assistantName).toBe('TLDR-bot');
+    expect(config.taskDeadlineSecs).toBe(840);
+    expect(config.slackMaxRetries).toBe(5);
+    expect(config.slackRetryBaseDelayMs).toBe(100);
+    expect(config.slackRetryMaxDelayMs).toBe(5_000);
+    expect(config.deliveryMaxRetries).toBe(2);
+    expect(config.disableImages).toBe(false);
+    expect(config.citeInline).toBe(false);
+    expect(config.summarySections).toEqual(['Summary', 'Links shared', 'Image highlights', 'Receipts']);
+    expect(config.detectSummaryLanguage).toBe(true);
+    expect(config.postAsUser).toBe(false);
+    expect(config.unfurlSummaryLinks).toBe(false);
+    expect(config.canvasToc).toBe(false);
+  });
+
+  it('leaves the Socket Mode app token unset unless configured', async () => {
+    process.env.SLACK_BOT_TOKEN = 'x';
+    process.env.SLACK_SIGNING_SECRET = 'y';
+    process.env.ANTHROPIC_API_KEY = 'sk-ant';
+    delete process.env.SLACK_APP_TOKEN;
+    delete process.env.SLACK_APP_TOKEN_PARAMETER_NAME;
+    expect((await loadConfig()).slackAppToken).toBeNull();
+
+    process.env.SLACK_APP_TOKEN = 'xapp-1-test';
+    expect((await loadConfig()).slackAppToken).toBe('xapp-1-test');
   });
 
   it('honours ANTHROPIC_MODEL override', async () => {
@@ -45,6 +90,58 @@ describe('loadConfig', () => {
     expect(config.anthropicModel).toBe('claude-opus-4-7');
   });
 
+  it('keeps a custom SUMMARY_FOOTER and turns the footer off with "off"', async () => {
+    process.env.SLACK_BOT_TOKEN = 'x';
+    process.env.SLACK_SIGNING_SECRET = 'y';
+    process.env.ANTHROPIC_API_KEY = 'sk-ant';
+    process.env.SUMMARY_FOOTER = '  _by {model}_  ';
+    expect((await loadConfig()).summaryFooter).toBe('_by {model}_');
+
+    resetConfigCacheForTests();
+    process.env.SUMMARY_FOOTER = 'OFF';
+    expect((await loadConfig()).summaryFooter).toBeNull();
+  });
+
+  it('reads SUMMARY_SECTIONS in order and refuses markup or Files shared', async () => {
+    process.env.SLACK_BOT_TOKEN = 'x';
+    process.env.SLACK_SIGNING_SECRET = 'y';
+    process.env.ANTHROPIC_API_KEY = 'sk-ant';
+    process.env.SUMMARY_SECTIONS = ' Summary, Decisions ,Action  items, decisions, Receipts ';
+    expect((await loadConfig()).summarySections).toEqual([
+      'Summary',
+      'Decisions',
+      'Action items',
+      'Receipts',
+    ]);
+
+    resetConfigCacheForTests();
+    process.env.SUMMARY_SECTIONS = 'Summary, *Bold*';
+    await expect(loadConfig()).rejects.toThrow(/SUMMARY_SECTIONS names must be plain text/);
+
+    resetConfigCacheForTests();
+    process.env.SUMMARY_SECTIONS = 'Summary, Files shared';
+    await expect(loadConfig()).rejects.toThrow(/SUMMARY_SECTIONS can't list "Files shared"/);
+  });
+
+  it('rejects a DEFAULT_TIMEZONE that is not an IANA zone', async () => {
+    process.env.SLACK_BOT_TOKEN = 'x';
+    process.env.SLACK_SIGNING_SECRET = 'y';
+    process.env.ANTHROPIC_API_KEY = 'sk-ant';
+    process.env.DEFAULT_TIMEZONE = 'America/Chicago';
+    await expect(loadConfig()).resolves.toMatchObject({ defaultTimezone: 'America/Chicago' });
+    process.env.DEFAULT_TIMEZONE = 'Central Time';
+    await expect(loadConfig()).rejects.toThrow(/DEFAULT_TIMEZONE must be an IANA time zone/);
+  });
+
+  it('parses IGNORED_USER_IDS as a comma-separated list', async () => {
+    process.env.SLACK_BOT_TOKEN = 'x';
+    process.env.SLACK_SIGNING_SECRET = 'y';
+    process.env.ANTHROPIC_API_KEY = 'sk-ant';
+    process.env.IGNORED_USER_IDS = ' U0CIBOT, B0ALERTS ,,';
+    const config = await loadConfig();
+    expect(config.ignoredUserIds).toEqual(['U0CIBOT', 'B0ALERTS']);
+  });
+
   it('honours ANTHROPIC_MAX_OUTPUT_TOKENS override and caps at 64000', async () => {
     process.env.SLACK_BOT_TOKEN = 'x';
     process.env.SLACK_SIGNING_SECRET = 'y';
@@ -68,6 +165,15 @@ describe('loadConfig', () => {
     expect(config.enableStreaming).toBe(true);
   });
 
+  it('parses DISABLE_IMAGES as boolean', async () => {
+    process.env.SLACK_BOT_TOKEN = 'x';
+    process.env.SLACK_SIGNING_SECRET = 'y';
+    process.env.ANTHROPIC_API_KEY = 'sk-ant';
+    process.env.DISABLE_IMAGES = 'true';
+    const config = await loadConfig();
+    expect(config.disableImages).toBe(true);
+  });
+
   it('throws when SLACK_BOT_TOKEN is missing', async () => {
     delete process.env.SLACK_BOT_TOKEN;
     delete process.env.SLACK_BOT_TOKEN_PARAMETER_NAME;
@@ -96,6 +202,24 @@ describe('loadConfig', () => {
     );
   });
 
+  it('reports missing and unparsable variables together in one ConfigError', async () => {
+    delete process.env.SLACK_BOT_TOKEN;
+    delete process.env.SLACK_BOT_TOKEN_PARAMETER_NAME;
+    process.env.SLACK_SIGNING_SECRET = 'y';
+    process.env.ANTHROPIC_API_KEY = 'sk-ant';
+    process.env.STREAM_MAX_CHUNK_CHARS = 'eight thousand';
+    process.env.ANTHROPIC_MAX_OUTPUT_TOKENS = '-5';
+
+    const error = await loadConfig().catch((err: unknown) => err);
+
+    expect(error).toBeInstanceOf(ConfigError);
+    expect((error as ConfigError).problems).toEqual([
+      'missing SLACK_BOT_TOKEN or SLACK_BOT_TOKEN_PARAMETER_NAME',
+      'STREAM_MAX_CHUNK_CHARS must be a positive integer (got "eight thousand")',
+      'ANTHROPIC_MAX_OUTPUT_TOKENS must be a positive integer (got "-5")',
+    ]);
+  });
+
   it('caps streamMaxChunkChars at the documented Slack limit', async () => {
     process.env.SLACK_BOT_TOKEN = 'x';
     process.env.SLACK_SIGNING_SECRET = 'y';
@@ -104,4 +228,116 @@ describe('loadConfig', () => {
     const config = await loadConfig();
     expect(config.streamMaxChunkChars).toBeLessThanOrEqual(12000);
   });
+
+  it('parses ANTHROPIC_TEMPERATURE and rejects out-of-range values', async () => {
+    process.env.SLACK_BOT_TOKEN = 'x';
+    process.env.SLACK_SIGNING_SECRET = 'y';
+    process.env.ANTHROPIC_API_KEY = 'sk-ant';
+    let config = await loadConfig();
+    expect(config.anthropicTemperature).toBeNull();
+
+    process.env.ANTHROPIC_TEMPERATURE = '0.3';
+    process.env.ANTHROPIC_STYLE_TEMPERATURE = '0.9';
+    config = await loadConfig();
+    expect(config.anthropicTemperature).toBe(0.3);
+    expect(config.anthropicStyleTemperature).toBe(0.9);
+
+    process.env.ANTHROPIC_TEMPERATURE = '2.5';
+    await expect(loadConfig()).rejects.toThrow(
+      'ANTHROPIC_TEMPERATURE must be a number from 0 to 1 (got "2.5")'
+    );
+
+    process.env.ANTHROPIC_TEMPERATURE = '0.3';
+    process.env.ANTHROPIC_STYLE_TEMPERATURE = 'warm';
+    await expect(loadConfig()).rejects.toThrow(ConfigError);
+  });
+
+  it('parses ANTHROPIC_REASONING_EFFORT and rejects unknown levels', async () => {
+    process.env.SLACK_BOT_TOKEN = 'x';
+    process.env.SLACK_SIGNING_SECRET = 'y';
+    process.env.ANTHROPIC_API_KEY = 'sk-ant';
+    expect((await loadConfig()).anthropicReasoningEffort).toBeNull();
+
+    process.env.ANTHROPIC_REASONING_EFFORT = ' Medium ';
+    expect((await loadConfig()).anthropicReasoningEffort).toBe('medium');
+
+    process.env.ANTHROPIC_REASONING_EFFORT = 'max';
+    await expect(loadConfig()).rejects.toThrow(
+      'ANTHROPIC_REASONING_EFFORT must be one of low, medium, high (got "max")'
+    );
+  });
+
+  it('reads ASSISTANT_NAME as one trimmed line', async () => {
+    process.env.SLACK_BOT_TOKEN = 'x';
+    process.env.SLACK_SIGNING_SECRET = 'y';
+    process.env.ANTHROPIC_API_KEY = 'sk-ant';
+    process.env.ASSISTANT_NAME = '  Acme\n  Recap ';
+    expect((await loadConfig()).assistantName).toBe('Acme Recap');
+
+    process.env.ASSISTANT_NAME = '   ';
+    expect((await loadConfig()).assistantName).toBe('TLDR-bot');
+  });
+
+  it('parses ANTHROPIC_BASE_URL and rejects invalid values', async () => {
+    process.env.SLACK_BOT_TOKEN = 'x';
+    process.env.SLACK_SIGNING_SECRET = 'y';
+    process.env.ANTHROPIC_API_KEY = 'sk-ant';
+    let config = await loadConfig();
+    expect(config.anthropicBaseUrl).toBeNull();
+
+    process.env.ANTHROPIC_BASE_URL = 'https://gateway.example.com/anthropic/';
+    config = await loadConfig();
+    expect(config.anthropicBaseUrl).toBe('https://gateway.example.com/anthropic');
+
+    process.env.ANTHROPIC_BASE_URL = 'not a url';
+    await expect(loadConfig()).rejects.toThrow(
+      'ANTHROPIC_BASE_URL must be an absolute http(s) URL (got "not a url")'
+    );
+
+    process.env.ANTHROPIC_BASE_URL = 'ftp://gateway.example.com';
+    await expect(loadConfig()).rejects.toThrow(ConfigError);
+  });
+
+  it('accepts a substantial SYSTEM_PROMPT_OVERRIDE and rejects a trivial one', async () => {
+    process.env.SLACK_BOT_TOKEN = 'x';
+    process.env.SLACK_SIGNING_SECRET = 'y';
+    process.env.ANTHROPIC_API_KEY = 'sk-ant';
+    const override = `You are AcmeBot. ${'Summarize with *Summary*, *Links shared*, *Image highlights*, *Receipts*. '.repeat(4)}`;
+    process.env.SYSTEM_PROMPT_OVERRIDE = `  ${override}  `;
+    expect((await loadConfig()).systemPromptOverride).toBe(override.trim());
+
+    process.env.SYSTEM_PROMPT_OVERRIDE = 'be nice';
+    await expect(loadConfig()).rejects.toThrow(
+      'SYSTEM_PROMPT_OVERRIDE must be at least 200 characters (got 7)'
+    );
+  });
+
+  it('accepts zero Slack retries but rejects negative ones', async () => {
+    process.env.SLACK_BOT_TOKEN = 'x';
+    process.env.SLACK_SIGNING_SECRET = 'y';
+    process.env.ANTHROPIC_API_KEY = 'sk-ant';
+    process.env.SLACK_MAX_RETRIES = '0';
+    expect((await loadConfig()).slackMaxRetries).toBe(0);
+
+    process.env.SLACK_MAX_RETRIES = '-1';
+    await expect(loadConfig()).rejects.toThrow(
+      'SLACK_MAX_RETRIES must be a non-negative integer (got "-1")'
+    );
+  });
+
+  it('honours INLINE_IMAGE_MAX_BYTES and caps it at the Anthropic image limit', async () => {
+    process.env.SLACK_BOT_TOKEN = 'x';
+    process.env.SLACK_SIGNING_SECRET = 'y';
+    process.env.ANTHROPIC_API_KEY = 'sk-ant';
+    process.env.INLINE_IMAGE_MAX_BYTES = '1048576';
+    expect((await loadConfig()).inlineImageMaxBytes).toBe(1_048_576);
+
+    process.env.INLINE_IMAGE_MAX_BYTES = String(64 * 1024 * 1024);
+    expect((await loadConfig()).inlineImageMaxBytes).toBe(5 * 1024 * 1024);
+
+    process.env.INLINE_IMAGE_MAX_BYTES = '0';
+    await expect(loadConfig()).rejects.toThrow(
+      'INLINE_IMAGE_MAX_BYTES must be a positive integer (got "0")'
+    );
+  });
 });
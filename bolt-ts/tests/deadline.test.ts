@@ -0,0 +1,57 @@
+import {
+  CLEANUP_RESERVE_MS,
+  DeadlineExceededError,
+  createDeadline,
+  currentDeadline,
+  runWithinDeadline,
+  taskBudgetMs,
+  withDeadline,
+} from '../src/deadline';
+
+describe('createDeadline', () => {
+  it('throws from check() once the budget is spent', () => {
+    expect(() => createDeadline(60_000).check()).not.toThrow();
+    expect(() => createDeadline(0).check()).toThrow(DeadlineExceededError);
+  });
+});
+
+describe('taskBudgetMs', () => {
+  it('uses the configured budget unless the Lambda has less time left', () => {
+    expect(taskBudgetMs(840_000, null)).toBe(840_000);
+    expect(taskBudgetMs(840_000, 900_000)).toBe(840_000);
+    expect(taskBudgetMs(840_000, 60_000)).toBe(60_000 - CLEANUP_RESERVE_MS);
+  });
+});
+
+describe('runWithinDeadline', () => {
+  it('does not start the work when the deadline has already passed', async () => {
+    const start = jest.fn().mockResolvedValue('done');
+    await expect(runWithinDeadline(createDeadline(0), start)).rejects.toBeInstanceOf(
+      DeadlineExceededError
+    );
+    expect(start).not.toHaveBeenCalled();
+  });
+
+  it('rejects when the work outlives the deadline', async () => {
+    const slow = (): Promise<string> => new Promise((resolve) => setTimeout(() => resolve('late'), 200));
+    await expect(runWithinDeadline(createDeadline(10), slow)).rejects.toBeInstanceOf(
+      DeadlineExceededError
+    );
+  });
+
+  it('passes results through, with or without a deadline', async () => {
+    await expect(runWithinDeadline(createDeadline(1_000), async () => 'ok')).resolves.toBe('ok');
+    await expect(runWithinDeadline(undefined, async () => 'ok')).resolves.toBe('ok');
+  });
+});
+
+describe('withDeadline', () => {
+  it('exposes the bound deadline to the async call tree', async () => {
+    const deadline = createDeadline(1_000);
+    expect(currentDeadline()).toBeUndefined();
+    await withDeadline(deadline, async () => {
+      await Promise.resolve();
+      expect(currentDeadline()).toBe(deadline);
+    });
+  });
+});
@@ -0,0 +1,411 @@
+import type { App } from '@slack/bolt';
+import type { WebClient } from '@slack/web-api';
+import { MODAL_CALLBACK_SHARE_PREVIEW, type SharePreviewPrivateMetadata } from '../../src/blocks';
+import type { AppConfig } from '../../src/config';
+import {
+  parseRerunButtonValue,
+  parseSharePreviewMetadata,
+  postSharedSummary,
+  registerActionHandlers,
+  saveSummaryToCanvas,
+} from '../../src/handlers/actions';
+import { MODAL_BUSY_MESSAGE } from '../../src/slack/client';
+import {
+  ACTION_SUMMARY_DESTINATION,
+  DESTINATION_CANVAS,
+  DESTINATION_REPOST,
+  buildSummaryActionButtons,
+} from '../../src/worker/deliver';
+
+type Listener = (args: Record<string, unknown>) => Promise<void>;
+
+/**
+ * Actions and views share one map; ids never collide. A view registered with
+ * a `{ callback_id, type }` constraint is keyed `type:callback_id`.
+ */
+function captureActions(): { app: App; listeners: Map<string, Listener> } {
+  const listeners = new Map<string, Listener>();
+  const register = (
+    id: string | { callback_id: string; type: string },
+    listener: Listener
+  ): void => {
+    listeners.set(typeof id === 'string' ? id : `${id.type}:${id.callback_id}`, listener);
+  };
+  const app = { action: register, view: register } as unknown as App;
+  return { app, listeners };
+}
+
+const shareMetadata: SharePreviewPrivateMetadata = {
+  sourceChannelId: 'C11111111',
+  count: 50,
+  style: null,
+  assistantChannelId: 'D11111111',
+  assistantThreadTs: '1700000000.000100',
+  summaryTs: '1700000000.000200',
+};
+
+describe('parseRerunButtonValue', () => {
+  it('returns the channel, clamped count, and style', () => {
+    expect(
+      parseRerunButtonValue(
+        JSON.stringify({ action: 'rerun_summary', channelId: 'C11111111', count: 9000, style: 'haiku' })
+      )
+    ).toEqual({ channelId: 'C11111111', count: 500, style: 'haiku' });
+  });
+
+  it('treats a missing or blank style as none', () => {
+    expect(
+      parseRerunButtonValue(JSON.stringify({ action: 'rerun_roast', channelId: 'C11111111', count: 50 }))
+    ).toEqual({ channelId: 'C11111111', count: 50, style: null });
+    expect(
+      parseRerunButtonValue(JSON.stringify({ channelId: 'C11111111', count: 50, style: '  ' }))?.style
+    ).toBeNull();
+  });
+
+  it('rejects malformed payloads and invalid channels', () => {
+    expect(parseRerunButtonValue('not json')).toBeNull();
+    expect(parseRerunButtonValue(undefined)).toBeNull();
+    expect(parseRerunButtonValue(JSON.stringify({ channelId: 'general', count: 50 }))).toBeNull();
+  });
+});
+
+describe('parseSharePreviewMetadata', () => {
+  it('round-trips valid metadata', () => {
+    expect(parseSharePreviewMetadata(JSON.stringify(shareMetadata))).toEqual(shareMetadata);
+  });
+
+  it('rejects malformed JSON and invalid ids', () => {
+    expect(parseSharePreviewMetadata('not json')).toBeNull();
+    expect(
+      parseSharePreviewMetadata(JSON.stringify({ ...shareMetadata, sourceChannelId: 'general' }))
+    ).toBeNull();
+    expect(parseSharePreviewMetadata(JSON.stringify({ ...shareMetadata, summaryTs: 'x' }))).toBeNull();
+  });
+});
+
+describe('postSharedSummary', () => {
+  const makeClient = (messages: unknown[]): { client: WebClient; postMessage: jest.Mock } => {
+    const postMessage = jest.fn().mockResolvedValue({ ok: true });
+    const client = {
+      chat: { postMessage },
+      conversations: { replies: jest.fn().mockResolvedValue({ messages }) },
+    } as unknown as WebClient;
+    return { client, postMessage };
+  };
+
+  it('posts the previewed summary with attribution and confirms in the thread', async () => {
+    const { client, postMessage } = makeClient([
+      { ts: '1700000000.000200', text: '*Summary*\nthings' },
+    ]);
+
+    await postSharedSummary(client, 'U1', shareMetadata);
+
+    expect(postMessage).toHaveBeenNthCalledWith(1, {
+      channel: 'C11111111',
+      text: '<@U1> asked TLDR to summarize the last 50 messages:\n\n*Summary*\nthings',
+    });
+    expect(postMessage).toHaveBeenNthCalledWith(2, {
+      channel: 'D11111111',
+      thread_ts: '1700000000.000100',
+      text: '✅ Shared to <#C11111111>',
+    });
+  });
+
+  it('posts nothing publicly when the summary is gone', async () => {
+    const { client, postMessage } = makeClient([]);
+
+    await postSharedSummary(client, 'U1', shareMetadata);
+
+    expect(postMessage).toHaveBeenCalledTimes(1);
+    expect(postMessage.mock.calls[0][0].channel).toBe('D11111111');
+  });
+});
+
+describe('saveSummaryToCanvas', () => {
+  it('copies the posted summary into a canvas for the user and links it', async () => {
+    const postMessage = jest.fn().mockResolvedValue({ ok: true });
+    const create = jest.fn().mockResolvedValue({ ok: true, canvas_id: 'F0CANVAS1' });
+    const accessSet = jest.fn().mockResolvedValue({ ok: true });
+    const client = {
+      chat: { postMessage },
+      conversations: {
+        replies: jest.fn().mockResolvedValue({
+          messages: [{ ts: '1700000000.000200', text: '*Summary*\nthings' }],
+        }),
+      },
+      canvases: { create, access: { set: accessSet } },
+      files: {
+        info: jest.fn().mockResolvedValue({ file: { permalink: 'https://x.slack.com/docs/F0CANVAS1' } }),
+      },
+    } as unknown as WebClient;
+
+    await saveSummaryToCanvas(client, 'U1', shareMetadata);
+
+    expect(create).toHaveBeenCalledWith({
+      title: 'TLDR: last 50 messages',
+      document_content: {
+        type: 'markdown',
+        markdown: 'Summary of ![](#C11111111)\n\n*Summary*\nthings',
+      },
+    });
+    expect(accessSet).toHaveBeenCalledWith({
+      canvas_id: 'F0CANVAS1',
+      access_level: 'write',
+      user_ids: ['U1'],
+    });
+    expect(postMessage).toHaveBeenCalledWith({
+      channel: 'D11111111',
+      thread_ts: '1700000000.000100',
+      text: '📝 Saved to a canvas: <https://x.slack.com/docs/F0CANVAS1|TLDR: last 50 messages>',
+    });
+  });
+
+  it('numbers the sections when a table of contents is asked for', async () => {
+    const create = jest.fn().mockResolvedValue({ ok: true, canvas_id: 'F0CANVAS1' });
+    const client = {
+      chat: { postMessage: jest.fn().mockResolvedValue({ ok: true }) },
+      conversations: {
+        replies: jest.fn().mockResolvedValue({
+          messages: [{ ts: '1700000000.000200', text: '*Summary*\nthings\n\n*Receipts*\n- x' }],
+        }),
+      },
+      canvases: { create, access: { set: jest.fn().mockResolvedValue({ ok: true }) } },
+      files: { info: jest.fn().mockResolvedValue({ file: {} }) },
+    } as unknown as WebClient;
+
+    await saveSummaryToCanvas(client, 'U1', shareMetadata, { toc: true });
+
+    expect(create.mock.calls[0][0].document_content.markdown).toBe(
+      'Summary of ![](#C11111111)\n\n**Contents**\n1. Summary\n2. Receipts\n\n' +
+        '## 1. Summary\nthings\n\n## 2. Receipts\n- x'
+    );
+  });
+});
+
+describe('registerActionHandlers', () => {
+  it('routes Summarize again and Jump to channel clicks', () => {
+    const { app, listeners } = captureActions();
+    registerActionHandlers(app, {} as AppConfig);
+    expect(listeners.has('rerun_summary')).toBe(true);
+    expect(listeners.has('jump_to_channel')).toBe(true);
+  });
+
+  it('only acks the Jump to channel link button', async () => {
+    const { app, listeners } = captureActions();
+    registerActionHandlers(app, {} as AppConfig);
+    const ack = jest.fn().mockResolvedValue(undefined);
+    const client = { chat: { postMessage: jest.fn() } };
+
+    await listeners.get('jump_to_channel')!({ ack, client });
+
+    expect(ack).toHaveBeenCalledTimes(1);
+    expect(client.chat.postMessage).not.toHaveBeenCalled();
+  });
+
+  it('skips Summarize again when the button value is invalid', async () => {
+    const { app, listeners } = captureActions();
+    registerActionHandlers(app, {} as AppConfig);
+    const ack = jest.fn().mockResolvedValue(undefined);
+    const client = { chat: { postMessage: jest.fn() } };
+
+    await listeners.get('rerun_summary')!({
+      ack,
+      client,
+      logger: { error: jest.fn() },
+      action: { type: 'button', value: '{"channelId":"nope"}' },
+      body: { user: { id: 'U1' } },
+    });
+
+    expect(ack).toHaveBeenCalledTimes(1);
+    expect(client.chat.postMessage).not.toHaveBeenCalled();
+  });
+
+  it('opens a preview instead of posting when Share is clicked', async () => {
+    const { app, listeners } = captureActions();
+    registerActionHandlers(app, {} as AppConfig);
+    const ack = jest.fn().mockResolvedValue(undefined);
+    const client = {
+      chat: { postMessage: jest.fn() },
+      conversations: { members: jest.fn().mockResolvedValue({ members: ['U1'] }) },
+      views: { open: jest.fn().mockResolvedValue({ ok: true }) },
+    };
+
+    await listeners.get('share_summary')!({
+      ack,
+      client,
+      logger: { error: jest.fn(), warn: jest.fn() },
+      action: {
+        type: 'button',
+        value: JSON.stringify({ action: 'share_summary', sourceChannelId: 'C11111111', count: 50, style: null }),
+      },
+      body: {
+        user: { id: 'U1' },
+        trigger_id: 'trigger-1',
+        channel: { id: 'D11111111' },
+        message: { ts: '1700000000.000200', thread_ts: '1700000000.000100', text: 'summary' },
+      },
+    });
+
+    expect(client.chat.postMessage).not.toHaveBeenCalled();
+    expect(client.views.open).toHaveBeenCalledTimes(1);
+    const { trigger_id, view } = client.views.open.mock.calls[0][0];
+    expect(trigger_id).toBe('trigger-1');
+    expect(view.callback_id).toBe(MODAL_CALLBACK_SHARE_PREVIEW);
+    expect(JSON.parse(view.private_metadata)).toEqual(shareMetadata);
+    expect(listeners.has(MODAL_CALLBACK_SHARE_PREVIEW)).toBe(true);
+  });
+
+  it('tells the user in the thread when Slack rate-limits the preview past the trigger_id', async () => {
+    const { app, listeners } = captureActions();
+    registerActionHandlers(app, {} as AppConfig);
+    const client = {
+      chat: { postMessage: jest.fn().mockResolvedValue({ ok: true }) },
+      conversations: { members: jest.fn().mockResolvedValue({ members: ['U1'] }) },
+      views: {
+        open: jest.fn().mockRejectedValue(
+          Object.assign(new Error('rate limited'), {
+            code: 'slack_webapi_rate_limited_error',
+            retryAfter: 30,
+          })
+        ),
+      },
+    };
+    const logger = { error: jest.fn(), warn: jest.fn() };
+
+    await listeners.get('share_summary')!({
+      ack: jest.fn().mockResolvedValue(undefined),
+      client,
+      logger,
+      action: {
+        type: 'button',
+        value: JSON.stringify({ action: 'share_summary', sourceChannelId: 'C11111111', count: 50, style: null }),
+      },
+      body: {
+        user: { id: 'U1' },
+        trigger_id: 'trigger-1',
+        channel: { id: 'D11111111' },
+        message: { ts: '1700000000.000200', thread_ts: '1700000000.000100', text: 'summary' },
+      },
+    });
+
+    expect(client.views.open).toHaveBeenCalledTimes(1);
+    expect(client.chat.postMessage).toHaveBeenCalledWith({
+      channel: 'D11111111',
+      thread_ts: '1700000000.000100',
+      text: MODAL_BUSY_MESSAGE,
+    });
+    expect(logger.error).not.toHaveBeenCalled();
+  });
+
+  it('acknowledges and logs a cancelled share preview without posting', async () => {
+    const { app, listeners } = captureActions();
+    registerActionHandlers(app, {} as AppConfig);
+    const ack = jest.fn().mockResolvedValue(undefined);
+    const logger = { debug: jest.fn(), error: jest.fn(), warn: jest.fn() };
+    const client = { chat: { postMessage: jest.fn() } };
+
+    await listeners.get(`view_closed:${MODAL_CALLBACK_SHARE_PREVIEW}`)!({
+      ack,
+      client,
+      logger,
+      body: { type: 'view_closed', user: { id: 'U1' }, is_cleared: false },
+      view: {
+        id: 'V0PREVIEW',
+        callback_id: MODAL_CALLBACK_SHARE_PREVIEW,
+        private_metadata: JSON.stringify(shareMetadata),
+      },
+    });
+
+    expect(ack).toHaveBeenCalledTimes(1);
+    expect(logger.debug).toHaveBeenCalledWith('Share preview closed without posting', {
+      viewId: 'V0PREVIEW',
+      userId: 'U1',
+    });
+    expect(client.chat.postMessage).not.toHaveBeenCalled();
+  });
+
+  describe('summary destination menu', () => {
+    const summaryMessage = {
+      ts: '1700000000.000200',
+      thread_ts: '1700000000.000100',
+      text: 'summary',
+      blocks: buildSummaryActionButtons({
+        sourceChannelId: 'C11111111',
+        messageCount: 50,
+        currentStyle: null,
+      }),
+    };
+    const makeClient = () => ({
+      chat: { postMessage: jest.fn().mockResolvedValue({ ok: true }) },
+      conversations: {
+        members: jest.fn().mockResolvedValue({ members: ['U1'] }),
+        replies: jest.fn().mockResolvedValue({ messages: [summaryMessage] }),
+      },
+      views: { open: jest.fn().mockResolvedValue({ ok: true }) },
+      canvases: {
+        create: jest.fn().mockResolvedValue({ ok: true, canvas_id: 'F0CANVAS1' }),
+        access: { set: jest.fn().mockResolvedValue({ ok: true }) },
+      },
+      files: { info: jest.fn().mockResolvedValue({ file: {} }) },
+    });
+    const select = async (value: string, client: ReturnType<typeof makeClient>): Promise<void> => {
+      const { app, listeners } = captureActions();
+      registerActionHandlers(app, {} as AppConfig);
+      await listeners.get(ACTION_SUMMARY_DESTINATION)!({
+        ack: jest.fn().mockResolvedValue(undefined),
+        client,
+        logger: { error: jest.fn(), warn: jest.fn() },
+        action: { type: 'overflow', selected_option: { value } },
+        body: {
+          user: { id: 'U1' },
+          trigger_id: 'trigger-1',
+          channel: { id: 'D11111111' },
+          message: summaryMessage,
+        },
+      });
+    };
+
+    it('opens the share preview for Repost to channel', async () => {
+      const client = makeClient();
+      await select(DESTINATION_REPOST, client);
+
+      expect(client.views.open).toHaveBeenCalledTimes(1);
+      const { view } = client.views.open.mock.calls[0][0];
+      expect(JSON.parse(view.private_metadata)).toEqual(shareMetadata);
+      expect(client.canvases.create).not.toHaveBeenCalled();
+    });
+
+    it('saves the existing summary to a canvas without regenerating it', async () => {
+      const client = makeClient();
+      await select(DESTINATION_CANVAS, client);
+
+      expect(client.canvases.create).toHaveBeenCalledTimes(1);
+      expect(client.views.open).not.toHaveBeenCalled();
+      expect(client.chat.postMessage).toHaveBeenCalledWith({
+        channel: 'D11111111',
+        thread_ts: '1700000000.000100',
+        text: '📝 Saved to a canvas: TLDR: last 50 messages',
+      });
+    });
+
+    it('ignores a message without a summary Share button', async () => {
+      const client = makeClient();
+      const { app, listeners } = captureActions();
+      registerActionHandlers(app, {} as AppConfig);
+      await listeners.get(ACTION_SUMMARY_DESTINATION)!({
+        ack: jest.fn().mockResolvedValue(undefined),
+        client,
+        logger: { error: jest.fn(), warn: jest.fn() },
+        action: { type: 'overflow', selected_option: { value: DESTINATION_CANVAS } },
+        body: {
+          user: { id: 'U1' },
+          channel: { id: 'D11111111' },
+          message: { ts: '1700000000.000200', text: 'hello' },
+        },
+      });
+
+      expect(client.canvases.create).not.toHaveBeenCalled();
+      expect(client.chat.postMessage).not.toHaveBeenCalled();
+    });
+  });
+});
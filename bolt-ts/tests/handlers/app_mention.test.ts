@@ -0,0 +1,35 @@
+import { parseMentionIntent, stripBotMention } from '../../src/handlers/app_mention';
+
+describe('stripBotMention', () => {
+  it('drops the leading bot mention', () => {
+    expect(stripBotMention('<@U0BOT1234> summarize last 50')).toBe('summarize last 50');
+    expect(stripBotMention('  <@U0BOT1234|tldr>   summarize')).toBe('summarize');
+  });
+
+  it('leaves mentions later in the text alone', () => {
+    expect(stripBotMention('<@U0BOT1234> summarize what <@U0123ABCD> said')).toBe(
+      'summarize what <@U0123ABCD> said'
+    );
+  });
+});
+
+describe('parseMentionIntent', () => {
+  it('parses the summarize intent after the mention', () => {
+    expect(parseMentionIntent('<@U0BOT1234> summarize last 50')).toMatchObject({
+      type: 'summarize',
+      count: 50,
+      targetChannel: null,
+    });
+    expect(parseMentionIntent('<@U0BOT1234> summarize <#C0123ABCD|eng> by thread')).toMatchObject({
+      type: 'summarize',
+      targetChannel: 'C0123ABCD',
+      groupByThread: true,
+    });
+  });
+
+  it('ignores mentions without a summarize intent', () => {
+    expect(parseMentionIntent('<@U0BOT1234> thanks!')).toBeNull();
+    expect(parseMentionIntent('<@U0BOT1234>')).toBeNull();
+    expect(parseMentionIntent('<@U0BOT1234> style: be funny')).toBeNull();
+  });
+});
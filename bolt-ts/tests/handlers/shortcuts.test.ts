@@ -0,0 +1,44 @@
+import { parseThreadShortcut } from '../../src/handlers/shortcuts';
+
+describe('parseThreadShortcut', () => {
+  const payload = {
+    type: 'message_action',
+    callback_id: 'summarize_thread',
+    user: { id: 'U0123ABCD' },
+    channel: { id: 'C0123ABCD', name: 'eng' },
+    message_ts: '1700000000.000100',
+    message: { ts: '1700000000.000100', text: 'launch plan?' },
+  };
+
+  it('replies under a top-level message', () => {
+    expect(parseThreadShortcut(payload)).toEqual({
+      userId: 'U0123ABCD',
+      channelId: 'C0123ABCD',
+      messageTs: '1700000000.000100',
+      threadTs: '1700000000.000100',
+    });
+  });
+
+  it('uses the thread root when the shortcut is run on a reply', () => {
+    expect(
+      parseThreadShortcut({
+        ...payload,
+        message_ts: '1700000050.000200',
+        message: { ts: '1700000050.000200', thread_ts: '1700000000.000100' },
+      })
+    ).toMatchObject({ messageTs: '1700000050.000200', threadTs: '1700000000.000100' });
+  });
+
+  it('falls back to message.ts when message_ts is absent', () => {
+    expect(parseThreadShortcut({ ...payload, message_ts: undefined })).toMatchObject({
+      messageTs: '1700000000.000100',
+    });
+  });
+
+  it('rejects payloads without a usable channel or timestamp', () => {
+    expect(parseThreadShortcut({ ...payload, channel: { id: 'general' } })).toBeNull();
+    expect(parseThreadShortcut({ ...payload, message_ts: 'x', message: {} })).toBeNull();
+    expect(parseThreadShortcut({ ...payload, user: {} })).toBeNull();
+    expect(parseThreadShortcut(null)).toBeNull();
+  });
+});
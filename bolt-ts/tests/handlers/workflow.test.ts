@@ -0,0 +1,38 @@
+import { parseWorkflowInputs } from '../../src/handlers/workflow';
+
+describe('parseWorkflowInputs', () => {
+  it('accepts a channel, user, and numeric count', () => {
+    expect(
+      parseWorkflowInputs({ channel_id: 'C11111111', user_id: 'U123', message_count: 120 })
+    ).toEqual({ ok: true, channelId: 'C11111111', userId: 'U123', messageCount: 120 });
+  });
+
+  it('defaults and clamps the message count', () => {
+    expect(parseWorkflowInputs({ channel_id: 'C11111111', user_id: 'U123' })).toMatchObject({
+      ok: true,
+      messageCount: 50,
+    });
+    expect(
+      parseWorkflowInputs({ channel_id: 'C11111111', user_id: 'U123', message_count: '9000' })
+    ).toMatchObject({ ok: true, messageCount: 500 });
+    expect(
+      parseWorkflowInputs({ channel_id: 'C11111111', user_id: 'U123', message_count: 'lots' })
+    ).toMatchObject({ ok: true, messageCount: 50 });
+  });
+
+  it('rejects a missing or malformed channel', () => {
+    expect(parseWorkflowInputs({ user_id: 'U123' })).toEqual({
+      ok: false,
+      error: 'A valid channel is required.',
+    });
+    expect(parseWorkflowInputs({ channel_id: 'general', user_id: 'U123' }).ok).toBe(false);
+    expect(parseWorkflowInputs(undefined).ok).toBe(false);
+  });
+
+  it('rejects a missing user', () => {
+    expect(parseWorkflowInputs({ channel_id: 'C11111111' })).toEqual({
+      ok: false,
+      error: 'A user is required.',
+    });
+  });
+});
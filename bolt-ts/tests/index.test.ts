@@ -0,0 +1,73 @@
+import { AwsLambdaReceiver } from '@slack/bolt';
+import type { AwsEvent } from '@slack/bolt/dist/receivers/AwsLambdaReceiver';
+import { resetConfigCacheForTests } from '../src/config';
+import { PAYLOAD_TOO_LARGE_RESPONSE, handler, isBodyTooLarge, lambdaRemainingMs } from '../src/index';
+
+function makeEvent(body: string): AwsEvent {
+  return {
+    body,
+    headers: { 'Content-Type': 'application/json' },
+    multiValueHeaders: {},
+    httpMethod: 'POST',
+    isBase64Encoded: false,
+    path: '/slack/events',
+    pathParameters: null,
+    queryStringParameters: null,
+    multiValueQueryStringParameters: null,
+    stageVariables: null,
+    requestContext: {},
+    resource: '/slack/events',
+  } as unknown as AwsEvent;
+}
+
+describe('isBodyTooLarge', () => {
+  it('compares the raw body length against the limit', () => {
+    expect(isBodyTooLarge({ body: 'x'.repeat(10) }, 10)).toBe(false);
+    expect(isBodyTooLarge({ body: 'x'.repeat(11) }, 10)).toBe(true);
+  });
+
+  it('ignores a missing body', () => {
+    expect(isBodyTooLarge({ body: null } as unknown as Pick<AwsEvent, 'body'>, 10)).toBe(false);
+  });
+});
+
+describe('lambdaRemainingMs', () => {
+  it("reads the Lambda context's remaining time when present", () => {
+    expect(lambdaRemainingMs({ getRemainingTimeInMillis: () => 42_000 })).toBe(42_000);
+    expect(lambdaRemainingMs({})).toBeNull();
+    expect(lambdaRemainingMs(undefined)).toBeNull();
+  });
+});
+
+describe('handler', () => {
+  const originalEnv = process.env;
+
+  beforeEach(() => {
+    process.env = {
+      ...originalEnv,
+      SLACK_BOT_TOKEN: 'xoxb-test',
+      SLACK_SIGNING_SECRET: 'secret',
+      ANTHROPIC_API_KEY: 'sk-ant-test',
+      MAX_REQUEST_BODY_BYTES: '64',
+    };
+    resetConfigCacheForTests();
+  });
+
+  afterEach(() => {
+    jest.restoreAllMocks();
+  });
+
+  afterAll(() => {
+    process.env = originalEnv;
+    resetConfigCacheForTests();
+  });
+
+  it('returns 413 for an oversized body before Bolt verifies the signature', async () => {
+    const toHandler = jest.spyOn(AwsLambdaReceiver.prototype, 'toHandler');
+
+    const response = await handler(makeEvent('x'.repeat(65)), {}, () => undefined);
+
+    expect(response).toEqual(PAYLOAD_TOO_LARGE_RESPONSE);
+    expect(toHandler).not.toHaveBeenCalled();
+  });
+});
@@ -2,7 +2,7 @@
  * Tests for intent parsing.
  */
 
-import { parseUserIntent } from '../src/intent';
+import { invalidBoundMessage, parseUserIntent } from '../src/intent';
 
 describe('parseUserIntent', () => {
   describe('help intent', () => {
@@ -38,6 +38,11 @@ describe('parseUserIntent', () => {
       expect(result).toEqual({ type: 'style', instructions: 'extremely concise' });
     });
 
+    it('should keep newline-separated directives', () => {
+      const result = parseUserIntent('style: tone: formal\nfocus: blockers');
+      expect(result).toEqual({ type: 'style', instructions: 'tone: formal\nfocus: blockers' });
+    });
+
     it('should treat "style:" with no instructions as help', () => {
       const result = parseUserIntent('style:   ');
       expect(result).toEqual({ type: 'help' });
@@ -79,7 +84,26 @@ describe('parseUserIntent', () => {
         count: null,
         targetChannel: null,
         postHere: false,
+        postChannel: null,
         styleOverride: null,
+        threadTs: null,
+        groupByThread: false,
+        includeBots: false,
+        filesOnly: false,
+        template: null,
+        notifyMe: false,
+        keywordFilter: null,
+        pinnedOnly: false,
+        canvasOnly: false,
+        timezone: null,
+        beforeTs: null,
+        afterTs: null,
+        invalidBefore: null,
+        invalidAfter: null,
+        sinceLastSummary: false,
+        skipImages: false,
+        bookmark: false,
+        allowMentions: false,
       });
     });
 
@@ -90,7 +114,26 @@ describe('parseUserIntent', () => {
         count: 50,
         targetChannel: null,
         postHere: false,
+        postChannel: null,
         styleOverride: null,
+        threadTs: null,
+        groupByThread: false,
+        includeBots: false,
+        filesOnly: false,
+        template: null,
+        notifyMe: false,
+        keywordFilter: null,
+        pinnedOnly: false,
+        canvasOnly: false,
+        timezone: null,
+        beforeTs: null,
+        afterTs: null,
+        invalidBefore: null,
+        invalidAfter: null,
+        sinceLastSummary: false,
+        skipImages: false,
+        bookmark: false,
+        allowMentions: false,
       });
     });
 
@@ -101,7 +144,26 @@ describe('parseUserIntent', () => {
         count: 100,
         targetChannel: null,
         postHere: false,
+        postChannel: null,
         styleOverride: null,
+        threadTs: null,
+        groupByThread: false,
+        includeBots: false,
+        filesOnly: false,
+        template: null,
+        notifyMe: false,
+        keywordFilter: null,
+        pinnedOnly: false,
+        canvasOnly: false,
+        timezone: null,
+        beforeTs: null,
+        afterTs: null,
+        invalidBefore: null,
+        invalidAfter: null,
+        sinceLastSummary: false,
+        skipImages: false,
+        bookmark: false,
+        allowMentions: false,
       });
     });
 
@@ -112,7 +174,26 @@ describe('parseUserIntent', () => {
         count: null,
         targetChannel: 'C123ABC',
         postHere: false,
+        postChannel: null,
         styleOverride: null,
+        threadTs: null,
+        groupByThread: false,
+        includeBots: false,
+        filesOnly: false,
+        template: null,
+        notifyMe: false,
+        keywordFilter: null,
+        pinnedOnly: false,
+        canvasOnly: false,
+        timezone: null,
+        beforeTs: null,
+        afterTs: null,
+        invalidBefore: null,
+        invalidAfter: null,
+        sinceLastSummary: false,
+        skipImages: false,
+        bookmark: false,
+        allowMentions: false,
       });
     });
 
@@ -123,29 +204,86 @@ describe('parseUserIntent', () => {
         count: null,
         targetChannel: null,
         postHere: true,
+        postChannel: null,
         styleOverride: null,
+        threadTs: null,
+        groupByThread: false,
+        includeBots: false,
+        filesOnly: false,
+        template: null,
+        notifyMe: false,
+        keywordFilter: null,
+        pinnedOnly: false,
+        canvasOnly: false,
+        timezone: null,
+        beforeTs: null,
+        afterTs: null,
+        invalidBefore: null,
+        invalidAfter: null,
+        sinceLastSummary: false,
+        skipImages: false,
+        bookmark: false,
+        allowMentions: false,
       });
     });
 
-    it('should recognize "public" flag', () => {
-      const result = parseUserIntent('summarize public');
+    it('should recognize "--public" flag', () => {
+      const result = parseUserIntent('summarize --public');
       expect(result).toEqual({
         type: 'summarize',
         count: null,
         targetChannel: null,
         postHere: true,
+        postChannel: null,
         styleOverride: null,
+        threadTs: null,
+        groupByThread: false,
+        includeBots: false,
+        filesOnly: false,
+        template: null,
+        notifyMe: false,
+        keywordFilter: null,
+        pinnedOnly: false,
+        canvasOnly: false,
+        timezone: null,
+        beforeTs: null,
+        afterTs: null,
+        invalidBefore: null,
+        invalidAfter: null,
+        sinceLastSummary: false,
+        skipImages: false,
+        bookmark: false,
+        allowMentions: false,
       });
     });
 
     it('should parse complex command with all options', () => {
-      const result = parseUserIntent('summarize last 25 <#C789XYZ|random> public');
+      const result = parseUserIntent('summarize last 25 <#C789XYZ|random> --public');
       expect(result).toEqual({
         type: 'summarize',
         count: 25,
         targetChannel: 'C789XYZ',
         postHere: true,
+        postChannel: null,
         styleOverride: null,
+        threadTs: null,
+        groupByThread: false,
+        includeBots: false,
+        filesOnly: false,
+        template: null,
+        notifyMe: false,
+        keywordFilter: null,
+        pinnedOnly: false,
+        canvasOnly: false,
+        timezone: null,
+        beforeTs: null,
+        afterTs: null,
+        invalidBefore: null,
+        invalidAfter: null,
+        sinceLastSummary: false,
+        skipImages: false,
+        bookmark: false,
+        allowMentions: false,
       });
     });
 
@@ -156,7 +294,26 @@ describe('parseUserIntent', () => {
         count: null,
         targetChannel: null,
         postHere: false,
+        postChannel: null,
         styleOverride: 'be funny',
+        threadTs: null,
+        groupByThread: false,
+        includeBots: false,
+        filesOnly: false,
+        template: null,
+        notifyMe: false,
+        keywordFilter: null,
+        pinnedOnly: false,
+        canvasOnly: false,
+        timezone: null,
+        beforeTs: null,
+        afterTs: null,
+        invalidBefore: null,
+        invalidAfter: null,
+        sinceLastSummary: false,
+        skipImages: false,
+        bookmark: false,
+        allowMentions: false,
       });
     });
 
@@ -167,7 +324,26 @@ describe('parseUserIntent', () => {
         count: 50,
         targetChannel: null,
         postHere: false,
+        postChannel: null,
         styleOverride: 'write as haiku',
+        threadTs: null,
+        groupByThread: false,
+        includeBots: false,
+        filesOnly: false,
+        template: null,
+        notifyMe: false,
+        keywordFilter: null,
+        pinnedOnly: false,
+        canvasOnly: false,
+        timezone: null,
+        beforeTs: null,
+        afterTs: null,
+        invalidBefore: null,
+        invalidAfter: null,
+        sinceLastSummary: false,
+        skipImages: false,
+        bookmark: false,
+        allowMentions: false,
       });
     });
 
@@ -178,9 +354,491 @@ describe('parseUserIntent', () => {
         count: null,
         targetChannel: null,
         postHere: false,
+        postChannel: null,
         styleOverride: 'extremely concise',
+        threadTs: null,
+        groupByThread: false,
+        includeBots: false,
+        filesOnly: false,
+        template: null,
+        notifyMe: false,
+        keywordFilter: null,
+        pinnedOnly: false,
+        canvasOnly: false,
+        timezone: null,
+        beforeTs: null,
+        afterTs: null,
+        invalidBefore: null,
+        invalidAfter: null,
+        sinceLastSummary: false,
+        skipImages: false,
+        bookmark: false,
+        allowMentions: false,
+      });
+    });
+  });
+
+  describe('post to channel', () => {
+    it('should separate the destination channel from the source channel', () => {
+      const result = parseUserIntent('summarize <#C111AAA|eng> post to <#C222BBB|leads>');
+      expect(result).toEqual({
+        type: 'summarize',
+        count: null,
+        targetChannel: 'C111AAA',
+        postHere: false,
+        postChannel: 'C222BBB',
+        styleOverride: null,
+        threadTs: null,
+        groupByThread: false,
+        includeBots: false,
+        filesOnly: false,
+        template: null,
+        notifyMe: false,
+        keywordFilter: null,
+        pinnedOnly: false,
+        canvasOnly: false,
+        timezone: null,
+        beforeTs: null,
+        afterTs: null,
+        invalidBefore: null,
+        invalidAfter: null,
+        sinceLastSummary: false,
+        skipImages: false,
+        bookmark: false,
+        allowMentions: false,
+      });
+    });
+
+    it('should accept "share in" with no source channel', () => {
+      const result = parseUserIntent('summarize last 20 share in <#C222BBB|leads>');
+      expect(result).toMatchObject({ type: 'summarize', targetChannel: null, postChannel: 'C222BBB' });
+    });
+  });
+
+  describe('thread permalink', () => {
+    it('should summarize the thread behind a pasted permalink', () => {
+      const result = parseUserIntent(
+        'summarize <https://acme.slack.com/archives/C0123ABCD/p1700000000000100>'
+      );
+      expect(result).toEqual({
+        type: 'summarize',
+        count: null,
+        targetChannel: 'C0123ABCD',
+        postHere: false,
+        postChannel: null,
+        styleOverride: null,
+        threadTs: '1700000000.000100',
+        groupByThread: false,
+        includeBots: false,
+        filesOnly: false,
+        template: null,
+        notifyMe: false,
+        keywordFilter: null,
+        pinnedOnly: false,
+        canvasOnly: false,
+        timezone: null,
+        beforeTs: null,
+        afterTs: null,
+        invalidBefore: null,
+        invalidAfter: null,
+        sinceLastSummary: false,
+        skipImages: false,
+        bookmark: false,
+        allowMentions: false,
       });
     });
+
+    it('should use the parent thread_ts when the permalink points at a reply', () => {
+      const result = parseUserIntent(
+        'summarize https://acme.slack.com/archives/C0123ABCD/p1700000005000000?thread_ts=1700000000.000100&cid=C0123ABCD'
+      );
+      expect(result).toMatchObject({ type: 'summarize', threadTs: '1700000000.000100' });
+    });
+  });
+
+  describe('files only', () => {
+    it('should target the pasted message itself, not its thread', () => {
+      const result = parseUserIntent(
+        'describe the images https://acme.slack.com/archives/C0123ABCD/p1700000005000000?thread_ts=1700000000.000100&cid=C0123ABCD'
+      );
+      expect(result).toMatchObject({
+        type: 'summarize',
+        targetChannel: 'C0123ABCD',
+        threadTs: '1700000005.000000',
+        filesOnly: true,
+      });
+    });
+
+    it('should ignore the flag without a permalink', () => {
+      expect(parseUserIntent('summarize files only')).toMatchObject({
+        type: 'summarize',
+        filesOnly: false,
+      });
+    });
+  });
+
+  describe('standup template', () => {
+    it.each(['summarize template=standup', 'summarize last 50 as a standup', 'summarize --standup'])(
+      'should recognize %s',
+      (text) => {
+        expect(parseUserIntent(text)).toMatchObject({ type: 'summarize', template: 'standup' });
+      }
+    );
+
+    it('should not treat a plain mention of standup as the template', () => {
+      expect(parseUserIntent('summarize the standup notes')).toMatchObject({
+        type: 'summarize',
+        template: null,
+      });
+    });
+  });
+
+  describe('notify me', () => {
+    it.each(['summarize post to <#C0123ABCD|leads> --notify-me', 'summarize post here and notify me'])(
+      'should recognize %s',
+      (text) => {
+        expect(parseUserIntent(text)).toMatchObject({ type: 'summarize', notifyMe: true });
+      }
+    );
+  });
+
+  describe('keyword filter', () => {
+    it('should parse contains=word', () => {
+      expect(parseUserIntent('summarize last 200 contains=deploy')).toMatchObject({
+        type: 'summarize',
+        count: 200,
+        keywordFilter: 'deploy',
+        pinnedOnly: false,
+        canvasOnly: false,
+        timezone: null,
+        beforeTs: null,
+        afterTs: null,
+        invalidBefore: null,
+        invalidAfter: null,
+        sinceLastSummary: false,
+        skipImages: false,
+        bookmark: false,
+        allowMentions: false,
+      });
+    });
+
+    it('should accept a quoted phrase, including curly quotes', () => {
+      expect(parseUserIntent('summarize contains:"release train"')).toMatchObject({
+        keywordFilter: 'release train',
+      });
+      expect(parseUserIntent('summarize contains=“release train”')).toMatchObject({
+        keywordFilter: 'release train',
+      });
+    });
+  });
+
+  describe('canvas', () => {
+    it.each(['summarize canvas', 'summarize the canvas in <#C0123ABCD|eng>', 'summarize --canvas'])(
+      'should recognize %s',
+      (text) => {
+        expect(parseUserIntent(text)).toMatchObject({ type: 'summarize', canvasOnly: true });
+      }
+    );
+
+    it('should not treat a passing mention of a canvas as canvas mode', () => {
+      expect(parseUserIntent('summarize the talk about the new canvas')).toMatchObject({
+        type: 'summarize',
+        canvasOnly: false,
+      });
+    });
+  });
+
+  describe('pinned messages', () => {
+    it.each([
+      'summarize pinned',
+      'summarize the pins in <#C0123ABCD|eng>',
+      'summarize <#C0123ABCD|eng> --pinned',
+      'summarize pinned messages in <#C0123ABCD|eng>',
+      'summarize <#C0123ABCD|eng> pins only',
+    ])('should recognize %s', (text) => {
+      expect(parseUserIntent(text)).toMatchObject({ type: 'summarize', pinnedOnly: true });
+    });
+
+    it('should not treat a passing mention of pins as the mode', () => {
+      expect(parseUserIntent('summarize, focus on the pinned decision')).toMatchObject({
+        type: 'summarize',
+        pinnedOnly: false,
+      });
+    });
+  });
+
+  describe('flags inside free-form values', () => {
+    it('should not read flags from a style override', () => {
+      expect(
+        parseUserIntent('summarize with style: summarize pinned items, text only, by thread')
+      ).toMatchObject({
+        type: 'summarize',
+        styleOverride: 'summarize pinned items, text only, by thread',
+        pinnedOnly: false,
+        skipImages: false,
+        groupByThread: false,
+      });
+    });
+
+    it('should not read flags from a contains= keyword', () => {
+      expect(parseUserIntent('summarize contains="notify me --bookmark"')).toMatchObject({
+        type: 'summarize',
+        keywordFilter: 'notify me --bookmark',
+        notifyMe: false,
+        bookmark: false,
+      });
+    });
+
+    it.each([
+      ['summarize contains=public', { keywordFilter: 'public' }],
+      ['summarize with style: make it public-facing', { styleOverride: 'make it public-facing' }],
+      ['summarize with style: what should we post here', { styleOverride: 'what should we post here' }],
+    ])('should not post publicly for %s', (text, fields) => {
+      expect(parseUserIntent(text)).toMatchObject({ type: 'summarize', postHere: false, ...fields });
+    });
+
+    it.each([
+      'summarize with style: focus on what happened before: launch',
+      'summarize with style: say what came after: the freeze',
+      'summarize contains="before: launch"',
+    ])('should not read bounds from %s', (text) => {
+      expect(parseUserIntent(text)).toMatchObject({
+        type: 'summarize',
+        beforeTs: null,
+        afterTs: null,
+        invalidBefore: null,
+        invalidAfter: null,
+      });
+    });
+
+    it('should not treat a bare "public" as the post-here flag', () => {
+      expect(parseUserIntent('summarize the public channel')).toMatchObject({ postHere: false });
+    });
+  });
+
+  describe('timezone override', () => {
+    it.each([
+      ['summarize tz=America/New_York', 'America/New_York'],
+      ['summarize last 50 --tz Europe/Berlin', 'Europe/Berlin'],
+      ['summarize timezone: Asia/Tokyo', 'Asia/Tokyo'],
+    ])('should parse %s', (text, timezone) => {
+      expect(parseUserIntent(text)).toMatchObject({ type: 'summarize', timezone });
+    });
+
+    it('should keep an unknown zone verbatim for the handler to reject', () => {
+      expect(parseUserIntent('summarize tz=Mars/Olympus_Mons')).toMatchObject({
+        type: 'summarize',
+        timezone: 'Mars/Olympus_Mons',
+      });
+    });
+
+    it.each([
+      'summarize with style: mention the tz: PST offsets',
+      'summarize contains="timezone: UTC"',
+    ])('should not read a zone from %s', (text) => {
+      expect(parseUserIntent(text)).toMatchObject({ type: 'summarize', timezone: null });
+    });
+  });
+
+  describe('before bound', () => {
+    it('should read the ts and channel from a permalink without summarizing its thread', () => {
+      expect(
+        parseUserIntent(
+          'summarize last 50 before=https://acme.slack.com/archives/C0123ABCD/p1700000000000100'
+        )
+      ).toMatchObject({
+        type: 'summarize',
+        count: 50,
+        targetChannel: 'C0123ABCD',
+        threadTs: null,
+        beforeTs: '1700000000.000100',
+      });
+    });
+
+    it('should accept a wrapped permalink and keep an explicit channel', () => {
+      expect(
+        parseUserIntent(
+          'summarize <#C0999ZZZZ|eng> before: <https://acme.slack.com/archives/C0123ABCD/p1700000000000100>'
+        )
+      ).toMatchObject({ targetChannel: 'C0999ZZZZ', beforeTs: '1700000000.000100' });
+    });
+
+    it.each([
+      ['summarize before=1700000000.000100', '1700000000.000100'],
+      ['summarize --before 1700000000', '1700000000.000000'],
+    ])('should parse %s', (text, beforeTs) => {
+      expect(parseUserIntent(text)).toMatchObject({ type: 'summarize', beforeTs, invalidBefore: null });
+    });
+
+    it('should keep a malformed value for a friendly error', () => {
+      expect(parseUserIntent('summarize before=not-a-message')).toMatchObject({
+        type: 'summarize',
+        beforeTs: null,
+        invalidBefore: 'not-a-message',
+      });
+      expect(invalidBoundMessage('before', 'yesterday')).toContain('"before=yesterday"');
+    });
+  });
+
+  describe('after bound', () => {
+    it('should read the ts and channel from a permalink without summarizing its thread', () => {
+      expect(
+        parseUserIntent(
+          'summarize last 100 after=https://acme.slack.com/archives/C0123ABCD/p1700000000000100'
+        )
+      ).toMatchObject({
+        type: 'summarize',
+        count: 100,
+        targetChannel: 'C0123ABCD',
+        threadTs: null,
+        afterTs: '1700000000.000100',
+        invalidBefore: null,
+        invalidAfter: null,
+      });
+    });
+
+    it('should combine with before= into one window', () => {
+      expect(
+        parseUserIntent('summarize after=1700000000.000100 before=1700000900.000200')
+      ).toMatchObject({ afterTs: '1700000000.000100', beforeTs: '1700000900.000200' });
+    });
+
+    it.each([
+      ['summarize after: <https://acme.slack.com/archives/C0123ABCD/p1700000000000100>', '1700000000.000100'],
+      ['summarize --after 1700000000', '1700000000.000000'],
+    ])('should parse %s', (text, afterTs) => {
+      expect(parseUserIntent(text)).toMatchObject({ type: 'summarize', afterTs, invalidAfter: null });
+    });
+
+    it('should keep a malformed value for a friendly error', () => {
+      const intent = parseUserIntent('summarize after=https://acme.slack.com/archives/C0123ABCD');
+      expect(intent).toMatchObject({
+        type: 'summarize',
+        afterTs: null,
+        invalidAfter: 'https://acme.slack.com/archives/C0123ABCD',
+      });
+      expect(invalidBoundMessage('after', 'yesterday')).toContain('"after=yesterday"');
+    });
+  });
+
+  describe('no images', () => {
+    it.each(['summarize --no-images', 'summarize last 200 without images', 'summarize text-only'])(
+      'should recognize %s',
+      (text) => {
+        expect(parseUserIntent(text)).toMatchObject({ type: 'summarize', skipImages: true });
+      }
+    );
+
+    it('should ignore it on a files-only run', () => {
+      expect(
+        parseUserIntent(
+          'describe the images https://acme.slack.com/archives/C0123ABCD/p1700000000000100 --no-images'
+        )
+      ).toMatchObject({ filesOnly: true, skipImages: false });
+    });
+  });
+
+  describe('allow mentions', () => {
+    it('should recognize --allow-mentions', () => {
+      expect(parseUserIntent('summarize --allow-mentions')).toMatchObject({
+        type: 'summarize',
+        allowMentions: true,
+      });
+    });
+  });
+
+  describe('since last summary', () => {
+    it.each(['summarize --since-last-summary', 'summarize <#C0123ABCD|eng> since the last summary'])(
+      'should recognize %s',
+      (text) => {
+        expect(parseUserIntent(text)).toMatchObject({ type: 'summarize', sinceLastSummary: true });
+      }
+    );
+  });
+
+  describe('bookmark', () => {
+    it.each(['summarize post to <#C0123ABCD|leads> --bookmark', 'summarize post here and bookmark it'])(
+      'should recognize %s',
+      (text) => {
+        expect(parseUserIntent(text)).toMatchObject({ type: 'summarize', bookmark: true });
+      }
+    );
+  });
+
+  describe('mentions intent', () => {
+    it('should recognize "summarize my mentions"', () => {
+      const result = parseUserIntent('summarize my mentions');
+      expect(result).toEqual({ type: 'mentions', count: null, allowMentions: false });
+    });
+
+    it('should parse a count for the mentions digest', () => {
+      const result = parseUserIntent('My Mentions last 20');
+      expect(result).toEqual({ type: 'mentions', count: 20, allowMentions: false });
+    });
+
+    it('should read --allow-mentions for the mentions digest', () => {
+      expect(parseUserIntent('summarize my mentions --allow-mentions')).toEqual({
+        type: 'mentions',
+        count: null,
+        allowMentions: true,
+      });
+    });
+
+    it('should not treat "mentions" without "my" as a digest request', () => {
+      const result = parseUserIntent('summarize mentions of the launch');
+      expect(result.type).toBe('summarize');
+    });
+
+    it.each([
+      ['summarize with style: call out my mentions', { styleOverride: 'call out my mentions' }],
+      ['summarize contains="my mentions"', { keywordFilter: 'my mentions' }],
+    ])('should not read a digest request from %s', (text, fields) => {
+      expect(parseUserIntent(text)).toMatchObject({ type: 'summarize', ...fields });
+    });
+  });
+
+  describe('group by thread', () => {
+    it('should opt into thread grouping with "by thread"', () => {
+      expect(parseUserIntent('summarize by thread')).toMatchObject({
+        type: 'summarize',
+        groupByThread: true,
+      });
+      expect(parseUserIntent('summarize last 100 grouped by threads')).toMatchObject({
+        type: 'summarize',
+        count: 100,
+        groupByThread: true,
+      });
+    });
+  });
+
+  describe('include bots', () => {
+    it('should keep bot messages when asked', () => {
+      expect(parseUserIntent('summarize include bots')).toMatchObject({
+        type: 'summarize',
+        includeBots: true,
+      });
+      expect(parseUserIntent('summarize last 200 with bots')).toMatchObject({
+        type: 'summarize',
+        count: 200,
+        includeBots: true,
+      });
+      expect(parseUserIntent('summarize --include-bots')).toMatchObject({ includeBots: true });
+      expect(parseUserIntent('summarize last 20')).toMatchObject({ includeBots: false });
+    });
+  });
+
+  describe('refresh intent', () => {
+    it('should recognize a bare "refresh"', () => {
+      expect(parseUserIntent('refresh')).toEqual({ type: 'refresh', count: null });
+    });
+
+    it('should parse a new count for the refresh', () => {
+      expect(parseUserIntent('Refresh last 200')).toEqual({ type: 'refresh', count: 200 });
+    });
+
+    it('should only match refresh at the start of the message', () => {
+      expect(parseUserIntent('summarize the refresh discussion').type).toBe('summarize');
+    });
   });
 
   describe('unknown intent', () => {
@@ -0,0 +1,51 @@
+import { detectLanguage, languageName } from '../src/lang';
+
+describe('detectLanguage', () => {
+  it.each([
+    [
+      'en',
+      'We shipped the new billing page today and it is working well. Can you check the invoices for the last week? I think we have to fix the tax bug before Friday, but the team is on it.',
+    ],
+    [
+      'es',
+      'Hoy lanzamos la nueva página de facturación y está funcionando muy bien. ¿Puedes revisar las facturas de la semana pasada? Creo que hay que arreglar el error de impuestos antes del viernes, pero el equipo ya lo tiene.',
+    ],
+    [
+      'de',
+      'Wir haben heute die neue Abrechnungsseite veröffentlicht und sie funktioniert gut. Kannst du die Rechnungen der letzten Woche prüfen? Ich denke, wir müssen den Steuerfehler noch vor Freitag beheben, aber das Team ist dran.',
+    ],
+    [
+      'pt',
+      'Hoje lançamos a nova página de faturamento e ela está funcionando muito bem. Você pode verificar as faturas da semana passada? Acho que temos que corrigir o erro de impostos antes de sexta, mas a equipe já está nisso.',
+    ],
+    [
+      'ja',
+      '今日新しい請求ページをリリースしました。問題なく動いています。先週の請求書を確認してもらえますか？金曜日までに税金のバグを直す必要があると思います。',
+    ],
+    [
+      'zh',
+      '我们今天发布了新的账单页面，运行良好。你能检查一下上周的发票吗？我认为我们需要在星期五之前修复税务错误，团队正在处理。',
+    ],
+    [
+      'ru',
+      'Сегодня мы выпустили новую страницу оплаты, и она работает хорошо. Можешь проверить счета за прошлую неделю? Думаю, нам нужно исправить ошибку с налогами до пятницы.',
+    ],
+  ])('detects %s', (lang, sample) => {
+    expect(detectLanguage(sample)).toBe(lang);
+  });
+
+  it('declines to call short or markup-heavy samples', () => {
+    expect(detectLanguage('ok sounds good')).toBeNull();
+    expect(
+      detectLanguage('deploy done ✅ <https://ci.example.com/run/1|run> :tada: lgtm merged PR-55 `make ship`')
+    ).toBeNull();
+  });
+});
+
+describe('languageName', () => {
+  it('names known codes in English and passes unknown ones through', () => {
+    expect(languageName('es')).toBe('Spanish');
+    expect(languageName('ja')).toBe('Japanese');
+    expect(languageName('not a code')).toBe('not a code');
+  });
+});
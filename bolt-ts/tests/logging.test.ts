@@ -0,0 +1,64 @@
+import { currentCorrelationId, log, withCorrelation, withCorrelationId } from '../src/logging';
+
+describe('withCorrelationId', () => {
+  afterEach(() => {
+    jest.restoreAllMocks();
+  });
+
+  it('exposes the id to nested async work and clears it afterwards', async () => {
+    const seen = await withCorrelationId('corr-1', async () => {
+      await new Promise((resolve) => setTimeout(resolve, 0));
+      return currentCorrelationId();
+    });
+    expect(seen).toBe('corr-1');
+    expect(currentCorrelationId()).toBeUndefined();
+  });
+
+  it('keeps concurrent runs isolated', async () => {
+    const run = (id: string, delay: number): Promise<string | undefined> =>
+      withCorrelationId(id, async () => {
+        await new Promise((resolve) => setTimeout(resolve, delay));
+        return currentCorrelationId();
+      });
+    await expect(Promise.all([run('a', 5), run('b', 0)])).resolves.toEqual(['a', 'b']);
+  });
+
+  it('stamps corr_id onto log lines emitted inside the run', async () => {
+    const spy = jest.spyOn(console, 'warn').mockImplementation(() => undefined);
+    await withCorrelationId('corr-2', async () => {
+      log.warn('downstream warning', { channel: 'C11111111' });
+    });
+    log.warn('outside any run');
+    expect(spy).toHaveBeenNthCalledWith(1, 'downstream warning', {
+      corr_id: 'corr-2',
+      channel: 'C11111111',
+    });
+    expect(spy).toHaveBeenNthCalledWith(2, 'outside any run', {});
+  });
+});
+
+describe('withCorrelation', () => {
+  it('adds the run workspace ids when known', async () => {
+    const meta = await withCorrelationId(
+      'corr-3',
+      async () => withCorrelation({ channel: 'C11111111' }),
+      { teamId: 'T0123ABCD', enterpriseId: 'E0123ABCD' }
+    );
+    expect(meta).toEqual({
+      corr_id: 'corr-3',
+      team_id: 'T0123ABCD',
+      enterprise_id: 'E0123ABCD',
+      channel: 'C11111111',
+    });
+    const withoutGrid = await withCorrelationId('corr-4', async () => withCorrelation(), {
+      teamId: 'T0123ABCD',
+      enterpriseId: null,
+    });
+    expect(withoutGrid).toEqual({ corr_id: 'corr-4', team_id: 'T0123ABCD' });
+  });
+
+  it('lets an explicit corr_id win', async () => {
+    const meta = await withCorrelationId('ambient', async () => withCorrelation({ corr_id: 'explicit' }));
+    expect(meta).toEqual({ corr_id: 'explicit' });
+  });
+});
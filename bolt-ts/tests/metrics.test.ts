@@ -0,0 +1,134 @@
+import type { PromptPayload } from '../src/ai/prompt';
+import { log } from '../src/logging';
+import {
+  MAX_CONTEXT_TOKENS,
+  METRICS_NAMESPACE,
+  buildEmfDocument,
+  emitMetric,
+  emitSummaryMetrics,
+  estimateTokens,
+  newSummaryRunStats,
+  promptOverflowTokens,
+  recordPromptOverflow,
+  recordPromptStats,
+} from '../src/metrics';
+
+describe('buildEmfDocument', () => {
+  it('matches the CloudWatch EMF shape', () => {
+    const doc = buildEmfDocument(
+      [
+        { name: 'SummaryLatency', value: 1234, unit: 'Milliseconds' },
+        { name: 'ImageCount', value: 2, unit: 'Count' },
+      ],
+      { Delivery: 'streaming' },
+      1_700_000_000_000
+    );
+    expect(doc).toEqual({
+      _aws: {
+        Timestamp: 1_700_000_000_000,
+        CloudWatchMetrics: [
+          {
+            Namespace: METRICS_NAMESPACE,
+            Dimensions: [['Delivery']],
+            Metrics: [
+              { Name: 'SummaryLatency', Unit: 'Milliseconds' },
+              { Name: 'ImageCount', Unit: 'Count' },
+            ],
+          },
+        ],
+      },
+      Delivery: 'streaming',
+      SummaryLatency: 1234,
+      ImageCount: 2,
+    });
+  });
+});
+
+describe('emitMetric', () => {
+  it('writes one JSON line to stdout', () => {
+    const spy = jest.spyOn(console, 'log').mockImplementation(() => undefined);
+    try {
+      emitMetric('Retries', 3, { Path: 'rollup' }, 'Count');
+      expect(spy).toHaveBeenCalledTimes(1);
+      const parsed = JSON.parse(spy.mock.calls[0][0] as string);
+      expect(parsed._aws.CloudWatchMetrics[0].Metrics).toEqual([{ Name: 'Retries', Unit: 'Count' }]);
+      expect(parsed.Retries).toBe(3);
+      expect(parsed.Path).toBe('rollup');
+    } finally {
+      spy.mockRestore();
+    }
+  });
+});
+
+describe('summary run stats', () => {
+  it('estimates input tokens from text blocks and counts images', () => {
+    const stats = newSummaryRunStats();
+    recordPromptStats(stats, 12, {
+      system: 'x'.repeat(40),
+      userContent: [
+        { type: 'text', text: 'y'.repeat(80) },
+        { type: 'image', source: { type: 'base64', media_type: 'image/png', data: 'AAAA' } },
+        { type: 'text', text: '<task>' },
+      ],
+    });
+    expect(stats.messages).toBe(12);
+    expect(stats.images).toBe(1);
+    expect(stats.inputTokens).toBe(10 + 20 + 1600 + estimateTokens('<task>'));
+  });
+
+  it('reports failures as a zero success value', () => {
+    const spy = jest.spyOn(console, 'log').mockImplementation(() => undefined);
+    try {
+      const doc = emitSummaryMetrics({
+        stats: newSummaryRunStats(),
+        latencyMs: 50,
+        success: false,
+        delivery: 'channel',
+      });
+      expect(doc.SummarySuccess).toBe(0);
+      expect(doc.Delivery).toBe('channel');
+    } finally {
+      spy.mockRestore();
+    }
+  });
+});
+
+describe('prompt overflow', () => {
+  const prompt: PromptPayload = {
+    system: 'x'.repeat(400),
+    userContent: [
+      { type: 'text', text: 'y'.repeat(4 * 210_000) },
+      { type: 'image', source: { type: 'base64', media_type: 'image/png', data: 'AAAA' } },
+    ],
+  };
+
+  it('measures how far input plus max output overruns the context window', () => {
+    // 100 + 210_000 + 1_600 input tokens, plus 16_000 reserved for output.
+    expect(promptOverflowTokens(prompt, 16_000)).toBe(227_700 - MAX_CONTEXT_TOKENS);
+    expect(promptOverflowTokens({ system: 'short', userContent: [] }, 16_000)).toBe(0);
+  });
+
+  it('logs and emits the overflow only when there is one', () => {
+    const out = jest.spyOn(console, 'log').mockImplementation(() => undefined);
+    const warn = jest.spyOn(log, 'warn').mockImplementation(() => undefined);
+    try {
+      expect(recordPromptOverflow(prompt, 16_000, 'streaming')).toBe(27_700);
+      expect(warn).toHaveBeenCalledWith(
+        'Prompt is likely over the context window',
+        expect.objectContaining({ estimatedInputTokens: 211_700, overflowTokens: 27_700 })
+      );
+      const doc = JSON.parse(out.mock.calls[0][0] as string);
+      expect(doc.PromptOverflowTokens).toBe(27_700);
+      expect(doc.Path).toBe('streaming');
+
+      out.mockClear();
+      warn.mockClear();
+      expect(recordPromptOverflow({ system: 'short', userContent: [] }, 16_000, 'non_streaming')).toBe(0);
+      expect(out).not.toHaveBeenCalled();
+      expect(warn).not.toHaveBeenCalled();
+    } finally {
+      out.mockRestore();
+      warn.mockRestore();
+    }
+  });
+});
@@ -0,0 +1,65 @@
+import { Semaphore } from '../src/semaphore';
+
+/** A promise plus its resolver, so a test decides when each task finishes. */
+function deferred(): { promise: Promise<void>; resolve: () => void } {
+  let resolve!: () => void;
+  const promise = new Promise<void>((r) => {
+    resolve = r;
+  });
+  return { promise, resolve };
+}
+
+const flush = (): Promise<void> => new Promise((r) => setImmediate(r));
+
+describe('Semaphore', () => {
+  it('never runs more than `limit` tasks at once and starts the rest in order', async () => {
+    const semaphore = new Semaphore(2);
+    const gates = Array.from({ length: 5 }, deferred);
+    const started: number[] = [];
+    let running = 0;
+    let peak = 0;
+
+    const runs = gates.map((gate, i) =>
+      semaphore.run(async () => {
+        started.push(i);
+        running += 1;
+        peak = Math.max(peak, running);
+        await gate.promise;
+        running -= 1;
+      })
+    );
+
+    await flush();
+    expect(started).toEqual([0, 1]);
+    expect(semaphore.queued).toBe(3);
+
+    for (const gate of gates) {
+      gate.resolve();
+      await flush();
+    }
+    await Promise.all(runs);
+
+    expect(started).toEqual([0, 1, 2, 3, 4]);
+    expect(peak).toBe(2);
+    expect(semaphore.inFlight).toBe(0);
+  });
+
+  it('releases the slot when the task throws', async () => {
+    const semaphore = new Semaphore(1);
+    await expect(
+      semaphore.run(async () => {
+        throw new Error('boom');
+      })
+    ).rejects.toThrow('boom');
+    expect(semaphore.inFlight).toBe(0);
+    await expect(semaphore.run(async () => 'next')).resolves.toBe('next');
+  });
+
+  it('ignores a second release of the same slot', async () => {
+    const semaphore = new Semaphore(1);
+    const release = await semaphore.acquire();
+    release();
+    release();
+    expect(semaphore.inFlight).toBe(0);
+  });
+});
@@ -1,17 +1,50 @@
 import type { WebClient } from '@slack/web-api';
 import {
+  addBookmark,
   appendStream,
+  CHANNEL_NAME_CACHE_TTL_MS,
+  buildAssistantStatusPayload,
+  buildFunctionCompleteErrorPayload,
+  buildFunctionCompleteSuccessPayload,
+  buildMentionSearchQuery,
+  canvasHtmlToMarkdown,
   downloadImageBytes,
   fetchImageHead,
+  buildSummaryRangeMetadata,
+  extractLastSummaryRange,
+  findStaleStreamTs,
   getBotUserId,
+  getCanvasContent,
+  getChannelCanvasId,
   getChannelName,
+  getChannelNames,
   getMessagePermalink,
+  getMessagePermalinks,
+  getPinnedMessages,
   getRecentMessages,
+  getSingleMessage,
   getUserDisplayName,
+  getUserDisplayNames,
+  hasSlackErrorCode,
   isMessageNotInStreamingStateError,
+  isMsgTooLongError,
+  isSlackTimeoutError,
+  isStreamingUnavailableError,
+  isTransientSlackError,
+  listChannelNames,
+  ModalOpenError,
+  openModal,
   pickFileDownloadUrl,
+  resetBotUserIdCacheForTests,
+  resetChannelNameCacheForTests,
+  searchMessages,
+  setAssistantStatus,
   startStream,
+  stopStaleStreams,
   stopStream,
+  summaryRangeOf,
+  STREAM_CLOSED_METADATA,
+  STREAM_OPEN_METADATA,
 } from '../../src/slack/client';
 
 function makeWebClient(overrides: Record<string, unknown>): WebClient {
@@ -28,12 +61,247 @@ describe('Slack client wrappers', () => {
     expect(history).toHaveBeenCalledWith({ channel: 'C1', limit: 1000 });
   });
 
+  it('extracts the newest summary range stamped for a channel', () => {
+    const stamp = (channel: string, oldest: string, latest: string) =>
+      buildSummaryRangeMetadata(summaryRangeOf(channel, [{ ts: latest }, { ts: oldest }])!);
+    const messages = [
+      { metadata: stamp('C1', '10.0', '20.0') },
+      { metadata: stamp('C1', '21.0', '30.0') },
+      { metadata: stamp('C2', '5.0', '99.0') },
+      { metadata: { event_type: 'tldr_thread_state', event_payload: { v: 1 } } },
+      {},
+    ];
+    expect(extractLastSummaryRange(messages, 'C1')).toEqual({
+      sourceChannelId: 'C1',
+      oldestTs: '21.0',
+      latestTs: '30.0',
+    });
+    expect(extractLastSummaryRange(messages, 'C3')).toBeNull();
+    expect(summaryRangeOf('C1', [])).toBeNull();
+  });
+
+  it('bounds history to messages before a ts when one is given', async () => {
+    const history = jest.fn().mockResolvedValue({ messages: [] });
+    const client = makeWebClient({ conversations: { history } });
+    await getRecentMessages(client, 'C1', 50, { latest: '1700000000.000100' });
+    expect(history).toHaveBeenCalledWith({ channel: 'C1', limit: 50, latest: '1700000000.000100' });
+  });
+
+  it("reads a channel's canvas id from conversations.info", async () => {
+    const info = jest
+      .fn()
+      .mockResolvedValueOnce({ channel: { id: 'C1', properties: { canvas: { file_id: 'F0CANVAS' } } } })
+      .mockResolvedValueOnce({ channel: { id: 'C2' } });
+    const client = makeWebClient({ conversations: { info } });
+    await expect(getChannelCanvasId(client, 'C1')).resolves.toBe('F0CANVAS');
+    await expect(getChannelCanvasId(client, 'C2')).resolves.toBeNull();
+  });
+
+  it('downloads canvas content with the bot token and flattens it to markdown', async () => {
+    const filesInfo = jest.fn().mockResolvedValue({
+      file: {
+        id: 'F0CANVAS',
+        title: 'Q3 planning',
+        updated: 1792031400,
+        url_private_download: 'https://files.slack.com/F0CANVAS/download',
+      },
+    });
+    const fetchImpl = jest
+      .fn()
+      .mockResolvedValue(
+        new Response('<h1>Goals</h1><ul><li>Ship search</li><li>Cut p95 &amp; p99</li></ul>')
+      );
+    const client = makeWebClient({ files: { info: filesInfo } });
+
+    const canvas = await getCanvasContent(client, {
+      canvasId: 'F0CANVAS',
+      botToken: 'xoxb-test',
+      fetchImpl: fetchImpl as unknown as typeof fetch,
+    });
+
+    expect(filesInfo).toHaveBeenCalledWith({ file: 'F0CANVAS' });
+    expect(fetchImpl).toHaveBeenCalledWith('https://files.slack.com/F0CANVAS/download', {
+      headers: { Authorization: 'Bearer xoxb-test' },
+    });
+    expect(canvas).toEqual({
+      canvasId: 'F0CANVAS',
+      title: 'Q3 planning',
+      markdown: '# Goals\n\n- Ship search\n- Cut p95 & p99',
+      updated: 1792031400,
+    });
+  });
+
+  it('returns null when the canvas cannot be downloaded', async () => {
+    const client = makeWebClient({ files: { info: jest.fn().mockResolvedValue({ file: { id: 'F1' } }) } });
+    await expect(getCanvasContent(client, { canvasId: 'F1', botToken: 'xoxb' })).resolves.toBeNull();
+
+    const withUrl = makeWebClient({
+      files: { info: jest.fn().mockResolvedValue({ file: { url_private: 'https://files/F1' } }) },
+    });
+    const fetchImpl = jest.fn().mockResolvedValue(new Response('', { status: 403 }));
+    await expect(
+      getCanvasContent(withUrl, {
+        canvasId: 'F1',
+        botToken: 'xoxb',
+        fetchImpl: fetchImpl as unknown as typeof fetch,
+      })
+    ).resolves.toBeNull();
+  });
+
+  it('flattens canvas HTML links, entities, and paragraphs', () => {
+    expect(
+      canvasHtmlToMarkdown(
+        '<p>See <a href="https://example.com/spec">the spec</a> &#8212; it\'s &quot;final&quot;</p><p>Next</p>'
+      )
+    ).toBe('See the spec (https://example.com/spec) — it\'s "final"\nNext');
+  });
+
+  it('leaves out-of-range numeric entities as written', () => {
+    expect(canvasHtmlToMarkdown('<p>&#99999999; &#x110000; &#x10FFFF; &#65;</p>')).toBe(
+      '&#99999999; &#x110000; \u{10ffff} A'
+    );
+  });
+
+  it('treats only old bot replies still marked as open streams as stale', () => {
+    const replies = [
+      { ts: '1000.000000', user: 'UUSER', openStream: false },
+      { ts: '1001.000000', user: 'UBOT', openStream: false },
+      { ts: '1002.000000', user: 'UBOT', openStream: true },
+      { ts: '1003.000000', user: 'UBOT', openStream: false },
+      { ts: '1004.000000', user: 'UUSER', openStream: true },
+      { ts: '1005.000000', user: 'UBOT', openStream: true },
+      { ts: '1900.000000', user: 'UBOT', openStream: true },
+    ];
+    expect(
+      findStaleStreamTs(replies, {
+        threadTs: '1000.000000',
+        botUserId: 'UBOT',
+        olderThanSecs: 900,
+        nowSecs: 2000,
+      })
+    ).toEqual(['1005.000000', '1002.000000']);
+    expect(
+      findStaleStreamTs(replies, {
+        threadTs: '1000.000000',
+        botUserId: 'UBOT',
+        olderThanSecs: 900,
+        nowSecs: 2000,
+        limit: 1,
+      })
+    ).toEqual(['1005.000000']);
+  });
+
+  it('stops stale streams and marks them closed', async () => {
+    const replies = jest.fn().mockResolvedValue({
+      messages: [
+        { ts: '1000.000000', user: 'UUSER' },
+        { ts: '1002.000000', user: 'UBOT', metadata: STREAM_OPEN_METADATA },
+        { ts: '1900.000000', user: 'UBOT', metadata: STREAM_OPEN_METADATA },
+      ],
+    });
+    const stop = jest.fn().mockResolvedValue({ ok: true });
+    const client = makeWebClient({ conversations: { replies }, chat: { stopStream: stop } });
+
+    const stopped = await stopStaleStreams(client, {
+      channel: 'D1',
+      threadTs: '1000.000000',
+      botUserId: 'UBOT',
+      olderThanSecs: 900,
+      now: 2_000_000,
+    });
+
+    expect(stopped).toBe(1);
+    expect(replies).toHaveBeenCalledWith({ channel: 'D1', ts: '1000.000000', include_all_metadata: true });
+    expect(stop).toHaveBeenCalledTimes(1);
+    expect(stop).toHaveBeenCalledWith({ channel: 'D1', ts: '1002.000000', metadata: STREAM_CLOSED_METADATA });
+  });
+
+  it('finds an orphaned stream behind newer plain notices', async () => {
+    const notice = (ts: string) => ({ ts, user: 'UBOT', text: "I can only summarize channels you're a member of." });
+    const replies = jest.fn().mockResolvedValue({
+      messages: [
+        { ts: '1000.000000', user: 'UUSER' },
+        { ts: '1001.000000', user: 'UBOT', metadata: STREAM_OPEN_METADATA },
+        { ts: '1002.000000', user: 'UBOT', metadata: STREAM_CLOSED_METADATA },
+        notice('1003.000000'),
+        notice('1004.000000'),
+        notice('1005.000000'),
+        notice('1006.000000'),
+      ],
+    });
+    const stop = jest.fn().mockResolvedValue({ ok: true });
+    const client = makeWebClient({ conversations: { replies }, chat: { stopStream: stop } });
+
+    const stopped = await stopStaleStreams(client, {
+      channel: 'D1',
+      threadTs: '1000.000000',
+      botUserId: 'UBOT',
+      olderThanSecs: 900,
+      now: 2_000_000,
+    });
+
+    expect(stopped).toBe(1);
+    expect(stop).toHaveBeenCalledWith({ channel: 'D1', ts: '1001.000000', metadata: STREAM_CLOSED_METADATA });
+  });
+
+  it('fetches a single message by ts and ignores the rest of its thread', async () => {
+    const replies = jest.fn().mockResolvedValue({
+      messages: [
+        { ts: '1.0', user: 'U1', text: 'root' },
+        { ts: '2.0', user: 'U2', text: 'screenshot', files: [] },
+      ],
+    });
+    const client = makeWebClient({ conversations: { replies } });
+    const message = await getSingleMessage(client, 'C1', '2.0');
+    expect(replies).toHaveBeenCalledWith({
+      channel: 'C1',
+      ts: '2.0',
+      oldest: '2.0',
+      latest: '2.0',
+      inclusive: true,
+    });
+    expect(message).toMatchObject({ ts: '2.0', user: 'U2', text: 'screenshot' });
+    await expect(getSingleMessage(client, 'C1', '9.0')).resolves.toBeNull();
+  });
+
+  it('maps pinned messages oldest first and skips pinned files', async () => {
+    const list = jest.fn().mockResolvedValue({
+      ok: true,
+      items: [
+        {
+          type: 'message',
+          created: 1700000300,
+          channel: 'C1',
+          message: { ts: '1700000200.000100', user: 'U2', text: 'Decision: ship Friday' },
+        },
+        { type: 'file', created: 1700000250, file: { id: 'F1', name: 'spec.pdf' } },
+        {
+          type: 'message',
+          created: 1700000100,
+          channel: 'C1',
+          message: { ts: '1700000000.000100', user: 'U1', text: 'Runbook: <https://wiki/runbook>' },
+        },
+      ],
+    });
+    const client = makeWebClient({ pins: { list } });
+    const pinned = await getPinnedMessages(client, 'C1');
+    expect(list).toHaveBeenCalledWith({ channel: 'C1' });
+    expect(pinned.map((m) => [m.ts, m.user, m.text])).toEqual([
+      ['1700000000.000100', 'U1', 'Runbook: <https://wiki/runbook>'],
+      ['1700000200.000100', 'U2', 'Decision: ship Friday'],
+    ]);
+
+    const empty = makeWebClient({ pins: { list: jest.fn().mockResolvedValue({ ok: true }) } });
+    await expect(getPinnedMessages(empty, 'C1')).resolves.toEqual([]);
+  });
+
   it('maps Slack history messages onto the simplified shape', async () => {
     const history = jest.fn().mockResolvedValue({
       messages: [
         {
           ts: '1',
           user: 'U1',
+          user_team: 'T1',
           text: 'hello',
           files: [
             {
@@ -50,6 +318,7 @@ describe('Slack client wrappers', () => {
     expect(messages[0]).toMatchObject({
       ts: '1',
       user: 'U1',
+      userTeam: 'T1',
       text: 'hello',
       files: [
         {
@@ -58,9 +327,19 @@ describe('Slack client wrappers', () => {
           mimeType: 'image/png',
         },
       ],
+      broadcast: false,
     });
   });
 
+  it('flags thread_broadcast messages', async () => {
+    const history = jest.fn().mockResolvedValue({
+      messages: [{ ts: '2', user: 'U1', text: 'shipped', subtype: 'thread_broadcast', thread_ts: '1' }],
+    });
+    const client = makeWebClient({ conversations: { history } });
+    const [message] = await getRecentMessages(client, 'C1', 1);
+    expect(message).toMatchObject({ ts: '2', threadTs: '1', broadcast: true });
+  });
+
   it('returns null when auth.test fails', async () => {
     const client = makeWebClient({
       auth: { test: jest.fn().mockRejectedValue(new Error('nope')) },
@@ -68,6 +347,48 @@ describe('Slack client wrappers', () => {
     expect(await getBotUserId(client)).toBeNull();
   });
 
+  describe('bot user id cache', () => {
+    beforeEach(() => resetBotUserIdCacheForTests());
+    afterEach(() => resetBotUserIdCacheForTests());
+
+    it('calls auth.test once per token, sharing an in-flight lookup', async () => {
+      const test = jest.fn().mockResolvedValue({ user_id: 'UBOT' });
+      const client = makeWebClient({ token: 'xoxb-1', auth: { test } });
+
+      const [first, second] = await Promise.all([getBotUserId(client), getBotUserId(client)]);
+      const third = await getBotUserId(makeWebClient({ token: 'xoxb-1', auth: { test } }));
+
+      expect([first, second, third]).toEqual(['UBOT', 'UBOT', 'UBOT']);
+      expect(test).toHaveBeenCalledTimes(1);
+    });
+
+    it('keeps tokens apart', async () => {
+      const first = makeWebClient({
+        token: 'xoxb-1',
+        auth: { test: jest.fn().mockResolvedValue({ user_id: 'UBOT1' }) },
+      });
+      const second = makeWebClient({
+        token: 'xoxb-2',
+        auth: { test: jest.fn().mockResolvedValue({ user_id: 'UBOT2' }) },
+      });
+
+      expect(await getBotUserId(first)).toBe('UBOT1');
+      expect(await getBotUserId(second)).toBe('UBOT2');
+    });
+
+    it('retries after a failed lookup', async () => {
+      const test = jest
+        .fn()
+        .mockRejectedValueOnce(new Error('nope'))
+        .mockResolvedValueOnce({ user_id: 'UBOT' });
+      const client = makeWebClient({ token: 'xoxb-1', auth: { test } });
+
+      expect(await getBotUserId(client)).toBeNull();
+      expect(await getBotUserId(client)).toBe('UBOT');
+      expect(test).toHaveBeenCalledTimes(2);
+    });
+  });
+
   it('falls back to the userId when users.info errors', async () => {
     const client = makeWebClient({
       users: { info: jest.fn().mockRejectedValue(new Error('not found')) },
@@ -87,6 +408,36 @@ describe('Slack client wrappers', () => {
     expect(await getUserDisplayName(client, 'U3')).toBe('U3');
   });
 
+  it('resolves many names with bounded concurrency and degrades failed lookups', async () => {
+    let inFlight = 0;
+    let maxInFlight = 0;
+    const info = jest.fn().mockImplementation(async ({ user }: { user: string }) => {
+      inFlight += 1;
+      maxInFlight = Math.max(maxInFlight, inFlight);
+      await new Promise((resolve) => setTimeout(resolve, 1));
+      inFlight -= 1;
+      if (user.startsWith('URATE')) {
+        throw { code: 'slack_webapi_rate_limited_error', retryAfter: 30 };
+      }
+      if (user === 'UGONE') {
+        throw { data: { error: 'user_not_found' } };
+      }
+      return { user: { profile: { real_name: `Name ${user}` } } };
+    });
+    const client = makeWebClient({ users: { info } });
+    const ids = ['U1', 'URATE1', 'U2', 'URATE2', 'UGONE', 'URATE3', 'U3', 'U1'];
+
+    const names = await getUserDisplayNames(client, ids, { concurrency: 2 });
+
+    expect(info).toHaveBeenCalledTimes(7);
+    expect(maxInFlight).toBe(2);
+    expect(names.get('U1')).toBe('Name U1');
+    expect(names.get('U3')).toBe('Name U3');
+    expect(names.get('URATE1')).toBe('URATE1');
+    expect(names.get('URATE3')).toBe('URATE3');
+    expect(names.get('UGONE')).toBeNull();
+  });
+
   it('returns the channel ID when conversations.info fails', async () => {
     const client = makeWebClient({
       conversations: { info: jest.fn().mockRejectedValue(new Error('no')) },
@@ -94,6 +445,134 @@ describe('Slack client wrappers', () => {
     expect(await getChannelName(client, 'C123')).toBe('C123');
   });
 
+  it('names a group DM after its members instead of its generated mpdm- name', async () => {
+    const info = jest.fn().mockImplementation(async ({ user }: { user: string }) =>
+      user === 'UGONE'
+        ? { user: { profile: {} } }
+        : { user: { profile: { real_name: `Name ${user}` } } }
+    );
+    const client = makeWebClient({
+      conversations: {
+        info: jest.fn().mockResolvedValue({
+          channel: { id: 'G123', name: 'mpdm-ana--bo--gone-1', is_mpim: true },
+        }),
+        members: jest.fn().mockResolvedValue({ members: ['U1', 'U2', 'UGONE'] }),
+      },
+      users: { info },
+    });
+    expect(await getChannelName(client, 'G123')).toBe('Name U1, Name U2');
+  });
+
+  it('falls back to the channel ID when a group DM's members cannot be listed', async () => {
+    const client = makeWebClient({
+      conversations: {
+        info: jest.fn().mockResolvedValue({ channel: { id: 'G123', is_mpim: true } }),
+        members: jest.fn().mockRejectedValue(new Error('missing_scope')),
+      },
+    });
+    expect(await getChannelName(client, 'G123')).toBe('G123');
+  });
+
+  describe('channel name cache', () => {
+    beforeEach(() => resetChannelNameCacheForTests());
+    afterEach(() => resetChannelNameCacheForTests());
+
+    const pagedList = () =>
+      jest
+        .fn()
+        .mockResolvedValueOnce({
+          channels: [
+            { id: 'C1', name: 'general' },
+            { id: 'C2', name: 'eng' },
+          ],
+          response_metadata: { next_cursor: 'page2' },
+        })
+        .mockResolvedValueOnce({
+          channels: [{ id: 'C3', name: 'random' }, { id: 'C4' }],
+          response_metadata: { next_cursor: '' },
+        });
+
+    it('follows conversations.list cursors into one id-to-name map', async () => {
+      const list = pagedList();
+      const client = makeWebClient({ conversations: { list } });
+
+      const names = await listChannelNames(client);
+
+      expect([...names]).toEqual([
+        ['C1', 'general'],
+        ['C2', 'eng'],
+        ['C3', 'random'],
+      ]);
+      expect(list).toHaveBeenCalledTimes(2);
+      expect(list).toHaveBeenNthCalledWith(1, {
+        types: 'public_channel,private_channel',
+        exclude_archived: true,
+        limit: 1000,
+      });
+      expect(list).toHaveBeenNthCalledWith(2, expect.objectContaining({ cursor: 'page2' }));
+    });
+
+    it('answers from the listed names and only asks conversations.info on a miss', async () => {
+      const list = pagedList();
+      const info = jest.fn().mockResolvedValue({ channel: { id: 'C9', name: 'new-channel' } });
+      const client = makeWebClient({ token: 'xoxb-1', conversations: { list, info } });
+
+      const names = await getChannelNames(client, ['C1', 'C3', 'C9']);
+      expect(await getChannelNames(client, ['C1'])).toEqual(new Map([['C1', 'general']]));
+
+      expect([...names]).toEqual([
+        ['C1', 'general'],
+        ['C3', 'random'],
+        ['C9', 'new-channel'],
+      ]);
+      expect(list).toHaveBeenCalledTimes(2);
+      expect(info).toHaveBeenCalledTimes(1);
+      expect(info).toHaveBeenCalledWith({ channel: 'C9' });
+    });
+
+    it('leaves single lookups on conversations.info', async () => {
+      const list = pagedList();
+      const info = jest.fn().mockResolvedValue({ channel: { id: 'C1', name: 'general' } });
+      const client = makeWebClient({ token: 'xoxb-1', conversations: { list, info } });
+
+      expect(await getChannelName(client, 'C1')).toBe('general');
+      expect(list).not.toHaveBeenCalled();
+    });
+
+    it('keeps a separate snapshot per token', async () => {
+      const first = makeWebClient({
+        token: 'xoxb-1',
+        conversations: { list: jest.fn().mockResolvedValue({ channels: [{ id: 'C1', name: 'one' }] }) },
+      });
+      const second = makeWebClient({
+        token: 'xoxb-2',
+        conversations: { list: jest.fn().mockResolvedValue({ channels: [{ id: 'C1', name: 'two' }] }) },
+      });
+
+      expect(await getChannelNames(first, ['C1'])).toEqual(new Map([['C1', 'one']]));
+      expect(await getChannelNames(second, ['C1'])).toEqual(new Map([['C1', 'two']]));
+    });
+
+    it('lists again once the snapshot is older than the TTL', async () => {
+      const now = jest.spyOn(Date, 'now').mockReturnValue(1_000_000);
+      try {
+        const list = jest.fn().mockResolvedValue({ channels: [{ id: 'C1', name: 'general' }] });
+        const client = makeWebClient({ token: 'xoxb-1', conversations: { list } });
+
+        await getChannelNames(client, ['C1']);
+        now.mockReturnValue(1_000_000 + CHANNEL_NAME_CACHE_TTL_MS - 1);
+        await getChannelNames(client, ['C1']);
+        expect(list).toHaveBeenCalledTimes(1);
+
+        now.mockReturnValue(1_000_000 + CHANNEL_NAME_CACHE_TTL_MS);
+        await getChannelNames(client, ['C1']);
+        expect(list).toHaveBeenCalledTimes(2);
+      } finally {
+        now.mockRestore();
+      }
+    });
+  });
+
   it('returns null permalink on error', async () => {
     const client = makeWebClient({
       chat: { getPermalink: jest.fn().mockRejectedValue(new Error('boom')) },
@@ -101,12 +580,192 @@ describe('Slack client wrappers', () => {
     expect(await getMessagePermalink(client, 'C1', '1.1')).toBeNull();
   });
 
-  it('startStream returns the streaming ts', async () => {
-    const client = makeWebClient({
-      chat: { startStream: jest.fn().mockResolvedValue({ ok: true, ts: '999.1' }) },
+  it('getMessagePermalinks keeps partial results when some lookups fail', async () => {
+    const getPermalink = jest.fn(async ({ message_ts }: { message_ts: string }) => {
+      if (message_ts === '2.0') {
+        throw new Error('message_not_found');
+      }
+      return { permalink: `https://slack/p/${message_ts}` };
+    });
+    const client = makeWebClient({ chat: { getPermalink } });
+
+    expect(await getMessagePermalinks(client, 'C1', ['1.0', '2.0', '3.0'])).toEqual([
+      { ts: '1.0', permalink: 'https://slack/p/1.0' },
+      { ts: '2.0', permalink: null },
+      { ts: '3.0', permalink: 'https://slack/p/3.0' },
+    ]);
+  });
+
+  it('getMessagePermalinks never runs more lookups than the concurrency', async () => {
+    let inFlight = 0;
+    let peak = 0;
+    const getPermalink = jest.fn(async () => {
+      inFlight += 1;
+      peak = Math.max(peak, inFlight);
+      await new Promise((resolve) => setTimeout(resolve, 5));
+      inFlight -= 1;
+      return { permalink: 'https://slack/p' };
+    });
+    const client = makeWebClient({ chat: { getPermalink } });
+
+    await getMessagePermalinks(client, 'C1', ['1', '2', '3', '4', '5', '6'], { concurrency: 2 });
+
+    expect(getPermalink).toHaveBeenCalledTimes(6);
+    expect(peak).toBe(2);
+  });
+
+  it('getMessagePermalinks returns what it has when the shared timeout runs out', async () => {
+    const getPermalink = jest.fn(({ message_ts }: { message_ts: string }) =>
+      message_ts === 'slow'
+        ? new Promise(() => undefined)
+        : Promise.resolve({ permalink: `https://slack/p/${message_ts}` })
+    );
+    const client = makeWebClient({ chat: { getPermalink } });
+
+    const results = await getMessagePermalinks(client, 'C1', ['fast', 'slow'], {
+      concurrency: 2,
+      timeoutMs: 20,
+    });
+
+    expect(results).toEqual([
+      { ts: 'fast', permalink: 'https://slack/p/fast' },
+      { ts: 'slow', permalink: null },
+    ]);
+  });
+
+  it('adds a link bookmark when the channel has none with that title', async () => {
+    const bookmarks = {
+      list: jest.fn().mockResolvedValue({
+        bookmarks: [{ id: 'Bk1', type: 'link', title: 'Runbook', link: 'https://wiki' }],
+      }),
+      add: jest.fn().mockResolvedValue({ ok: true }),
+      edit: jest.fn(),
+    };
+    const client = makeWebClient({ bookmarks });
+
+    await expect(
+      addBookmark(client, { channelId: 'C1', title: 'Latest summary: #eng', link: 'https://slack/p/1' })
+    ).resolves.toBe('added');
+    expect(bookmarks.list).toHaveBeenCalledWith({ channel_id: 'C1' });
+    expect(bookmarks.add).toHaveBeenCalledWith({
+      channel_id: 'C1',
+      title: 'Latest summary: #eng',
+      type: 'link',
+      link: 'https://slack/p/1',
+    });
+    expect(bookmarks.edit).not.toHaveBeenCalled();
+  });
+
+  it('repoints an existing bookmark with the same title instead of adding another', async () => {
+    const bookmarks = {
+      list: jest.fn().mockResolvedValue({
+        bookmarks: [{ id: 'Bk2', type: 'link', title: 'Latest summary: #eng', link: 'https://slack/p/0' }],
+      }),
+      add: jest.fn(),
+      edit: jest.fn().mockResolvedValue({ ok: true }),
+    };
+    const client = makeWebClient({ bookmarks });
+
+    await expect(
+      addBookmark(client, { channelId: 'C1', title: 'Latest summary: #eng', link: 'https://slack/p/2' })
+    ).resolves.toBe('updated');
+    expect(bookmarks.edit).toHaveBeenCalledWith({
+      channel_id: 'C1',
+      bookmark_id: 'Bk2',
+      link: 'https://slack/p/2',
+    });
+    expect(bookmarks.add).not.toHaveBeenCalled();
+  });
+
+  describe('openModal', () => {
+    const view = { type: 'modal', title: { type: 'plain_text', text: 'x' }, blocks: [] } as const;
+    const rateLimited = (retryAfter: number) =>
+      Object.assign(new Error('rate limited'), {
+        code: 'slack_webapi_rate_limited_error',
+        retryAfter,
+      });
+
+    it('retries a 429 whose Retry-After fits inside the trigger window', async () => {
+      const open = jest
+        .fn()
+        .mockRejectedValueOnce(rateLimited(1))
+        .mockResolvedValueOnce({ ok: true });
+      const sleep = jest.fn().mockResolvedValue(undefined);
+      const client = makeWebClient({ views: { open } });
+
+      await openModal(client, { triggerId: 'T1', view }, { sleep });
+
+      expect(sleep).toHaveBeenCalledWith(1000);
+      expect(open).toHaveBeenCalledTimes(2);
+      expect(open).toHaveBeenLastCalledWith({ trigger_id: 'T1', view });
     });
+
+    it('gives up on a 429 whose Retry-After outlives the trigger_id', async () => {
+      const open = jest.fn().mockRejectedValue(rateLimited(30));
+      const sleep = jest.fn().mockResolvedValue(undefined);
+      const client = makeWebClient({ views: { open } });
+
+      await expect(openModal(client, { triggerId: 'T1', view }, { sleep })).rejects.toBeInstanceOf(
+        ModalOpenError
+      );
+      expect(open).toHaveBeenCalledTimes(1);
+      expect(sleep).not.toHaveBeenCalled();
+    });
+
+    it('abandons a call still pending when the budget runs out', async () => {
+      const open = jest.fn(() => new Promise(() => undefined));
+      const client = makeWebClient({ views: { open } });
+
+      await expect(
+        openModal(client, { triggerId: 'T1', view }, { budgetMs: 20 })
+      ).rejects.toBeInstanceOf(ModalOpenError);
+    });
+
+    it('propagates errors that are not rate limits', async () => {
+      const open = jest.fn().mockRejectedValue(new Error('An API error occurred: invalid_arguments'));
+      const client = makeWebClient({ views: { open } });
+
+      await expect(openModal(client, { triggerId: 'T1', view })).rejects.toThrow('invalid_arguments');
+      expect(open).toHaveBeenCalledTimes(1);
+    });
+  });
+
+  it('buildAssistantStatusPayload maps onto setStatus params', () => {
+    expect(buildAssistantStatusPayload('D123', '1700000000.000100', 'Writing summary…')).toEqual({
+      channel_id: 'D123',
+      thread_ts: '1700000000.000100',
+      status: 'Writing summary…',
+    });
+  });
+
+  it('setAssistantStatus ignores errors for non-assistant channels', async () => {
+    const setStatus = jest.fn().mockRejectedValue(new Error('channel_not_found'));
+    const client = { assistant: { threads: { setStatus } } } as unknown as WebClient;
+    await expect(setAssistantStatus(client, 'C123', '1.0', 'Reading messages…')).resolves.toBe(false);
+    expect(setStatus).toHaveBeenCalledWith({ channel_id: 'C123', thread_ts: '1.0', status: 'Reading messages…' });
+  });
+
+  it('builds Workflow Builder completion payloads', () => {
+    expect(buildFunctionCompleteSuccessPayload('Fx123', { summary: 'done' })).toEqual({
+      function_execution_id: 'Fx123',
+      outputs: { summary: 'done' },
+    });
+    expect(buildFunctionCompleteErrorPayload('Fx123', 'nope')).toEqual({
+      function_execution_id: 'Fx123',
+      error: 'nope',
+    });
+  });
+
+  it('startStream returns the streaming ts and marks the stream open', async () => {
+    const start = jest.fn().mockResolvedValue({ ok: true, ts: '999.1' });
+    const client = makeWebClient({ chat: { startStream: start } });
     const ts = await startStream(client, { channel: 'D1', threadTs: '170.0' });
     expect(ts).toBe('999.1');
+    expect(start).toHaveBeenCalledWith({
+      channel: 'D1',
+      thread_ts: '170.0',
+      metadata: STREAM_OPEN_METADATA,
+    });
   });
 
   it('startStream throws when Slack omits ts', async () => {
@@ -218,4 +877,121 @@ describe('Slack client wrappers', () => {
     expect(isMessageNotInStreamingStateError(new Error('other'))).toBe(false);
     expect(isMessageNotInStreamingStateError(null)).toBe(false);
   });
+
+  it('hasSlackErrorCode matches data.error or the message text', () => {
+    expect(hasSlackErrorCode({ data: { error: 'channel_not_found' } }, 'channel_not_found')).toBe(true);
+    expect(hasSlackErrorCode(new Error('An API error occurred: ratelimited'), 'ratelimited')).toBe(true);
+    expect(hasSlackErrorCode({ data: { error: 'other' } }, 'ratelimited')).toBe(false);
+    expect(hasSlackErrorCode(undefined, 'ratelimited')).toBe(false);
+  });
+
+  it('isSlackTimeoutError recognises fetch, socket, and wrapped Web API timeouts', () => {
+    const timeout = Object.assign(new Error('The operation was aborted due to timeout'), {
+      name: 'TimeoutError',
+    });
+    expect(isSlackTimeoutError(timeout)).toBe(true);
+    expect(isSlackTimeoutError(Object.assign(new Error('socket hang up'), { code: 'ETIMEDOUT' }))).toBe(true);
+    expect(
+      isSlackTimeoutError({
+        code: 'slack_webapi_request_error',
+        original: Object.assign(new Error('timeout of 0ms exceeded'), { code: 'ECONNABORTED' }),
+      })
+    ).toBe(true);
+    expect(isSlackTimeoutError({ code: 'slack_webapi_platform_error', data: { error: 'msg_too_long' } })).toBe(false);
+    expect(isSlackTimeoutError(new Error('boom'))).toBe(false);
+    expect(isSlackTimeoutError(null)).toBe(false);
+  });
+
+  it('isTransientSlackError separates retryable failures from permanent ones', () => {
+    expect(isTransientSlackError({ data: { error: 'internal_error' } })).toBe(true);
+    expect(isTransientSlackError(new Error('An API error occurred: ratelimited'))).toBe(true);
+    expect(isTransientSlackError({ code: 'slack_webapi_request_error', original: new Error('ECONNRESET') })).toBe(
+      true
+    );
+    expect(isTransientSlackError(Object.assign(new Error('socket hang up'), { code: 'ETIMEDOUT' }))).toBe(true);
+    expect(isTransientSlackError({ data: { error: 'channel_not_found' } })).toBe(false);
+    expect(isTransientSlackError(null)).toBe(false);
+  });
+
+  it('isStreamingUnavailableError matches workspace-level streaming errors only', () => {
+    expect(isStreamingUnavailableError({ data: { error: 'method_not_supported' } })).toBe(true);
+    expect(isStreamingUnavailableError(new Error('An API error occurred: not_allowed_token_type'))).toBe(
+      true
+    );
+    expect(isStreamingUnavailableError({ data: { error: 'ratelimited' } })).toBe(false);
+    expect(isStreamingUnavailableError(null)).toBe(false);
+  });
+
+  it('isMsgTooLongError handles WebApiError shape', () => {
+    expect(isMsgTooLongError({ data: { error: 'msg_too_long' } })).toBe(true);
+    expect(isMsgTooLongError(new Error('An API error occurred: msg_too_long'))).toBe(true);
+    expect(isMsgTooLongError(new Error('other'))).toBe(false);
+    expect(isMsgTooLongError(null)).toBe(false);
+  });
+
+  it('buildMentionSearchQuery spells out the requester mention', () => {
+    expect(buildMentionSearchQuery('U123')).toBe('<@U123>');
+  });
+
+  it('searchMessages sends a newest-first query and flattens matches', async () => {
+    const messages = jest.fn().mockResolvedValue({
+      messages: {
+        matches: [
+          {
+            ts: '1.1',
+            user: 'U2',
+            text: 'hey <@U1>',
+            permalink: 'https://acme.slack.com/archives/C1/p11',
+            channel: { id: 'C1', name: 'general' },
+          },
+        ],
+        paging: { page: 1, pages: 1 },
+      },
+    });
+    const client = makeWebClient({ search: { messages } });
+    const matches = await searchMessages(client, '<@U1>', 25);
+    expect(messages).toHaveBeenCalledWith({
+      query: '<@U1>',
+      count: 25,
+      page: 1,
+      sort: 'timestamp',
+      sort_dir: 'desc',
+    });
+    expect(matches).toEqual([
+      {
+        ts: '1.1',
+        user: 'U2',
+        text: 'hey <@U1>',
+        channelId: 'C1',
+        channelName: 'general',
+        permalink: 'https://acme.slack.com/archives/C1/p11',
+      },
+    ]);
+  });
+
+  it('searchMessages paginates until maxResults is reached', async () => {
+    const page = (n: number) => ({
+      messages: {
+        matches: Array.from({ length: 100 }, (_, i) => ({ ts: `${n}.${i}`, text: 'x' })),
+        paging: { page: n, pages: 3 },
+      },
+    });
+    const messages = jest
+      .fn()
+      .mockResolvedValueOnce(page(1))
+      .mockResolvedValueOnce(page(2))
+      .mockResolvedValueOnce(page(3));
+    const client = makeWebClient({ search: { messages } });
+    const matches = await searchMessages(client, '<@U1>', 150);
+    expect(matches).toHaveLength(150);
+    expect(messages).toHaveBeenCalledTimes(2);
+    expect(messages.mock.calls[1][0]).toMatchObject({ count: 100, page: 2 });
+  });
+
+  it('searchMessages scopes the search to a Grid workspace when given a team id', async () => {
+    const messages = jest.fn().mockResolvedValue({ messages: { matches: [] } });
+    const client = makeWebClient({ search: { messages } });
+    await searchMessages(client, '<@U1>', 25, 'T0123ABCD');
+    expect(messages.mock.calls[0][0]).toMatchObject({ team_id: 'T0123ABCD' });
+  });
 });
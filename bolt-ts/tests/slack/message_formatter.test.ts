@@ -0,0 +1,85 @@
+import { numberSummarySections, toMrkdwn } from '../../src/slack/message_formatter';
+
+describe('toMrkdwn', () => {
+  it('converts Markdown bold and underscore italics', () => {
+    expect(toMrkdwn('**Summary**')).toBe('*Summary*');
+    expect(toMrkdwn('this is __important__ stuff')).toBe('this is _important_ stuff');
+    expect(toMrkdwn('**a** and **b**')).toBe('*a* and *b*');
+  });
+
+  it('handles nested and combined emphasis', () => {
+    expect(toMrkdwn('**bold with __italic__ inside**')).toBe('*bold with _italic_ inside*');
+    expect(toMrkdwn('***both***')).toBe('*_both_*');
+  });
+
+  it('leaves existing mrkdwn and stray markers alone', () => {
+    expect(toMrkdwn('*Summary*\n- _note_')).toBe('*Summary*\n- _note_');
+    expect(toMrkdwn('2 ** 3 and snake__case')).toBe('2 ** 3 and snake__case');
+  });
+
+  it('leaves inline code and code fences untouched', () => {
+    expect(toMrkdwn('run `a **b** c` now')).toBe('run `a **b** c` now');
+    const fenced = '```\nconst x = **y**;\n__init__()\n```';
+    expect(toMrkdwn(`**Code**\n${fenced}`)).toBe(`*Code*\n${fenced}`);
+  });
+
+  it('leaves Slack links and mentions intact', () => {
+    expect(toMrkdwn('see <https://x.test/__a__|**doc**> **now**')).toBe(
+      'see <https://x.test/__a__|**doc**> *now*'
+    );
+  });
+});
+
+describe('numberSummarySections', () => {
+  const summary = [
+    '*Summary from <#C1>* · Oct 3',
+    '',
+    '*Summary*',
+    '- shipped *v2*',
+    '',
+    '**Links shared**',
+    '- none',
+    '```',
+    '*not a header*',
+    '```',
+    '*Receipts*',
+    '- <https://slack.com/p1|alice>',
+  ].join('\n');
+
+  it('renders canvas headings under a contents list', () => {
+    expect(numberSummarySections(summary, 'canvas')).toBe(
+      [
+        '**Contents**',
+        '1. Summary',
+        '2. Links shared',
+        '3. Receipts',
+        '',
+        '*Summary from <#C1>* · Oct 3',
+        '',
+        '## 1. Summary',
+        '- shipped *v2*',
+        '',
+        '## 2. Links shared',
+        '- none',
+        '```',
+        '*not a header*',
+        '```',
+        '## 3. Receipts',
+        '- <https://slack.com/p1|alice>',
+      ].join('\n')
+    );
+  });
+
+  it('keeps numbered headers as bold mrkdwn for Slack', () => {
+    const out = numberSummarySections(summary, 'mrkdwn');
+    expect(out).toContain('*1. Summary*\n- shipped *v2*');
+    expect(out).toContain('*2. Links shared*');
+    expect(out).toContain('*3. Receipts*');
+    expect(out).not.toContain('Contents');
+    expect(out.startsWith('*Summary from <#C1>* · Oct 3')).toBe(true);
+  });
+
+  it('leaves text without section headers unchanged', () => {
+    expect(numberSummarySections('just *one* line', 'canvas')).toBe('just *one* line');
+  });
+});
@@ -0,0 +1,69 @@
+import { createServer, type Server } from 'http';
+import type { AddressInfo } from 'net';
+import { LogLevel, WebClient } from '@slack/web-api';
+import { retryPolicyFromConfig, slackClientOptions, toRetryOptions } from '../../src/slack/retry';
+
+describe('toRetryOptions', () => {
+  it('maps the policy onto exponential backoff with jitter', () => {
+    expect(toRetryOptions({ maxRetries: 5, baseDelayMs: 100, maxDelayMs: 5_000 })).toEqual({
+      retries: 5,
+      factor: 2,
+      minTimeout: 100,
+      maxTimeout: 5_000,
+      randomize: true,
+    });
+  });
+
+  it('never caps a delay below the base delay', () => {
+    expect(toRetryOptions({ maxRetries: 1, baseDelayMs: 500, maxDelayMs: 100 }).maxTimeout).toBe(500);
+  });
+
+  it('reads the policy from config', () => {
+    expect(
+      retryPolicyFromConfig({ slackMaxRetries: 0, slackRetryBaseDelayMs: 50, slackRetryMaxDelayMs: 200 })
+    ).toEqual({ maxRetries: 0, baseDelayMs: 50, maxDelayMs: 200 });
+  });
+});
+
+describe('slackClientOptions', () => {
+  // Answers every call with a retryable 429 (no wait) and counts attempts.
+  let server: Server;
+  let attempts: number;
+  let apiUrl: string;
+
+  beforeAll(async () => {
+    server = createServer((req, res) => {
+      attempts += 1;
+      req.resume();
+      res.writeHead(429, { 'Retry-After': '0', 'Content-Type': 'application/json' });
+      res.end(JSON.stringify({ ok: false, error: 'ratelimited' }));
+    });
+    await new Promise<void>((resolve) => server.listen(0, '127.0.0.1', resolve));
+    apiUrl = `http://127.0.0.1:${(server.address() as AddressInfo).port}/api/`;
+  });
+
+  beforeEach(() => {
+    attempts = 0;
+  });
+
+  afterAll(async () => {
+    await new Promise((resolve) => server.close(resolve));
+  });
+
+  const clientWith = (slackMaxRetries: number): WebClient =>
+    new WebClient('xoxb-test', {
+      ...slackClientOptions({ slackMaxRetries, slackRetryBaseDelayMs: 1, slackRetryMaxDelayMs: 1 }),
+      slackApiUrl: apiUrl,
+      logLevel: LogLevel.ERROR,
+    });
+
+  it('makes a single attempt with no retries when maxRetries is 0', async () => {
+    await expect(clientWith(0).auth.test()).rejects.toThrow();
+    expect(attempts).toBe(1);
+  });
+
+  it('retries up to maxRetries times after the first attempt', async () => {
+    await expect(clientWith(2).auth.test()).rejects.toThrow();
+    expect(attempts).toBe(3);
+  });
+});
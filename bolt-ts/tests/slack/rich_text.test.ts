@@ -0,0 +1,64 @@
+import { extractTextFromBlocks } from '../../src/slack/rich_text';
+
+const section = (...elements: unknown[]) => ({ type: 'rich_text_section', elements });
+const text = (t: string) => ({ type: 'text', text: t });
+
+describe('extractTextFromBlocks', () => {
+  it('returns an empty string for missing or non-array blocks', () => {
+    expect(extractTextFromBlocks(undefined)).toBe('');
+    expect(extractTextFromBlocks({ type: 'rich_text' })).toBe('');
+  });
+
+  it('renders sections with links, mentions, and emoji', () => {
+    const blocks = [
+      {
+        type: 'rich_text',
+        elements: [
+          section(
+            text('ship it '),
+            { type: 'user', user_id: 'U123' },
+            text(' see '),
+            { type: 'link', url: 'https://example.com', text: 'docs' },
+            text(' '),
+            { type: 'emoji', name: 'rocket' }
+          ),
+        ],
+      },
+    ];
+    expect(extractTextFromBlocks(blocks)).toBe(
+      'ship it <@U123> see <https://example.com|docs> :rocket:'
+    );
+  });
+
+  it('renders nested bullet and ordered lists using indent', () => {
+    const blocks = [
+      {
+        type: 'rich_text',
+        elements: [
+          section(text('Plan:')),
+          { type: 'rich_text_list', style: 'ordered', indent: 0, elements: [section(text('build')), section(text('test'))] },
+          { type: 'rich_text_list', style: 'bullet', indent: 1, elements: [section(text('unit'))] },
+        ],
+      },
+    ];
+    expect(extractTextFromBlocks(blocks)).toBe('Plan:\n1. build\n2. test\n  - unit');
+  });
+
+  it('renders code blocks and quotes', () => {
+    const blocks = [
+      {
+        type: 'rich_text',
+        elements: [
+          { type: 'rich_text_preformatted', elements: [text('npm test')] },
+          { type: 'rich_text_quote', elements: [text('they said friday')] },
+        ],
+      },
+    ];
+    expect(extractTextFromBlocks(blocks)).toBe('```\nnpm test\n```\n> they said friday');
+  });
+
+  it('falls back to mrkdwn section text for non-rich_text blocks', () => {
+    const blocks = [{ type: 'section', text: { type: 'mrkdwn', text: '*Deploy* done' } }];
+    expect(extractTextFromBlocks(blocks)).toBe('*Deploy* done');
+  });
+});
@@ -19,4 +19,17 @@ describe('sanitizeGeneratedSlackMrkdwn', () => {
       'Hello `<@W987654321>`'
     );
   });
+
+  it('leaves user mentions live when allowed', () => {
+    expect(
+      sanitizeGeneratedSlackMrkdwn('Thanks <@U123ABC456>', { allowUserMentions: true })
+    ).toBe('Thanks <@U123ABC456>');
+  });
+
+  it('still neutralises broadcast and user-group mentions when user mentions are allowed', () => {
+    const text = '<!here> <!everyone> <!subteam^S123|ops> <@U123ABC456>';
+    expect(sanitizeGeneratedSlackMrkdwn(text, { allowUserMentions: true })).toBe(
+      '`<!here>` `<!everyone>` `<!subteam^S123|ops>` <@U123ABC456>'
+    );
+  });
 });
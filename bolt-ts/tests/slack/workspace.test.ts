@@ -0,0 +1,55 @@
+import { extractWorkspaceIds } from '../../src/slack/workspace';
+
+describe('extractWorkspaceIds', () => {
+  it('reads team_id and enterprise_id from an Events API envelope', () => {
+    const envelope = {
+      token: 'x',
+      team_id: 'T0123ABCD',
+      enterprise_id: 'E0123ABCD',
+      api_app_id: 'A0123ABCD',
+      event: { type: 'message', channel: 'D0123ABCD', user: 'U0123ABCD', text: 'summarize' },
+      type: 'event_callback',
+      authorizations: [{ enterprise_id: 'E0123ABCD', team_id: 'T0123ABCD', user_id: 'U0BOT' }],
+    };
+    expect(extractWorkspaceIds(envelope)).toEqual({
+      teamId: 'T0123ABCD',
+      enterpriseId: 'E0123ABCD',
+    });
+  });
+
+  it('reads the team and enterprise objects from an interactive payload', () => {
+    const blockActions = {
+      type: 'block_actions',
+      user: { id: 'U0123ABCD', team_id: 'T0123ABCD' },
+      team: { id: 'T0123ABCD', domain: 'acme', enterprise_id: 'E0123ABCD' },
+      enterprise: { id: 'E0123ABCD', name: 'Acme' },
+      actions: [{ action_id: 'share_summary' }],
+    };
+    expect(extractWorkspaceIds(blockActions)).toEqual({
+      teamId: 'T0123ABCD',
+      enterpriseId: 'E0123ABCD',
+    });
+  });
+
+  it("falls back to the user's workspace when an org-wide install sends team: null", () => {
+    const viewSubmission = {
+      type: 'view_submission',
+      team: null,
+      enterprise: { id: 'E0123ABCD', name: 'Acme' },
+      user: { id: 'U0123ABCD', team_id: 'T0456WXYZ' },
+    };
+    expect(extractWorkspaceIds(viewSubmission)).toEqual({
+      teamId: 'T0456WXYZ',
+      enterpriseId: 'E0123ABCD',
+    });
+  });
+
+  it('returns nulls for non-Grid or missing payloads', () => {
+    expect(extractWorkspaceIds({ team_id: 'T0123ABCD', enterprise_id: null })).toEqual({
+      teamId: 'T0123ABCD',
+      enterpriseId: null,
+    });
+    expect(extractWorkspaceIds(undefined)).toEqual({ teamId: null, enterpriseId: null });
+    expect(extractWorkspaceIds({ team_id: 'not an id' })).toEqual({ teamId: null, enterpriseId: null });
+  });
+});
@@ -0,0 +1,64 @@
+import { SocketModeReceiver } from '@slack/bolt';
+import type { AppConfig } from '../src/config';
+import { createSocketModeReceiver } from '../src/socket';
+
+function makeConfig(overrides: Partial<AppConfig> = {}): AppConfig {
+  return {
+    slackBotToken: 'xoxb',
+    slackSigningSecret: 'sig',
+    slackUserToken: null,
+    slackAppToken: null,
+    anthropicApiKey: 'sk-ant',
+    anthropicModel: 'claude-test',
+    anthropicMaxOutputTokens: 4096,
+    anthropicBaseUrl: null,
+    anthropicTemperature: null,
+    anthropicStyleTemperature: null,
+    anthropicReasoningEffort: null,
+    enableStreaming: true,
+    streamMaxChunkChars: 4000,
+    streamMinAppendIntervalMs: 0,
+    streamStallTimeoutMs: 120_000,
+    streamMaxBufferBytes: 1_048_576,
+    staleStreamMaxAgeSecs: 900,
+    enableReceipts: true,
+    includeAttachmentText: true,
+    disableImages: false,
+    citeInline: false,
+    summarySections: ['Summary', 'Links shared', 'Image highlights', 'Receipts'],
+    detectSummaryLanguage: false,
+    postAsUser: false,
+    unfurlSummaryLinks: false,
+    canvasToc: false,
+    summaryFooter: null,
+    maxRequestBodyBytes: 1_048_576,
+    imageDownloadConcurrency: 3,
+    inlineImageMaxBytes: 4_194_304,
+    permalinkConcurrency: 4,
+    maxConcurrentSummaries: 4,
+    hardMessageCap: 1000,
+    ignoredUserIds: [],
+    defaultTimezone: 'UTC',
+    maxCustomStyleChars: 500,
+    noMessagesMessage: 'No messages found to summarize.',
+    systemPromptOverride: null,
+    assistantName: 'TLDR-bot',
+    taskDeadlineSecs: 840,
+    slackMaxRetries: 5,
+    slackRetryBaseDelayMs: 100,
+    slackRetryMaxDelayMs: 5_000,
+    deliveryMaxRetries: 2,
+    ...overrides,
+  };
+}
+
+describe('createSocketModeReceiver', () => {
+  it('requires an app-level token', () => {
+    expect(() => createSocketModeReceiver(makeConfig())).toThrow(/SLACK_APP_TOKEN/);
+  });
+
+  it('builds a Socket Mode receiver without connecting', () => {
+    const receiver = createSocketModeReceiver(makeConfig({ slackAppToken: 'xapp-1-test' }));
+    expect(receiver).toBeInstanceOf(SocketModeReceiver);
+  });
+});
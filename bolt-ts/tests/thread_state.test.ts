@@ -24,6 +24,7 @@ describe('thread_state', () => {
       const meta = buildThreadStateMetadata({
         viewingChannelId: null,
         customStyle: null,
+        tone: null,
         defaultMessageCount: null,
       });
       expect(meta.event_type).toBe(TLDR_THREAD_STATE_EVENT_TYPE);
@@ -34,6 +35,7 @@ describe('thread_state', () => {
       const meta = buildThreadStateMetadata({
         viewingChannelId: 'C123',
         customStyle: 'write as a haiku',
+        tone: null,
         defaultMessageCount: null,
       });
       expect(meta.event_payload).toEqual({
@@ -43,10 +45,21 @@ describe('thread_state', () => {
       });
     });
 
+    it('should include the tone preset when set', () => {
+      const meta = buildThreadStateMetadata({
+        viewingChannelId: null,
+        customStyle: null,
+        tone: 'executive',
+        defaultMessageCount: null,
+      });
+      expect(meta.event_payload).toEqual({ v: 1, tone: 'executive' });
+    });
+
     it('should include default message count when present', () => {
       const meta = buildThreadStateMetadata({
         viewingChannelId: null,
         customStyle: null,
+        tone: null,
         defaultMessageCount: 25,
       });
       expect(meta.event_payload).toEqual({
@@ -61,11 +74,13 @@ describe('thread_state', () => {
       expect(parseThreadContextFromMetadata(null)).toEqual({
         viewingChannelId: null,
         customStyle: null,
+        tone: null,
         defaultMessageCount: null,
       });
       expect(parseThreadContextFromMetadata('nope')).toEqual({
         viewingChannelId: null,
         customStyle: null,
+        tone: null,
         defaultMessageCount: null,
       });
     });
@@ -80,10 +95,16 @@ describe('thread_state', () => {
       ).toEqual({
         viewingChannelId: 'C999999999',
         customStyle: 'be funny',
+        tone: null,
         defaultMessageCount: null,
       });
     });
 
+    it('should keep a preset tone and drop an unknown one', () => {
+      expect(parseThreadContextFromMetadata({ v: 1, tone: 'friendly' }).tone).toBe('friendly');
+      expect(parseThreadContextFromMetadata({ v: 1, tone: 'pirate' }).tone).toBeNull();
+    });
+
     it('should parse default_message_count when present', () => {
       expect(
         parseThreadContextFromMetadata({
@@ -93,6 +114,7 @@ describe('thread_state', () => {
       ).toEqual({
         viewingChannelId: null,
         customStyle: null,
+        tone: null,
         defaultMessageCount: 100,
       });
     });
@@ -107,6 +129,7 @@ describe('thread_state', () => {
       ).toEqual({
         viewingChannelId: null,
         customStyle: null,
+        tone: null,
         defaultMessageCount: null,
       });
     });
@@ -118,7 +141,7 @@ describe('thread_state', () => {
       setCachedThreadState({
         threadKey,
         stateMessageTs: '171.0002',
-        state: { viewingChannelId: 'C111111111', customStyle: 'x', defaultMessageCount: 25 },
+        state: { viewingChannelId: 'C111111111', customStyle: 'x', tone: null, defaultMessageCount: 25 },
       });
 
       type RepliesArgs = Parameters<SlackWebApiClient['conversations']['replies']>[0];
@@ -137,7 +160,7 @@ describe('thread_state', () => {
       expect(result).toEqual({
         thread_key: threadKey,
         state_message_ts: '171.0002',
-        state: { viewingChannelId: 'C111111111', customStyle: 'x', defaultMessageCount: 25 },
+        state: { viewingChannelId: 'C111111111', customStyle: 'x', tone: null, defaultMessageCount: 25 },
       });
       expect(replies).not.toHaveBeenCalled();
     });
@@ -186,7 +209,7 @@ describe('thread_state', () => {
       expect(result).toEqual({
         thread_key: makeThreadKey('D-FIND', '170.0000'),
         state_message_ts: '170.0003',
-        state: { viewingChannelId: 'C222222222', customStyle: 'new', defaultMessageCount: 75 },
+        state: { viewingChannelId: 'C222222222', customStyle: 'new', tone: null, defaultMessageCount: 75 },
       });
     });
   });
@@ -0,0 +1,41 @@
+import {
+  DEFAULT_TIMEZONE,
+  formatInTimeZone,
+  isValidTimeZone,
+  unknownTimeZoneMessage,
+} from '../src/time';
+
+describe('isValidTimeZone', () => {
+  it('accepts IANA zones and rejects anything else', () => {
+    expect(isValidTimeZone(DEFAULT_TIMEZONE)).toBe(true);
+    expect(isValidTimeZone('America/New_York')).toBe(true);
+    expect(isValidTimeZone('Mars/Olympus_Mons')).toBe(false);
+  });
+});
+
+describe('unknownTimeZoneMessage', () => {
+  it('names the rejected zone and suggests an IANA one', () => {
+    expect(unknownTimeZoneMessage('Mars/Olympus_Mons')).toBe(
+      'I don\'t recognise the time zone "Mars/Olympus_Mons". Use an IANA name like America/New_York.'
+    );
+  });
+});
+
+describe('formatInTimeZone', () => {
+  // 2026-10-15T02:30:00Z
+  const ts = '1792031400.000100';
+
+  it('renders the local calendar date of a Slack ts in each zone', () => {
+    expect(formatInTimeZone(ts, 'UTC')).toBe('Thu, Oct 15, 2026');
+    expect(formatInTimeZone(ts, 'America/Los_Angeles')).toBe('Wed, Oct 14, 2026');
+    expect(formatInTimeZone(ts, 'Asia/Tokyo')).toBe('Thu, Oct 15, 2026');
+  });
+
+  it('uses the DST offset in effect at that instant', () => {
+    // 2026-03-09T04:30:00Z is 00:30 EDT (UTC-4) on Mar 9; a fixed EST
+    // offset (UTC-5) would put it on Mar 8.
+    expect(formatInTimeZone(1773030600, 'America/New_York')).toBe('Mon, Mar 9, 2026');
+    // 2026-01-09T04:30:00Z is 23:30 EST on Jan 8.
+    expect(formatInTimeZone(1767933000, 'America/New_York')).toBe('Thu, Jan 8, 2026');
+  });
+});
@@ -0,0 +1,40 @@
+import { MAX_ATTACHMENT_TEXT_CHARS, extractAttachmentText } from '../../src/worker/attachments';
+
+describe('extractAttachmentText', () => {
+  it('pulls title and text from link unfurls', () => {
+    const msg = {
+      attachments: [
+        {
+          from_url: 'https://example.com/post',
+          service_name: 'Example Blog',
+          title: 'Why we rewrote the worker',
+          title_link: 'https://example.com/post',
+          text: 'A look at\nlatency wins.',
+          fallback: 'Example Blog: Why we rewrote the worker',
+        },
+        { title: 'Only a title' },
+        { text: 'Only text' },
+      ],
+    };
+    expect(extractAttachmentText(msg)).toEqual([
+      'Why we rewrote the worker — A look at latency wins.',
+      'Only a title',
+      'Only text',
+    ]);
+  });
+
+  it('falls back to fallback text and skips empty attachments', () => {
+    const msg = { attachments: [{ fallback: '[PR #42] Fix flaky test' }, { color: '#36a64f' }, null] };
+    expect(extractAttachmentText(msg)).toEqual(['[PR #42] Fix flaky test']);
+  });
+
+  it('clips long attachment text', () => {
+    const [line] = extractAttachmentText({ attachments: [{ text: 'x'.repeat(1000) }] });
+    expect([...line]).toHaveLength(MAX_ATTACHMENT_TEXT_CHARS);
+    expect(line.endsWith('...')).toBe(true);
+  });
+
+  it('returns nothing when attachments are missing', () => {
+    expect(extractAttachmentText({})).toEqual([]);
+  });
+});
@@ -1,4 +1,4 @@
-import { takeStreamChunk } from '../../src/worker/chunks';
+import { splitIntoChunks, takeStreamChunk } from '../../src/worker/chunks';
 
 function takeAll(buffer: string, maxChars: number): string[] {
   const chunks: string[] = [];
@@ -68,3 +68,33 @@ describe('takeStreamChunk', () => {
     }
   });
 });
+
+describe('splitIntoChunks', () => {
+  // Half of a surrogate pair, i.e. a codepoint that was split.
+  const LONE_SURROGATE = /[\uD800-\uDBFF](?![\uDC00-\uDFFF])|(?<![\uD800-\uDBFF])[\uDC00-\uDFFF]/;
+
+  it('never splits a codepoint and loses nothing at any cap', () => {
+    const text = '😀😀 你好世界 🎉\n\n𠜎𠜱 café 👍🏽 早上好 🇯🇵 end';
+    for (let max = 1; max <= [...text].length + 1; max++) {
+      const chunks = splitIntoChunks(text, max);
+      expect(chunks.join('')).toBe(text);
+      for (const chunk of chunks) {
+        expect(chunk).not.toMatch(LONE_SURROGATE);
+        expect([...chunk].length).toBeGreaterThan(0);
+        expect([...chunk].length).toBeLessThanOrEqual(max);
+      }
+    }
+  });
+
+  it('hard-splits a run of emoji between codepoints', () => {
+    expect(splitIntoChunks('😀🎉👍😀🎉', 2)).toEqual(['😀🎉', '👍😀', '🎉']);
+  });
+
+  it('hard-splits astral CJK between codepoints', () => {
+    expect(splitIntoChunks('𠜎𠜱𠝹𠱓', 3)).toEqual(['𠜎𠜱𠝹', '𠱓']);
+  });
+
+  it('treats a cap below one as one codepoint per chunk', () => {
+    expect(splitIntoChunks('😀你', 0)).toEqual(['😀', '你']);
+  });
+});
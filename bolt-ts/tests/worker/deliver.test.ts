@@ -1,8 +1,33 @@
-import { buildSummaryActionButtons } from '../../src/worker/deliver';
+import type { Overflow } from '@slack/types';
+import type { WebClient } from '@slack/web-api';
+import {
+  ACTION_SUMMARY_DESTINATION,
+  CONTEXT_TEXT_MAX_CHARS,
+  DESTINATION_CANVAS,
+  DESTINATION_REPOST,
+  SLACK_BUTTON_VALUE_MAX_CHARS,
+  buildChannelDeliveryText,
+  buildNotifyMeText,
+  buildSummaryActionButtons,
+  buildSummaryFooterBlocks,
+  channelUrl,
+  deliverOrDmRequester,
+  newLongMessageProgress,
+  notInChannelMessage,
+  notifyRequester,
+  postLongMessage,
+  postSummaryToChannel,
+  renderSummaryFooter,
+  resolveDeliveryTarget,
+  resolveRefreshTarget,
+  retryTransientDelivery,
+  selectPostingToken,
+  splitForSlackMessages,
+} from '../../src/worker/deliver';
 
 interface ActionsBlock {
   type: 'actions';
-  elements: Array<{ action_id: string; value: string }>;
+  elements: Array<{ action_id: string; value?: string; url?: string }>;
 }
 
 function actionIds(blocks: unknown[]): string[] {
@@ -16,7 +41,14 @@ describe('buildSummaryActionButtons', () => {
       messageCount: 25,
       currentStyle: null,
     });
-    expect(actionIds(blocks)).toEqual(['share_summary', 'rerun_roast', 'rerun_receipts']);
+    expect(actionIds(blocks)).toEqual([
+      'share_summary',
+      'rerun_summary',
+      'rerun_roast',
+      'rerun_receipts',
+      'jump_to_channel',
+      'summary_destination',
+    ]);
   });
 
   it('hides Roast when the current style already roasts', () => {
@@ -25,7 +57,13 @@ describe('buildSummaryActionButtons', () => {
       messageCount: 25,
       currentStyle: 'roast everyone',
     });
-    expect(actionIds(blocks)).toEqual(['share_summary', 'rerun_receipts']);
+    expect(actionIds(blocks)).toEqual([
+      'share_summary',
+      'rerun_summary',
+      'rerun_receipts',
+      'jump_to_channel',
+      'summary_destination',
+    ]);
   });
 
   it('hides Receipts when the current style pulls receipts', () => {
@@ -34,7 +72,25 @@ describe('buildSummaryActionButtons', () => {
       messageCount: 25,
       currentStyle: 'bring receipts',
     });
-    expect(actionIds(blocks)).toEqual(['share_summary', 'rerun_roast']);
+    expect(actionIds(blocks)).toEqual([
+      'share_summary',
+      'rerun_summary',
+      'rerun_roast',
+      'jump_to_channel',
+      'summary_destination',
+    ]);
+  });
+
+  it('ends with a destination menu offering repost and canvas', () => {
+    const blocks = buildSummaryActionButtons({
+      sourceChannelId: 'C1',
+      messageCount: 25,
+      currentStyle: null,
+    });
+    const menu = (blocks[0] as ActionsBlock).elements.at(-1) as unknown as Overflow;
+    expect(menu.type).toBe('overflow');
+    expect(menu.action_id).toBe(ACTION_SUMMARY_DESTINATION);
+    expect(menu.options.map((o) => o.value)).toEqual([DESTINATION_REPOST, DESTINATION_CANVAS]);
   });
 
   it('embeds count and source channel in Share value payload', () => {
@@ -45,11 +101,439 @@ describe('buildSummaryActionButtons', () => {
     });
     const block = blocks[0] as ActionsBlock;
     const share = block.elements.find((e) => e.action_id === 'share_summary')!;
-    expect(JSON.parse(share.value)).toEqual({
+    expect(JSON.parse(share.value!)).toEqual({
       action: 'share_summary',
       sourceChannelId: 'C42',
       count: 100,
       style: 'be funny',
     });
   });
+
+  it('carries the current style in the Summarize again value payload', () => {
+    const blocks = buildSummaryActionButtons({
+      sourceChannelId: 'C42',
+      messageCount: 100,
+      currentStyle: 'be funny',
+    });
+    const again = (blocks[0] as ActionsBlock).elements.find((e) => e.action_id === 'rerun_summary')!;
+    expect(JSON.parse(again.value!)).toEqual({
+      action: 'rerun_summary',
+      channelId: 'C42',
+      count: 100,
+      style: 'be funny',
+    });
+  });
+
+  it.each([
+    ['share_summary', { action: 'share_summary', sourceChannelId: 'C42', count: 100 }],
+    ['rerun_summary', { action: 'rerun_summary', channelId: 'C42', count: 100 }],
+  ])('keeps the %s value within Slack\'s 2000-char limit', (actionId, payload) => {
+    const room = SLACK_BUTTON_VALUE_MAX_CHARS - JSON.stringify({ ...payload, style: '' }).length;
+    const valueFor = (style: string): string => {
+      const blocks = buildSummaryActionButtons({
+        sourceChannelId: 'C42',
+        messageCount: 100,
+        currentStyle: style,
+      });
+      return (blocks[0] as ActionsBlock).elements.find((e) => e.action_id === actionId)!.value!;
+    };
+
+    const fits = valueFor('x'.repeat(room));
+    expect(fits).toHaveLength(SLACK_BUTTON_VALUE_MAX_CHARS);
+    expect(JSON.parse(fits).style).toBe('x'.repeat(room));
+
+    const tooLong = valueFor('x'.repeat(room + 1));
+    expect(tooLong.length).toBeLessThanOrEqual(SLACK_BUTTON_VALUE_MAX_CHARS);
+    expect(JSON.parse(tooLong)).toEqual({ ...payload, style: null });
+  });
+
+  it('links the Jump button to the source channel', () => {
+    const blocks = buildSummaryActionButtons({
+      sourceChannelId: 'C42',
+      messageCount: 100,
+      currentStyle: null,
+    });
+    const jump = (blocks[0] as ActionsBlock).elements.find((e) => e.action_id === 'jump_to_channel')!;
+    expect(jump.url).toBe(channelUrl('C42'));
+    expect(jump.url).toBe('https://slack.com/app_redirect?channel=C42');
+    expect(jump.value).toBeUndefined();
+  });
+});
+
+describe('renderSummaryFooter', () => {
+  it('substitutes every {model} and {count} placeholder', () => {
+    expect(
+      renderSummaryFooter('_Generated by {model} from {count} messages. May contain errors._', {
+        model: 'claude-sonnet-4-6',
+        count: 50,
+      })
+    ).toBe('_Generated by claude-sonnet-4-6 from 50 messages. May contain errors._');
+    expect(renderSummaryFooter('{count}/{count}', { model: 'm', count: 3 })).toBe('3/3');
+  });
+
+  it('links {channel} to the summarized channel', () => {
+    expect(
+      renderSummaryFooter('_{model} · {count} messages from {channel}_', {
+        model: 'm',
+        count: 20,
+        channelId: 'C42',
+      })
+    ).toBe('_m · 20 messages from <#C42>_');
+    expect(renderSummaryFooter('from {channel}', { model: 'm', count: 1 })).toBe(
+      'from the channel'
+    );
+  });
+
+  it('returns null when no footer is configured or it renders blank', () => {
+    expect(renderSummaryFooter(null, { model: 'm', count: 1 })).toBeNull();
+    expect(renderSummaryFooter('   ', { model: 'm', count: 1 })).toBeNull();
+  });
+});
+
+describe('buildSummaryFooterBlocks', () => {
+  it('wraps the footer in a single mrkdwn context block', () => {
+    expect(buildSummaryFooterBlocks('_from 50 messages_')).toEqual([
+      { type: 'context', elements: [{ type: 'mrkdwn', text: '_from 50 messages_' }] },
+    ]);
+    expect(buildSummaryFooterBlocks(null)).toEqual([]);
+  });
+
+  it("clamps the footer to Slack's context text limit", () => {
+    const [block] = buildSummaryFooterBlocks('x'.repeat(CONTEXT_TEXT_MAX_CHARS + 50));
+    const text = (block as { elements: Array<{ text: string }> }).elements[0].text;
+    expect(text).toHaveLength(CONTEXT_TEXT_MAX_CHARS);
+    expect(text.endsWith('…')).toBe(true);
+  });
+});
+
+describe('resolveDeliveryTarget', () => {
+  it('keeps the summary in the assistant thread when no post channel is set', () => {
+    expect(resolveDeliveryTarget('C1', null)).toEqual({ kind: 'assistant_thread' });
+  });
+
+  it('posts into the source channel without a cross-channel flag', () => {
+    expect(resolveDeliveryTarget('C1', 'C1')).toEqual({
+      kind: 'channel',
+      channelId: 'C1',
+      crossChannel: false,
+    });
+  });
+
+  it('flags delivery to a different channel as cross-channel', () => {
+    expect(resolveDeliveryTarget('C1', 'C2')).toEqual({
+      kind: 'channel',
+      channelId: 'C2',
+      crossChannel: true,
+    });
+  });
+
+  it('targets the earlier summary message when refreshing', () => {
+    expect(resolveDeliveryTarget('C1', null, '9.9')).toEqual({ kind: 'replace', ts: '9.9' });
+  });
+});
+
+describe('buildChannelDeliveryText', () => {
+  it('adds a "Summary of" note only for cross-channel posts', () => {
+    expect(buildChannelDeliveryText('body', 'C1', { crossChannel: false })).toBe('body');
+    expect(buildChannelDeliveryText('body', 'C1', { crossChannel: true })).toBe(
+      '_Summary of <#C1>_\n\nbody'
+    );
+  });
+});
+
+describe('selectPostingToken', () => {
+  const tokens = { slackBotToken: 'xoxb-bot', slackUserToken: 'xoxp-user' };
+
+  it('posts as the user only when postAsUser is on and a user token exists', () => {
+    expect(selectPostingToken({ ...tokens, postAsUser: true })).toBe('xoxp-user');
+    expect(selectPostingToken({ ...tokens, postAsUser: false })).toBe('xoxb-bot');
+    expect(selectPostingToken({ ...tokens, slackUserToken: null, postAsUser: true })).toBe(
+      'xoxb-bot'
+    );
+  });
+});
+
+describe('postSummaryToChannel', () => {
+  it('reports not_in_channel instead of throwing', async () => {
+    const err = Object.assign(new Error('An API error occurred: not_in_channel'), {
+      data: { ok: false, error: 'not_in_channel' },
+    });
+    const client = {
+      chat: { postMessage: jest.fn().mockRejectedValue(err) },
+    } as unknown as WebClient;
+    await expect(postSummaryToChannel(client, 'C2', 'hi')).resolves.toEqual({
+      ok: false,
+      reason: 'not_in_channel',
+    });
+  });
+
+  it('rethrows other Slack errors', async () => {
+    const client = {
+      chat: { postMessage: jest.fn().mockRejectedValue(new Error('ratelimited')) },
+    } as unknown as WebClient;
+    await expect(postSummaryToChannel(client, 'C2', 'hi')).rejects.toThrow('ratelimited');
+  });
+
+  it('returns the ts of the published message', async () => {
+    const client = {
+      chat: { postMessage: jest.fn().mockResolvedValue({ ok: true, ts: '9.9' }) },
+    } as unknown as WebClient;
+    await expect(postSummaryToChannel(client, 'C2', 'hi')).resolves.toEqual({ ok: true, ts: '9.9' });
+  });
+});
+
+describe('buildNotifyMeText', () => {
+  it('links the published summary', () => {
+    expect(buildNotifyMeText('C2', 'https://acme.slack.com/archives/C2/p99')).toBe(
+      'Your summary is ready in <#C2>: https://acme.slack.com/archives/C2/p99'
+    );
+  });
+
+  it('falls back to the channel when there is no permalink', () => {
+    expect(buildNotifyMeText('C2', null)).toBe('Your summary is ready in <#C2>.');
+  });
+});
+
+describe('notInChannelMessage', () => {
+  it('names the configured assistant in the invite', () => {
+    expect(notInChannelMessage('C2', 'Digest')).toBe(
+      "I'm not a member of <#C2>. Invite me with `/invite @Digest` and try again."
+    );
+  });
+
+  it('falls back to TLDR without a name', () => {
+    expect(notInChannelMessage('C2', null)).toContain('`/invite @TLDR`');
+  });
+});
+
+describe('notifyRequester', () => {
+  it('DMs the requester a permalink to the summary', async () => {
+    const postMessage = jest.fn().mockResolvedValue({ ok: true });
+    const getPermalink = jest.fn().mockResolvedValue({ permalink: 'https://slack/p/9' });
+    const client = { chat: { postMessage, getPermalink } } as unknown as WebClient;
+
+    await notifyRequester(client, 'U1', 'C2', '9.9');
+
+    expect(getPermalink).toHaveBeenCalledWith({ channel: 'C2', message_ts: '9.9' });
+    expect(postMessage).toHaveBeenCalledWith({
+      channel: 'U1',
+      text: 'Your summary is ready in <#C2>: https://slack/p/9',
+    });
+  });
+
+  it('swallows DM failures since the summary is already delivered', async () => {
+    const postMessage = jest.fn().mockRejectedValue(new Error('channel_not_found'));
+    const client = { chat: { postMessage, getPermalink: jest.fn() } } as unknown as WebClient;
+    await expect(notifyRequester(client, 'U1', 'C2', null)).resolves.toBeUndefined();
+  });
+});
+
+describe('resolveRefreshTarget', () => {
+  const summaryBlocks = (channel: string, count: number, style: string | null): unknown[] =>
+    buildSummaryActionButtons({ sourceChannelId: channel, messageCount: count, currentStyle: style });
+
+  it('picks the newest bot summary and reads its source from the Share button', () => {
+    const messages = [
+      { ts: '1.0', user: 'UHUMAN', blocks: undefined },
+      { ts: '2.0', user: 'UBOT', blocks: summaryBlocks('C11111111', 50, null) },
+      { ts: '3.0', user: 'UBOT', blocks: summaryBlocks('C22222222', 100, 'be funny') },
+      { ts: '4.0', user: 'UHUMAN', blocks: undefined },
+    ];
+    expect(resolveRefreshTarget(messages, 'UBOT')).toEqual({
+      ts: '3.0',
+      sourceChannelId: 'C22222222',
+      messageCount: 100,
+      style: 'be funny',
+    });
+  });
+
+  it('ignores other authors and bot messages without a summary', () => {
+    const messages = [
+      { ts: '1.0', user: 'UBOT', blocks: summaryBlocks('C11111111', 50, null) },
+      { ts: '2.0', user: 'UBOT', blocks: [{ type: 'section' }] },
+      { ts: '3.0', user: 'UOTHER', blocks: summaryBlocks('C33333333', 10, null) },
+    ];
+    expect(resolveRefreshTarget(messages, 'UBOT')?.ts).toBe('1.0');
+  });
+
+  it('returns null when the thread has no summary', () => {
+    const messages = [
+      {
+        ts: '1.0',
+        user: 'UBOT',
+        blocks: [{ type: 'actions', elements: [{ action_id: 'share_summary', value: 'not json' }] }],
+      },
+    ];
+    expect(resolveRefreshTarget(messages, 'UBOT')).toBeNull();
+    expect(resolveRefreshTarget([], 'UBOT')).toBeNull();
+  });
+});
+
+describe('splitForSlackMessages', () => {
+  it('returns text that fits as a single unlabelled part', () => {
+    expect(splitForSlackMessages('short summary', 100)).toEqual(['short summary']);
+  });
+
+  it('splits on paragraph boundaries into ordered, numbered, under-limit parts', () => {
+    const paragraphs = ['alpha '.repeat(10).trim(), 'bravo '.repeat(10).trim(), 'charlie '.repeat(8).trim()];
+    const parts = splitForSlackMessages(paragraphs.join('\n\n'), 90);
+
+    expect(parts).toEqual([
+      `(1/3) ${paragraphs[0]}`,
+      `(2/3) ${paragraphs[1]}`,
+      `(3/3) ${paragraphs[2]}`,
+    ]);
+    for (const part of parts) {
+      expect([...part].length).toBeLessThanOrEqual(90);
+    }
+  });
+
+  it('keeps multibyte text under the limit', () => {
+    const parts = splitForSlackMessages('🎉'.repeat(100), 40);
+    expect(parts.length).toBeGreaterThan(1);
+    for (const part of parts) {
+      expect([...part].length).toBeLessThanOrEqual(40);
+    }
+    expect(parts.map((p) => p.replace(/^\(\d+\/\d+\) /, '')).join('')).toBe('🎉'.repeat(100));
+  });
+
+  it('keeps emoji and CJK intact when they straddle a part boundary', () => {
+    const text = `${'a'.repeat(25)}你😀好${'b'.repeat(25)}`;
+    const parts = splitForSlackMessages(text, 40);
+    expect(parts.length).toBeGreaterThan(1);
+    for (const part of parts) {
+      expect([...part].length).toBeLessThanOrEqual(40);
+      expect(part).not.toMatch(/[\uD800-\uDBFF](?![\uDC00-\uDFFF])/);
+    }
+    expect(parts.map((p) => p.replace(/^\(\d+\/\d+\) /, '')).join('')).toBe(text);
+  });
+});
+
+describe('postLongMessage', () => {
+  const tooLong = Object.assign(new Error('An API error occurred: msg_too_long'), {
+    data: { ok: false, error: 'msg_too_long' },
+  });
+
+  it('re-posts as numbered parts with blocks on the last one after msg_too_long', async () => {
+    const postMessage = jest.fn().mockRejectedValueOnce(tooLong).mockResolvedValue({ ok: true });
+    const client = { chat: { postMessage } } as unknown as WebClient;
+    const blocks = buildSummaryActionButtons({ sourceChannelId: 'C1', messageCount: 5, currentStyle: null });
+    const text = `${'a'.repeat(30_000)}\n\n${'b'.repeat(30_000)}`;
+
+    await postLongMessage(client, { channel: 'D1', threadTs: '1.0', text, blocks });
+
+    expect(postMessage).toHaveBeenCalledTimes(3);
+    const [, first, second] = postMessage.mock.calls.map((c) => c[0]);
+    expect(first.text.startsWith('(1/2) a')).toBe(true);
+    expect(first.blocks).toBeUndefined();
+    expect(first.thread_ts).toBe('1.0');
+    expect(second.text.startsWith('(2/2) b')).toBe(true);
+    expect(second.blocks).toBe(blocks);
+  });
+
+  it('turns off link and media unfurls unless asked to unfurl', async () => {
+    const postMessage = jest.fn().mockResolvedValue({ ok: true, ts: '2.0' });
+    const client = { chat: { postMessage } } as unknown as WebClient;
+    await postLongMessage(client, { channel: 'D1', text: '- <https://example.com|Spec>' });
+    await postLongMessage(client, { channel: 'D1', text: 'links', unfurlLinks: true });
+    expect(postMessage.mock.calls[0][0]).toMatchObject({ unfurl_links: false, unfurl_media: false });
+    expect(postMessage.mock.calls[1][0]).toMatchObject({ unfurl_links: true, unfurl_media: true });
+  });
+
+  it('resumes after the last posted part when retried with the same progress', async () => {
+    const transient = { data: { ok: false, error: 'internal_error' } };
+    const postMessage = jest
+      .fn()
+      .mockRejectedValueOnce(tooLong)
+      .mockResolvedValueOnce({ ok: true, ts: '5.0' })
+      .mockRejectedValueOnce(transient)
+      .mockResolvedValue({ ok: true, ts: '6.0' });
+    const client = { chat: { postMessage } } as unknown as WebClient;
+    const text = `${'a'.repeat(30_000)}\n\n${'b'.repeat(30_000)}`;
+    const progress = newLongMessageProgress();
+
+    await expect(postLongMessage(client, { channel: 'D1', text, progress })).rejects.toBe(transient);
+    await expect(postLongMessage(client, { channel: 'D1', text, progress })).resolves.toBe('5.0');
+
+    const texts = postMessage.mock.calls.map((c) => c[0].text as string);
+    expect(texts.filter((t) => t.startsWith('(1/2)'))).toHaveLength(1);
+    expect(texts.filter((t) => t.startsWith('(2/2)'))).toHaveLength(2);
+    expect(progress).toEqual({ partsPosted: 2, firstTs: '5.0' });
+  });
+
+  it('rethrows msg_too_long when there is nothing to split', async () => {
+    const postMessage = jest.fn().mockRejectedValue(tooLong);
+    const client = { chat: { postMessage } } as unknown as WebClient;
+    await expect(postLongMessage(client, { channel: 'D1', text: 'short' })).rejects.toBe(tooLong);
+    expect(postMessage).toHaveBeenCalledTimes(1);
+  });
+});
+
+describe('retryTransientDelivery', () => {
+  const transient = { data: { ok: false, error: 'internal_error' } };
+  const noSleep = jest.fn().mockResolvedValue(undefined);
+
+  it('retries transient failures until the delivery succeeds', async () => {
+    const deliver = jest.fn().mockRejectedValueOnce(transient).mockResolvedValue('ok');
+    await expect(retryTransientDelivery(deliver, { retries: 2, sleep: noSleep })).resolves.toBe('ok');
+    expect(deliver).toHaveBeenCalledTimes(2);
+  });
+
+  it('gives up after the configured retries and on permanent errors', async () => {
+    const failing = jest.fn().mockRejectedValue(transient);
+    await expect(retryTransientDelivery(failing, { retries: 2, sleep: noSleep })).rejects.toBe(transient);
+    expect(failing).toHaveBeenCalledTimes(3);
+
+    const permanent = { data: { ok: false, error: 'channel_not_found' } };
+    const rejected = jest.fn().mockRejectedValue(permanent);
+    await expect(retryTransientDelivery(rejected, { retries: 2, sleep: noSleep })).rejects.toBe(permanent);
+    expect(rejected).toHaveBeenCalledTimes(1);
+  });
+
+  it('does not retry a timeout, which may already have been posted', async () => {
+    const timeout = Object.assign(new Error('The operation was aborted due to timeout'), {
+      name: 'TimeoutError',
+    });
+    const timedOut = jest.fn().mockRejectedValue(timeout);
+    await expect(retryTransientDelivery(timedOut, { retries: 2, sleep: noSleep })).rejects.toBe(timeout);
+    expect(timedOut).toHaveBeenCalledTimes(1);
+  });
+});
+
+describe('deliverOrDmRequester', () => {
+  const transient = { data: { ok: false, error: 'service_unavailable' } };
+  const noSleep = jest.fn().mockResolvedValue(undefined);
+
+  it('DMs the requester the full summary once retries are exhausted', async () => {
+    const postMessage = jest.fn().mockResolvedValue({ ok: true, ts: '2.0' });
+    const client = { chat: { postMessage } } as unknown as WebClient;
+    const deliver = jest.fn().mockRejectedValue(transient);
+
+    const outcome = await deliverOrDmRequester(client, {
+      deliver,
+      userId: 'U1',
+      summary: '*Summary*\nthings',
+      retries: 1,
+      sleep: noSleep,
+    });
+
+    expect(outcome).toEqual({ dmed: true });
+    expect(deliver).toHaveBeenCalledTimes(2);
+    expect(postMessage).toHaveBeenCalledWith(
+      expect.objectContaining({ channel: 'U1', text: expect.stringContaining('*Summary*\nthings') })
+    );
+  });
+
+  it('rethrows the delivery error when the DM fails too', async () => {
+    const postMessage = jest.fn().mockRejectedValue(new Error('cannot_dm_bot'));
+    const client = { chat: { postMessage } } as unknown as WebClient;
+    await expect(
+      deliverOrDmRequester(client, {
+        deliver: jest.fn().mockRejectedValue(transient),
+        userId: 'U1',
+        summary: 'text',
+        retries: 0,
+      })
+    ).rejects.toBe(transient);
+  });
 });
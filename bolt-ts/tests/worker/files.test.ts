@@ -0,0 +1,54 @@
+import { MAX_FILES_SHARED, extractSharedFiles, isImageCandidate } from '../../src/worker/files';
+import type { SlackFile } from '../../src/slack/client';
+
+function file(overrides: Partial<SlackFile>): SlackFile {
+  return { urlPrivateDownload: 'https://files.example/f', urlPrivate: null, mimeType: null, ...overrides };
+}
+
+describe('isImageCandidate', () => {
+  it('treats allowed images and missing or generic MIME hints as images', () => {
+    expect(isImageCandidate(file({ mimeType: 'image/png' }))).toBe(true);
+    expect(isImageCandidate(file({ mimeType: null }))).toBe(true);
+    expect(isImageCandidate(file({ mimeType: 'application/octet-stream' }))).toBe(true);
+    expect(isImageCandidate(file({ mimeType: 'application/pdf' }))).toBe(false);
+  });
+});
+
+describe('extractSharedFiles', () => {
+  it('lists documents and skips images, which are inlined instead', () => {
+    const messages = [
+      {
+        ts: '1.0',
+        user: 'U1',
+        text: '',
+        files: [
+          file({ name: 'Q3 plan.pdf', fileType: 'pdf', mimeType: 'application/pdf' }),
+          file({ name: 'screenshot.png', fileType: 'png', mimeType: 'image/png' }),
+        ],
+      },
+      {
+        ts: '2.0',
+        user: 'U2',
+        text: '',
+        files: [
+          file({ name: 'migrate.py', mimeType: 'text/x-python' }),
+          file({ name: 'Q3 plan.pdf', fileType: 'pdf', mimeType: 'application/pdf' }),
+          file({ name: '  ', mimeType: 'text/plain' }),
+        ],
+      },
+    ];
+    expect(extractSharedFiles(messages)).toEqual([
+      { name: 'Q3 plan.pdf', type: 'pdf' },
+      { name: 'migrate.py', type: 'text/x-python' },
+    ]);
+  });
+
+  it('caps the list', () => {
+    const files = Array.from({ length: MAX_FILES_SHARED + 5 }, (_, i) =>
+      file({ name: `doc-${i}.pdf`, fileType: 'pdf', mimeType: 'application/pdf' })
+    );
+    expect(extractSharedFiles([{ ts: '1.0', user: 'U1', text: '', files }])).toHaveLength(
+      MAX_FILES_SHARED
+    );
+  });
+});
@@ -1,8 +1,11 @@
 import {
+  classifyLink,
   extractLinksFromMessage,
   extractLinksFromMessages,
   extractLinksFromText,
   normaliseAndDedupe,
+  parseSlackPermalink,
+  tagLink,
 } from '../../src/worker/links';
 
 describe('extractLinksFromText', () => {
@@ -48,6 +51,12 @@ describe('normaliseAndDedupe', () => {
     expect(links).toEqual(['https://example.com/a']);
   });
 
+  it('keeps look-alike hosts that only end in "slack.com"', () => {
+    expect(normaliseAndDedupe(['https://evilslack.com/archives/C123/p1234567890'])).toEqual([
+      'https://evilslack.com/archives/C123/p1234567890',
+    ]);
+  });
+
   it('strips trailing slashes and fragments', () => {
     expect(normaliseAndDedupe(['https://example.com/path/#section'])).toEqual([
       'https://example.com/path',
@@ -70,3 +79,73 @@ describe('extractLinksFromMessages', () => {
     expect(result).toEqual(expect.arrayContaining(['https://example.com/a', 'https://example.com/b']));
   });
 });
+
+describe('classifyLink', () => {
+  it('recognises GitHub pull requests and issues', () => {
+    expect(classifyLink('https://github.com/acme/app/pull/12')).toBe('github_pr');
+    expect(classifyLink('https://github.com/acme/app/pull/12/files')).toBe('github_pr');
+    expect(classifyLink('https://www.github.com/acme/app/issues/3')).toBe('github_issue');
+    expect(classifyLink('https://github.com/acme/app')).toBe('generic');
+    expect(classifyLink('https://github.com/acme/app/pulls')).toBe('generic');
+  });
+
+  it('recognises Jira tickets on Cloud and self-hosted instances', () => {
+    expect(classifyLink('https://acme.atlassian.net/browse/OPS-42')).toBe('jira');
+    expect(classifyLink('https://jira.acme.io/browse/OPS-42')).toBe('jira');
+    expect(
+      classifyLink('https://acme.atlassian.net/jira/software/projects/OPS/boards/1?selectedIssue=OPS-7')
+    ).toBe('jira');
+    expect(classifyLink('https://acme.atlassian.net/wiki/spaces/ENG')).toBe('generic');
+  });
+
+  it('recognises Google Docs, Sheets, and Slides', () => {
+    expect(classifyLink('https://docs.google.com/document/d/abc123/edit')).toBe('google_doc');
+    expect(classifyLink('https://docs.google.com/spreadsheets/d/abc123')).toBe('google_doc');
+    expect(classifyLink('https://docs.google.com/presentation/d/abc123/edit')).toBe('google_doc');
+  });
+
+  it('leaves shortened links, look-alike hosts, and junk generic', () => {
+    expect(classifyLink('https://bit.ly/3xYzAbc')).toBe('generic');
+    expect(classifyLink('https://t.co/abc123')).toBe('generic');
+    expect(classifyLink('https://github.com.evil.example/acme/app/pull/1')).toBe('generic');
+    expect(classifyLink('not a url')).toBe('generic');
+  });
+});
+
+describe('tagLink', () => {
+  it('prefixes recognised links and leaves generic ones alone', () => {
+    expect(tagLink('https://github.com/acme/app/pull/12')).toBe(
+      '[PR] https://github.com/acme/app/pull/12'
+    );
+    expect(tagLink('https://acme.atlassian.net/browse/OPS-42')).toBe(
+      '[Jira] https://acme.atlassian.net/browse/OPS-42'
+    );
+    expect(tagLink('https://example.com/a')).toBe('https://example.com/a');
+  });
+});
+
+describe('parseSlackPermalink', () => {
+  it('decodes channel and ts from a message permalink', () => {
+    expect(
+      parseSlackPermalink('https://acme.slack.com/archives/C0123ABCD/p1700000000000100')
+    ).toEqual({ channelId: 'C0123ABCD', ts: '1700000000.000100', threadTs: null });
+  });
+
+  it('reads thread_ts for reply permalinks and unwraps Slack markup', () => {
+    expect(
+      parseSlackPermalink(
+        '<https://acme.slack.com/archives/C0123ABCD/p1700000005000000?thread_ts=1700000000.000100|link>'
+      )
+    ).toEqual({ channelId: 'C0123ABCD', ts: '1700000005.000000', threadTs: '1700000000.000100' });
+  });
+
+  it('rejects non-Slack and malformed links', () => {
+    expect(parseSlackPermalink('https://example.com/archives/C1/p1700000000000100')).toBeNull();
+    expect(parseSlackPermalink('https://acme.slack.com/archives/C0123ABCD/pnope')).toBeNull();
+    expect(parseSlackPermalink('not a url')).toBeNull();
+  });
+
+  it('rejects hosts that only end in "slack.com"', () => {
+    expect(parseSlackPermalink('https://evilslack.com/archives/C0123ABCD/p1700000000000100')).toBeNull();
+  });
+});
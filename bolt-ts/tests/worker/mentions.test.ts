@@ -0,0 +1,239 @@
+import type { WebClient } from '@slack/web-api';
+import { LlmClient } from '../../src/ai/anthropic';
+import type { AppConfig } from '../../src/config';
+import { createDeadline, DeadlineExceededError } from '../../src/deadline';
+import { resetChannelNameCacheForTests } from '../../src/slack/client';
+import {
+  MENTIONS_TOKEN_MISSING_MESSAGE,
+  formatMentionLine,
+  runMentionsDigest,
+} from '../../src/worker/mentions';
+import { failureMessageFor } from '../../src/worker/streaming';
+
+function makeConfig(overrides: Partial<AppConfig> = {}): AppConfig {
+  return {
+    slackBotToken: 'xoxb',
+    slackSigningSecret: 'sig',
+    slackUserToken: null,
+    slackAppToken: null,
+    anthropicApiKey: 'sk-ant',
+    anthropicModel: 'claude-test',
+    anthropicMaxOutputTokens: 4096,
+    anthropicBaseUrl: null,
+    anthropicTemperature: null,
+    anthropicStyleTemperature: null,
+    anthropicReasoningEffort: null,
+    enableStreaming: false,
+    streamMaxChunkChars: 4000,
+    streamMinAppendIntervalMs: 0,
+    streamStallTimeoutMs: 120_000,
+    streamMaxBufferBytes: 1_048_576,
+    staleStreamMaxAgeSecs: 900,
+    enableReceipts: true,
+    includeAttachmentText: true,
+    disableImages: false,
+    citeInline: false,
+    summarySections: ['Summary', 'Links shared', 'Image highlights', 'Receipts'],
+    detectSummaryLanguage: false,
+    postAsUser: false,
+    unfurlSummaryLinks: false,
+    canvasToc: false,
+    summaryFooter: null,
+    maxRequestBodyBytes: 1_048_576,
+    imageDownloadConcurrency: 3,
+    inlineImageMaxBytes: 4_194_304,
+    permalinkConcurrency: 4,
+    maxConcurrentSummaries: 4,
+    hardMessageCap: 1000,
+    ignoredUserIds: [],
+    defaultTimezone: 'UTC',
+    maxCustomStyleChars: 500,
+    noMessagesMessage: 'No messages found to summarize.',
+    systemPromptOverride: null,
+    assistantName: 'TLDR-bot',
+    taskDeadlineSecs: 840,
+    slackMaxRetries: 5,
+    slackRetryBaseDelayMs: 100,
+    slackRetryMaxDelayMs: 5_000,
+    deliveryMaxRetries: 2,
+    ...overrides,
+  };
+}
+
+const request = {
+  correlationId: 'cid',
+  userId: 'U1',
+  originChannelId: 'D1',
+  threadTs: '1.0',
+  messageCount: 25,
+  customStyle: null,
+  teamId: null,
+  enterpriseId: null,
+  allowMentions: false,
+};
+
+describe('formatMentionLine', () => {
+  it('labels each line with the source channel name', () => {
+    const line = formatMentionLine(
+      { ts: '1.1', user: 'U2', text: 'ping <@U1>', channelId: 'C1', channelName: 'eng', permalink: null },
+      'Bob'
+    );
+    expect(line).toBe('[1.1] #eng — Bob: ping <@U1>');
+  });
+
+  it('falls back to the channel ID when the name is missing', () => {
+    const line = formatMentionLine(
+      { ts: '1.1', user: null, text: 'hi', channelId: 'C9', channelName: null, permalink: null },
+      'Unknown User'
+    );
+    expect(line).toBe('[1.1] C9 — Unknown User: hi');
+  });
+});
+
+describe('runMentionsDigest', () => {
+  it('explains the missing user token instead of searching', async () => {
+    const postMessage = jest.fn().mockResolvedValue({ ok: true });
+    const client = { chat: { postMessage } } as unknown as WebClient;
+    await runMentionsDigest({ config: makeConfig(), client, request });
+    expect(postMessage).toHaveBeenCalledWith(
+      expect.objectContaining({ channel: 'D1', text: MENTIONS_TOKEN_MISSING_MESSAGE })
+    );
+  });
+
+  it('drops hits from channels the requester is not a member of', async () => {
+    const postMessage = jest.fn().mockResolvedValue({ ok: true });
+    const members = jest.fn(({ channel }: { channel: string }) =>
+      Promise.resolve({ members: channel === 'C11111111' ? ['U1'] : ['U9'] })
+    );
+    const client = {
+      chat: { postMessage },
+      conversations: { members },
+      users: { info: jest.fn().mockResolvedValue({ user: { profile: { real_name: 'Bob' } } }) },
+    } as unknown as WebClient;
+    const searchClient = {
+      search: {
+        messages: jest.fn().mockResolvedValue({
+          messages: {
+            matches: [
+              { ts: '1.1', user: 'U2', text: 'visible', channel: { id: 'C11111111', name: 'eng' } },
+              { ts: '1.2', user: 'U2', text: 'hidden', channel: { id: 'C22222222', name: 'secret' } },
+            ],
+            paging: { pages: 1 },
+          },
+        }),
+      },
+    } as unknown as WebClient;
+    const llm = new LlmClient({ apiKey: 'sk-ant', model: 'claude-test' });
+    const generate = jest.spyOn(llm, 'generateSummary').mockResolvedValue('*Summary*\nok');
+
+    await runMentionsDigest({ config: makeConfig(), client, request, searchClient, llm });
+
+    const prompt = generate.mock.calls[0][0];
+    const text = prompt.userContent.map((b) => (b.type === 'text' ? b.text : '')).join('');
+    expect(text).toContain('#eng — Bob: visible');
+    expect(text).not.toContain('hidden');
+    expect(postMessage).toHaveBeenCalledWith(
+      expect.objectContaining({ text: expect.stringContaining('*Your mentions digest*') })
+    );
+  });
+
+  it('names channels search left unnamed from one channel listing', async () => {
+    resetChannelNameCacheForTests();
+    const list = jest.fn().mockResolvedValue({
+      channels: [
+        { id: 'C11111111', name: 'eng' },
+        { id: 'C22222222', name: 'ops' },
+      ],
+    });
+    const client = {
+      token: 'xoxb-mentions',
+      chat: { postMessage: jest.fn().mockResolvedValue({ ok: true }) },
+      conversations: { members: jest.fn().mockResolvedValue({ members: ['U1'] }), list },
+      users: { info: jest.fn().mockResolvedValue({ user: { profile: { real_name: 'Bob' } } }) },
+    } as unknown as WebClient;
+    const searchClient = {
+      search: {
+        messages: jest.fn().mockResolvedValue({
+          messages: {
+            matches: [
+              { ts: '1.1', user: 'U2', text: 'first', channel: { id: 'C11111111' } },
+              { ts: '1.2', user: 'U2', text: 'second', channel: { id: 'C22222222' } },
+            ],
+            paging: { pages: 1 },
+          },
+        }),
+      },
+    } as unknown as WebClient;
+    const llm = new LlmClient({ apiKey: 'sk-ant', model: 'claude-test' });
+    const generate = jest.spyOn(llm, 'generateSummary').mockResolvedValue('*Summary*\nok');
+
+    await runMentionsDigest({ config: makeConfig(), client, request, searchClient, llm });
+
+    const prompt = generate.mock.calls[0][0];
+    const text = prompt.userContent.map((b) => (b.type === 'text' ? b.text : '')).join('');
+    expect(text).toContain('#eng — Bob: first');
+    expect(text).toContain('#ops — Bob: second');
+    expect(list).toHaveBeenCalledTimes(1);
+    resetChannelNameCacheForTests();
+  });
+
+  function makeDigestClients(): {
+    client: WebClient;
+    searchClient: WebClient;
+    postMessage: jest.Mock;
+    search: jest.Mock;
+  } {
+    const postMessage = jest.fn().mockResolvedValue({ ok: true });
+    const client = {
+      chat: { postMessage },
+      conversations: { members: jest.fn().mockResolvedValue({ members: ['U1'] }) },
+      users: { info: jest.fn().mockResolvedValue({ user: { profile: { real_name: 'Bob' } } }) },
+    } as unknown as WebClient;
+    const search = jest.fn().mockResolvedValue({
+      messages: {
+        matches: [{ ts: '1.1', user: 'U2', text: 'ping <@U1>', channel: { id: 'C11111111', name: 'eng' } }],
+        paging: { pages: 1 },
+      },
+    });
+    const searchClient = { search: { messages: search } } as unknown as WebClient;
+    return { client, searchClient, postMessage, search };
+  }
+
+  it('neutralises user mentions in the digest unless allowMentions is set', async () => {
+    const { client, searchClient, postMessage } = makeDigestClients();
+    const llm = new LlmClient({ apiKey: 'sk-ant', model: 'claude-test' });
+    jest.spyOn(llm, 'generateSummary').mockResolvedValue('*Summary*\n<@U2> asked <@U1>');
+
+    await runMentionsDigest({ config: makeConfig(), client, request, searchClient, llm });
+    await runMentionsDigest({
+      config: makeConfig(),
+      client,
+      request: { ...request, allowMentions: true },
+      searchClient,
+      llm,
+    });
+
+    const [neutralised, live] = postMessage.mock.calls.map((c) => c[0].text as string);
+    expect(neutralised).toContain('`<@U2>` asked `<@U1>`');
+    expect(live).toContain('<@U2> asked <@U1>');
+  });
+
+  it('reports a missed deadline without searching', async () => {
+    const { client, searchClient, postMessage, search } = makeDigestClients();
+    const llm = new LlmClient({ apiKey: 'sk-ant', model: 'claude-test' });
+
+    await runMentionsDigest({
+      config: makeConfig(),
+      client,
+      request,
+      searchClient,
+      llm,
+      deadline: createDeadline(0),
+    });
+
+    expect(search).not.toHaveBeenCalled();
+    expect(postMessage).toHaveBeenCalledWith(
+      expect.objectContaining({ text: failureMessageFor(new DeadlineExceededError()) })
+    );
+  });
+});
@@ -1,4 +1,16 @@
-import { applySafetyNetSections } from '../../src/worker/prompt_builder';
+import type { WebClient } from '@slack/web-api';
+import {
+  applySafetyNetSections,
+  buildSummarizePromptData,
+  detectSummaryLanguage,
+  downloadInlineImages,
+  fetchCanvasMessages,
+  filterMessagesByKeyword,
+  filterUserMessages,
+  formatMessagesForPrompt,
+  isImageHeavy,
+  MAX_IMAGES_TOTAL,
+} from '../../src/worker/prompt_builder';
 
 describe('applySafetyNetSections', () => {
   it('appends Links shared, Image highlights, and Receipts when missing', () => {
@@ -13,6 +25,23 @@ describe('applySafetyNetSections', () => {
     expect(result).toContain('- None');
   });
 
+  it('fills in configured sections and skips the ones that were dropped', () => {
+    const result = applySafetyNetSections('*Summary*\nThings happened.\n\n*Receipts*\n- one', {
+      linksShared: ['https://example.com/a'],
+      filesShared: [{ name: 'plan.pdf', type: 'pdf' }],
+      receiptPermalinks: [],
+      hasAnyImages: false,
+      sections: ['Summary', 'Decisions', 'Links shared', 'Receipts'],
+    });
+    expect(result).toBe(
+      '*Summary*\nThings happened.\n\n*Receipts*\n- one' +
+        '\n\n*Decisions*\n- None\n' +
+        '\n\n*Links shared*\n- https://example.com/a\n' +
+        '\n\n*Files shared*\n- plan.pdf (pdf)\n'
+    );
+    expect(result).not.toContain('Image highlights');
+  });
+
   it('does not duplicate sections already present in the summary', () => {
     const summary = '*Summary*\nfoo\n*Links shared*\n- existing\n*Image highlights*\n- existing\n*Receipts*\n- existing';
     const result = applySafetyNetSections(summary, {
@@ -23,6 +52,26 @@ describe('applySafetyNetSections', () => {
     expect(result).toBe(summary);
   });
 
+  it('says image highlights are disabled when images were skipped by config', () => {
+    const result = applySafetyNetSections('*Summary*\nthings.', {
+      linksShared: [],
+      receiptPermalinks: [],
+      hasAnyImages: false,
+      imagesEnabled: false,
+    });
+    expect(result).toContain('*Image highlights*\n- Disabled');
+  });
+
+  it('tags recognised links in the fallback Links shared section', () => {
+    const result = applySafetyNetSections('*Summary*\nthings.', {
+      linksShared: ['https://github.com/acme/app/pull/7', 'https://docs.google.com/document/d/x/edit'],
+      receiptPermalinks: [],
+      hasAnyImages: false,
+    });
+    expect(result).toContain('- [PR] https://github.com/acme/app/pull/7\n');
+    expect(result).toContain('- [Doc] https://docs.google.com/document/d/x/edit\n');
+  });
+
   it('inserts known links and receipts when sections are missing', () => {
     const result = applySafetyNetSections('*Summary*\nthings.', {
       linksShared: ['https://example.com'],
@@ -33,4 +82,610 @@ describe('applySafetyNetSections', () => {
     expect(result).toContain('- https://slack.example/archives/C/p1');
     expect(result).toContain('- (No image highlights provided.)');
   });
+
+  it('adds a Files shared section only when documents were shared', () => {
+    const withFiles = applySafetyNetSections('*Summary*\nthings.', {
+      linksShared: [],
+      filesShared: [{ name: 'Q3 plan.pdf', type: 'pdf' }],
+      receiptPermalinks: [],
+      hasAnyImages: false,
+    });
+    expect(withFiles).toContain('*Files shared*\n- Q3 plan.pdf (pdf)');
+    const withoutFiles = applySafetyNetSections('*Summary*\nthings.', {
+      linksShared: [],
+      filesShared: [],
+      receiptPermalinks: [],
+      hasAnyImages: false,
+    });
+    expect(withoutFiles).not.toContain('Files shared');
+  });
+
+  it('marks Receipts as disabled when receipts were turned off', () => {
+    const result = applySafetyNetSections('*Summary*\nthings.', {
+      linksShared: [],
+      receiptPermalinks: [],
+      hasAnyImages: false,
+      receiptsEnabled: false,
+    });
+    expect(result).toContain('*Receipts*\n- Disabled');
+  });
+});
+
+describe('buildSummarizePromptData', () => {
+  const makeClient = () => ({
+    conversations: { info: jest.fn().mockResolvedValue({ channel: { name: 'general' } }) },
+    users: { info: jest.fn().mockResolvedValue({ user: { profile: { real_name: 'Alice' } } }) },
+    chat: { getPermalink: jest.fn().mockResolvedValue({ permalink: 'https://slack.example/p1' }) },
+  });
+  const messages = [{ ts: '1.0', user: 'U1', text: 'we ship friday', files: [] }];
+
+  it('fetches receipt permalinks by default', async () => {
+    const client = makeClient();
+    const data = await buildSummarizePromptData({
+      client: client as unknown as WebClient,
+      botToken: 'xoxb-test',
+      channelId: 'C11111111',
+      messages,
+      customStyle: null,
+    });
+    expect(client.chat.getPermalink).toHaveBeenCalled();
+    expect(data.receiptPermalinks).toEqual(['https://slack.example/p1']);
+  });
+
+  it('skips chat.getPermalink when receipts are disabled', async () => {
+    const client = makeClient();
+    const data = await buildSummarizePromptData({
+      client: client as unknown as WebClient,
+      botToken: 'xoxb-test',
+      channelId: 'C11111111',
+      messages,
+      customStyle: null,
+      enableReceipts: false,
+    });
+    expect(client.chat.getPermalink).not.toHaveBeenCalled();
+    expect(data.receiptPermalinks).toEqual([]);
+    expect(data.receiptsEnabled).toBe(false);
+  });
+
+  it('never touches image files when images are disabled', async () => {
+    const client = makeClient();
+    const fetchImpl = jest.fn();
+    const withImage = [
+      {
+        ts: '1.0',
+        user: 'U1',
+        text: 'see screenshot',
+        files: [{ urlPrivateDownload: 'https://files.example/0', urlPrivate: null, mimeType: 'image/png' }],
+      },
+    ];
+    for (const filesOnly of [false, true]) {
+      const data = await buildSummarizePromptData({
+        client: client as unknown as WebClient,
+        botToken: 'xoxb-test',
+        channelId: 'C11111111',
+        messages: withImage,
+        customStyle: null,
+        filesOnly,
+        disableImages: true,
+        fetchImpl: fetchImpl as unknown as typeof fetch,
+      });
+      expect(data.promptArgs.images).toEqual([]);
+      expect(data.hasAnyImages).toBe(false);
+      expect(data.imagesEnabled).toBe(false);
+    }
+    expect(fetchImpl).not.toHaveBeenCalled();
+  });
+
+  it('sends a canvas verbatim as one document, without receipts', async () => {
+    const client = makeClient();
+    const canvas = { ts: '1792031400', user: null, text: '# Q3 planning\n\n- Ship search', files: [] };
+    const data = await buildSummarizePromptData({
+      client: client as unknown as WebClient,
+      botToken: 'xoxb-test',
+      channelId: 'C11111111',
+      messages: [canvas],
+      customStyle: null,
+      canvasOnly: true,
+    });
+    expect(data.promptArgs.formattedMessages).toEqual(['# Q3 planning\n\n- Ship search']);
+    expect(data.promptArgs.canvasOnly).toBe(true);
+    expect(client.chat.getPermalink).not.toHaveBeenCalled();
+    expect(data.receiptsEnabled).toBe(false);
+  });
+
+  it('keeps raw ids for authors whose lookup stays rate-limited', async () => {
+    const client = makeClient();
+    client.users.info.mockImplementation(async ({ user }: { user: string }) => {
+      if (user === 'U1') {
+        return { user: { profile: { real_name: 'Alice' } } };
+      }
+      throw { code: 'slack_webapi_rate_limited_error', retryAfter: 30 };
+    });
+    const data = await buildSummarizePromptData({
+      client: client as unknown as WebClient,
+      botToken: 'xoxb-test',
+      channelId: 'C11111111',
+      messages: [
+        { ts: '1.0', user: 'U1', text: 'we ship friday', files: [] },
+        { ts: '2.0', user: 'U2', text: 'sounds good', files: [] },
+        { ts: '3.0', user: 'U3', text: 'same', files: [] },
+      ],
+      customStyle: null,
+    });
+    expect(data.promptArgs.formattedMessages).toEqual([
+      expect.stringContaining('Alice: we ship friday'),
+      expect.stringContaining('U2: sounds good'),
+      expect.stringContaining('U3: same'),
+    ]);
+  });
+
+  it('labels authors whose profile lookup fails instead of aborting the batch', async () => {
+    const client = makeClient();
+    client.users.info.mockImplementation(async ({ user }: { user: string }) => {
+      if (user === 'U1') {
+        return { user: { profile: { real_name: 'Alice' } } };
+      }
+      throw new Error('user_not_found');
+    });
+    const data = await buildSummarizePromptData({
+      client: client as unknown as WebClient,
+      botToken: 'xoxb-test',
+      channelId: 'C11111111',
+      messages: [
+        { ts: '1.0', user: 'U1', userTeam: 'THOME', text: 'we ship friday', files: [] },
+        { ts: '2.0', user: 'UEXT', userTeam: 'TPARTNER', text: 'works for us', files: [] },
+        { ts: '3.0', user: 'UOTHER', text: 'same', files: [] },
+      ],
+      customStyle: null,
+      teamId: 'THOME',
+    });
+    expect(data.promptArgs.formattedMessages).toEqual([
+      expect.stringContaining('Alice: we ship friday'),
+      expect.stringContaining('External user (TPARTNER): works for us'),
+      expect.stringContaining('UOTHER: same'),
+    ]);
+    expect(data.promptArgs.formattedMessages.join('\n')).not.toContain('UEXT');
+  });
+
+  it('does not label same-workspace authors as external', async () => {
+    const client = makeClient();
+    client.users.info.mockImplementation(async ({ user }: { user: string }) => {
+      if (user === 'UNONAME') {
+        return { user: { profile: { real_name: '', display_name: '' } } };
+      }
+      if (user === 'UDISPLAY') {
+        return { user: { profile: { real_name: '', display_name: 'dee' } } };
+      }
+      throw new Error('account_inactive');
+    });
+    const data = await buildSummarizePromptData({
+      client: client as unknown as WebClient,
+      botToken: 'xoxb-test',
+      channelId: 'C11111111',
+      messages: [
+        { ts: '1.0', user: 'UNONAME', userTeam: 'THOME', text: 'we ship friday', files: [] },
+        { ts: '2.0', user: 'UDISPLAY', userTeam: 'THOME', text: 'works for us', files: [] },
+        { ts: '3.0', user: 'UGONE', userTeam: 'THOME', text: 'same', files: [] },
+      ],
+      customStyle: null,
+      teamId: 'THOME',
+    });
+    expect(data.promptArgs.formattedMessages).toEqual([
+      expect.stringContaining('UNONAME: we ship friday'),
+      expect.stringContaining('dee: works for us'),
+      expect.stringContaining('UGONE: same'),
+    ]);
+    expect(data.promptArgs.formattedMessages.join('\n')).not.toContain('External user');
+  });
+
+  it('groups the transcript by author for the standup template', async () => {
+    const client = makeClient();
+    client.users.info.mockImplementation(async ({ user }: { user: string }) => ({
+      user: { profile: { real_name: user === 'U1' ? 'Alice' : 'Bob' } },
+    }));
+    const data = await buildSummarizePromptData({
+      client: client as unknown as WebClient,
+      botToken: 'xoxb-test',
+      channelId: 'C11111111',
+      messages: [
+        { ts: '3.0', user: 'U1', text: 'today: docs', files: [] },
+        { ts: '2.0', user: 'U2', text: 'blocked on CI', files: [] },
+        { ts: '1.0', user: 'U1', text: 'yesterday: tests', files: [] },
+      ],
+      customStyle: null,
+      template: 'standup',
+    });
+    const lines = data.promptArgs.formattedMessages;
+    expect(lines.filter((l) => l.startsWith('Author: '))).toEqual(['Author: Alice', 'Author: Bob']);
+    expect(lines[1]).toContain('Alice: yesterday: tests');
+    expect(lines[2]).toContain('Alice: today: docs');
+    expect(data.promptArgs.template).toBe('standup');
+  });
+
+  it('keeps only keyword matches and tells the model the view is filtered', async () => {
+    const client = makeClient();
+    const data = await buildSummarizePromptData({
+      client: client as unknown as WebClient,
+      botToken: 'xoxb-test',
+      channelId: 'C11111111',
+      messages: [
+        { ts: '1.0', user: 'U1', text: 'Deploy is green', files: [] },
+        { ts: '2.0', user: 'U1', text: 'lunch?', files: [] },
+        { ts: '3.0', user: 'U1', text: 'rolling back the deploy', files: [] },
+      ],
+      customStyle: null,
+      keywordFilter: 'DEPLOY',
+    });
+    expect(data.promptArgs.formattedMessages).toEqual([
+      expect.stringContaining('Deploy is green'),
+      expect.stringContaining('rolling back the deploy'),
+    ]);
+    const text = data.prompt.userContent.map((b) => (b.type === 'text' ? b.text : '')).join('');
+    expect(text).toContain('filtered view: only messages containing "DEPLOY"');
+  });
+
+  it('uses the image-focused prompt when images outnumber text messages', async () => {
+    const client = makeClient();
+    const fetchImpl = (async (_url: string, init?: RequestInit) =>
+      init?.method === 'HEAD'
+        ? new Response(null, { headers: { 'content-type': 'image/png' } })
+        : new Response(
+            new Uint8Array([0x89, 0x50, 0x4e, 0x47, 0x0d, 0x0a, 0x1a, 0x0a])
+          )) as unknown as typeof fetch;
+    const screenshot = (i: number) => ({
+      urlPrivateDownload: `https://files.example/${i}`,
+      urlPrivate: null,
+      mimeType: 'image/png',
+    });
+    const data = await buildSummarizePromptData({
+      client: client as unknown as WebClient,
+      botToken: 'xoxb-test',
+      channelId: 'C11111111',
+      messages: [
+        { ts: '1.0', user: 'U1', text: 'prod dashboards', files: [screenshot(0), screenshot(1)] },
+        { ts: '2.0', user: 'U1', text: '', files: [screenshot(2)] },
+      ],
+      customStyle: null,
+      fetchImpl,
+    });
+    expect(data.promptArgs.imageFocused).toBe(true);
+    expect(data.prompt.system).toContain('<image_focus>');
+  });
+
+  it('sends only the inlined images with an empty transcript in files-only mode', async () => {
+    const client = makeClient();
+    const fetchImpl = (async (_url: string, init?: RequestInit) =>
+      init?.method === 'HEAD'
+        ? new Response(null, { headers: { 'content-type': 'image/png' } })
+        : new Response(
+            new Uint8Array([0x89, 0x50, 0x4e, 0x47, 0x0d, 0x0a, 0x1a, 0x0a])
+          )) as unknown as typeof fetch;
+    const data = await buildSummarizePromptData({
+      client: client as unknown as WebClient,
+      botToken: 'xoxb-test',
+      channelId: 'C11111111',
+      messages: [
+        {
+          ts: '1.0',
+          user: 'U1',
+          text: 'see https://example.com/spec',
+          files: [{ urlPrivateDownload: 'https://files.example/0', urlPrivate: null, mimeType: 'image/png' }],
+        },
+      ],
+      customStyle: null,
+      filesOnly: true,
+      fetchImpl,
+    });
+    expect(data.promptArgs.formattedMessages).toEqual([]);
+    expect(data.linksShared).toEqual([]);
+    expect(data.hasAnyImages).toBe(true);
+    expect(client.chat.getPermalink).not.toHaveBeenCalled();
+    expect(data.prompt.userContent.map((block) => block.type)).toEqual(['text', 'image', 'text']);
+    expect((data.prompt.userContent[2] as { text: string }).text).toContain('Describe these images.');
+  });
+});
+
+describe('fetchCanvasMessages', () => {
+  it('returns nothing for a channel without a canvas', async () => {
+    const filesInfo = jest.fn();
+    const client = {
+      conversations: { info: jest.fn().mockResolvedValue({ channel: { id: 'C11111111' } }) },
+      files: { info: filesInfo },
+    } as unknown as WebClient;
+    await expect(fetchCanvasMessages(client, 'C11111111', 'xoxb-test')).resolves.toEqual([]);
+    expect(filesInfo).not.toHaveBeenCalled();
+  });
+
+  it('wraps the canvas title and content as a single message', async () => {
+    const client = {
+      conversations: {
+        info: jest.fn().mockResolvedValue({
+          channel: { id: 'C11111111', properties: { canvas: { file_id: 'F0CANVAS' } } },
+        }),
+      },
+      files: {
+        info: jest.fn().mockResolvedValue({
+          file: { title: 'Q3 planning', updated: 1792031400, url_private: 'https://files/F0CANVAS' },
+        }),
+      },
+    } as unknown as WebClient;
+    const fetchImpl = jest.fn().mockResolvedValue(new Response('<p>Ship search by Friday</p>'));
+
+    const messages = await fetchCanvasMessages(
+      client,
+      'C11111111',
+      'xoxb-test',
+      fetchImpl as unknown as typeof fetch
+    );
+
+    expect(messages).toEqual([
+      { ts: '1792031400', user: null, text: '# Q3 planning\n\nShip search by Friday', files: [] },
+    ]);
+  });
+});
+
+describe('isImageHeavy', () => {
+  const msg = (text: string) => ({ ts: '1.0', user: 'U1', text, files: [] });
+
+  it('is true only when inlined images outnumber messages with text', () => {
+    const messages = [msg('look'), msg('  '), msg('and this')];
+    expect(isImageHeavy(3, messages)).toBe(true);
+    expect(isImageHeavy(2, messages)).toBe(false);
+    expect(isImageHeavy(0, [msg('')])).toBe(false);
+  });
+});
+
+describe('detectSummaryLanguage', () => {
+  const msg = (text: string) => ({ ts: '1.0', user: 'U1', text, files: [] });
+
+  it('names a non-English conversation and stays quiet for English', () => {
+    const spanish = [
+      msg('Hoy lanzamos la nueva página de facturación y está funcionando muy bien.'),
+      msg('¿Puedes revisar las facturas de la semana pasada?'),
+      msg('Creo que hay que arreglar el error de impuestos antes del viernes, pero el equipo ya lo tiene.'),
+    ];
+    expect(detectSummaryLanguage(spanish)).toBe('Spanish');
+    const english = [
+      msg('We shipped the new billing page today and it is working well.'),
+      msg('Can you check the invoices for the last week?'),
+      msg('I think we have to fix the tax bug before Friday, but the team is on it.'),
+    ];
+    expect(detectSummaryLanguage(english)).toBeNull();
+    expect(detectSummaryLanguage([msg('ok')])).toBeNull();
+  });
+});
+
+describe('filterUserMessages', () => {
+  const messages = [
+    { ts: '1.0', user: 'UHUMAN', text: 'hi', files: [] },
+    { ts: '2.0', user: 'UTLDR', text: 'summary', files: [] },
+    { ts: '3.0', user: 'UCIBOT', text: 'build failed', files: [] },
+    { ts: '4.0', user: null, botId: 'BALERTS', text: 'disk full', files: [] },
+  ];
+
+  it('drops only the bot itself when nothing else is ignored', () => {
+    expect(filterUserMessages(messages, 'UTLDR').map((m) => m.ts)).toEqual(['1.0', '3.0', '4.0']);
+  });
+
+  it('applies the configured ignore list alongside the self-filter', () => {
+    expect(
+      filterUserMessages(messages, 'UTLDR', ['UCIBOT', 'BALERTS']).map((m) => m.ts)
+    ).toEqual(['1.0']);
+  });
+
+  it('drops earlier summaries whoever posted them', () => {
+    const summaries = [
+      { ts: '5.0', user: 'UHUMAN', text: '*Summary from <#C1>* · Oct 3\n\n*Summary*\n- x', files: [] },
+      { ts: '6.0', user: 'UHUMAN', text: '_Style: pirate_\n\n*Summary from <#C1>*\n\n- y', files: [] },
+      { ts: '7.0', user: 'UHUMAN', text: '(1/2) *Summary from <#C1>*\n\n- z', files: [] },
+      { ts: '8.0', user: 'UHUMAN', text: 'see the *Summary from <#C1>* above', files: [] },
+    ];
+    expect(filterUserMessages([...messages, ...summaries], null).map((m) => m.ts)).toEqual([
+      '1.0',
+      '2.0',
+      '3.0',
+      '4.0',
+      '8.0',
+    ]);
+  });
+
+  it('keeps one copy of a thread reply that was also broadcast to the channel', () => {
+    const thread = [
+      { ts: '10.0', user: 'UHUMAN', text: 'deploy?', files: [], threadTs: '10.0', replyCount: 1 },
+      { ts: '11.0', user: 'UOPS', text: 'shipped', files: [], threadTs: '10.0' },
+      { ts: '11.0', user: 'UOPS', text: 'shipped', files: [], threadTs: '10.0', broadcast: true },
+      { ts: '12.0', user: 'UOPS', text: 'also', files: [], threadTs: '10.0', broadcast: true },
+    ];
+    const kept = filterUserMessages(thread, 'UTLDR');
+    expect(kept.map((m) => m.ts)).toEqual(['10.0', '11.0', '12.0']);
+    expect(kept[1].broadcast).toBeUndefined();
+  });
+
+  it('still applies the ignore list when the bot id is unknown', () => {
+    expect(filterUserMessages(messages, null, ['UCIBOT']).map((m) => m.ts)).toEqual([
+      '1.0',
+      '2.0',
+      '4.0',
+    ]);
+  });
+});
+
+describe('filterMessagesByKeyword', () => {
+  const messages = [
+    { ts: '1.0', user: 'U1', text: 'The Deploy went out', files: [] },
+    { ts: '2.0', user: 'U1', text: 'unrelated', files: [] },
+    {
+      ts: '3.0',
+      user: 'U1',
+      text: '',
+      files: [],
+      blocks: [
+        {
+          type: 'rich_text',
+          elements: [{ type: 'rich_text_section', elements: [{ type: 'text', text: 'deploy notes' }] }],
+        },
+      ],
+    },
+  ];
+
+  it('matches case-insensitively, including rich_text-only messages', () => {
+    expect(filterMessagesByKeyword(messages, 'deploy').map((m) => m.ts)).toEqual(['1.0', '3.0']);
+  });
+
+  it('returns nothing when no message matches', () => {
+    expect(filterMessagesByKeyword(messages, 'incident')).toEqual([]);
+  });
+
+  it('passes everything through without a keyword', () => {
+    expect(filterMessagesByKeyword(messages, null)).toBe(messages);
+    expect(filterMessagesByKeyword(messages, '  ')).toBe(messages);
+  });
+});
+
+describe('downloadInlineImages', () => {
+  const PNG_SIGNATURE = [0x89, 0x50, 0x4e, 0x47, 0x0d, 0x0a, 0x1a, 0x0a];
+  const pngBytes = (marker: number) => new Uint8Array([...PNG_SIGNATURE, marker]);
+  const imageMessages = (count: number) =>
+    Array.from({ length: count }, (_, i) => ({
+      ts: `${i + 1}.0`,
+      user: 'U1',
+      text: '',
+      files: [{ urlPrivateDownload: `https://files.example/${i}`, urlPrivate: null, mimeType: 'image/png' }],
+    }));
+
+  /** Fake fetch that records peak parallel GETs; file `i` takes `delays[i]` ms. */
+  function countingFetch(delays: number[]) {
+    let inFlight = 0;
+    let peak = 0;
+    const fetchImpl = (async (url: string, init?: RequestInit) => {
+      if (init?.method === 'HEAD') {
+        return new Response(null, { headers: { 'content-type': 'image/png' } });
+      }
+      const index = Number(url.split('/').pop());
+      inFlight += 1;
+      peak = Math.max(peak, inFlight);
+      await new Promise((resolve) => setTimeout(resolve, delays[index] ?? 1));
+      inFlight -= 1;
+      return new Response(pngBytes(index));
+    }) as unknown as typeof fetch;
+    return { fetchImpl, peak: () => peak };
+  }
+
+  it('never runs more downloads than the configured concurrency', async () => {
+    const fake = countingFetch([5, 5, 5, 5, 5, 5]);
+    const images = await downloadInlineImages({
+      messages: imageMessages(6),
+      botToken: 'xoxb-test',
+      concurrency: 2,
+      fetchImpl: fake.fetchImpl,
+    });
+    expect(images).toHaveLength(6);
+    expect(fake.peak()).toBe(2);
+  });
+
+  it('keeps message order even when later downloads finish first', async () => {
+    const fake = countingFetch([30, 1, 10]);
+    const images = await downloadInlineImages({
+      messages: imageMessages(3),
+      botToken: 'xoxb-test',
+      concurrency: 3,
+      fetchImpl: fake.fetchImpl,
+    });
+    const markers = images.map((img) => {
+      const bytes = Buffer.from(img.source.data, 'base64');
+      return bytes[bytes.length - 1];
+    });
+    expect(markers).toEqual([0, 1, 2]);
+  });
+
+  it('caps the total and skips files that are not images', async () => {
+    const fake = countingFetch([]);
+    const messages = [
+      {
+        ts: '0.5',
+        user: 'U1',
+        text: '',
+        files: [{ urlPrivateDownload: 'https://files.example/doc', urlPrivate: null, mimeType: 'application/pdf' }],
+      },
+      ...imageMessages(MAX_IMAGES_TOTAL + 2),
+    ];
+    const images = await downloadInlineImages({
+      messages,
+      botToken: 'xoxb-test',
+      concurrency: 3,
+      fetchImpl: fake.fetchImpl,
+    });
+    expect(images).toHaveLength(MAX_IMAGES_TOTAL);
+  });
+
+  it('drops images over the configured byte cap', async () => {
+    const fetchImpl = (async (url: string, init?: RequestInit) => {
+      if (init?.method === 'HEAD') {
+        return new Response(null, { headers: { 'content-type': 'image/png' } });
+      }
+      const index = Number(url.split('/').pop());
+      const padding = new Array(index === 1 ? 64 : 0).fill(0);
+      return new Response(new Uint8Array([...PNG_SIGNATURE, index, ...padding]));
+    }) as unknown as typeof fetch;
+    const images = await downloadInlineImages({
+      messages: imageMessages(3),
+      botToken: 'xoxb-test',
+      concurrency: 3,
+      maxBytes: 32,
+      fetchImpl,
+    });
+    const markers = images.map((img) => Buffer.from(img.source.data, 'base64')[8]);
+    expect(markers).toEqual([0, 2]);
+  });
+});
+
+describe('formatMessagesForPrompt', () => {
+  const names = new Map([
+    ['U1', 'Alice'],
+    ['U2', 'Bob'],
+  ]);
+  const msg = (ts: string, user: string, text: string) => ({ ts, user, text, files: [] });
+
+  it('formats plain history without a topic label', () => {
+    const lines = formatMessagesForPrompt([msg('1.0', 'U1', 'hi'), msg('2.0', 'U2', 'yo')], names);
+    expect(lines).toEqual(['[1.0] Alice: hi', '[2.0] Bob: yo']);
+  });
+
+  it('falls back to rich_text blocks when message text is empty', () => {
+    const blocks = [
+      {
+        type: 'rich_text',
+        elements: [{ type: 'rich_text_section', elements: [{ type: 'text', text: 'from blocks' }] }],
+      },
+    ];
+    const lines = formatMessagesForPrompt([{ ts: '1.0', user: 'U1', text: '', files: [], blocks }], names);
+    expect(lines).toEqual(['[1.0] Alice: from blocks']);
+  });
+
+  it('appends link-unfurl text unless disabled', () => {
+    const attachments = [{ title: 'Q3 roadmap', text: 'What we ship next', fallback: 'roadmap' }];
+    const message = { ts: '1.0', user: 'U1', text: 'see this', files: [], attachments };
+    expect(formatMessagesForPrompt([message], names)).toEqual([
+      '[1.0] Alice: see this [attachment: Q3 roadmap — What we ship next]',
+    ]);
+    expect(formatMessagesForPrompt([message], names, null, false)).toEqual(['[1.0] Alice: see this']);
+  });
+
+  it('marks edited messages and leaves unedited ones alone', () => {
+    const lines = formatMessagesForPrompt(
+      [{ ...msg('1.0', 'U1', 'ship friday'), edited: true }, msg('2.0', 'U2', 'ok')],
+      names
+    );
+    expect(lines).toEqual(['[1.0] Alice: ship friday (edited)', '[2.0] Bob: ok']);
+  });
+
+  it('labels the thread root once and drops a duplicated root', () => {
+    const lines = formatMessagesForPrompt(
+      [msg('1.0', 'U1', 'launch plan?'), msg('1.0', 'U1', 'launch plan?'), msg('2.0', 'U2', 'friday')],
+      names,
+      '1.0'
+    );
+    expect(lines).toEqual(['Thread topic: [1.0] Alice: launch plan?', '[2.0] Bob: friday']);
+    expect(lines.filter((l) => l.startsWith('Thread topic:'))).toHaveLength(1);
+  });
 });
@@ -0,0 +1,33 @@
+import { topReacted, totalReactions } from '../../src/worker/reactions';
+
+const msg = (ts: string, ...counts: number[]) => ({
+  ts,
+  reactions: counts.map((count, i) => ({ name: `emoji${i}`, count })),
+});
+
+describe('totalReactions', () => {
+  it('sums counts across every emoji', () => {
+    expect(totalReactions(msg('1.0', 3, 2))).toBe(5);
+    expect(totalReactions({ ts: '1.0' })).toBe(0);
+  });
+});
+
+describe('topReacted', () => {
+  it('ranks messages by total reaction count', () => {
+    const messages = [msg('1.0', 1), msg('2.0', 4, 1), msg('3.0', 2), msg('4.0', 9)];
+    expect(topReacted(messages, 3).map((m) => m.ts)).toEqual(['4.0', '2.0', '3.0']);
+  });
+
+  it('breaks ties by the earlier timestamp', () => {
+    const messages = [msg('1700000300.000000', 2), msg('1700000100.000000', 2), msg('1700000200.000000', 1, 1)];
+    expect(topReacted(messages, 3).map((m) => m.ts)).toEqual([
+      '1700000100.000000',
+      '1700000200.000000',
+      '1700000300.000000',
+    ]);
+  });
+
+  it('skips messages without reactions', () => {
+    expect(topReacted([{ ts: '1.0' }, msg('2.0'), msg('3.0', 1)], 3).map((m) => m.ts)).toEqual(['3.0']);
+  });
+});
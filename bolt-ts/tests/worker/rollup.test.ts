@@ -0,0 +1,55 @@
+import { LlmClient, MAX_ROLLUP_WINDOWS, TOO_LARGE_MESSAGE } from '../../src/ai/anthropic';
+import { estimateTokens, summarizeInWindows, windowMessagesByTokens } from '../../src/worker/rollup';
+
+describe('windowMessagesByTokens', () => {
+  it('keeps every window within the token estimate and preserves order', () => {
+    const lines = Array.from({ length: 50 }, (_, i) => `[${i}] alice: ${'x'.repeat(40)}`);
+    const windows = windowMessagesByTokens(lines, 60);
+    expect(windows.length).toBeGreaterThan(1);
+    for (const window of windows) {
+      const tokens = window.reduce((sum, line) => sum + estimateTokens(line) + 1, 0);
+      expect(tokens).toBeLessThanOrEqual(60);
+    }
+    expect(windows.flat()).toEqual(lines);
+  });
+
+  it('gives an oversized line a window of its own', () => {
+    const big = 'y'.repeat(1000);
+    expect(windowMessagesByTokens(['a', big, 'b'], 50)).toEqual([['a'], [big], ['b']]);
+  });
+
+  it('returns no windows for no lines', () => {
+    expect(windowMessagesByTokens([], 100)).toEqual([]);
+  });
+});
+
+describe('summarizeInWindows', () => {
+  const promptArgs = {
+    channelName: 'demo',
+    formattedMessages: Array.from({ length: 40 }, (_, i) => `[${i}] bob: ${'z'.repeat(80)}`),
+    linksShared: [],
+    receipts: [],
+    images: [],
+    customStyle: null,
+  };
+
+  it('caps map passes and feeds partials into a single reduce pass', async () => {
+    const llm = new LlmClient({ apiKey: 'sk-ant' });
+    const generate = jest
+      .spyOn(llm, 'generateSummary')
+      .mockImplementation(async (prompt) =>
+        JSON.stringify(prompt).includes('Partial summary') ? 'final' : 'partial'
+      );
+
+    const result = await summarizeInWindows(llm, promptArgs, 30);
+
+    expect(result).toBe('final');
+    expect(generate).toHaveBeenCalledTimes(MAX_ROLLUP_WINDOWS + 1);
+  });
+
+  it('falls back to the too-large message when every window fails', async () => {
+    const llm = new LlmClient({ apiKey: 'sk-ant' });
+    jest.spyOn(llm, 'generateSummary').mockResolvedValue(TOO_LARGE_MESSAGE);
+    await expect(summarizeInWindows(llm, promptArgs, 30)).resolves.toBe(TOO_LARGE_MESSAGE);
+  });
+});
@@ -0,0 +1,59 @@
+import type { RecentMessage } from '../../src/slack/client';
+import { formatAuthorGroups, groupMessagesByAuthor } from '../../src/worker/standup';
+
+function msg(ts: string, user: string | null, text: string): RecentMessage {
+  return { ts, user, text, files: [] };
+}
+
+const userNames = new Map([
+  ['U1', 'Alice'],
+  ['U2', 'Bob'],
+]);
+
+const formatLines = (messages: RecentMessage[]): string[] =>
+  messages.map((m) => `[${m.ts}] ${m.text}`);
+
+describe('groupMessagesByAuthor', () => {
+  it('produces one group per author, ordered by their first message', () => {
+    // conversations.history returns newest first.
+    const history = [
+      msg('4.0', 'U1', 'today: review PRs'),
+      msg('3.0', 'U2', 'blocked on staging'),
+      msg('2.0', 'U1', 'yesterday: shipped search'),
+      msg('1.0', 'U2', 'yesterday: on-call'),
+    ];
+
+    const groups = groupMessagesByAuthor(history, userNames);
+
+    expect(groups.map((g) => g.author)).toEqual(['Bob', 'Alice']);
+    expect(groups[0].messages.map((m) => m.ts)).toEqual(['1.0', '3.0']);
+    expect(groups[1].messages.map((m) => m.ts)).toEqual(['2.0', '4.0']);
+  });
+
+  it('falls back to the user id and groups messages without one as Unknown User', () => {
+    const groups = groupMessagesByAuthor(
+      [msg('1.0', 'U9', 'hi'), msg('2.0', null, 'a'), msg('3.0', null, 'b')],
+      userNames
+    );
+    expect(groups.map((g) => [g.author, g.messages.length])).toEqual([
+      ['U9', 1],
+      ['Unknown User', 2],
+    ]);
+  });
+});
+
+describe('formatAuthorGroups', () => {
+  it('emits one Author block per person with their messages indented', () => {
+    const groups = groupMessagesByAuthor(
+      [msg('1.0', 'U1', 'a1'), msg('2.0', 'U2', 'b1'), msg('3.0', 'U1', 'a2')],
+      userNames
+    );
+    expect(formatAuthorGroups(groups, formatLines)).toEqual([
+      'Author: Alice',
+      '  [1.0] a1',
+      '  [3.0] a2',
+      'Author: Bob',
+      '  [2.0] b1',
+    ]);
+  });
+});
@@ -1,4 +1,24 @@
-import { buildStreamPrefix } from '../../src/worker/streaming';
+import { EventEmitter } from 'events';
+import { APIConnectionTimeoutError } from '@anthropic-ai/sdk';
+import { WebClientEvent, type WebClient } from '@slack/web-api';
+import { LlmTimeoutError, RefusalError } from '../../src/ai/anthropic';
+import { DeadlineExceededError } from '../../src/deadline';
+import {
+  CANONICAL_FAILURE_MESSAGE,
+  DEADLINE_FAILURE_MESSAGE,
+  MAX_ESTIMATED_PROGRESS,
+  PROGRESS_STATUS_INTERVAL_MS,
+  TIMEOUT_FAILURE_MESSAGE,
+  buildStreamPrefix,
+  estimateExpectedOutputChars,
+  estimateProgressPercent,
+  failureMessageFor,
+  newProgressReporter,
+  cooldownRemainingMs,
+  newAppendCooldown,
+  noteRateLimited,
+  watchAppendRateLimits,
+} from '../../src/worker/streaming';
 
 describe('buildStreamPrefix', () => {
   it('includes only the channel header when no style is set', () => {
@@ -23,4 +43,102 @@ describe('buildStreamPrefix', () => {
   it('drops empty/whitespace styles', () => {
     expect(buildStreamPrefix('C1', '   ')).toBe('*Summary from <#C1>*\n\n');
   });
+
+  it('appends the header date to the title line', () => {
+    expect(buildStreamPrefix('C1', null, 'Thu, Oct 15, 2026')).toBe(
+      '*Summary from <#C1>* · Thu, Oct 15, 2026\n\n'
+    );
+  });
+});
+
+describe('append cooldown', () => {
+  it('holds every later append until the Retry-After window passes', () => {
+    const cooldown = newAppendCooldown();
+    expect(cooldownRemainingMs(cooldown, 1_000)).toBe(0);
+
+    noteRateLimited(cooldown, 2, 1_000);
+    expect(cooldownRemainingMs(cooldown, 1_000)).toBe(2_000);
+    expect(cooldownRemainingMs(cooldown, 2_500)).toBe(500);
+    expect(cooldownRemainingMs(cooldown, 3_000)).toBe(0);
+
+    // A fresh 429 restarts the window from when it arrived.
+    noteRateLimited(cooldown, 1, 3_500);
+    expect(cooldownRemainingMs(cooldown, 4_000)).toBe(500);
+  });
+
+  it('picks up appendStream rate limits from the client and ignores other methods', () => {
+    const emitter = new EventEmitter();
+    const client = emitter as unknown as WebClient;
+    const cooldown = newAppendCooldown();
+    const unwatch = watchAppendRateLimits(client, cooldown);
+
+    emitter.emit(WebClientEvent.RATE_LIMITED, 5, { url: 'chat.postMessage' });
+    expect(cooldown.last429At).toBeNull();
+
+    emitter.emit(WebClientEvent.RATE_LIMITED, 3, { url: 'chat.appendStream' });
+    expect(cooldown.retryAfterMs).toBe(3_000);
+    expect(cooldownRemainingMs(cooldown)).toBeGreaterThan(0);
+
+    unwatch();
+    expect(emitter.listenerCount(WebClientEvent.RATE_LIMITED)).toBe(0);
+  });
+
+  it('tolerates clients that are not event emitters', () => {
+    const unwatch = watchAppendRateLimits({} as WebClient, newAppendCooldown());
+    expect(unwatch).not.toThrow();
+  });
+});
+
+describe('failureMessageFor', () => {
+  it('tells the user to try fewer messages when Anthropic or Slack times out', () => {
+    const slackTimeout = Object.assign(new Error('request failed'), {
+      code: 'slack_webapi_request_error',
+      original: Object.assign(new Error('socket hang up'), { code: 'ETIMEDOUT' }),
+    });
+    expect(failureMessageFor(new APIConnectionTimeoutError())).toBe(TIMEOUT_FAILURE_MESSAGE);
+    expect(failureMessageFor(new LlmTimeoutError('stream stalled'))).toBe(TIMEOUT_FAILURE_MESSAGE);
+    expect(failureMessageFor(slackTimeout)).toBe(TIMEOUT_FAILURE_MESSAGE);
+  });
+
+  it('keeps the deadline, refusal, and canonical wording for other failures', () => {
+    expect(failureMessageFor(new DeadlineExceededError())).toBe(DEADLINE_FAILURE_MESSAGE);
+    expect(failureMessageFor(new RefusalError())).toBe(
+      "I couldn't summarize this content: the model declined to process it."
+    );
+    expect(failureMessageFor(new Error('boom'))).toBe(CANONICAL_FAILURE_MESSAGE);
+  });
+});
+
+describe('streaming progress estimate', () => {
+  it('scales the expected length with the input, within a typical summary size', () => {
+    expect(estimateExpectedOutputChars(1_000, 16_000)).toBe(300 * 4);
+    expect(estimateExpectedOutputChars(20_000, 16_000)).toBe(1_000 * 4);
+    expect(estimateExpectedOutputChars(500_000, 16_000)).toBe(1_500 * 4);
+    expect(estimateExpectedOutputChars(500_000, 800)).toBe(800 * 4);
+  });
+
+  it('reports the share written so far and never claims to be done', () => {
+    expect(estimateProgressPercent(0, 4_000)).toBe(0);
+    expect(estimateProgressPercent(2_400, 4_000)).toBe(60);
+    expect(estimateProgressPercent(9_000, 4_000)).toBe(MAX_ESTIMATED_PROGRESS);
+    expect(estimateProgressPercent(100, 0)).toBe(0);
+  });
+
+  it('throttles status updates and only reports rising percentages', () => {
+    let clock = 0;
+    const setStatus = jest.fn().mockResolvedValue(true);
+    const report = newProgressReporter(setStatus, 1_000, () => clock);
+
+    report(300);
+    clock += PROGRESS_STATUS_INTERVAL_MS;
+    report(300);
+    clock += 1;
+    report(600);
+    clock += PROGRESS_STATUS_INTERVAL_MS;
+    report(200);
+    clock += PROGRESS_STATUS_INTERVAL_MS;
+    report(600);
+
+    expect(setStatus.mock.calls).toEqual([['Writing summary… 30%'], ['Writing summary… 60%']]);
+  });
 });
@@ -0,0 +1,56 @@
+import { parseStructuredSummary } from '../../src/worker/structured';
+
+describe('parseStructuredSummary', () => {
+  it('splits a well-formed multi-section summary into its sections', () => {
+    const text = [
+      '*Summary*',
+      'The team decided to ship the onboarding flow on Friday.',
+      'Alex will draft release notes.',
+      '',
+      '*Action items*',
+      '- Alex: draft release notes',
+      '- Sam: run the metrics review',
+      '',
+      '*Links shared*',
+      '- [PR] <https://github.com/acme/app/pull/7|Fix login redirect>',
+      '- <https://example.com/spec|Onboarding spec>',
+      '',
+      '*Files shared*',
+      '- launch-plan.pdf (pdf)',
+      '',
+      '*Image highlights*',
+      '- A redesigned welcome screen with one "Get started" button.',
+      '',
+      '*Receipts*',
+      '- <https://acme.slack.com/archives/C123/p1700000000|Alex>: "ship Friday"',
+    ].join('\n');
+
+    expect(parseStructuredSummary(text)).toEqual({
+      summary:
+        'The team decided to ship the onboarding flow on Friday.\nAlex will draft release notes.',
+      actionItems: ['Alex: draft release notes', 'Sam: run the metrics review'],
+      links: [
+        '[PR] <https://github.com/acme/app/pull/7|Fix login redirect>',
+        '<https://example.com/spec|Onboarding spec>',
+      ],
+      filesShared: ['launch-plan.pdf (pdf)'],
+      imageHighlights: ['A redesigned welcome screen with one "Get started" button.'],
+      receipts: ['<https://acme.slack.com/archives/C123/p1700000000|Alex>: "ship Friday"'],
+    });
+  });
+
+  it('drops placeholder bullets and accepts Markdown-style headers', () => {
+    const text = '**Summary**\nQuiet day.\n\n## Image highlights\n- None\n\n*Receipts*\n- Disabled';
+    const parsed = parseStructuredSummary(text);
+    expect(parsed.summary).toBe('Quiet day.');
+    expect(parsed.imageHighlights).toEqual([]);
+    expect(parsed.receipts).toEqual([]);
+  });
+
+  it('keeps standup bullets and bold names inside the summary prose', () => {
+    const text = '*Summary*\n- *Alex* — Yesterday: tests · Today: deploy · Blockers: —\n\n*Receipts*\n- None';
+    expect(parseStructuredSummary(text).summary).toBe(
+      '- *Alex* — Yesterday: tests · Today: deploy · Blockers: —'
+    );
+  });
+});
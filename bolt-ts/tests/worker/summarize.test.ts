@@ -1,24 +1,73 @@
 import type { WebClient } from '@slack/web-api';
-import { runSummarization } from '../../src/worker/summarize';
-import { LlmClient } from '../../src/ai/anthropic';
-import type { AppConfig } from '../../src/config';
+import {
+  messageCapNotice,
+  resolveHeaderDate,
+  runSummarization,
+} from '../../src/worker/summarize';
+import { DEADLINE_FAILURE_MESSAGE } from '../../src/worker/streaming';
+import {
+  IMAGES_DISABLED_MESSAGE,
+  NO_CANVAS_MESSAGE,
+  NO_NEW_MESSAGES_MESSAGE,
+  NO_PINNED_MESSAGES_MESSAGE,
+} from '../../src/worker/prompt_builder';
+import { createDeadline, DeadlineExceededError, type Deadline } from '../../src/deadline';
+import { LlmClient, RefusalError, TOO_LARGE_MESSAGE } from '../../src/ai/anthropic';
+import { DEFAULT_SUMMARY_FOOTER, type AppConfig } from '../../src/config';
 
 function makeConfig(overrides: Partial<AppConfig> = {}): AppConfig {
   return {
     slackBotToken: 'xoxb',
     slackSigningSecret: 'sig',
+    slackUserToken: null,
+    slackAppToken: null,
     anthropicApiKey: 'sk-ant',
     anthropicModel: 'claude-test',
     anthropicMaxOutputTokens: 4096,
+    anthropicBaseUrl: null,
+    anthropicTemperature: null,
+    anthropicStyleTemperature: null,
+    anthropicReasoningEffort: null,
     enableStreaming: false,
     streamMaxChunkChars: 4000,
     streamMinAppendIntervalMs: 0,
+    streamStallTimeoutMs: 120_000,
+    streamMaxBufferBytes: 1_048_576,
+    staleStreamMaxAgeSecs: 900,
+    enableReceipts: true,
+    includeAttachmentText: true,
+    disableImages: false,
+    citeInline: false,
+    summarySections: ['Summary', 'Links shared', 'Image highlights', 'Receipts'],
+    detectSummaryLanguage: false,
+    postAsUser: false,
+    unfurlSummaryLinks: false,
+    canvasToc: false,
+    summaryFooter: null,
+    maxRequestBodyBytes: 1_048_576,
+    imageDownloadConcurrency: 3,
+    inlineImageMaxBytes: 4_194_304,
+    permalinkConcurrency: 4,
+    maxConcurrentSummaries: 4,
+    hardMessageCap: 1000,
+    ignoredUserIds: [],
+    defaultTimezone: 'UTC',
+    maxCustomStyleChars: 500,
+    noMessagesMessage: 'No messages found to summarize.',
+    systemPromptOverride: null,
+    assistantName: 'TLDR-bot',
+    taskDeadlineSecs: 840,
+    slackMaxRetries: 5,
+    slackRetryBaseDelayMs: 100,
+    slackRetryMaxDelayMs: 5_000,
+    deliveryMaxRetries: 2,
     ...overrides,
   };
 }
 
 function makeWebClient(history: unknown[]): { client: WebClient; spies: Record<string, jest.Mock> } {
   const postMessage = jest.fn().mockResolvedValue({ ok: true, ts: '1.1' });
+  const chatUpdate = jest.fn().mockResolvedValue({ ok: true });
   const conversationsHistory = jest.fn().mockResolvedValue({ messages: history });
   const conversationsInfo = jest.fn().mockResolvedValue({ channel: { name: 'demo' } });
   const usersInfo = jest.fn().mockResolvedValue({ user: { profile: { real_name: 'Alice' } } });
@@ -26,7 +75,7 @@ function makeWebClient(history: unknown[]): { client: WebClient; spies: Record<s
   const chatGetPermalink = jest.fn().mockResolvedValue({ permalink: 'https://slack/p/1' });
 
   const client = {
-    chat: { postMessage, getPermalink: chatGetPermalink },
+    chat: { postMessage, update: chatUpdate, getPermalink: chatGetPermalink },
     conversations: { history: conversationsHistory, info: conversationsInfo },
     users: { info: usersInfo },
     auth: { test: authTest },
@@ -36,6 +85,7 @@ function makeWebClient(history: unknown[]): { client: WebClient; spies: Record<s
     client,
     spies: {
       postMessage,
+      chatUpdate,
       conversationsHistory,
       conversationsInfo,
       usersInfo,
@@ -49,6 +99,37 @@ function makeLlm(): LlmClient {
   return new LlmClient({ apiKey: 'sk-ant', model: 'claude-test' });
 }
 
+describe('resolveHeaderDate', () => {
+  // 2026-10-15T02:30:00Z: already Oct 15 in UTC, still Oct 14 in Los Angeles.
+  const now = new Date(Date.UTC(2026, 9, 15, 2, 30));
+  const clientWithZone = (tz: string | undefined): WebClient =>
+    ({ users: { info: jest.fn().mockResolvedValue({ user: { tz } }) } }) as unknown as WebClient;
+  const requester = { userId: 'U1', timezone: null };
+
+  it("prefers the requester's Slack time zone", async () => {
+    await expect(
+      resolveHeaderDate(clientWithZone('America/Los_Angeles'), requester, 'UTC', now)
+    ).resolves.toBe('Wed, Oct 14, 2026');
+  });
+
+  it('lets a tz= override win over the Slack zone', async () => {
+    const client = clientWithZone('America/Los_Angeles');
+    await expect(
+      resolveHeaderDate(client, { userId: 'U1', timezone: 'Asia/Tokyo' }, 'UTC', now)
+    ).resolves.toBe('Thu, Oct 15, 2026');
+    expect(client.users.info).not.toHaveBeenCalled();
+  });
+
+  it('falls back to the configured default zone', async () => {
+    await expect(resolveHeaderDate(clientWithZone(undefined), requester, 'UTC', now)).resolves.toBe(
+      'Thu, Oct 15, 2026'
+    );
+    await expect(resolveHeaderDate(clientWithZone('Not/AZone'), requester, 'Asia/Tokyo', now)).resolves.toBe(
+      'Thu, Oct 15, 2026'
+    );
+  });
+});
+
 describe('runSummarization (non-streaming)', () => {
   it('posts a no-messages reply when history is empty', async () => {
     const { client, spies } = makeWebClient([]);
@@ -63,6 +144,27 @@ describe('runSummarization (non-streaming)', () => {
         threadTs: '1.0',
         messageCount: 25,
         customStyle: null,
+        sourceThreadTs: null,
+        groupByThread: false,
+        includeBots: false,
+        filesOnly: false,
+        template: null,
+        notifyMe: false,
+        keywordFilter: null,
+        pinnedOnly: false,
+        canvasOnly: false,
+        teamId: null,
+        enterpriseId: null,
+        postChannelId: null,
+        replaceTs: null,
+        timezone: null,
+        beforeTs: null,
+        afterTs: null,
+        sinceLastSummary: false,
+        skipImages: false,
+        bookmark: false,
+        allowMentions: false,
+        tone: null,
       },
       llm: makeLlm(),
     });
@@ -75,6 +177,615 @@ describe('runSummarization (non-streaming)', () => {
     );
   });
 
+  it('posts the configured no-messages reply on both delivery paths', async () => {
+    for (const enableStreaming of [false, true]) {
+      const { client, spies } = makeWebClient([]);
+      await runSummarization({
+        config: makeConfig({ enableStreaming, noMessagesMessage: 'Quiet in here today.' }),
+        client,
+        request: {
+          correlationId: 'cid',
+          userId: 'U1',
+          channelId: 'C1',
+          originChannelId: 'D1',
+          threadTs: '1.0',
+          messageCount: 25,
+          customStyle: null,
+          sourceThreadTs: null,
+          groupByThread: false,
+          includeBots: false,
+          filesOnly: false,
+          template: null,
+          notifyMe: false,
+          keywordFilter: null,
+          pinnedOnly: false,
+          canvasOnly: false,
+          teamId: null,
+          enterpriseId: null,
+          postChannelId: null,
+          replaceTs: null,
+          timezone: null,
+          beforeTs: null,
+          afterTs: null,
+          sinceLastSummary: false,
+          skipImages: false,
+          bookmark: false,
+          allowMentions: false,
+          tone: null,
+        },
+        llm: makeLlm(),
+      });
+      expect(spies.postMessage).toHaveBeenCalledWith(
+        expect.objectContaining({ channel: 'D1', text: 'Quiet in here today.' })
+      );
+    }
+  });
+
+  it('explains an empty keyword filter instead of calling the model', async () => {
+    for (const enableStreaming of [false, true]) {
+      const { client, spies } = makeWebClient([
+        { ts: '1', user: 'U1', text: 'lunch plans', files: [] },
+      ]);
+      const llm = makeLlm();
+      const generate = jest.spyOn(llm, 'generateSummary');
+      await runSummarization({
+        config: makeConfig({ enableStreaming }),
+        client,
+        request: {
+          correlationId: 'cid',
+          userId: 'U1',
+          channelId: 'C1',
+          originChannelId: 'D1',
+          threadTs: '1.0',
+          messageCount: 25,
+          customStyle: null,
+          sourceThreadTs: null,
+          groupByThread: false,
+          includeBots: false,
+          filesOnly: false,
+          template: null,
+          notifyMe: false,
+          keywordFilter: 'deploy',
+          pinnedOnly: false,
+          canvasOnly: false,
+          teamId: null,
+          enterpriseId: null,
+          postChannelId: null,
+          replaceTs: null,
+          timezone: null,
+          beforeTs: null,
+          afterTs: null,
+          sinceLastSummary: false,
+          skipImages: false,
+          bookmark: false,
+          allowMentions: false,
+          tone: null,
+        },
+        llm,
+      });
+      expect(generate).not.toHaveBeenCalled();
+      expect(spies.postMessage).toHaveBeenCalledWith(
+        expect.objectContaining({
+          channel: 'D1',
+          text: expect.stringContaining('No messages mentioning "deploy"'),
+        })
+      );
+    }
+  });
+
+  it('explains a channel with no pins on a pinned-only run', async () => {
+    const { client, spies } = makeWebClient([{ ts: '1', user: 'U1', text: 'recent', files: [] }]);
+    const pinsList = jest.fn().mockResolvedValue({ ok: true, items: [] });
+    (client as unknown as { pins: unknown }).pins = { list: pinsList };
+    await runSummarization({
+      config: makeConfig(),
+      client,
+      request: {
+        correlationId: 'cid',
+        userId: 'U1',
+        channelId: 'C1',
+        originChannelId: 'D1',
+        threadTs: '1.0',
+        messageCount: 25,
+        customStyle: null,
+        sourceThreadTs: null,
+        groupByThread: false,
+        includeBots: false,
+        filesOnly: false,
+        template: null,
+        notifyMe: false,
+        keywordFilter: null,
+        pinnedOnly: true,
+        canvasOnly: false,
+        teamId: null,
+        enterpriseId: null,
+        postChannelId: null,
+        replaceTs: null,
+        timezone: null,
+        beforeTs: null,
+        afterTs: null,
+        sinceLastSummary: false,
+        skipImages: false,
+        bookmark: false,
+        allowMentions: false,
+        tone: null,
+      },
+      llm: makeLlm(),
+    });
+    expect(pinsList).toHaveBeenCalledWith({ channel: 'C1' });
+    expect(spies.conversationsHistory).not.toHaveBeenCalled();
+    expect(spies.postMessage).toHaveBeenCalledWith(
+      expect.objectContaining({ channel: 'D1', text: NO_PINNED_MESSAGES_MESSAGE })
+    );
+  });
+
+  it("reads only what's new since the thread's last summary of the channel", async () => {
+    const { client, spies } = makeWebClient([]);
+    const replies = jest.fn().mockResolvedValue({
+      messages: [
+        { ts: '1.0', text: 'root' },
+        {
+          ts: '2.0',
+          metadata: {
+            event_type: 'tldr_summary',
+            event_payload: { source_channel_id: 'C1', oldest_ts: '10.0', latest_ts: '50.0' },
+          },
+        },
+      ],
+    });
+    (client.conversations as unknown as { replies: unknown }).replies = replies;
+    await runSummarization({
+      config: makeConfig(),
+      client,
+      request: {
+        correlationId: 'cid',
+        userId: 'U1',
+        channelId: 'C1',
+        originChannelId: 'D1',
+        threadTs: '1.0',
+        messageCount: 25,
+        customStyle: null,
+        sourceThreadTs: null,
+        groupByThread: false,
+        includeBots: false,
+        filesOnly: false,
+        template: null,
+        notifyMe: false,
+        keywordFilter: null,
+        pinnedOnly: false,
+        canvasOnly: false,
+        teamId: null,
+        enterpriseId: null,
+        postChannelId: null,
+        replaceTs: null,
+        timezone: null,
+        beforeTs: null,
+        afterTs: null,
+        sinceLastSummary: true,
+        skipImages: false,
+        bookmark: false,
+        allowMentions: false,
+        tone: null,
+      },
+      llm: makeLlm(),
+    });
+    expect(replies).toHaveBeenCalledWith(
+      expect.objectContaining({ channel: 'D1', ts: '1.0', include_all_metadata: true })
+    );
+    expect(spies.conversationsHistory).toHaveBeenCalledWith({
+      channel: 'C1',
+      limit: 25,
+      oldest: '50.0',
+    });
+    expect(spies.postMessage).toHaveBeenCalledWith(
+      expect.objectContaining({ channel: 'D1', text: NO_NEW_MESSAGES_MESSAGE })
+    );
+  });
+
+  it('clamps the requested count to the hard cap and tells the requester', async () => {
+    const { client, spies } = makeWebClient([]);
+    await runSummarization({
+      config: makeConfig({ hardMessageCap: 300 }),
+      client,
+      request: {
+        correlationId: 'cid',
+        userId: 'U1',
+        channelId: 'C1',
+        originChannelId: 'D1',
+        threadTs: '1.0',
+        messageCount: 500,
+        customStyle: null,
+        sourceThreadTs: null,
+        groupByThread: false,
+        includeBots: false,
+        filesOnly: false,
+        template: null,
+        notifyMe: false,
+        keywordFilter: null,
+        pinnedOnly: false,
+        canvasOnly: false,
+        teamId: null,
+        enterpriseId: null,
+        postChannelId: null,
+        replaceTs: null,
+        timezone: null,
+        beforeTs: null,
+        afterTs: null,
+        sinceLastSummary: false,
+        skipImages: false,
+        bookmark: false,
+        allowMentions: false,
+        tone: null,
+      },
+      llm: makeLlm(),
+    });
+    expect(spies.conversationsHistory).toHaveBeenCalledWith(
+      expect.objectContaining({ channel: 'C1', limit: 300 })
+    );
+    expect(spies.postMessage).toHaveBeenCalledWith({
+      channel: 'D1',
+      thread_ts: '1.0',
+      text: messageCapNotice(500, 300),
+    });
+  });
+
+  it('reads history after an after= bound, keeping the later of it and the last summary', async () => {
+    for (const [afterTs, oldest] of [
+      ['60.0', '60.0'],
+      ['40.0', '50.0'],
+    ]) {
+      const { client, spies } = makeWebClient([]);
+      (client.conversations as unknown as { replies: unknown }).replies = jest.fn().mockResolvedValue({
+        messages: [
+          {
+            ts: '2.0',
+            metadata: {
+              event_type: 'tldr_summary',
+              event_payload: { source_channel_id: 'C1', oldest_ts: '10.0', latest_ts: '50.0' },
+            },
+          },
+        ],
+      });
+      await runSummarization({
+        config: makeConfig(),
+        client,
+        request: {
+          correlationId: 'cid',
+          userId: 'U1',
+          channelId: 'C1',
+          originChannelId: 'D1',
+          threadTs: '1.0',
+          messageCount: 25,
+          customStyle: null,
+          sourceThreadTs: null,
+          groupByThread: false,
+          includeBots: false,
+          filesOnly: false,
+          template: null,
+          notifyMe: false,
+          keywordFilter: null,
+          pinnedOnly: false,
+          canvasOnly: false,
+          teamId: null,
+          enterpriseId: null,
+          postChannelId: null,
+          replaceTs: null,
+          timezone: null,
+          beforeTs: null,
+          afterTs,
+          sinceLastSummary: true,
+          skipImages: false,
+          bookmark: false,
+          allowMentions: false,
+          tone: null,
+        },
+        llm: makeLlm(),
+      });
+      expect(spies.conversationsHistory).toHaveBeenCalledWith({ channel: 'C1', limit: 25, oldest });
+    }
+  });
+
+  it('explains a channel without a canvas on both delivery paths', async () => {
+    for (const enableStreaming of [false, true]) {
+      const { client, spies } = makeWebClient([{ ts: '1', user: 'U1', text: 'recent', files: [] }]);
+      await runSummarization({
+        config: makeConfig({ enableStreaming }),
+        client,
+        request: {
+          correlationId: 'cid',
+          userId: 'U1',
+          channelId: 'C1',
+          originChannelId: 'D1',
+          threadTs: '1.0',
+          messageCount: 25,
+          customStyle: null,
+          sourceThreadTs: null,
+          groupByThread: false,
+          includeBots: false,
+          filesOnly: false,
+          template: null,
+          notifyMe: false,
+          keywordFilter: null,
+          pinnedOnly: false,
+          canvasOnly: true,
+          teamId: null,
+          enterpriseId: null,
+          postChannelId: null,
+          replaceTs: null,
+          timezone: null,
+          beforeTs: null,
+          afterTs: null,
+          sinceLastSummary: false,
+          skipImages: false,
+          bookmark: false,
+          allowMentions: false,
+          tone: null,
+        },
+        llm: makeLlm(),
+      });
+      expect(spies.conversationsHistory).not.toHaveBeenCalled();
+      expect(spies.postMessage).toHaveBeenCalledWith(
+        expect.objectContaining({ channel: 'D1', text: NO_CANVAS_MESSAGE })
+      );
+    }
+  });
+
+  it('declines to describe images when images are disabled', async () => {
+    const { client, spies } = makeWebClient([]);
+    const llm = makeLlm();
+    const generate = jest.spyOn(llm, 'generateSummary');
+    await runSummarization({
+      config: makeConfig({ disableImages: true }),
+      client,
+      request: {
+        correlationId: 'cid',
+        userId: 'U1',
+        channelId: 'C1',
+        originChannelId: 'D1',
+        threadTs: '1.0',
+        messageCount: 25,
+        customStyle: null,
+        sourceThreadTs: '1700000000.000100',
+        groupByThread: false,
+        includeBots: false,
+        filesOnly: true,
+        template: null,
+        notifyMe: false,
+        keywordFilter: null,
+        pinnedOnly: false,
+        canvasOnly: false,
+        teamId: null,
+        enterpriseId: null,
+        postChannelId: null,
+        replaceTs: null,
+        timezone: null,
+        beforeTs: null,
+        afterTs: null,
+        sinceLastSummary: false,
+        skipImages: false,
+        bookmark: false,
+        allowMentions: false,
+        tone: null,
+      },
+      llm,
+    });
+    expect(generate).not.toHaveBeenCalled();
+    expect(spies.postMessage).toHaveBeenCalledWith(
+      expect.objectContaining({ channel: 'D1', text: IMAGES_DISABLED_MESSAGE })
+    );
+  });
+
+  it('emits an EMF metrics line once the summary is posted', async () => {
+    const spy = jest.spyOn(console, 'log').mockImplementation(() => undefined);
+    try {
+      const { client } = makeWebClient([{ ts: '1', user: 'U1', text: 'hello world', files: [] }]);
+      const llm = makeLlm();
+      jest.spyOn(llm, 'generateSummary').mockResolvedValue('*Summary*\nthings');
+      await runSummarization({
+        config: makeConfig(),
+        client,
+        request: {
+          correlationId: 'cid',
+          userId: 'U1',
+          channelId: 'C1',
+          originChannelId: 'D1',
+          threadTs: '1.0',
+          messageCount: 25,
+          customStyle: null,
+          sourceThreadTs: null,
+          groupByThread: false,
+          includeBots: false,
+          filesOnly: false,
+          template: null,
+          notifyMe: false,
+          keywordFilter: null,
+          pinnedOnly: false,
+          canvasOnly: false,
+          teamId: null,
+          enterpriseId: null,
+          postChannelId: null,
+          replaceTs: null,
+          timezone: null,
+          beforeTs: null,
+          afterTs: null,
+          sinceLastSummary: false,
+          skipImages: false,
+          bookmark: false,
+          allowMentions: false,
+          tone: null,
+        },
+        llm,
+      });
+      const emf = spy.mock.calls
+        .map((c) => c[0])
+        .filter((line): line is string => typeof line === 'string' && line.includes('"_aws"'))
+        .map((line) => JSON.parse(line));
+      expect(emf).toHaveLength(1);
+      expect(emf[0]).toMatchObject({
+        Delivery: 'assistant_thread',
+        MessageCount: 1,
+        ImageCount: 0,
+        SummarySuccess: 1,
+      });
+      expect(emf[0].InputTokens).toBeGreaterThan(0);
+      expect(emf[0].OutputTokens).toBeGreaterThan(0);
+    } finally {
+      spy.mockRestore();
+    }
+  });
+
+  it('aborts gracefully on both delivery paths once the task deadline has passed', async () => {
+    for (const enableStreaming of [false, true]) {
+      const { client, spies } = makeWebClient([{ ts: '1', user: 'U1', text: 'hello', files: [] }]);
+      const llm = makeLlm();
+      const generateSummary = jest.spyOn(llm, 'generateSummary');
+      const generateSummaryStream = jest.spyOn(llm, 'generateSummaryStream');
+      await expect(
+        runSummarization({
+          config: makeConfig({ enableStreaming }),
+          client,
+          request: {
+            correlationId: 'cid',
+            userId: 'U1',
+            channelId: 'C1',
+            originChannelId: 'D1',
+            threadTs: '1.0',
+            messageCount: 25,
+            customStyle: null,
+            sourceThreadTs: null,
+            groupByThread: false,
+            includeBots: false,
+            filesOnly: false,
+            template: null,
+            notifyMe: false,
+            keywordFilter: null,
+            pinnedOnly: false,
+            canvasOnly: false,
+            teamId: null,
+            enterpriseId: null,
+            postChannelId: null,
+            replaceTs: null,
+            timezone: null,
+            beforeTs: null,
+            afterTs: null,
+            sinceLastSummary: false,
+            skipImages: false,
+            bookmark: false,
+            allowMentions: false,
+            tone: null,
+          },
+          llm,
+          deadline: createDeadline(0),
+        })
+      ).resolves.toBeUndefined();
+      expect(generateSummary).not.toHaveBeenCalled();
+      expect(generateSummaryStream).not.toHaveBeenCalled();
+      expect(spies.conversationsHistory).not.toHaveBeenCalled();
+      expect(spies.postMessage).toHaveBeenCalledWith(
+        expect.objectContaining({ channel: 'D1', text: DEADLINE_FAILURE_MESSAGE })
+      );
+    }
+  });
+
+  it('stops waiting on a slow history fetch once the deadline passes while streaming', async () => {
+    const { client, spies } = makeWebClient([]);
+    spies.conversationsHistory.mockReturnValue(new Promise(() => undefined));
+    const llm = makeLlm();
+    const generateSummaryStream = jest.spyOn(llm, 'generateSummaryStream');
+    await expect(
+      runSummarization({
+        config: makeConfig({ enableStreaming: true }),
+        client,
+        request: {
+          correlationId: 'cid',
+          userId: 'U1',
+          channelId: 'C1',
+          originChannelId: 'D1',
+          threadTs: '1.0',
+          messageCount: 25,
+          customStyle: null,
+          sourceThreadTs: null,
+          groupByThread: false,
+          includeBots: false,
+          filesOnly: false,
+          template: null,
+          notifyMe: false,
+          keywordFilter: null,
+          pinnedOnly: false,
+          canvasOnly: false,
+          teamId: null,
+          enterpriseId: null,
+          postChannelId: null,
+          replaceTs: null,
+          timezone: null,
+          beforeTs: null,
+          afterTs: null,
+          sinceLastSummary: false,
+          skipImages: false,
+          bookmark: false,
+          allowMentions: false,
+          tone: null,
+        },
+        llm,
+        deadline: createDeadline(20),
+      })
+    ).resolves.toBeUndefined();
+    expect(spies.conversationsHistory).toHaveBeenCalled();
+    expect(generateSummaryStream).not.toHaveBeenCalled();
+    expect(spies.postMessage).toHaveBeenCalledWith(
+      expect.objectContaining({ channel: 'D1', text: DEADLINE_FAILURE_MESSAGE })
+    );
+  });
+
+  it('keeps ignored integration messages but still drops its own when includeBots is set', async () => {
+    const messages = [
+      { ts: '1', user: 'U1', text: 'hello world', files: [] },
+      { ts: '2', user: 'UCIBOT', text: 'build failed on main', files: [] },
+      { ts: '3', user: 'UBOT', text: 'previous tldr summary', files: [] },
+    ];
+    const promptFor = async (includeBots: boolean): Promise<string> => {
+      const { client } = makeWebClient(messages);
+      const llm = makeLlm();
+      const generate = jest.spyOn(llm, 'generateSummary').mockResolvedValue('*Summary*\nthings');
+      await runSummarization({
+        config: makeConfig({ ignoredUserIds: ['UCIBOT'] }),
+        client,
+        request: {
+          correlationId: 'cid',
+          userId: 'U1',
+          channelId: 'C123',
+          originChannelId: 'D1',
+          threadTs: '1.0',
+          messageCount: 25,
+          customStyle: null,
+          sourceThreadTs: null,
+          groupByThread: false,
+          includeBots,
+          postChannelId: null,
+          replaceTs: null,
+          timezone: null,
+          beforeTs: null,
+          afterTs: null,
+          sinceLastSummary: false,
+          skipImages: false,
+          bookmark: false,
+          allowMentions: false,
+          tone: null,
+        },
+        llm,
+      });
+      return JSON.stringify(generate.mock.calls[0][0]);
+    };
+
+    const withBots = await promptFor(true);
+    expect(withBots).toContain('build failed on main');
+    expect(withBots).not.toContain('previous tldr summary');
+
+    const withoutBots = await promptFor(false);
+    expect(withoutBots).not.toContain('build failed on main');
+    expect(withoutBots).toContain('hello world');
+  });
+
   it('runs the full non-streaming flow and posts the summary with action buttons', async () => {
     const messages = [{ ts: '1', user: 'U1', text: 'hello world', files: [] }];
     const { client, spies } = makeWebClient(messages);
@@ -93,6 +804,27 @@ describe('runSummarization (non-streaming)', () => {
         threadTs: '1.0',
         messageCount: 25,
         customStyle: null,
+        sourceThreadTs: null,
+        groupByThread: false,
+        includeBots: false,
+        filesOnly: false,
+        template: null,
+        notifyMe: false,
+        keywordFilter: null,
+        pinnedOnly: false,
+        canvasOnly: false,
+        teamId: null,
+        enterpriseId: null,
+        postChannelId: null,
+        replaceTs: null,
+        timezone: null,
+        beforeTs: null,
+        afterTs: null,
+        sinceLastSummary: false,
+        skipImages: false,
+        bookmark: false,
+        allowMentions: false,
+        tone: null,
       },
       llm,
     });
@@ -108,6 +840,553 @@ describe('runSummarization (non-streaming)', () => {
     expect(args.blocks).toBeDefined();
     const actions = (args.blocks as Array<{ type: string; elements: Array<{ action_id: string }> }>)[0];
     expect(actions.elements.map((e) => e.action_id)).toContain('share_summary');
+    expect(args.metadata).toEqual({
+      event_type: 'tldr_summary',
+      event_payload: { closed: true, source_channel_id: 'C123', oldest_ts: '1', latest_ts: '1' },
+    });
+  });
+
+  it('leaves images out of a --no-images run only', async () => {
+    const messages = [
+      {
+        ts: '1',
+        user: 'U1',
+        text: 'new mockups',
+        files: [{ url_private_download: 'https://files.example/1.png', mimetype: 'image/png' }],
+      },
+    ];
+    for (const skipImages of [false, true]) {
+      const { client } = makeWebClient(messages);
+      const fetchImpl = jest.fn().mockResolvedValue({ ok: false, status: 404, headers: new Headers() });
+      const llm = makeLlm();
+      jest.spyOn(llm, 'generateSummary').mockResolvedValue('*Summary*\nthings');
+      await runSummarization({
+        config: makeConfig(),
+        client,
+        request: {
+          correlationId: 'cid',
+          userId: 'U1',
+          channelId: 'C123',
+          originChannelId: 'D1',
+          threadTs: '1.0',
+          messageCount: 25,
+          customStyle: null,
+          sourceThreadTs: null,
+          groupByThread: false,
+          includeBots: false,
+          filesOnly: false,
+          template: null,
+          notifyMe: false,
+          keywordFilter: null,
+          pinnedOnly: false,
+          canvasOnly: false,
+          teamId: null,
+          enterpriseId: null,
+          postChannelId: null,
+          replaceTs: null,
+          timezone: null,
+          beforeTs: null,
+          afterTs: null,
+          sinceLastSummary: false,
+          skipImages,
+        },
+        llm,
+        fetchImpl: fetchImpl as unknown as typeof fetch,
+      });
+      expect(fetchImpl.mock.calls.length > 0).toBe(!skipImages);
+      expect(llm.generateSummary).toHaveBeenCalled();
+    }
+  });
+
+  it('DMs the summary to the requester when the thread post keeps failing', async () => {
+    const messages = [{ ts: '1', user: 'U1', text: 'hello world', files: [] }];
+    const { client, spies } = makeWebClient(messages);
+    spies.postMessage.mockImplementation(async (args: { channel: string }) => {
+      if (args.channel === 'D1') {
+        throw { data: { ok: false, error: 'internal_error' } };
+      }
+      return { ok: true, ts: '2.0' };
+    });
+    const llm = makeLlm();
+    jest.spyOn(llm, 'generateSummary').mockResolvedValue('*Summary*\nthings');
+
+    await runSummarization({
+      config: makeConfig({ deliveryMaxRetries: 1 }),
+      client,
+      request: {
+        correlationId: 'cid',
+        userId: 'U1',
+        channelId: 'C123',
+        originChannelId: 'D1',
+        threadTs: '1.0',
+        messageCount: 25,
+        customStyle: null,
+        sourceThreadTs: null,
+        groupByThread: false,
+        includeBots: false,
+        filesOnly: false,
+        template: null,
+        notifyMe: false,
+        keywordFilter: null,
+        pinnedOnly: false,
+        canvasOnly: false,
+        teamId: null,
+        enterpriseId: null,
+        postChannelId: null,
+        replaceTs: null,
+        timezone: null,
+        beforeTs: null,
+        afterTs: null,
+        sinceLastSummary: false,
+        skipImages: false,
+        bookmark: false,
+        allowMentions: false,
+        tone: null,
+      },
+      llm,
+      sleep: async () => undefined,
+    });
+
+    const threadPosts = spies.postMessage.mock.calls.filter((c) => c[0].channel === 'D1');
+    expect(threadPosts).toHaveLength(2);
+    expect(spies.postMessage).toHaveBeenCalledWith(
+      expect.objectContaining({
+        channel: 'U1',
+        text: expect.stringContaining('*Summary from <#C123>*'),
+      })
+    );
+  });
+
+  it('normalises Markdown bold to mrkdwn before posting', async () => {
+    const messages = [{ ts: '1', user: 'U1', text: 'hello world', files: [] }];
+    const { client, spies } = makeWebClient(messages);
+    const llm = makeLlm();
+    jest.spyOn(llm, 'generateSummary').mockResolvedValue('**Summary**\nthings');
+
+    await runSummarization({
+      config: makeConfig(),
+      client,
+      request: {
+        correlationId: 'cid',
+        userId: 'U1',
+        channelId: 'C123',
+        originChannelId: 'D1',
+        threadTs: '1.0',
+        messageCount: 25,
+        customStyle: null,
+        sourceThreadTs: null,
+        groupByThread: false,
+        includeBots: false,
+        filesOnly: false,
+        template: null,
+        notifyMe: false,
+        keywordFilter: null,
+        pinnedOnly: false,
+        canvasOnly: false,
+        teamId: null,
+        enterpriseId: null,
+        postChannelId: null,
+        replaceTs: null,
+        timezone: null,
+        beforeTs: null,
+        afterTs: null,
+        sinceLastSummary: false,
+        skipImages: false,
+        bookmark: false,
+        allowMentions: false,
+        tone: null,
+      },
+      llm,
+    });
+
+    const text = spies.postMessage.mock.calls[0][0].text as string;
+    expect(text).toContain('*Summary*\nthings');
+    expect(text).not.toContain('**Summary**');
+  });
+
+  it('renders the configured footer above the action buttons', async () => {
+    const messages = [{ ts: '1', user: 'U1', text: 'hello world', files: [] }];
+    const { client, spies } = makeWebClient(messages);
+    const llm = makeLlm();
+    jest.spyOn(llm, 'generateSummary').mockResolvedValue('*Summary*\nthings');
+
+    await runSummarization({
+      config: makeConfig({ summaryFooter: 'By {model} from {count} messages' }),
+      client,
+      request: {
+        correlationId: 'cid',
+        userId: 'U1',
+        channelId: 'C123',
+        originChannelId: 'D1',
+        threadTs: '1.0',
+        messageCount: 25,
+        customStyle: null,
+        sourceThreadTs: null,
+        groupByThread: false,
+        includeBots: false,
+        filesOnly: false,
+        template: null,
+        notifyMe: false,
+        keywordFilter: null,
+        pinnedOnly: false,
+        canvasOnly: false,
+        teamId: null,
+        enterpriseId: null,
+        postChannelId: null,
+        replaceTs: null,
+        timezone: null,
+        beforeTs: null,
+        afterTs: null,
+        sinceLastSummary: false,
+        skipImages: false,
+        bookmark: false,
+        allowMentions: false,
+        tone: null,
+      },
+      llm,
+    });
+
+    const blocks = spies.postMessage.mock.calls[0][0].blocks as Array<{ type: string }>;
+    expect(blocks.map((b) => b.type)).toEqual(['context', 'actions']);
+    expect(blocks[0]).toEqual({
+      type: 'context',
+      elements: [{ type: 'mrkdwn', text: 'By claude-test from 25 messages' }],
+    });
+  });
+
+  it('edits the earlier summary in place on refresh, even with streaming on', async () => {
+    const messages = [{ ts: '1', user: 'U1', text: 'hello world', files: [] }];
+    const { client, spies } = makeWebClient(messages);
+    const llm = makeLlm();
+    jest.spyOn(llm, 'generateSummary').mockResolvedValue('*Summary*\nthings');
+
+    await runSummarization({
+      config: makeConfig({ enableStreaming: true }),
+      client,
+      request: {
+        correlationId: 'cid',
+        userId: 'U1',
+        channelId: 'C123',
+        originChannelId: 'D1',
+        threadTs: '1.0',
+        messageCount: 200,
+        customStyle: null,
+        sourceThreadTs: null,
+        groupByThread: false,
+        includeBots: false,
+        filesOnly: false,
+        template: null,
+        notifyMe: false,
+        keywordFilter: null,
+        pinnedOnly: false,
+        canvasOnly: false,
+        teamId: null,
+        enterpriseId: null,
+        postChannelId: null,
+        replaceTs: '5.5',
+        timezone: null,
+        beforeTs: null,
+        afterTs: null,
+        sinceLastSummary: false,
+        skipImages: false,
+        bookmark: false,
+        allowMentions: false,
+        tone: null,
+      },
+      llm,
+    });
+
+    expect(spies.chatUpdate).toHaveBeenCalledWith(
+      expect.objectContaining({
+        channel: 'D1',
+        ts: '5.5',
+        text: expect.stringContaining('*Summary from <#C123>*'),
+        blocks: expect.any(Array),
+      })
+    );
+    expect(spies.postMessage).not.toHaveBeenCalled();
+  });
+
+  it('publishes cross-channel summaries to the post channel and confirms in the thread', async () => {
+    const messages = [{ ts: '1', user: 'U1', text: 'hello world', files: [] }];
+    const { client, spies } = makeWebClient(messages);
+    const llm = makeLlm();
+    jest.spyOn(llm, 'generateSummary').mockResolvedValue('*Summary*\nthings');
+
+    await runSummarization({
+      config: makeConfig({ enableStreaming: true }),
+      client,
+      request: {
+        correlationId: 'cid',
+        userId: 'U1',
+        channelId: 'C123',
+        originChannelId: 'D1',
+        threadTs: '1.0',
+        messageCount: 25,
+        customStyle: null,
+        sourceThreadTs: null,
+        groupByThread: false,
+        includeBots: false,
+        filesOnly: false,
+        template: null,
+        notifyMe: false,
+        keywordFilter: null,
+        pinnedOnly: false,
+        canvasOnly: false,
+        teamId: null,
+        enterpriseId: null,
+        postChannelId: 'C456',
+        replaceTs: null,
+        timezone: null,
+        beforeTs: null,
+        afterTs: null,
+        sinceLastSummary: false,
+        skipImages: false,
+        bookmark: false,
+        allowMentions: false,
+        tone: null,
+      },
+      llm,
+    });
+
+    const published = spies.postMessage.mock.calls.find((c) => c[0]?.channel === 'C456');
+    expect(published).toBeDefined();
+    expect(published![0].text).toContain('_Summary of <#C123>_');
+    expect(published![0].thread_ts).toBeUndefined();
+    expect(spies.postMessage).toHaveBeenCalledWith(
+      expect.objectContaining({ channel: 'D1', thread_ts: '1.0', text: '✅ Posted to <#C456>' })
+    );
+  });
+
+  it('DMs the summary to the requester when the channel post keeps failing', async () => {
+    const messages = [{ ts: '1', user: 'U1', text: 'hello world', files: [] }];
+    const { client, spies } = makeWebClient(messages);
+    spies.postMessage.mockImplementation(async (args: { channel: string }) => {
+      if (args.channel === 'C456') {
+        throw { data: { ok: false, error: 'service_unavailable' } };
+      }
+      return { ok: true, ts: '2.0' };
+    });
+    const llm = makeLlm();
+    jest.spyOn(llm, 'generateSummary').mockResolvedValue('*Summary*\nthings');
+
+    await runSummarization({
+      config: makeConfig({ deliveryMaxRetries: 1 }),
+      client,
+      request: {
+        correlationId: 'cid',
+        userId: 'U1',
+        channelId: 'C123',
+        originChannelId: 'D1',
+        threadTs: '1.0',
+        messageCount: 25,
+        customStyle: null,
+        sourceThreadTs: null,
+        groupByThread: false,
+        includeBots: false,
+        filesOnly: false,
+        template: null,
+        notifyMe: false,
+        keywordFilter: null,
+        pinnedOnly: false,
+        canvasOnly: false,
+        teamId: null,
+        enterpriseId: null,
+        postChannelId: 'C456',
+        replaceTs: null,
+        timezone: null,
+        beforeTs: null,
+        afterTs: null,
+        sinceLastSummary: false,
+        skipImages: false,
+        bookmark: false,
+        allowMentions: false,
+        tone: null,
+      },
+      llm,
+      sleep: async () => undefined,
+    });
+
+    expect(spies.postMessage.mock.calls.filter((c) => c[0].channel === 'C456')).toHaveLength(2);
+    expect(spies.postMessage).toHaveBeenCalledWith(
+      expect.objectContaining({ channel: 'U1', text: expect.stringContaining('things') })
+    );
+    expect(spies.postMessage).toHaveBeenCalledWith(
+      expect.objectContaining({
+        channel: 'D1',
+        thread_ts: '1.0',
+        text: "I couldn't post to <#C456>, so I sent the summary to you by DM.",
+      })
+    );
+  });
+
+  it('DMs the requester a permalink to the published summary with notifyMe', async () => {
+    const messages = [{ ts: '1', user: 'U1', text: 'hello world', files: [] }];
+    const { client, spies } = makeWebClient(messages);
+    spies.postMessage.mockImplementation(async (args: { channel: string }) => ({
+      ok: true,
+      ts: args.channel === 'C456' ? '7.7' : '1.1',
+    }));
+    const llm = makeLlm();
+    jest.spyOn(llm, 'generateSummary').mockResolvedValue('*Summary*\nthings');
+
+    await runSummarization({
+      config: makeConfig(),
+      client,
+      request: {
+        correlationId: 'cid',
+        userId: 'U1',
+        channelId: 'C123',
+        originChannelId: 'D1',
+        threadTs: '1.0',
+        messageCount: 25,
+        customStyle: null,
+        sourceThreadTs: null,
+        groupByThread: false,
+        includeBots: false,
+        filesOnly: false,
+        template: null,
+        notifyMe: true,
+        keywordFilter: null,
+        pinnedOnly: false,
+        canvasOnly: false,
+        teamId: null,
+        enterpriseId: null,
+        postChannelId: 'C456',
+        replaceTs: null,
+        timezone: null,
+        beforeTs: null,
+        afterTs: null,
+        sinceLastSummary: false,
+        skipImages: false,
+        bookmark: false,
+        allowMentions: false,
+        tone: null,
+      },
+      llm,
+    });
+
+    expect(spies.chatGetPermalink).toHaveBeenCalledWith({ channel: 'C456', message_ts: '7.7' });
+    expect(spies.postMessage).toHaveBeenCalledWith({
+      channel: 'U1',
+      text: 'Your summary is ready in <#C456>: https://slack/p/1',
+    });
+  });
+
+  it('bookmarks the published summary in its channel with bookmark', async () => {
+    const messages = [{ ts: '1', user: 'U1', text: 'hello world', files: [] }];
+    const { client, spies } = makeWebClient(messages);
+    spies.postMessage.mockImplementation(async (args: { channel: string }) => ({
+      ok: true,
+      ts: args.channel === 'C456' ? '7.7' : '1.1',
+    }));
+    const bookmarks = {
+      list: jest.fn().mockResolvedValue({ bookmarks: [] }),
+      add: jest.fn().mockResolvedValue({ ok: true }),
+      edit: jest.fn(),
+    };
+    (client as unknown as { bookmarks: unknown }).bookmarks = bookmarks;
+    const llm = makeLlm();
+    jest.spyOn(llm, 'generateSummary').mockResolvedValue('*Summary*\nthings');
+
+    await runSummarization({
+      config: makeConfig(),
+      client,
+      request: {
+        correlationId: 'cid',
+        userId: 'U1',
+        channelId: 'C123',
+        originChannelId: 'D1',
+        threadTs: '1.0',
+        messageCount: 25,
+        customStyle: null,
+        sourceThreadTs: null,
+        groupByThread: false,
+        includeBots: false,
+        filesOnly: false,
+        template: null,
+        notifyMe: false,
+        keywordFilter: null,
+        pinnedOnly: false,
+        canvasOnly: false,
+        teamId: null,
+        enterpriseId: null,
+        postChannelId: 'C456',
+        replaceTs: null,
+        timezone: null,
+        beforeTs: null,
+        afterTs: null,
+        sinceLastSummary: false,
+        skipImages: false,
+        bookmark: true,
+        tone: null,
+      },
+      llm,
+    });
+
+    expect(spies.chatGetPermalink).toHaveBeenCalledWith({ channel: 'C456', message_ts: '7.7' });
+    expect(bookmarks.add).toHaveBeenCalledWith({
+      channel_id: 'C456',
+      title: 'Latest summary: #demo',
+      type: 'link',
+      link: 'https://slack/p/1',
+    });
+  });
+
+  it('asks for an invite when the bot is not in the post channel', async () => {
+    const messages = [{ ts: '1', user: 'U1', text: 'hello world', files: [] }];
+    const { client, spies } = makeWebClient(messages);
+    spies.postMessage.mockImplementation(async (args: { channel: string }) => {
+      if (args.channel === 'C123') {
+        throw Object.assign(new Error('not_in_channel'), { data: { error: 'not_in_channel' } });
+      }
+      return { ok: true, ts: '1.1' };
+    });
+    const llm = makeLlm();
+    jest.spyOn(llm, 'generateSummary').mockResolvedValue('*Summary*\nthings');
+
+    await runSummarization({
+      config: makeConfig(),
+      client,
+      request: {
+        correlationId: 'cid',
+        userId: 'U1',
+        channelId: 'C123',
+        originChannelId: 'D1',
+        threadTs: '1.0',
+        messageCount: 25,
+        customStyle: null,
+        sourceThreadTs: null,
+        groupByThread: false,
+        includeBots: false,
+        filesOnly: false,
+        template: null,
+        notifyMe: false,
+        keywordFilter: null,
+        pinnedOnly: false,
+        canvasOnly: false,
+        teamId: null,
+        enterpriseId: null,
+        postChannelId: 'C123',
+        replaceTs: null,
+        timezone: null,
+        beforeTs: null,
+        afterTs: null,
+        sinceLastSummary: false,
+        skipImages: false,
+        bookmark: false,
+        allowMentions: false,
+        tone: null,
+      },
+      llm,
+    });
+
+    expect(spies.postMessage).toHaveBeenCalledWith(
+      expect.objectContaining({
+        channel: 'D1',
+        text: expect.stringContaining("I'm not a member of <#C123>"),
+      })
+    );
   });
 
   it('posts the canonical failure message when the model errors', async () => {
@@ -127,6 +1406,27 @@ describe('runSummarization (non-streaming)', () => {
         threadTs: '1.0',
         messageCount: 25,
         customStyle: null,
+        sourceThreadTs: null,
+        groupByThread: false,
+        includeBots: false,
+        filesOnly: false,
+        template: null,
+        notifyMe: false,
+        keywordFilter: null,
+        pinnedOnly: false,
+        canvasOnly: false,
+        teamId: null,
+        enterpriseId: null,
+        postChannelId: null,
+        replaceTs: null,
+        timezone: null,
+        beforeTs: null,
+        afterTs: null,
+        sinceLastSummary: false,
+        skipImages: false,
+        bookmark: false,
+        allowMentions: false,
+        tone: null,
       },
       llm,
     });
@@ -136,6 +1436,53 @@ describe('runSummarization (non-streaming)', () => {
     );
     expect(fail).toBeDefined();
   });
+
+  it('explains a model refusal instead of the canonical failure', async () => {
+    const messages = [{ ts: '1', user: 'U1', text: 'hello', files: [] }];
+    const { client, spies } = makeWebClient(messages);
+    const llm = makeLlm();
+    jest.spyOn(llm, 'generateSummary').mockRejectedValue(new RefusalError('flagged'));
+
+    await runSummarization({
+      config: makeConfig(),
+      client,
+      request: {
+        correlationId: 'cid',
+        userId: 'U1',
+        channelId: 'C1',
+        originChannelId: 'D1',
+        threadTs: '1.0',
+        messageCount: 25,
+        customStyle: null,
+        sourceThreadTs: null,
+        groupByThread: false,
+        includeBots: false,
+        filesOnly: false,
+        template: null,
+        notifyMe: false,
+        keywordFilter: null,
+        pinnedOnly: false,
+        canvasOnly: false,
+        teamId: null,
+        enterpriseId: null,
+        postChannelId: null,
+        replaceTs: null,
+        timezone: null,
+        beforeTs: null,
+        afterTs: null,
+        sinceLastSummary: false,
+        skipImages: false,
+        bookmark: false,
+        allowMentions: false,
+        tone: null,
+      },
+      llm,
+    });
+
+    expect(spies.postMessage).toHaveBeenCalledWith(
+      expect.objectContaining({ text: "I couldn't summarize this content: flagged." })
+    );
+  });
 });
 
 describe('runSummarization (streaming)', () => {
@@ -171,6 +1518,27 @@ describe('runSummarization (streaming)', () => {
         threadTs: '1.0',
         messageCount: 5,
         customStyle: null,
+        sourceThreadTs: null,
+        groupByThread: false,
+        includeBots: false,
+        filesOnly: false,
+        template: null,
+        notifyMe: false,
+        keywordFilter: null,
+        pinnedOnly: false,
+        canvasOnly: false,
+        teamId: null,
+        enterpriseId: null,
+        postChannelId: null,
+        replaceTs: null,
+        timezone: null,
+        beforeTs: null,
+        afterTs: null,
+        sinceLastSummary: false,
+        skipImages: false,
+        bookmark: false,
+        allowMentions: false,
+        tone: null,
       },
       llm,
     });
@@ -182,12 +1550,296 @@ describe('runSummarization (streaming)', () => {
     expect(spies.conversationsHistory).toHaveBeenCalled();
   });
 
-  it('posts a too-large message inline when the prompt is too big', async () => {
+  it('finishes the stream with a footer linking back to the source channel', async () => {
+    const messages = [{ ts: '1', user: 'U1', text: 'hello', files: [] }];
+    const { client } = makeWebClient(messages);
+
+    const startStream = jest.fn().mockResolvedValue({ ok: true, ts: 'STREAM1' });
+    const appendStream = jest.fn().mockResolvedValue({ ok: true });
+    const stopStream = jest.fn().mockResolvedValue({ ok: true });
+    (client.chat as Record<string, unknown>).startStream = startStream;
+    (client.chat as Record<string, unknown>).appendStream = appendStream;
+    (client.chat as Record<string, unknown>).stopStream = stopStream;
+
+    const llm = makeLlm();
+    jest.spyOn(llm, 'generateSummaryStream').mockResolvedValue({
+      kind: 'active',
+      iterator: (async function* () {
+        yield { kind: 'text_delta', delta: 'hello world' };
+        yield { kind: 'completed' };
+      })(),
+      cancel: async () => {},
+    });
+
+    await runSummarization({
+      config: makeConfig({ enableStreaming: true, summaryFooter: DEFAULT_SUMMARY_FOOTER }),
+      client,
+      request: {
+        correlationId: 'cid',
+        userId: 'U1',
+        channelId: 'C1',
+        originChannelId: 'D1',
+        threadTs: '1.0',
+        messageCount: 5,
+        customStyle: null,
+        sourceThreadTs: null,
+        groupByThread: false,
+        includeBots: false,
+        filesOnly: false,
+        template: null,
+        notifyMe: false,
+        keywordFilter: null,
+        pinnedOnly: false,
+        canvasOnly: false,
+        teamId: null,
+        enterpriseId: null,
+        postChannelId: null,
+        replaceTs: null,
+        timezone: null,
+        beforeTs: null,
+        afterTs: null,
+        sinceLastSummary: false,
+        skipImages: false,
+        bookmark: false,
+        allowMentions: false,
+        tone: null,
+      },
+      llm,
+    });
+
+    const [{ blocks }] = stopStream.mock.calls[0] as [{ blocks: unknown[] }];
+    expect(blocks[0]).toEqual({
+      type: 'context',
+      elements: [{ type: 'mrkdwn', text: '_claude-test · 5 messages from <#C1>_' }],
+    });
+    // Slack rejects messages with more than 50 blocks.
+    expect(blocks.length).toBeLessThanOrEqual(50);
+  });
+
+  it('still finalises a delivered stream when the deadline passes after the last chunk', async () => {
+    const messages = [{ ts: '1', user: 'U1', text: 'hello', files: [] }];
+    const { client, spies } = makeWebClient(messages);
+
+    const startStream = jest.fn().mockResolvedValue({ ok: true, ts: 'STREAM1' });
+    const appendStream = jest.fn().mockResolvedValue({ ok: true });
+    const stopStream = jest.fn().mockResolvedValue({ ok: true });
+    (client.chat as Record<string, unknown>).startStream = startStream;
+    (client.chat as Record<string, unknown>).appendStream = appendStream;
+    (client.chat as Record<string, unknown>).stopStream = stopStream;
+
+    let expired = false;
+    const deadline: Deadline = {
+      expiresAt: Number.MAX_SAFE_INTEGER,
+      remainingMs: () => (expired ? 0 : 60_000),
+      check: () => {
+        if (expired) {
+          throw new DeadlineExceededError();
+        }
+      },
+    };
+    const llm = makeLlm();
+    jest.spyOn(llm, 'generateSummaryStream').mockResolvedValue({
+      kind: 'active',
+      iterator: (async function* () {
+        yield { kind: 'text_delta', delta: 'hello world' };
+        expired = true;
+        yield { kind: 'completed' };
+      })(),
+      cancel: async () => {},
+    });
+
+    await runSummarization({
+      config: makeConfig({ enableStreaming: true }),
+      client,
+      request: {
+        correlationId: 'cid',
+        userId: 'U1',
+        channelId: 'C1',
+        originChannelId: 'D1',
+        threadTs: '1.0',
+        messageCount: 5,
+        customStyle: null,
+        sourceThreadTs: null,
+        groupByThread: false,
+        includeBots: false,
+        filesOnly: false,
+        template: null,
+        notifyMe: false,
+        keywordFilter: null,
+        pinnedOnly: false,
+        canvasOnly: false,
+        teamId: null,
+        enterpriseId: null,
+        postChannelId: null,
+        replaceTs: null,
+        timezone: null,
+        beforeTs: null,
+        afterTs: null,
+        sinceLastSummary: false,
+        skipImages: false,
+        bookmark: false,
+        allowMentions: false,
+        tone: null,
+      },
+      llm,
+      deadline,
+    });
+
+    expect(stopStream).toHaveBeenCalledWith(
+      expect.objectContaining({ channel: 'D1', ts: 'STREAM1', blocks: expect.any(Array) })
+    );
+    expect(stopStream).toHaveBeenCalledTimes(1);
+    expect(spies.chatUpdate).not.toHaveBeenCalled();
+    expect(spies.postMessage).not.toHaveBeenCalledWith(
+      expect.objectContaining({ text: DEADLINE_FAILURE_MESSAGE })
+    );
+  });
+
+  it('posts the collected text as one message when chat.startStream is unavailable', async () => {
+    const messages = [{ ts: '1', user: 'U1', text: 'hello', files: [] }];
+    const { client, spies } = makeWebClient(messages);
+
+    const unsupported = Object.assign(new Error('An API error occurred: method_not_supported'), {
+      data: { ok: false, error: 'method_not_supported' },
+    });
+    const startStream = jest.fn().mockRejectedValue(unsupported);
+    const appendStream = jest.fn();
+    const stopStream = jest.fn();
+    (client.chat as Record<string, unknown>).startStream = startStream;
+    (client.chat as Record<string, unknown>).appendStream = appendStream;
+    (client.chat as Record<string, unknown>).stopStream = stopStream;
+
+    const llm = makeLlm();
+    jest.spyOn(llm, 'generateSummaryStream').mockResolvedValue({
+      kind: 'active',
+      iterator: (async function* () {
+        yield { kind: 'text_delta', delta: '*Summary*\nhello ' };
+        yield { kind: 'text_delta', delta: 'world' };
+        yield { kind: 'completed' };
+      })(),
+      cancel: async () => {},
+    });
+
+    await runSummarization({
+      config: makeConfig({ enableStreaming: true }),
+      client,
+      request: {
+        correlationId: 'cid',
+        userId: 'U1',
+        channelId: 'C1',
+        originChannelId: 'D1',
+        threadTs: '1.0',
+        messageCount: 5,
+        customStyle: null,
+        sourceThreadTs: null,
+        groupByThread: false,
+        includeBots: false,
+        filesOnly: false,
+        template: null,
+        notifyMe: false,
+        keywordFilter: null,
+        pinnedOnly: false,
+        canvasOnly: false,
+        teamId: null,
+        enterpriseId: null,
+        postChannelId: null,
+        replaceTs: null,
+        timezone: null,
+        beforeTs: null,
+        afterTs: null,
+        sinceLastSummary: false,
+        skipImages: false,
+        bookmark: false,
+        allowMentions: false,
+        tone: null,
+      },
+      llm,
+    });
+
+    expect(startStream).toHaveBeenCalledTimes(1);
+    expect(appendStream).not.toHaveBeenCalled();
+    expect(stopStream).not.toHaveBeenCalled();
+    const posted = spies.postMessage.mock.calls.map((c) => c[0]);
+    expect(posted).toContainEqual(
+      expect.objectContaining({
+        channel: 'D1',
+        thread_ts: '1.0',
+        text: expect.stringContaining('hello world'),
+        blocks: expect.any(Array),
+      })
+    );
+    expect(posted.map((p) => p.text)).not.toContain(
+      "Sorry, I couldn't generate a summary at this time. Please try again later."
+    );
+  });
+
+  it('rolls up window summaries inline when the prompt is too big', async () => {
+    const messages = [{ ts: '1', user: 'U1', text: 'hello', files: [] }];
+    const { client, spies } = makeWebClient(messages);
+
+    const llm = makeLlm();
+    jest.spyOn(llm, 'generateSummaryStream').mockResolvedValue({ kind: 'too_large' });
+    jest
+      .spyOn(llm, 'generateSummary')
+      .mockResolvedValueOnce('partial')
+      .mockResolvedValueOnce('*Summary*\nrolled up');
+
+    await runSummarization({
+      config: makeConfig({ enableStreaming: true, summaryFooter: DEFAULT_SUMMARY_FOOTER }),
+      client,
+      request: {
+        correlationId: 'cid',
+        userId: 'U1',
+        channelId: 'C1',
+        originChannelId: 'D1',
+        threadTs: '1.0',
+        messageCount: 5,
+        customStyle: null,
+        sourceThreadTs: null,
+        groupByThread: false,
+        includeBots: false,
+        filesOnly: false,
+        template: null,
+        notifyMe: false,
+        keywordFilter: null,
+        pinnedOnly: false,
+        canvasOnly: false,
+        teamId: null,
+        enterpriseId: null,
+        postChannelId: null,
+        replaceTs: null,
+        timezone: null,
+        beforeTs: null,
+        afterTs: null,
+        sinceLastSummary: false,
+        skipImages: false,
+        bookmark: false,
+        allowMentions: false,
+        tone: null,
+      },
+      llm,
+    });
+
+    expect(llm.generateSummary).toHaveBeenCalledTimes(2);
+    const call = spies.postMessage.mock.calls.find((c) =>
+      typeof c[0]?.text === 'string' && c[0].text.includes('rolled up')
+    );
+    expect(call).toBeDefined();
+    const blocks = call![0].blocks as Array<{ type: string; elements: Array<{ action_id?: string }> }>;
+    expect(blocks[0]).toEqual({
+      type: 'context',
+      elements: [{ type: 'mrkdwn', text: '_claude-test · 5 messages from <#C1>_' }],
+    });
+    expect(blocks[1].elements.map((e) => e.action_id)).toContain('share_summary');
+  });
+
+  it('posts a too-large message inline when even the rollup is too big', async () => {
     const messages = [{ ts: '1', user: 'U1', text: 'hello', files: [] }];
     const { client, spies } = makeWebClient(messages);
 
     const llm = makeLlm();
     jest.spyOn(llm, 'generateSummaryStream').mockResolvedValue({ kind: 'too_large' });
+    jest.spyOn(llm, 'generateSummary').mockResolvedValue(TOO_LARGE_MESSAGE);
 
     await runSummarization({
       config: makeConfig({ enableStreaming: true }),
@@ -200,6 +1852,27 @@ describe('runSummarization (streaming)', () => {
         threadTs: '1.0',
         messageCount: 5,
         customStyle: null,
+        sourceThreadTs: null,
+        groupByThread: false,
+        includeBots: false,
+        filesOnly: false,
+        template: null,
+        notifyMe: false,
+        keywordFilter: null,
+        pinnedOnly: false,
+        canvasOnly: false,
+        teamId: null,
+        enterpriseId: null,
+        postChannelId: null,
+        replaceTs: null,
+        timezone: null,
+        beforeTs: null,
+        afterTs: null,
+        sinceLastSummary: false,
+        skipImages: false,
+        bookmark: false,
+        allowMentions: false,
+        tone: null,
       },
       llm,
     });
@@ -0,0 +1,93 @@
+import type { WebClient } from '@slack/web-api';
+import type { RecentMessage } from '../../src/slack/client';
+import {
+  fetchThreadReplies,
+  formatThreadGroups,
+  groupMessagesByThread,
+} from '../../src/worker/threads';
+
+function msg(ts: string, text: string, extra: Partial<RecentMessage> = {}): RecentMessage {
+  return { ts, user: 'U1', text, files: [], threadTs: null, replyCount: 0, ...extra };
+}
+
+const formatLines = (messages: RecentMessage[]): string[] =>
+  messages.map((m) => `[${m.ts}] ${m.text}`);
+
+describe('groupMessagesByThread', () => {
+  it('orders topics oldest first and attaches fetched replies to their root', () => {
+    // conversations.history returns newest first.
+    const history = [
+      msg('3.0', 'standalone'),
+      msg('2.0', 'launch plan?', { threadTs: '2.0', replyCount: 2 }),
+      msg('1.0', 'morning'),
+    ];
+    const replies = new Map([
+      ['2.0', [msg('2.2', 'ship friday', { threadTs: '2.0' }), msg('2.1', 'needs QA', { threadTs: '2.0' })]],
+    ]);
+
+    const groups = groupMessagesByThread(history, replies);
+
+    expect(groups.map((g) => g.root.ts)).toEqual(['1.0', '2.0', '3.0']);
+    expect(groups[1].replies.map((r) => r.ts)).toEqual(['2.1', '2.2']);
+    expect(groups[0].replies).toEqual([]);
+  });
+
+  it('folds a broadcast reply under its root without duplicating it', () => {
+    const history = [
+      msg('2.5', 'also sent to channel', { threadTs: '2.0' }),
+      msg('2.0', 'topic', { threadTs: '2.0', replyCount: 1 }),
+    ];
+    const replies = new Map([['2.0', [msg('2.5', 'also sent to channel', { threadTs: '2.0' })]]]);
+
+    const groups = groupMessagesByThread(history, replies);
+
+    expect(groups).toHaveLength(1);
+    expect(groups[0].replies.map((r) => r.ts)).toEqual(['2.5']);
+  });
+
+  it('keeps a broadcast whose root is outside the window as its own topic', () => {
+    const groups = groupMessagesByThread([msg('9.5', 'late reply', { threadTs: '1.0' })], new Map());
+    expect(groups.map((g) => g.root.ts)).toEqual(['9.5']);
+  });
+});
+
+describe('formatThreadGroups', () => {
+  it('renders topics with indented replies and leaves lone messages flat', () => {
+    const groups = [
+      { root: msg('1.0', 'morning'), replies: [] },
+      {
+        root: msg('2.0', 'launch plan?'),
+        replies: [msg('2.1', 'needs QA'), msg('2.2', 'ship friday')],
+      },
+    ];
+    expect(formatThreadGroups(groups, formatLines)).toEqual([
+      '[1.0] morning',
+      'Topic: [2.0] launch plan?',
+      '  reply: [2.1] needs QA',
+      '  reply: [2.2] ship friday',
+    ]);
+  });
+});
+
+describe('fetchThreadReplies', () => {
+  it('fetches only threaded roots, newest first, up to the bound, dropping the root', async () => {
+    const replies = jest.fn().mockImplementation(({ ts }: { ts: string }) =>
+      Promise.resolve({
+        messages: [{ ts, text: 'root' }, { ts: `${ts}1`, text: 'reply', thread_ts: ts }],
+      })
+    );
+    const client = { conversations: { replies } } as unknown as WebClient;
+    const history = [
+      msg('1.0', 'no replies'),
+      msg('2.0', 'old thread', { threadTs: '2.0', replyCount: 3 }),
+      msg('3.0', 'new thread', { threadTs: '3.0', replyCount: 1 }),
+    ];
+
+    const result = await fetchThreadReplies(client, 'C11111111', history, 1);
+
+    expect(replies).toHaveBeenCalledTimes(1);
+    expect(replies).toHaveBeenCalledWith(expect.objectContaining({ channel: 'C11111111', ts: '3.0' }));
+    expect([...result.keys()]).toEqual(['3.0']);
+    expect(result.get('3.0')?.map((m) => m.ts)).toEqual(['3.01']);
+  });
+});
@@ -35,11 +35,41 @@ new TldrStack(app, 'TldrStack', {
   slackBotTokenParameterName: requiredEnv('SLACK_BOT_TOKEN_PARAMETER_NAME'),
   slackSigningSecretParameterName: requiredEnv('SLACK_SIGNING_SECRET_PARAMETER_NAME'),
   anthropicApiKeyParameterName: requiredEnv('ANTHROPIC_API_KEY_PARAMETER_NAME'),
+  slackUserTokenParameterName: process.env.SLACK_USER_TOKEN_PARAMETER_NAME,
   anthropicModel: process.env.ANTHROPIC_MODEL,
   anthropicMaxOutputTokens: process.env.ANTHROPIC_MAX_OUTPUT_TOKENS,
+  anthropicBaseUrl: process.env.ANTHROPIC_BASE_URL,
+  anthropicReasoningEffort: process.env.ANTHROPIC_REASONING_EFFORT,
   enableStreaming: process.env.ENABLE_STREAMING || 'true',
   streamMinAppendIntervalMs: process.env.STREAM_MIN_APPEND_INTERVAL_MS,
   streamMaxChunkChars: process.env.STREAM_MAX_CHUNK_CHARS,
+  streamStallTimeoutMs: process.env.STREAM_STALL_TIMEOUT_MS,
+  streamMaxBufferBytes: process.env.STREAM_MAX_BUFFER_BYTES,
+  staleStreamMaxAgeSecs: process.env.STALE_STREAM_MAX_AGE_SECS,
+  maxRequestBodyBytes: process.env.MAX_REQUEST_BODY_BYTES,
+  imageDownloadConcurrency: process.env.IMAGE_DOWNLOAD_CONCURRENCY,
+  inlineImageMaxBytes: process.env.INLINE_IMAGE_MAX_BYTES,
+  permalinkConcurrency: process.env.PERMALINK_CONCURRENCY,
+  maxConcurrentSummaries: process.env.MAX_CONCURRENT_SUMMARIES,
+  hardMessageCap: process.env.HARD_MESSAGE_CAP,
+  ignoredUserIds: process.env.IGNORED_USER_IDS,
+  defaultTimezone: process.env.DEFAULT_TIMEZONE,
+  maxCustomStyleChars: process.env.MAX_CUSTOM_STYLE_CHARS,
+  noMessagesMessage: process.env.NO_MESSAGES_MESSAGE,
+  taskDeadlineSecs: process.env.TASK_DEADLINE_SECS,
+  slackMaxRetries: process.env.SLACK_MAX_RETRIES,
+  slackRetryBaseDelayMs: process.env.SLACK_RETRY_BASE_DELAY_MS,
+  slackRetryMaxDelayMs: process.env.SLACK_RETRY_MAX_DELAY_MS,
+  deliveryMaxRetries: process.env.DELIVERY_MAX_RETRIES,
+  disableImages: process.env.DISABLE_IMAGES,
+  citeInline: process.env.CITE_INLINE,
+  summarySections: process.env.SUMMARY_SECTIONS,
+  detectSummaryLanguage: process.env.DETECT_SUMMARY_LANGUAGE,
+  postAsUser: process.env.POST_AS_USER,
+  unfurlSummaryLinks: process.env.UNFURL_SUMMARY_LINKS,
+  canvasToc: process.env.CANVAS_TOC,
+  systemPromptOverride: process.env.SYSTEM_PROMPT_OVERRIDE,
+  assistantName: process.env.ASSISTANT_NAME,
   env: {
     account: accountId,
     region: process.env.CDK_DEFAULT_REGION || 'us-east-2',
@@ -10,11 +10,41 @@ interface TldrStackProps extends cdk.StackProps {
   slackBotTokenParameterName: string;
   slackSigningSecretParameterName: string;
   anthropicApiKeyParameterName: string;
+  slackUserTokenParameterName?: string;
   anthropicModel?: string;
   anthropicMaxOutputTokens?: string;
+  anthropicBaseUrl?: string;
+  anthropicReasoningEffort?: string;
   enableStreaming: string;
   streamMinAppendIntervalMs?: string;
   streamMaxChunkChars?: string;
+  streamStallTimeoutMs?: string;
+  streamMaxBufferBytes?: string;
+  staleStreamMaxAgeSecs?: string;
+  maxRequestBodyBytes?: string;
+  imageDownloadConcurrency?: string;
+  inlineImageMaxBytes?: string;
+  permalinkConcurrency?: string;
+  maxConcurrentSummaries?: string;
+  hardMessageCap?: string;
+  ignoredUserIds?: string;
+  defaultTimezone?: string;
+  maxCustomStyleChars?: string;
+  noMessagesMessage?: string;
+  taskDeadlineSecs?: string;
+  slackMaxRetries?: string;
+  slackRetryBaseDelayMs?: string;
+  slackRetryMaxDelayMs?: string;
+  deliveryMaxRetries?: string;
+  disableImages?: string;
+  citeInline?: string;
+  summarySections?: string;
+  detectSummaryLanguage?: string;
+  postAsUser?: string;
+  unfurlSummaryLinks?: string;
+  canvasToc?: string;
+  systemPromptOverride?: string;
+  assistantName?: string;
 }
 
 /**
@@ -48,10 +78,17 @@ export class TldrStack extends cdk.Stack {
       SLACK_BOT_TOKEN_PARAMETER_NAME: props.slackBotTokenParameterName,
       SLACK_SIGNING_SECRET_PARAMETER_NAME: props.slackSigningSecretParameterName,
       ANTHROPIC_API_KEY_PARAMETER_NAME: props.anthropicApiKeyParameterName,
+      ...(props.slackUserTokenParameterName
+        ? { SLACK_USER_TOKEN_PARAMETER_NAME: props.slackUserTokenParameterName }
+        : {}),
       ...(props.anthropicModel ? { ANTHROPIC_MODEL: props.anthropicModel } : {}),
       ...(props.anthropicMaxOutputTokens
         ? { ANTHROPIC_MAX_OUTPUT_TOKENS: props.anthropicMaxOutputTokens }
         : {}),
+      ...(props.anthropicBaseUrl ? { ANTHROPIC_BASE_URL: props.anthropicBaseUrl } : {}),
+      ...(props.anthropicReasoningEffort
+        ? { ANTHROPIC_REASONING_EFFORT: props.anthropicReasoningEffort }
+        : {}),
       ENABLE_STREAMING: props.enableStreaming,
       ...(props.streamMinAppendIntervalMs
         ? { STREAM_MIN_APPEND_INTERVAL_MS: props.streamMinAppendIntervalMs }
@@ -59,6 +96,61 @@ export class TldrStack extends cdk.Stack {
       ...(props.streamMaxChunkChars
         ? { STREAM_MAX_CHUNK_CHARS: props.streamMaxChunkChars }
         : {}),
+      ...(props.streamStallTimeoutMs
+        ? { STREAM_STALL_TIMEOUT_MS: props.streamStallTimeoutMs }
+        : {}),
+      ...(props.streamMaxBufferBytes
+        ? { STREAM_MAX_BUFFER_BYTES: props.streamMaxBufferBytes }
+        : {}),
+      ...(props.staleStreamMaxAgeSecs
+        ? { STALE_STREAM_MAX_AGE_SECS: props.staleStreamMaxAgeSecs }
+        : {}),
+      ...(props.maxRequestBodyBytes
+        ? { MAX_REQUEST_BODY_BYTES: props.maxRequestBodyBytes }
+        : {}),
+      ...(props.imageDownloadConcurrency
+        ? { IMAGE_DOWNLOAD_CONCURRENCY: props.imageDownloadConcurrency }
+        : {}),
+      ...(props.inlineImageMaxBytes
+        ? { INLINE_IMAGE_MAX_BYTES: props.inlineImageMaxBytes }
+        : {}),
+      ...(props.permalinkConcurrency
+        ? { PERMALINK_CONCURRENCY: props.permalinkConcurrency }
+        : {}),
+      ...(props.maxConcurrentSummaries
+        ? { MAX_CONCURRENT_SUMMARIES: props.maxConcurrentSummaries }
+        : {}),
+      ...(props.hardMessageCap ? { HARD_MESSAGE_CAP: props.hardMessageCap } : {}),
+      ...(props.ignoredUserIds ? { IGNORED_USER_IDS: props.ignoredUserIds } : {}),
+      ...(props.defaultTimezone ? { DEFAULT_TIMEZONE: props.defaultTimezone } : {}),
+      ...(props.maxCustomStyleChars
+        ? { MAX_CUSTOM_STYLE_CHARS: props.maxCustomStyleChars }
+        : {}),
+      ...(props.noMessagesMessage ? { NO_MESSAGES_MESSAGE: props.noMessagesMessage } : {}),
+      ...(props.taskDeadlineSecs ? { TASK_DEADLINE_SECS: props.taskDeadlineSecs } : {}),
+      ...(props.slackMaxRetries ? { SLACK_MAX_RETRIES: props.slackMaxRetries } : {}),
+      ...(props.slackRetryBaseDelayMs
+        ? { SLACK_RETRY_BASE_DELAY_MS: props.slackRetryBaseDelayMs }
+        : {}),
+      ...(props.slackRetryMaxDelayMs
+        ? { SLACK_RETRY_MAX_DELAY_MS: props.slackRetryMaxDelayMs }
+        : {}),
+      ...(props.deliveryMaxRetries ? { DELIVERY_MAX_RETRIES: props.deliveryMaxRetries } : {}),
+      ...(props.disableImages ? { DISABLE_IMAGES: props.disableImages } : {}),
+      ...(props.citeInline ? { CITE_INLINE: props.citeInline } : {}),
+      ...(props.summarySections ? { SUMMARY_SECTIONS: props.summarySections } : {}),
+      ...(props.detectSummaryLanguage
+        ? { DETECT_SUMMARY_LANGUAGE: props.detectSummaryLanguage }
+        : {}),
+      ...(props.postAsUser ? { POST_AS_USER: props.postAsUser } : {}),
+      ...(props.unfurlSummaryLinks
+        ? { UNFURL_SUMMARY_LINKS: props.unfurlSummaryLinks }
+        : {}),
+      ...(props.canvasToc ? { CANVAS_TOC: props.canvasToc } : {}),
+      ...(props.systemPromptOverride
+        ? { SYSTEM_PROMPT_OVERRIDE: props.systemPromptOverride }
+        : {}),
+      ...(props.assistantName ? { ASSISTANT_NAME: props.assistantName } : {}),
       NODE_OPTIONS: '--enable-source-maps',
     } as const;
 
@@ -79,6 +171,9 @@ export class TldrStack extends cdk.Stack {
     this.grantSsmParameterRead(tldrFunction, props.slackBotTokenParameterName);
     this.grantSsmParameterRead(tldrFunction, props.slackSigningSecretParameterName);
     this.grantSsmParameterRead(tldrFunction, props.anthropicApiKeyParameterName);
+    if (props.slackUserTokenParameterName) {
+      this.grantSsmParameterRead(tldrFunction, props.slackUserTokenParameterName);
+    }
 
     const api = new apigateway.RestApi(this, 'TldrApi', {
       restApiName: 'Tldr API',